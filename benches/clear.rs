@@ -0,0 +1,40 @@
+//! Compares recycling a bimap with `clear`, which keeps the memory of both
+//! sides, with replacing it by a new bimap that has to allocate again.
+//!
+//! Run with `cargo bench --bench clear`.
+
+use bimap::BiHashMap;
+use std::{hint::black_box, time::Instant};
+
+const PAIRS: u32 = 10_000;
+const ROUNDS: u32 = 50;
+
+/// Runs `f` once to warm up, then `ROUNDS` more times, and prints the mean
+/// time per pair.
+fn bench(name: &str, mut f: impl FnMut()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    let nanos = start.elapsed().as_secs_f64() * 1e9 / f64::from(ROUNDS * PAIRS);
+    println!("{:<36} {:>8.1} ns/pair", name, nanos);
+}
+
+fn main() {
+    let mut bimap: BiHashMap<u32, u32> = BiHashMap::new();
+    bench("BiHashMap clear and refill", || {
+        bimap.clear();
+        for i in 0..PAIRS {
+            bimap.insert(i, i);
+        }
+        black_box(&bimap);
+    });
+    bench("BiHashMap new and refill", || {
+        bimap = BiHashMap::new();
+        for i in 0..PAIRS {
+            bimap.insert(i, i);
+        }
+        black_box(&bimap);
+    });
+}
@@ -0,0 +1,51 @@
+//! Compares re-inserting pairs that are already present, which `insert`
+//! detects and skips, with removing and inserting them again, which is what
+//! `insert` did for such pairs before.
+//!
+//! Run with `cargo bench --bench insert`.
+
+use bimap::{BiBTreeMap, BiHashMap};
+use std::{hint::black_box, time::Instant};
+
+const PAIRS: u32 = 10_000;
+const ROUNDS: u32 = 50;
+
+/// Runs `f` once to warm up, then `ROUNDS` more times, and prints the mean
+/// time per pair.
+fn bench(name: &str, mut f: impl FnMut()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    let nanos = start.elapsed().as_secs_f64() * 1e9 / f64::from(ROUNDS * PAIRS);
+    println!("{:<36} {:>8.1} ns/pair", name, nanos);
+}
+
+fn main() {
+    let mut hash: BiHashMap<u32, u32> = (0..PAIRS).map(|i| (i, i)).collect();
+    bench("BiHashMap re-insert", || {
+        for i in 0..PAIRS {
+            black_box(hash.insert(i, i));
+        }
+    });
+    bench("BiHashMap remove and insert", || {
+        for i in 0..PAIRS {
+            black_box(hash.remove_by_left(&i));
+            black_box(hash.insert(i, i));
+        }
+    });
+
+    let mut btree: BiBTreeMap<u32, u32> = (0..PAIRS).map(|i| (i, i)).collect();
+    bench("BiBTreeMap re-insert", || {
+        for i in 0..PAIRS {
+            black_box(btree.insert(i, i));
+        }
+    });
+    bench("BiBTreeMap remove and insert", || {
+        for i in 0..PAIRS {
+            black_box(btree.remove_by_left(&i));
+            black_box(btree.insert(i, i));
+        }
+    });
+}
@@ -0,0 +1,145 @@
+//! Implementations of `arbitrary::Arbitrary` for `BiHashMap` and
+//! `BiBTreeMap`.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `arbitrary` feature in your dependency
+//! manifest.
+//!
+//! An arbitrary bimap is built from an arbitrary sequence of left-right pairs
+//! using the overwriting semantics of `insert`, so the raw input may contain
+//! duplicate values but the result is always a valid bimap. As a consequence,
+//! the bimap may have fewer pairs than were read from the input.
+//!
+//! # Examples
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use bimap::BiBTreeMap;
+//!
+//! let bytes = [1, 7, 0, 1, 2, 1, 7, 0, 3, 0];
+//! let mut u = Unstructured::new(&bytes);
+//! let bimap = BiBTreeMap::<u16, u8>::arbitrary(&mut u).unwrap();
+//! for (left, right) in &bimap {
+//!     assert_eq!(bimap.get_by_right(right), Some(left));
+//! }
+//! ```
+
+use crate::BiBTreeMap;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+impl<'a, L, R> Arbitrary<'a> for BiBTreeMap<L, R>
+where
+    L: Arbitrary<'a> + Ord,
+    R: Arbitrary<'a> + Ord,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        // the bimap may be empty, and has no upper bound on its size
+        (0, None)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, L, R, LS, RS> Arbitrary<'a> for crate::BiHashMap<L, R, LS, RS>
+where
+    L: Arbitrary<'a> + Eq + std::hash::Hash,
+    R: Arbitrary<'a> + Eq + std::hash::Hash,
+    LS: std::hash::BuildHasher + Default,
+    RS: std::hash::BuildHasher + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?.collect()
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        // the bimap may be empty, and has no upper bound on its size
+        (0, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    /// Generates pseudorandom fuzzer input.
+    fn inputs() -> impl Iterator<Item = Vec<u8>> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        (0..500).map(move |i| {
+            (0..i)
+                .map(|_| {
+                    // xorshift
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    // bias towards small values so that duplicates are common
+                    (state % 8) as u8
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn btree_invariants() {
+        for bytes in inputs() {
+            let mut u = Unstructured::new(&bytes);
+            let bimap = BiBTreeMap::<u8, String>::arbitrary(&mut u).unwrap();
+            assert_eq!(bimap.left_values().count(), bimap.len());
+            assert_eq!(bimap.right_values().count(), bimap.len());
+            for (l, r) in &bimap {
+                assert_eq!(bimap.get_by_left(l), Some(r));
+                assert_eq!(bimap.get_by_right(r), Some(l));
+            }
+
+            let rest =
+                BiBTreeMap::<u8, u8>::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+            for (l, r) in &rest {
+                assert_eq!(rest.get_by_right(r), Some(l));
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_invariants() {
+        use crate::BiHashMap;
+
+        for bytes in inputs() {
+            let mut u = Unstructured::new(&bytes);
+            let bimap = BiHashMap::<u16, String>::arbitrary(&mut u).unwrap();
+            assert_eq!(bimap.left_values().count(), bimap.len());
+            assert_eq!(bimap.right_values().count(), bimap.len());
+            for (l, r) in &bimap {
+                assert_eq!(bimap.get_by_left(l), Some(r));
+                assert_eq!(bimap.get_by_right(r), Some(l));
+            }
+
+            let rest = BiHashMap::<u8, u8>::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+            for (l, r) in &rest {
+                assert_eq!(rest.get_by_right(r), Some(l));
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates_overwrite() {
+        // two pairs with the same right value, each preceded by a continue
+        // byte, and then a stop byte
+        let bytes = [1, 1, 9, 1, 2, 9, 0];
+        let mut u = Unstructured::new(&bytes);
+        let bimap = BiBTreeMap::<u8, u8>::arbitrary(&mut u).unwrap();
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_right(&9), Some(&2));
+    }
+}
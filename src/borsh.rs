@@ -0,0 +1,262 @@
+//! Implementations of `BorshSerialize` and `BorshDeserialize` for
+//! `BiHashMap` and `BiBTreeMap`.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `borsh` feature in your dependency
+//! manifest.
+//!
+//! # Encoding
+//!
+//! A bimap is encoded as a `u32` length followed by its left-right pairs,
+//! each written as the left value and then the right value. This is the same
+//! encoding borsh uses for a `BTreeMap<L, R>`.
+//!
+//! The pairs are always written in ascending order of their left values, so
+//! the encoding of a bimap only depends on its contents. For a `BiHashMap` this
+//! requires `L: Ord`, and the pairs are sorted before they are written. As a
+//! result, a `BiHashMap` and a `BiBTreeMap` with the same pairs have the same
+//! encoding, regardless of insertion order or hasher.
+//!
+//! Deserializing rejects input that contains the same left value or the same
+//! right value more than once with an `InvalidData` error, instead of
+//! silently overwriting the earlier pair. The order of the pairs is not
+//! checked.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{BiBTreeMap, BiHashMap};
+//!
+//! let mut hash = BiHashMap::new();
+//! hash.insert(b'b', 2u8);
+//! hash.insert(b'a', 1u8);
+//!
+//! let mut btree = BiBTreeMap::new();
+//! btree.insert(b'a', 1u8);
+//! btree.insert(b'b', 2u8);
+//!
+//! let bytes = borsh::to_vec(&hash).unwrap();
+//! assert_eq!(bytes, borsh::to_vec(&btree).unwrap());
+//! assert_eq!(bytes, [2, 0, 0, 0, b'a', 1, b'b', 2]);
+//!
+//! let hash2: BiHashMap<u8, u8> = borsh::from_slice(&bytes).unwrap();
+//! assert_eq!(hash, hash2);
+//!
+//! // duplicate right values are rejected
+//! let bytes = [2, 0, 0, 0, b'a', 1, b'b', 1];
+//! assert!(borsh::from_slice::<BiBTreeMap<u8, u8>>(&bytes).is_err());
+//! ```
+
+use crate::BiBTreeMap;
+use borsh::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    BorshDeserialize, BorshSerialize,
+};
+use core::convert::TryFrom;
+
+/// The largest number of pairs preallocated from an untrusted length.
+const MAX_PREALLOC: usize = 4096;
+
+const ERROR_DUPLICATE: &str = "duplicate left or right value in bimap";
+
+/// Writes the length and then the given pairs, which must already be in
+/// canonical order.
+fn serialize_pairs<'a, L, R, I, W>(len: usize, pairs: I, writer: &mut W) -> Result<()>
+where
+    L: BorshSerialize + 'a,
+    R: BorshSerialize + 'a,
+    I: IntoIterator<Item = (&'a L, &'a R)>,
+    W: Write,
+{
+    u32::try_from(len)
+        .map_err(|_| ErrorKind::InvalidData)?
+        .serialize(writer)?;
+    for (left, right) in pairs {
+        left.serialize(writer)?;
+        right.serialize(writer)?;
+    }
+    Ok(())
+}
+
+/// Reads the length and then the pairs into a map created by `with_capacity`.
+///
+/// `insert` returns `false` if the pair conflicts with an earlier one.
+fn deserialize_pairs<L, R, M, Rd>(
+    reader: &mut Rd,
+    with_capacity: impl FnOnce(usize) -> M,
+    mut insert: impl FnMut(&mut M, L, R) -> bool,
+) -> Result<M>
+where
+    L: BorshDeserialize,
+    R: BorshDeserialize,
+    Rd: Read,
+{
+    let len = u32::deserialize_reader(reader)?;
+    // the length is untrusted, so don't let it dictate the allocation size
+    let mut map = with_capacity((len as usize).min(MAX_PREALLOC));
+    for _ in 0..len {
+        let left = L::deserialize_reader(reader)?;
+        let right = R::deserialize_reader(reader)?;
+        if !insert(&mut map, left, right) {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_DUPLICATE));
+        }
+    }
+    Ok(map)
+}
+
+impl<L, R> BorshSerialize for BiBTreeMap<L, R>
+where
+    L: BorshSerialize + Ord,
+    R: BorshSerialize + Ord,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        // the pairs are already in ascending left order
+        serialize_pairs(self.len(), self.iter(), writer)
+    }
+}
+
+impl<L, R> BorshDeserialize for BiBTreeMap<L, R>
+where
+    L: BorshDeserialize + Ord,
+    R: BorshDeserialize + Ord,
+{
+    fn deserialize_reader<Rd: Read>(reader: &mut Rd) -> Result<Self> {
+        deserialize_pairs(
+            reader,
+            |_| BiBTreeMap::new(),
+            |map, left, right| map.insert_no_overwrite(left, right).is_ok(),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+mod hash {
+    use super::{deserialize_pairs, serialize_pairs};
+    use crate::BiHashMap;
+    use borsh::{
+        io::{Read, Result, Write},
+        BorshDeserialize, BorshSerialize,
+    };
+    use std::hash::{BuildHasher, Hash};
+
+    impl<L, R, LS, RS> BorshSerialize for BiHashMap<L, R, LS, RS>
+    where
+        L: BorshSerialize + Eq + Hash + Ord,
+        R: BorshSerialize + Eq + Hash,
+    {
+        fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+            let mut pairs = self.iter().collect::<Vec<_>>();
+            pairs.sort_unstable_by_key(|&(left, _)| left);
+            serialize_pairs(pairs.len(), pairs, writer)
+        }
+    }
+
+    impl<L, R, LS, RS> BorshDeserialize for BiHashMap<L, R, LS, RS>
+    where
+        L: BorshDeserialize + Eq + Hash,
+        R: BorshDeserialize + Eq + Hash,
+        LS: BuildHasher + Default,
+        RS: BuildHasher + Default,
+    {
+        fn deserialize_reader<Rd: Read>(reader: &mut Rd) -> Result<Self> {
+            deserialize_pairs(
+                reader,
+                |capacity| {
+                    BiHashMap::with_capacity_and_hashers(capacity, LS::default(), RS::default())
+                },
+                |map, left, right| map.insert_no_overwrite(left, right).is_ok(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    fn encode_pairs(pairs: &[(&str, u32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        (pairs.len() as u32).serialize(&mut bytes).unwrap();
+        for (left, right) in pairs {
+            left.serialize(&mut bytes).unwrap();
+            right.serialize(&mut bytes).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn btree_encoding() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(String::from("b"), 2);
+        bimap.insert(String::from("a"), 1);
+        assert_eq!(
+            borsh::to_vec(&bimap).unwrap(),
+            encode_pairs(&[("a", 1), ("b", 2)])
+        );
+    }
+
+    #[test]
+    fn btree_round_trip() {
+        let bimap = (0..100u32)
+            .map(|i| (i, i64::from(i) * -3))
+            .collect::<BiBTreeMap<_, _>>();
+        let bytes = borsh::to_vec(&bimap).unwrap();
+        assert_eq!(
+            borsh::from_slice::<BiBTreeMap<_, _>>(&bytes).unwrap(),
+            bimap
+        );
+
+        let empty = BiBTreeMap::<String, String>::new();
+        let bytes = borsh::to_vec(&empty).unwrap();
+        assert_eq!(bytes, [0, 0, 0, 0]);
+        assert_eq!(
+            borsh::from_slice::<BiBTreeMap<_, _>>(&bytes).unwrap(),
+            empty
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_deterministic() {
+        use crate::BiHashMap;
+
+        let pairs = (0..100u32)
+            .map(|i| (format!("key{}", i), i))
+            .collect::<Vec<_>>();
+        let forward = pairs.iter().cloned().collect::<BiHashMap<_, _>>();
+        let backward = pairs.iter().rev().cloned().collect::<BiHashMap<_, _>>();
+        let btree = pairs.iter().cloned().collect::<BiBTreeMap<_, _>>();
+
+        let bytes = borsh::to_vec(&forward).unwrap();
+        assert_eq!(bytes, borsh::to_vec(&backward).unwrap());
+        assert_eq!(bytes, borsh::to_vec(&btree).unwrap());
+        assert_eq!(
+            borsh::from_slice::<BiHashMap<String, u32>>(&bytes).unwrap(),
+            forward
+        );
+    }
+
+    #[test]
+    fn reject_duplicates() {
+        let duplicate_left = encode_pairs(&[("a", 1), ("a", 2)]);
+        let duplicate_right = encode_pairs(&[("a", 1), ("b", 1)]);
+        let duplicate_pair = encode_pairs(&[("a", 1), ("a", 1)]);
+        for bytes in &[duplicate_left, duplicate_right, duplicate_pair] {
+            let err = borsh::from_slice::<BiBTreeMap<String, u32>>(bytes).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            #[cfg(feature = "std")]
+            {
+                let err = borsh::from_slice::<crate::BiHashMap<String, u32>>(bytes).unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::InvalidData);
+            }
+        }
+    }
+
+    #[test]
+    fn reject_truncated() {
+        let mut bytes = encode_pairs(&[("a", 1), ("b", 2)]);
+        bytes[0] = 3;
+        assert!(borsh::from_slice::<BiBTreeMap<String, u32>>(&bytes).is_err());
+    }
+}
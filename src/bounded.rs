@@ -0,0 +1,657 @@
+//! A bimap that holds at most a fixed number of pairs.
+//!
+//! A [`BoundedBiMap`] is a [`BiIndexMap`] with a maximum length. Once it is
+//! full, inserting a new pair evicts the pair at the front of the bimap, and
+//! [`insert`](BoundedBiMap::insert) hands the evicted pair back alongside any
+//! overwritten ones. Which pair is at the front depends on the
+//! [`EvictionPolicy`]:
+//!
+//! - with [`Fifo`](EvictionPolicy::Fifo), pairs are evicted in the order they
+//!   were inserted;
+//! - with [`Lru`](EvictionPolicy::Lru), a successful `get_by_left` or
+//!   `get_by_right` also moves the pair to the back, so the least recently used
+//!   pair is evicted first.
+//!
+//! Either way, inserting or overwriting a pair moves it to the back. The
+//! `peek_*` and `contains_*` methods never change the order.
+//!
+//! Evicting and promoting a pair shifts the pairs behind it, which takes time
+//! proportional to the number of pairs, so a `BoundedBiMap` is best suited to
+//! small and medium-sized limits.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{BoundedBiMap, EvictionPolicy, Overwritten};
+//!
+//! let mut recent = BoundedBiMap::with_max_len_and_policy(2, EvictionPolicy::Lru);
+//! recent.insert("alice", 1);
+//! recent.insert("bob", 2);
+//!
+//! // looking up alice makes bob the least recently used pair
+//! assert_eq!(recent.get_by_left(&"alice"), Some(&1));
+//! assert_eq!(
+//!     recent.insert("carol", 3),
+//!     (Overwritten::Neither, Some(("bob", 2)))
+//! );
+//! assert_eq!(recent.get_by_right(&2), None);
+//! ```
+
+use crate::{index::Iter, BiIndexMap, Overwritten};
+use std::{borrow::Borrow, fmt, hash::Hash};
+
+/// Determines which pair a [`BoundedBiMap`] evicts when it is full.
+///
+/// See the [module-level documentation] for details.
+///
+/// [module-level documentation]: crate::bounded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EvictionPolicy {
+    /// Evict the pair that was inserted least recently.
+    #[default]
+    Fifo,
+    /// Evict the pair that was inserted or looked up least recently.
+    Lru,
+}
+
+/// An insertion-ordered bimap that evicts pairs to stay within a maximum
+/// length.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::bounded
+pub struct BoundedBiMap<L, R> {
+    inner: BiIndexMap<L, R>,
+    max_len: usize,
+    policy: EvictionPolicy,
+}
+
+impl<L, R> BoundedBiMap<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `BoundedBiMap` that holds at most `max_len` pairs
+    /// and evicts them in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BoundedBiMap, EvictionPolicy};
+    ///
+    /// let bimap: BoundedBiMap<char, i32> = BoundedBiMap::with_max_len(16);
+    /// assert_eq!(bimap.max_len(), 16);
+    /// assert_eq!(bimap.policy(), EvictionPolicy::Fifo);
+    /// ```
+    pub fn with_max_len(max_len: usize) -> Self {
+        Self::with_max_len_and_policy(max_len, EvictionPolicy::Fifo)
+    }
+
+    /// Creates an empty `BoundedBiMap` that holds at most `max_len` pairs
+    /// and evicts them according to the given policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BoundedBiMap, EvictionPolicy};
+    ///
+    /// let bimap: BoundedBiMap<char, i32> =
+    ///     BoundedBiMap::with_max_len_and_policy(16, EvictionPolicy::Lru);
+    /// assert_eq!(bimap.policy(), EvictionPolicy::Lru);
+    /// ```
+    pub fn with_max_len_and_policy(max_len: usize, policy: EvictionPolicy) -> Self {
+        Self {
+            inner: BiIndexMap::new(),
+            max_len,
+            policy,
+        }
+    }
+
+    /// Returns the maximum number of pairs the bimap holds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let bimap: BoundedBiMap<char, i32> = BoundedBiMap::with_max_len(3);
+    /// assert_eq!(bimap.max_len(), 3);
+    /// ```
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Changes the maximum number of pairs the bimap holds.
+    ///
+    /// If the bimap holds more than `max_len` pairs, the excess pairs are
+    /// evicted right away and returned in the order they were evicted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(3);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// assert_eq!(bimap.set_max_len(1), [('a', 1), ('b', 2)]);
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.set_max_len(5), []);
+    /// ```
+    pub fn set_max_len(&mut self, max_len: usize) -> Vec<(L, R)> {
+        self.max_len = max_len;
+        let mut evicted = Vec::new();
+        while self.inner.len() > self.max_len {
+            evicted.extend(self.inner.shift_remove_index(0));
+        }
+        evicted
+    }
+
+    /// Returns the eviction policy of the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BoundedBiMap, EvictionPolicy};
+    ///
+    /// let bimap: BoundedBiMap<char, i32> = BoundedBiMap::with_max_len(3);
+    /// assert_eq!(bimap.policy(), EvictionPolicy::Fifo);
+    /// ```
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// assert!(bimap.is_empty());
+    /// bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Removes all left-right pairs from the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// bimap.clear();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap, starting
+    /// with the pair that would be evicted next.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'b', &2)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        self.inner.iter()
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// With [`EvictionPolicy::Lru`], a found pair becomes the most recently
+    /// used one. Use [`peek_by_left`] to look up a pair without that effect.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    ///
+    /// [`peek_by_left`]: BoundedBiMap::peek_by_left
+    pub fn get_by_left<Q>(&mut self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.inner.get_index_of_left(left)?;
+        let index = self.promote(index);
+        self.inner.get_index(index).map(|(_, r)| r)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// With [`EvictionPolicy::Lru`], a found pair becomes the most recently
+    /// used one. Use [`peek_by_right`] to look up a pair without that effect.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    ///
+    /// [`peek_by_right`]: BoundedBiMap::peek_by_right
+    pub fn get_by_right<Q>(&mut self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let index = self.inner.get_index_of_right(right)?;
+        let index = self.promote(index);
+        self.inner.get_index(index).map(|(l, _)| l)
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value without changing the eviction order.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BoundedBiMap, EvictionPolicy};
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len_and_policy(2, EvictionPolicy::Lru);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.peek_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.insert('c', 3).1, Some(('a', 1)));
+    /// ```
+    pub fn peek_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.get_by_left(left)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value without changing the eviction order.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BoundedBiMap, EvictionPolicy};
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len_and_policy(2, EvictionPolicy::Lru);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.peek_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.insert('c', 3).1, Some(('a', 1)));
+    /// ```
+    pub fn peek_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.get_by_right(right)
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise. The eviction order is not changed.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.contains_left(left)
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise. The eviction order is not changed.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.contains_right(right)
+    }
+
+    /// Removes the left-right pair corresponding to the given left value.
+    ///
+    /// Returns the previous left-right pair if the map contained the left
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.remove_by_left(&'a'), Some(('a', 1)));
+    /// assert_eq!(bimap.remove_by_left(&'a'), None);
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.shift_remove_by_left(left)
+    }
+
+    /// Removes the left-right pair corresponding to the given right value.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BoundedBiMap;
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.remove_by_right(&1), Some(('a', 1)));
+    /// assert_eq!(bimap.remove_by_right(&1), None);
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.shift_remove_by_right(right)
+    }
+
+    /// Inserts the given left-right pair at the back of the bimap.
+    ///
+    /// Returns the pairs that were overwritten, as [`BiIndexMap::insert`]
+    /// does, along with the pair that was evicted to make room, if any. A
+    /// pair is only evicted if the insertion grew the bimap beyond its
+    /// maximum length, so an insertion that overwrites a pair never evicts
+    /// another one. With a maximum length of zero, the inserted pair itself
+    /// is evicted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BoundedBiMap, Overwritten};
+    ///
+    /// let mut bimap = BoundedBiMap::with_max_len(2);
+    /// assert_eq!(bimap.insert('a', 1), (Overwritten::Neither, None));
+    /// assert_eq!(bimap.insert('b', 2), (Overwritten::Neither, None));
+    ///
+    /// // the bimap is full, so the oldest pair is evicted
+    /// assert_eq!(bimap.insert('c', 3), (Overwritten::Neither, Some(('a', 1))));
+    ///
+    /// // overwriting a pair makes no room, but doesn't need any either
+    /// assert_eq!(bimap.insert('b', 4), (Overwritten::Left('b', 2), None));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> (Overwritten<L, R>, Option<(L, R)>) {
+        let overwritten = self.inner.insert(left, right);
+        let evicted = if self.inner.len() > self.max_len {
+            self.inner.shift_remove_index(0)
+        } else {
+            None
+        };
+        (overwritten, evicted)
+    }
+
+    /// Moves the pair at `index` to the back if the policy is LRU, returning
+    /// its new index.
+    fn promote(&mut self, index: usize) -> usize {
+        match self.policy {
+            EvictionPolicy::Fifo => index,
+            EvictionPolicy::Lru => {
+                let last = self.inner.len() - 1;
+                if index != last {
+                    self.inner.move_index(index, last);
+                }
+                last
+            }
+        }
+    }
+}
+
+impl<L, R> Clone for BoundedBiMap<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    fn clone(&self) -> BoundedBiMap<L, R> {
+        BoundedBiMap {
+            inner: self.inner.clone(),
+            max_len: self.max_len,
+            policy: self.policy,
+        }
+    }
+}
+
+impl<L, R> fmt::Debug for BoundedBiMap<L, R>
+where
+    L: fmt::Debug + Eq + Hash,
+    R: fmt::Debug + Eq + Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a BoundedBiMap<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lefts(bimap: &BoundedBiMap<char, i32>) -> Vec<char> {
+        bimap.iter().map(|(l, _)| *l).collect()
+    }
+
+    #[test]
+    fn fifo_eviction_order() {
+        let mut bimap = BoundedBiMap::with_max_len(3);
+        for (l, r) in [('a', 1), ('b', 2), ('c', 3)] {
+            assert_eq!(bimap.insert(l, r), (Overwritten::Neither, None));
+        }
+        // lookups don't affect the order
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+
+        assert_eq!(bimap.insert('d', 4).1, Some(('a', 1)));
+        assert_eq!(bimap.insert('e', 5).1, Some(('b', 2)));
+        assert_eq!(bimap.insert('f', 6).1, Some(('c', 3)));
+        assert_eq!(lefts(&bimap), ['d', 'e', 'f']);
+    }
+
+    #[test]
+    fn lru_eviction_order() {
+        let mut bimap = BoundedBiMap::with_max_len_and_policy(3, EvictionPolicy::Lru);
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(lefts(&bimap), ['b', 'c', 'a']);
+        assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+        assert_eq!(lefts(&bimap), ['c', 'a', 'b']);
+
+        // misses and peeks don't promote anything
+        assert_eq!(bimap.get_by_left(&'z'), None);
+        assert_eq!(bimap.peek_by_left(&'c'), Some(&3));
+        assert_eq!(bimap.peek_by_right(&3), Some(&'c'));
+        assert!(bimap.contains_left(&'c'));
+
+        assert_eq!(bimap.insert('d', 4).1, Some(('c', 3)));
+        assert_eq!(bimap.insert('e', 5).1, Some(('a', 1)));
+        assert_eq!(lefts(&bimap), ['b', 'd', 'e']);
+    }
+
+    #[test]
+    fn overwrite_moves_to_back_without_evicting() {
+        for policy in [EvictionPolicy::Fifo, EvictionPolicy::Lru] {
+            let mut bimap = BoundedBiMap::with_max_len_and_policy(2, policy);
+            bimap.insert('a', 1);
+            bimap.insert('b', 2);
+            assert_eq!(bimap.insert('a', 3), (Overwritten::Left('a', 1), None));
+            assert_eq!(lefts(&bimap), ['b', 'a']);
+            assert_eq!(bimap.insert('c', 4).1, Some(('b', 2)));
+        }
+    }
+
+    #[test]
+    fn evicted_pairs_are_gone_in_both_directions() {
+        for policy in [EvictionPolicy::Fifo, EvictionPolicy::Lru] {
+            let mut bimap = BoundedBiMap::with_max_len_and_policy(4, policy);
+            for i in 0..100u8 {
+                let (_, evicted) = bimap.insert(char::from(b'!' + i), i32::from(i));
+                if let Some((l, r)) = evicted {
+                    assert!(!bimap.contains_left(&l));
+                    assert!(!bimap.contains_right(&r));
+                    assert_eq!(bimap.get_by_right(&r), None);
+                    assert_eq!(bimap.peek_by_right(&r), None);
+                }
+                assert!(bimap.len() <= 4);
+            }
+            for r in 0..96 {
+                assert_eq!(bimap.get_by_right(&r), None);
+            }
+            for r in 96..100 {
+                assert!(bimap.get_by_right(&r).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn set_max_len() {
+        let mut bimap = BoundedBiMap::with_max_len_and_policy(4, EvictionPolicy::Lru);
+        for (l, r) in [('a', 1), ('b', 2), ('c', 3), ('d', 4)] {
+            bimap.insert(l, r);
+        }
+        bimap.get_by_left(&'a');
+
+        assert_eq!(bimap.set_max_len(2), [('b', 2), ('c', 3)]);
+        assert_eq!(bimap.max_len(), 2);
+        assert_eq!(lefts(&bimap), ['d', 'a']);
+        assert_eq!(bimap.get_by_right(&2), None);
+        assert_eq!(bimap.get_by_right(&3), None);
+
+        assert_eq!(bimap.set_max_len(3), []);
+        assert_eq!(bimap.insert('e', 5).1, None);
+        assert_eq!(bimap.insert('f', 6).1, Some(('d', 4)));
+    }
+
+    #[test]
+    fn zero_max_len() {
+        let mut bimap = BoundedBiMap::with_max_len(0);
+        assert_eq!(bimap.insert('a', 1), (Overwritten::Neither, Some(('a', 1))));
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn remove_and_debug() {
+        let mut bimap = BoundedBiMap::with_max_len(3);
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+        assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
+        assert_eq!(bimap.remove_by_right(&2), None);
+        assert_eq!(format!("{:?}", bimap), "{'a' <> 1, 'c' <> 3}");
+
+        bimap.insert('d', 4);
+        assert_eq!(bimap.insert('e', 5).1, Some(('a', 1)));
+    }
+}
@@ -2,11 +2,14 @@
 
 use crate::{
     mem::{Ref, Wrapper},
-    Overwritten,
+    stats::Counters,
+    BatchError, BatchOp, BiMapLike, InvariantViolation, KeyNotFound, ModifyError, OccupiedError,
+    OverwriteReport, Overwritten, Transaction,
 };
 use alloc::{
-    collections::{btree_map, BTreeMap},
+    collections::{btree_map, BTreeMap, BTreeSet},
     rc::Rc,
+    vec::Vec,
 };
 use core::{
     borrow::Borrow,
@@ -14,7 +17,7 @@ use core::{
     fmt,
     hash::{Hash, Hasher},
     iter::{Extend, FromIterator, FusedIterator},
-    ops::RangeBounds,
+    ops::{Deref, RangeBounds},
 };
 
 /// A bimap backed by two `BTreeMap`s.
@@ -25,6 +28,8 @@ use core::{
 pub struct BiBTreeMap<L, R> {
     left2right: BTreeMap<Ref<L>, Ref<R>>,
     right2left: BTreeMap<Ref<R>, Ref<L>>,
+    generation: u64,
+    stats: Counters,
 }
 
 impl<L, R> BiBTreeMap<L, R>
@@ -41,10 +46,25 @@ where
     ///
     /// let bimap = BiBTreeMap::<char, i32>::new();
     /// ```
-    pub fn new() -> Self {
+    ///
+    /// Like `BTreeMap::new`, this is a `const fn`, so a `BiBTreeMap` can be
+    /// placed in a `static` without lazy initialization:
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use bimap::BiBTreeMap;
+    ///
+    /// static TABLE: Mutex<BiBTreeMap<u32, &str>> = Mutex::new(BiBTreeMap::new());
+    ///
+    /// TABLE.lock().unwrap().insert(1, "one");
+    /// assert_eq!(TABLE.lock().unwrap().get_by_left(&1), Some(&"one"));
+    /// ```
+    pub const fn new() -> Self {
         Self {
             left2right: BTreeMap::new(),
             right2left: BTreeMap::new(),
+            generation: 0,
+            stats: Counters::new(),
         }
     }
 
@@ -84,8 +104,47 @@ where
         self.left2right.is_empty()
     }
 
+    /// Returns the bimap's generation, a counter that changes whenever the
+    /// left-right pairs may have changed.
+    ///
+    /// Every method that inserts, removes or modifies pairs, including
+    /// `clear` and `retain`, advances the generation, while lookups and
+    /// capacity changes leave it unchanged. Comparing generations is a cheap
+    /// way to tell whether a cache derived from the bimap is stale. The
+    /// counter starts at zero and only increases, but its exact value is not
+    /// specified; clones and deserialized bimaps start their own count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// let generation = bimap.generation();
+    ///
+    /// // lookups don't change the generation
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.generation(), generation);
+    ///
+    /// // mutations do
+    /// bimap.remove_by_left(&'a');
+    /// assert!(bimap.generation() > generation);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Advances the generation after a mutation.
+    fn bump_generation(&mut self) {
+        self.generation += 1;
+    }
+
     /// Removes all left-right pairs from the bimap.
     ///
+    /// Unlike `BiHashMap::clear`, this frees the memory of both sides, since a
+    /// `BTreeMap` has no spare capacity to keep.
+    ///
     /// # Examples
     ///
     /// ```
@@ -101,6 +160,41 @@ where
     pub fn clear(&mut self) {
         self.left2right.clear();
         self.right2left.clear();
+        self.bump_generation();
+    }
+
+    /// Removes all left-right pairs from the bimap and returns them in
+    /// ascending order by left value.
+    ///
+    /// The returned vector is allocated up front with room for exactly the
+    /// pairs of the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    /// assert_eq!(bimap.take(), [('a', 2), ('b', 1)]);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn take(&mut self) -> Vec<(L, R)> {
+        let mut pairs = Vec::with_capacity(self.len());
+        // drop the reverse references first, so that the values are uniquely
+        // owned
+        self.right2left.clear();
+        let left2right = core::mem::take(&mut self.left2right);
+        // unwraps are safe because right2left has been cleared
+        pairs.extend(left2right.into_iter().map(|(l, r)| {
+            (
+                Rc::try_unwrap(l.0).ok().unwrap(),
+                Rc::try_unwrap(r.0).ok().unwrap(),
+            )
+        }));
+        self.bump_generation();
+        pairs
     }
 
     /// Creates an iterator over the left-right pairs in the bimap in ascending
@@ -121,6 +215,11 @@ where
     /// for (left, right) in bimap.iter() {
     ///     println!("({}, {})", left, right);
     /// }
+    ///
+    /// // the iterator is double-ended, so pairs can be visited in descending
+    /// // order by left value too
+    /// let descending = bimap.iter().rev().collect::<Vec<_>>();
+    /// assert_eq!(descending, vec![(&'c', &3), (&'b', &2), (&'a', &1)]);
     /// ```
     pub fn iter(&self) -> Iter<'_, L, R> {
         Iter {
@@ -178,6 +277,34 @@ where
         }
     }
 
+    /// Folds every left-right pair in the bimap into an accumulator by
+    /// applying an operation, returning the final result.
+    ///
+    /// Pairs are visited in ascending order by left value. This is equivalent
+    /// to `iter().fold()` but passes the left and right values as separate
+    /// arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// let sum = bimap.fold_pairs(0, |acc, _left, right| acc + right);
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn fold_pairs<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &L, &R) -> B,
+    {
+        let mut f = f;
+        self.iter().fold(init, |acc, (l, r)| f(acc, l, r))
+    }
+
     /// Returns a reference to the right value corresponding to the given left
     /// value.
     ///
@@ -200,7 +327,9 @@ where
         L: Borrow<Q>,
         Q: Ord + ?Sized,
     {
-        self.left2right.get(Wrapper::wrap(left)).map(|l| &*l.0)
+        let right = self.left2right.get(Wrapper::wrap(left)).map(|l| &*l.0);
+        self.stats.record_left(right.is_some());
+        right
     }
 
     /// Returns a reference to the left value corresponding to the given right
@@ -225,7 +354,182 @@ where
         R: Borrow<Q>,
         Q: Ord + ?Sized,
     {
-        self.right2left.get(Wrapper::wrap(right)).map(|r| &*r.0)
+        let left = self.right2left.get(Wrapper::wrap(right)).map(|r| &*r.0);
+        self.stats.record_right(left.is_some());
+        left
+    }
+
+    /// Returns a clone of the right value corresponding to the given left
+    /// value, converted into `T`.
+    ///
+    /// This is a shorthand for `get_by_left(..).cloned().map(T::from)`.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1u8);
+    /// let right: Option<u32> = bimap.get_by_left_as(&'a');
+    /// assert_eq!(right, Some(1));
+    /// assert_eq!(bimap.get_by_left_as::<u32, _>(&'z'), None);
+    /// ```
+    pub fn get_by_left_as<T, Q>(&self, left: &Q) -> Option<T>
+    where
+        L: Borrow<Q>,
+        R: Clone,
+        T: From<R>,
+        Q: Ord + ?Sized,
+    {
+        self.get_by_left(left).cloned().map(T::from)
+    }
+
+    /// Returns a clone of the left value corresponding to the given right
+    /// value, converted into `T`.
+    ///
+    /// This is a shorthand for `get_by_right(..).cloned().map(T::from)`.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but the
+    /// ordering on the borrowed form *must* match the ordering on the right
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert(b'a', 1);
+    /// let left: Option<u32> = bimap.get_by_right_as(&1);
+    /// assert_eq!(left, Some(97));
+    /// assert_eq!(bimap.get_by_right_as::<u32, _>(&2), None);
+    /// ```
+    pub fn get_by_right_as<T, Q>(&self, right: &Q) -> Option<T>
+    where
+        R: Borrow<Q>,
+        L: Clone,
+        T: From<L>,
+        Q: Ord + ?Sized,
+    {
+        self.get_by_right(right).cloned().map(T::from)
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value, or a [`KeyNotFound`] error if the left value is not in the
+    /// bimap.
+    ///
+    /// This is the same as [`get_by_left`](Self::get_by_left), but works with
+    /// the `?` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, KeyNotFound};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.try_get_by_left(&'a'), Ok(&1));
+    /// assert_eq!(bimap.try_get_by_left(&'z'), Err(KeyNotFound));
+    /// ```
+    pub fn try_get_by_left<Q>(&self, left: &Q) -> Result<&R, KeyNotFound>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_by_left(left).ok_or(KeyNotFound)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value, or a [`KeyNotFound`] error if the right value is not in the
+    /// bimap.
+    ///
+    /// This is the same as [`get_by_right`](Self::get_by_right), but works
+    /// with the `?` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, KeyNotFound};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.try_get_by_right(&1), Ok(&'a'));
+    /// assert_eq!(bimap.try_get_by_right(&2), Err(KeyNotFound));
+    /// ```
+    pub fn try_get_by_right<Q>(&self, right: &Q) -> Result<&L, KeyNotFound>
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_by_right(right).ok_or(KeyNotFound)
+    }
+
+    /// Returns the left-right pair with the smallest right value, or `None` if
+    /// the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.first_by_right(), None);
+    /// bimap.insert('a', 3);
+    /// bimap.insert('b', 1);
+    /// bimap.insert('c', 2);
+    /// assert_eq!(bimap.first_by_right(), Some((&'b', &1)));
+    /// ```
+    pub fn first_by_right(&self) -> Option<(&L, &R)> {
+        self.right2left.iter().next().map(|(r, l)| (&*l.0, &*r.0))
+    }
+
+    /// Returns the left-right pair with the largest right value, or `None` if
+    /// the bimap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.last_by_right(), None);
+    /// bimap.insert('a', 3);
+    /// bimap.insert('b', 1);
+    /// bimap.insert('c', 2);
+    /// assert_eq!(bimap.last_by_right(), Some((&'a', &3)));
+    /// ```
+    pub fn last_by_right(&self) -> Option<(&L, &R)> {
+        self.right2left
+            .iter()
+            .next_back()
+            .map(|(r, l)| (&*l.0, &*r.0))
+    }
+
+    /// Returns the left-right pair with the smallest left value without
+    /// removing it, or `None` if the bimap is empty.
+    ///
+    /// This is the pair that [`pop`] would remove.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.peek(), None);
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    /// assert_eq!(bimap.peek(), Some((&'a', &2)));
+    /// ```
+    ///
+    /// [`pop`]: BiBTreeMap::pop
+    pub fn peek(&self) -> Option<(&L, &R)> {
+        self.iter().next()
     }
 
     /// Returns `true` if the bimap contains the given left value and `false`
@@ -250,7 +554,9 @@ where
         L: Borrow<Q>,
         Q: Ord + ?Sized,
     {
-        self.left2right.contains_key(Wrapper::wrap(left))
+        let found = self.left2right.contains_key(Wrapper::wrap(left));
+        self.stats.record_left(found);
+        found
     }
 
     /// Returns `true` if the map contains the given right value and `false`
@@ -275,7 +581,69 @@ where
         R: Borrow<Q>,
         Q: Ord + ?Sized,
     {
-        self.right2left.contains_key(Wrapper::wrap(right))
+        let found = self.right2left.contains_key(Wrapper::wrap(right));
+        self.stats.record_right(found);
+        found
+    }
+
+    /// Returns the number of lookups performed on the bimap so far, and how
+    /// many of them found a pair.
+    ///
+    /// Calls to [`get_by_left`], [`get_by_right`], [`contains_left`] and
+    /// [`contains_right`] are counted, including calls made by other methods
+    /// such as `try_get_by_left` and `insert_no_overwrite`. A cloned bimap
+    /// starts with fresh counters.
+    ///
+    /// This method is only available with the `stats` feature. Without it,
+    /// lookups are not counted and the bimap carries no counters.
+    ///
+    /// [`get_by_left`]: BiBTreeMap::get_by_left
+    /// [`get_by_right`]: BiBTreeMap::get_by_right
+    /// [`contains_left`]: BiBTreeMap::contains_left
+    /// [`contains_right`]: BiBTreeMap::contains_right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// bimap.get_by_left(&'a');
+    /// bimap.get_by_left(&'b');
+    /// bimap.contains_right(&1);
+    ///
+    /// let stats = bimap.lookup_stats();
+    /// assert_eq!(stats.by_left, 2);
+    /// assert_eq!(stats.by_right, 1);
+    /// assert_eq!(stats.hits, 2);
+    /// assert_eq!(stats.misses, 1);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn lookup_stats(&self) -> crate::LookupStats {
+        self.stats.snapshot()
+    }
+
+    /// Resets all lookup counters of the bimap to zero.
+    ///
+    /// This method is only available with the `stats` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, LookupStats};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.get_by_left(&'a');
+    ///
+    /// bimap.reset_lookup_stats();
+    /// assert_eq!(bimap.lookup_stats(), LookupStats::default());
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn reset_lookup_stats(&self) {
+        self.stats.reset();
     }
 
     /// Removes the left-right pair corresponding to the given left value.
@@ -306,6 +674,7 @@ where
         Q: Ord + ?Sized,
     {
         self.left2right.remove(Wrapper::wrap(left)).map(|right_rc| {
+            self.bump_generation();
             // unwrap is safe because we know right2left contains the key (it's a bimap)
             let left_rc = self.right2left.remove(&right_rc).unwrap();
             // at this point we can safely unwrap because the other pointers are gone
@@ -344,6 +713,7 @@ where
         Q: Ord + ?Sized,
     {
         self.right2left.remove(Wrapper::wrap(right)).map(|left_rc| {
+            self.bump_generation();
             // unwrap is safe because we know left2right contains the key (it's a bimap)
             let right_rc = self.left2right.remove(&left_rc).unwrap();
             // at this point we can safely unwrap because the other pointers are gone
@@ -354,12 +724,20 @@ where
         })
     }
 
-    /// Retains only elements specified by a predicate
+    /// Takes the pair of the given left value out of the bimap and returns a
+    /// [`Lease`] on it, or `None` if the bimap doesn't contain the left value.
     ///
-    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
-    /// &r)` returns `false`.
+    /// The lease dereferences to the right value. Dropping it removes the
+    /// pair for good, while [`Lease::keep`] puts the pair back. This supports
+    /// a "take it out, and put it back only if committed" pattern. The
+    /// bimap stays mutably borrowed while the lease exists, so no other
+    /// changes can happen in between.
     ///
-    /// # Example
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use bimap::BiBTreeMap;
@@ -367,103 +745,1269 @@ where
     /// let mut bimap = BiBTreeMap::new();
     /// bimap.insert('a', 1);
     /// bimap.insert('b', 2);
-    /// bimap.insert('c', 3);
     ///
-    /// bimap.retain(|&l, _| l != 'b');
-    /// assert_eq!(bimap.len(), 2);
-    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
-    /// assert_eq!(bimap.get_by_right(&2), None);
-    /// assert_eq!(bimap.get_by_right(&3), Some(&'c'));
+    /// // committed: the pair goes back into the bimap
+    /// let lease = bimap.lease_by_left(&'a').unwrap();
+    /// assert_eq!(*lease, 1);
+    /// lease.keep();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    ///
+    /// // not committed: the pair is removed
+    /// let lease = bimap.lease_by_left(&'b').unwrap();
+    /// assert_eq!(*lease, 2);
+    /// drop(lease);
+    /// assert!(!bimap.contains_left(&'b'));
+    /// assert!(!bimap.contains_right(&2));
     /// ```
-    pub fn retain<F>(&mut self, f: F)
+    pub fn lease_by_left<Q>(&mut self, left: &Q) -> Option<Lease<'_, L, R>>
     where
-        F: FnMut(&L, &R) -> bool,
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
     {
-        let mut f = f;
-        let right2left = &mut self.right2left;
-        self.left2right.retain(|l, r| {
-            let to_retain = f(&l.0, &r.0);
-            if !to_retain {
-                right2left.remove(r);
-            }
-            to_retain
+        let (left, right) = self.remove_by_left(left)?;
+        Some(Lease {
+            bimap: self,
+            left,
+            right,
         })
     }
 
-    /// Inserts the given left-right pair into the bimap.
+    /// Removes and returns the left-right pair with the smallest right value,
+    /// or `None` if the bimap is empty.
     ///
-    /// Returns an enum `Overwritten` representing any left-right pairs that
-    /// were overwritten by the call to `insert`. The example below details
-    /// all possible enum variants that can be returned.
+    /// # Examples
     ///
-    /// # Warnings
+    /// ```
+    /// use bimap::BiBTreeMap;
     ///
-    /// Somewhat paradoxically, calling `insert()` can actually reduce the size
-    /// of the bimap! This is because of the invariant that each left value
-    /// maps to exactly one right value and vice versa.
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 3);
+    /// bimap.insert('b', 1);
+    /// bimap.insert('c', 2);
+    ///
+    /// assert_eq!(bimap.pop_first_by_right(), Some(('b', 1)));
+    /// assert_eq!(bimap.pop_first_by_right(), Some(('c', 2)));
+    /// assert_eq!(bimap.pop_first_by_right(), Some(('a', 3)));
+    /// assert_eq!(bimap.pop_first_by_right(), None);
+    /// ```
+    pub fn pop_first_by_right(&mut self) -> Option<(L, R)> {
+        let right_rc = self.right2left.keys().next()?.clone();
+        Some(self.remove_by_right_rc(right_rc))
+    }
+
+    /// Removes and returns the left-right pair with the largest right value,
+    /// or `None` if the bimap is empty.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bimap::{BiBTreeMap, Overwritten};
+    /// use bimap::BiBTreeMap;
     ///
     /// let mut bimap = BiBTreeMap::new();
-    /// assert_eq!(bimap.len(), 0); // {}
+    /// bimap.insert('a', 3);
+    /// bimap.insert('b', 1);
+    /// bimap.insert('c', 2);
     ///
-    /// // no values are overwritten.
-    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
-    /// assert_eq!(bimap.len(), 1); // {'a' <> 1}
+    /// assert_eq!(bimap.pop_last_by_right(), Some(('a', 3)));
+    /// assert_eq!(bimap.pop_last_by_right(), Some(('c', 2)));
+    /// assert_eq!(bimap.pop_last_by_right(), Some(('b', 1)));
+    /// assert_eq!(bimap.pop_last_by_right(), None);
+    /// ```
+    pub fn pop_last_by_right(&mut self) -> Option<(L, R)> {
+        let right_rc = self.right2left.keys().next_back()?.clone();
+        Some(self.remove_by_right_rc(right_rc))
+    }
+
+    /// Removes and returns the left-right pair with the smallest left value,
+    /// or `None` if the bimap is empty.
     ///
-    /// // no values are overwritten.
-    /// assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
-    /// assert_eq!(bimap.len(), 2); // {'a' <> 1, 'b' <> 2}
+    /// The removed pair is the one returned by [`peek`].
     ///
-    /// // ('a', 1) already exists, so inserting ('a', 4) overwrites 'a', the left value.
-    /// // the previous left-right pair ('a', 1) is returned.
-    /// assert_eq!(bimap.insert('a', 4), Overwritten::Left('a', 1));
-    /// assert_eq!(bimap.len(), 2); // {'a' <> 4, 'b' <> 2}
+    /// # Examples
     ///
-    /// // ('b', 2) already exists, so inserting ('c', 2) overwrites 2, the right value.
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    ///
+    /// assert_eq!(bimap.pop(), Some(('a', 2)));
+    /// assert_eq!(bimap.pop(), Some(('b', 1)));
+    /// assert_eq!(bimap.pop(), None);
+    /// ```
+    ///
+    /// [`peek`]: BiBTreeMap::peek
+    pub fn pop(&mut self) -> Option<(L, R)> {
+        let right_rc = self.left2right.values().next()?.clone();
+        Some(self.remove_by_right_rc(right_rc))
+    }
+
+    /// Removes the pair of a right value that is known to be in the bimap,
+    /// given an extra pointer to it.
+    fn remove_by_right_rc(&mut self, right_rc: Ref<R>) -> (L, R) {
+        self.bump_generation();
+        // unwrap is safe because the right value came from right2left
+        let left_rc = self.right2left.remove(&right_rc).unwrap();
+        drop(right_rc);
+        // unwrap is safe because we know left2right contains the key (it's a bimap)
+        let right_rc = self.left2right.remove(&left_rc).unwrap();
+        // at this point we can safely unwrap because the other pointers are gone
+        (
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        )
+    }
+
+    /// Retains only elements specified by a predicate
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &r)` returns `false`.
+    ///
+    /// The pairs are visited exactly once each, in ascending order of their
+    /// left values, so a stateful predicate behaves deterministically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// bimap.retain(|&l, _| l != 'b');
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// assert_eq!(bimap.get_by_right(&3), Some(&'c'));
+    ///
+    /// // keep the pair with the smallest left value
+    /// let mut kept = 0;
+    /// bimap.retain(|_, _| {
+    ///     kept += 1;
+    ///     kept <= 1
+    /// });
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        self.bump_generation();
+        let mut f = f;
+        let right2left = &mut self.right2left;
+        self.left2right.retain(|l, r| {
+            let to_retain = f(&l.0, &r.0);
+            if !to_retain {
+                right2left.remove(r);
+            }
+            to_retain
+        })
+    }
+
+    /// Retains only the left-right pairs that are also in `other`.
+    ///
+    /// A pair is kept if `other` contains the same left value paired with the
+    /// same right value, so this turns the bimap into the intersection of
+    /// both bimaps without allocating a new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut live: BiBTreeMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// let snapshot: BiBTreeMap<_, _> = vec![('a', 1), ('b', 5), ('d', 4)].into_iter().collect();
+    ///
+    /// live.retain_intersection(&snapshot);
+    /// assert_eq!(live.len(), 1);
+    /// assert_eq!(live.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn retain_intersection(&mut self, other: &Self) {
+        self.retain(|l, r| other.get_by_left(l) == Some(r));
+    }
+
+    /// Removes all left-right pairs that are also in `other`.
+    ///
+    /// A pair is removed if `other` contains the same left value paired with
+    /// the same right value, so this turns the bimap into the difference of
+    /// both bimaps without allocating a new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut live: BiBTreeMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// let stale: BiBTreeMap<_, _> = vec![('a', 1), ('b', 5), ('d', 4)].into_iter().collect();
+    ///
+    /// live.remove_all(&stale);
+    /// assert_eq!(live.len(), 2);
+    /// assert_eq!(live.get_by_left(&'a'), None);
+    /// assert_eq!(live.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn remove_all(&mut self, other: &Self) {
+        self.retain(|l, r| other.get_by_left(l) != Some(r));
+    }
+
+    /// Retains only the elements specified by the predicate and returns the
+    /// number of left-right pairs that were removed.
+    ///
+    /// This is the same as [`retain`], which returns `()` like the method of
+    /// the same name on the standard maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<char, i32> = ('a'..='e').zip(1..).collect();
+    /// assert_eq!(bimap.retain_count(|_, &r| r % 2 == 1), 2);
+    /// assert_eq!(bimap.len(), 3);
+    /// assert_eq!(bimap.retain_count(|_, _| true), 0);
+    /// ```
+    ///
+    /// [`retain`]: BiBTreeMap::retain
+    pub fn retain_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        let len = self.len();
+        self.retain(f);
+        len - self.len()
+    }
+
+    /// Removes the pairs for which the predicate returns `true` and returns
+    /// them in ascending order of their left values.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn extract_where<F>(&mut self, mut f: F) -> Vec<(L, R)>
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        self.bump_generation();
+        let mut removed = Vec::new();
+        let right2left = &mut self.right2left;
+        self.left2right.retain(|l, r| {
+            let to_remove = f(&l.0, &r.0);
+            if to_remove {
+                right2left.remove(r);
+                removed.push((l.clone(), r.clone()));
+            }
+            !to_remove
+        });
+        // unwrap is safe because the bimap no longer holds the removed pairs
+        removed
+            .into_iter()
+            .map(|(l, r)| {
+                (
+                    Rc::try_unwrap(l.0).ok().unwrap(),
+                    Rc::try_unwrap(r.0).ok().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    /// Retains only the elements specified by the predicate, allowing the
+    /// predicate to modify the right values of the retained pairs.
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &mut r)` returns `false`. Like [`retain`], the pairs are visited in
+    /// ascending order of their left values. Right values are re-indexed
+    /// after every pair has been visited.
+    ///
+    /// If modifying the right values causes two retained pairs to share a
+    /// right value, only the pair with the smallest left value is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.retain_mut_right(|&l, r| {
+    ///     *r = 4 - *r;
+    ///     l != 'b'
+    /// });
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_right(&3), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'c'));
+    /// assert_eq!(bimap.right_values().collect::<Vec<_>>(), vec![&1, &3]);
+    /// ```
+    ///
+    /// [`retain`]: BiBTreeMap::retain
+    pub fn retain_mut_right<F>(&mut self, f: F)
+    where
+        F: FnMut(&L, &mut R) -> bool,
+    {
+        self.bump_generation();
+        let mut f = f;
+        // drop the reverse references so that the right values are uniquely
+        // owned, then rebuild the reverse map once the sweep has finished (or
+        // panicked)
+        self.right2left.clear();
+        let guard = ReindexGuard { bimap: self };
+        guard.bimap.left2right.retain(|l, r| {
+            // unwrap is safe because right2left has been cleared
+            let right = Rc::get_mut(&mut r.0).unwrap();
+            f(&l.0, right)
+        });
+    }
+
+    /// Replaces the right values of the pairs lying within a range of left
+    /// values in place, visiting the pairs in ascending order by left.
+    ///
+    /// The closure `f` is called once for each pair in the range with its
+    /// left value and a mutable reference to its right value. Each modified
+    /// right value is then moved to its new position in the bimap's
+    /// right-ordered tree, while pairs outside the range are left untouched.
+    ///
+    /// The range bounds may be any borrowed form of the bimap's left type, but
+    /// the ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if two pairs end up sharing a right value. In
+    /// release builds, only the pair with the smallest left value is kept, as
+    /// with [`retain_mut_right`]. If `f` panics, the right-ordered tree is
+    /// rebuilt in the same way before the panic propagates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// bimap.range_replace_right('b'..='c', |_, r| *r *= 10);
+    /// let pairs: Vec<_> = bimap.right_range(..).collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'d', &4), (&'b', &20), (&'c', &30)]);
+    /// ```
+    ///
+    /// [`retain_mut_right`]: BiBTreeMap::retain_mut_right
+    pub fn range_replace_right<T, A, F>(&mut self, range: A, f: F)
+    where
+        L: Borrow<T>,
+        A: RangeBounds<T>,
+        T: Ord + ?Sized,
+        F: FnMut(&L, &mut R),
+    {
+        self.bump_generation();
+        let mut f = f;
+        let start = Wrapper::wrap_bound(range.start_bound());
+        let end = Wrapper::wrap_bound(range.end_bound());
+        // drop the reverse references of the pairs in the range so that their
+        // right values are uniquely owned
+        for (_, r) in self.left2right.range::<Wrapper<_>, _>((start, end)) {
+            self.right2left.remove(r);
+        }
+        // the guard rebuilds the reverse map if f panics or the new right
+        // values collide, and is forgotten otherwise
+        let guard = ReindexGuard { bimap: self };
+        let mut collided = false;
+        for (l, r) in guard
+            .bimap
+            .left2right
+            .range_mut::<Wrapper<_>, _>((start, end))
+        {
+            // unwrap is safe because the reverse references have been removed
+            f(&l.0, Rc::get_mut(&mut r.0).unwrap());
+            collided |= guard
+                .bimap
+                .right2left
+                .insert(r.clone(), l.clone())
+                .is_some();
+        }
+        debug_assert!(!collided, "replaced right values collide");
+        if !collided {
+            core::mem::forget(guard);
+        }
+    }
+
+    /// Consumes the bimap and returns its left values as a `BTreeSet`,
+    /// dropping the right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    /// let lefts: BTreeSet<char> = bimap.into_left_btreeset();
+    /// assert_eq!(lefts.into_iter().collect::<Vec<_>>(), vec!['a', 'b']);
+    /// ```
+    pub fn into_left_btreeset(self) -> BTreeSet<L> {
+        let BiBTreeMap {
+            left2right,
+            right2left,
+            ..
+        } = self;
+        // drop the right values and the reverse references first, so that
+        // the left values are uniquely owned
+        drop(right2left);
+        // unwrap is safe because right2left has been dropped
+        left2right
+            .into_keys()
+            .map(|l| Rc::try_unwrap(l.0).ok().unwrap())
+            .collect()
+    }
+
+    /// Consumes the bimap and returns its right values as a `BTreeSet`,
+    /// dropping the left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// let rights: BTreeSet<i32> = bimap.into_right_btreeset();
+    /// assert_eq!(rights.into_iter().collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    pub fn into_right_btreeset(self) -> BTreeSet<R> {
+        let BiBTreeMap {
+            left2right,
+            right2left,
+            ..
+        } = self;
+        // drop the left values and the forward references first, so that the
+        // right values are uniquely owned
+        drop(left2right);
+        // unwrap is safe because left2right has been dropped
+        right2left
+            .into_keys()
+            .map(|r| Rc::try_unwrap(r.0).ok().unwrap())
+            .collect()
+    }
+
+    /// Returns a `BTreeMap` from left values to right values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    /// let map: BTreeMap<char, i32> = bimap.to_btree_map();
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [('a', 2), ('b', 1)]);
+    /// ```
+    pub fn to_btree_map(&self) -> BTreeMap<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        // the pairs are already sorted, so the map is built bottom-up
+        self.iter().map(|(l, r)| (l.clone(), r.clone())).collect()
+    }
+
+    /// Returns a `BTreeMap` from right values to left values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// let map: BTreeMap<i32, char> = bimap.to_btree_map_by_right();
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 'b'), (2, 'a')]);
+    /// ```
+    pub fn to_btree_map_by_right(&self) -> BTreeMap<R, L>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        self.right2left
+            .iter()
+            .map(|(r, l)| ((*r.0).clone(), (*l.0).clone()))
+            .collect()
+    }
+
+    /// Returns a `HashMap` from left values to right values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let map: HashMap<char, i32> = bimap.to_hash_map();
+    /// assert_eq!(map[&'a'], 1);
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_hash_map(&self) -> std::collections::HashMap<L, R>
+    where
+        L: Clone + Hash,
+        R: Clone,
+    {
+        self.iter().map(|(l, r)| (l.clone(), r.clone())).collect()
+    }
+
+    /// Returns a `HashMap` from right values to left values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let map: HashMap<i32, char> = bimap.to_hash_map_by_right();
+    /// assert_eq!(map[&1], 'a');
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_hash_map_by_right(&self) -> std::collections::HashMap<R, L>
+    where
+        L: Clone,
+        R: Clone + Hash,
+    {
+        self.iter().map(|(l, r)| (r.clone(), l.clone())).collect()
+    }
+
+    /// Returns the pairs that inserting the given left-right pair would
+    /// overwrite, without modifying the bimap.
+    ///
+    /// The returned [`OverwriteReport`] has the same variant that
+    /// [`insert`](BiBTreeMap::insert) would return, but borrows the pairs from
+    /// the bimap instead of removing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.what_would_overwrite(&'c', &3), Overwritten::Neither);
+    /// assert_eq!(bimap.what_would_overwrite(&'a', &3), Overwritten::Left(&'a', &1));
+    /// assert_eq!(bimap.what_would_overwrite(&'c', &2), Overwritten::Right(&'b', &2));
+    /// assert_eq!(bimap.what_would_overwrite(&'a', &1), Overwritten::Unchanged(&'a', &1));
+    /// assert_eq!(
+    ///     bimap.what_would_overwrite(&'a', &2),
+    ///     Overwritten::Both((&'a', &1), (&'b', &2))
+    /// );
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn what_would_overwrite(&self, left: &L, right: &R) -> OverwriteReport<'_, L, R> {
+        let by_left = self
+            .left2right
+            .get_key_value(Wrapper::wrap(left))
+            .map(|(l, r)| (&*l.0, &*r.0));
+        let by_right = self
+            .right2left
+            .get_key_value(Wrapper::wrap(right))
+            .map(|(r, l)| (&*l.0, &*r.0));
+        match (by_left, by_right) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) => Overwritten::Left(l, r),
+            (None, Some((l, r))) => Overwritten::Right(l, r),
+            // both lookups found the same pair if it is already present
+            (Some(l_pair), Some(r_pair)) if core::ptr::eq(l_pair.0, r_pair.0) => {
+                Overwritten::Unchanged(l_pair.0, l_pair.1)
+            }
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`. The example below details
+    /// all possible enum variants that can be returned.
+    ///
+    /// If the bimap already contains exactly the given pair, it is left
+    /// untouched and `Overwritten::Unchanged` hands the given values back, so
+    /// idempotent re-inserts don't have to rebuild the pair. The values
+    /// stored in the bimap are then the ones inserted first, which only
+    /// matters for types whose equal values can still be told apart.
+    ///
+    /// # Warnings
+    ///
+    /// Somewhat paradoxically, calling `insert()` can actually reduce the size
+    /// of the bimap! This is because of the invariant that each left value
+    /// maps to exactly one right value and vice versa.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.len(), 0); // {}
+    ///
+    /// // no values are overwritten.
+    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(bimap.len(), 1); // {'a' <> 1}
+    ///
+    /// // no values are overwritten.
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(bimap.len(), 2); // {'a' <> 1, 'b' <> 2}
+    ///
+    /// // ('a', 1) already exists, so inserting ('a', 4) overwrites 'a', the left value.
+    /// // the previous left-right pair ('a', 1) is returned.
+    /// assert_eq!(bimap.insert('a', 4), Overwritten::Left('a', 1));
+    /// assert_eq!(bimap.len(), 2); // {'a' <> 4, 'b' <> 2}
+    ///
+    /// // ('b', 2) already exists, so inserting ('c', 2) overwrites 2, the right value.
     /// // the previous left-right pair ('b', 2) is returned.
     /// assert_eq!(bimap.insert('c', 2), Overwritten::Right('b', 2));
     /// assert_eq!(bimap.len(), 2); // {'a' <> 1, 'c' <> 2}
     ///
-    /// // both ('a', 4) and ('c', 2) already exist, so inserting ('a', 2) overwrites both.
-    /// // ('a', 4) has the overwritten left value ('a'), so it's the first tuple returned.
-    /// // ('c', 2) has the overwritten right value (2), so it's the second tuple returned.
-    /// assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 4), ('c', 2)));
-    /// assert_eq!(bimap.len(), 1); // {'a' <> 2} // bimap is smaller than before!
+    /// // both ('a', 4) and ('c', 2) already exist, so inserting ('a', 2) overwrites both.
+    /// // ('a', 4) has the overwritten left value ('a'), so it's the first tuple returned.
+    /// // ('c', 2) has the overwritten right value (2), so it's the second tuple returned.
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 4), ('c', 2)));
+    /// assert_eq!(bimap.len(), 1); // {'a' <> 2} // bimap is smaller than before!
+    ///
+    /// // ('a', 2) already exists, so inserting ('a', 2) leaves the bimap as it is.
+    /// // the given left-right pair ('a', 2) is returned.
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Unchanged('a', 2));
+    /// assert_eq!(bimap.len(), 1); // {'a' <> 2}
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        if matches!(self.left2right.get(Wrapper::wrap(&left)), Some(r) if *r.0 == right) {
+            // the pair is already present, so neither map needs to change
+            return Overwritten::Unchanged(left, right);
+        }
+        let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
+            (None, None) => Overwritten::Neither,
+            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
+            (Some(l_pair), None) => Overwritten::Left(l_pair.0, l_pair.1),
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        };
+        self.insert_unchecked(left, right);
+        retval
+    }
+
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right))` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('b', 2), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
+    /// assert_eq!(bimap.insert_no_overwrite('c', 2), Err(('c', 2)));
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            Err((left, right))
+        } else {
+            self.insert_unchecked(left, right);
+            Ok(())
+        }
+    }
+
+    /// Tries to insert the given left-right pair into the bimap, returning
+    /// references to the inserted values on success.
+    ///
+    /// Like [`insert_no_overwrite`], this never overwrites existing pairs. If
+    /// the left or right value is already present, the bimap is unchanged and
+    /// an [`OccupiedError`] is returned that holds the rejected pair along
+    /// with references to the existing pairs it conflicts with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// assert_eq!(bimap.try_insert('a', 1).unwrap(), (&'a', &1));
+    /// bimap.insert('b', 2);
+    ///
+    /// let error = bimap.try_insert('a', 2).unwrap_err();
+    /// assert_eq!(error.pair, ('a', 2));
+    /// assert_eq!(error.left_conflict, Some((&'a', &1)));
+    /// assert_eq!(error.right_conflict, Some((&'b', &2)));
+    /// ```
+    ///
+    /// [`insert_no_overwrite`]: BiBTreeMap::insert_no_overwrite
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(&L, &R), OccupiedError<'_, L, R>> {
+        if self.left2right.contains_key(Wrapper::wrap(&left))
+            || self.right2left.contains_key(Wrapper::wrap(&right))
+        {
+            let left_conflict = self
+                .left2right
+                .get_key_value(Wrapper::wrap(&left))
+                .map(|(l, r)| (&*l.0, &*r.0));
+            let right_conflict = self
+                .right2left
+                .get_key_value(Wrapper::wrap(&right))
+                .map(|(r, l)| (&*l.0, &*r.0));
+            return Err(OccupiedError {
+                pair: (left, right),
+                left_conflict,
+                right_conflict,
+            });
+        }
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.left2right.insert(left.clone(), right.clone());
+        self.right2left.insert(right, left.clone());
+        self.bump_generation();
+        // unwrap is safe because the pair was just inserted
+        let (l, r) = self.left2right.get_key_value(&left).unwrap();
+        Ok((&*l.0, &*r.0))
+    }
+
+    /// Inserts the given left-right pair into the bimap and returns a reference
+    /// to the stored right value.
+    ///
+    /// Any pairs that conflict with the new pair are removed and dropped, just
+    /// like with [`insert`]. The reference is taken while inserting, so this
+    /// saves the lookup of calling [`get_by_left`] afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let right = bimap.insert_and_get('a', 2);
+    /// assert_eq!(*right, 2);
+    /// assert_eq!(bimap.get_by_right(&1), None);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`get_by_left`]: BiBTreeMap::get_by_left
+    pub fn insert_and_get(&mut self, left: L, right: R) -> &R {
+        self.remove_by_left(&left);
+        self.remove_by_right(&right);
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.right2left.insert(right.clone(), left.clone());
+        self.bump_generation();
+        &self.left2right.entry(left).or_insert(right).0
+    }
+
+    /// Inserts the given left-right pair into the bimap and returns a reference
+    /// to the stored left value.
+    ///
+    /// This is the same as [`insert_and_get`], but for the left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let left = bimap.insert_and_get_left('b', 1);
+    /// assert_eq!(*left, 'b');
+    /// assert_eq!(bimap.get_by_left(&'a'), None);
+    /// ```
+    ///
+    /// [`insert_and_get`]: BiBTreeMap::insert_and_get
+    pub fn insert_and_get_left(&mut self, left: L, right: R) -> &L {
+        self.remove_by_left(&left);
+        self.remove_by_right(&right);
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.left2right.insert(left.clone(), right.clone());
+        self.bump_generation();
+        &self.right2left.entry(right).or_insert(left).0
+    }
+
+    /// Replaces the right value paired with the given left value, returning
+    /// the old right value.
+    ///
+    /// If the bimap doesn't contain the left value, nothing happens and `None`
+    /// is returned. Unlike [`insert`], this never adds a new left value.
+    ///
+    /// If `new_right` is already paired with a different left value, that
+    /// pair is removed to maintain the bijection, just like with [`insert`].
+    /// Use [`update_by_left_detailed`] instead to get the removed pair back,
+    /// or check [`contains_right`] first to avoid removing it.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.update_by_left(&'a', 3), Some(1));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&3));
+    /// assert_eq!(bimap.get_by_right(&1), None);
+    ///
+    /// // missing left values are not inserted
+    /// assert_eq!(bimap.update_by_left(&'c', 4), None);
+    /// assert!(!bimap.contains_left(&'c'));
+    ///
+    /// // the pair ('b', 2) is removed because 2 is now paired with 'a'
+    /// assert_eq!(bimap.update_by_left(&'a', 2), Some(3));
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`update_by_left_detailed`]: BiBTreeMap::update_by_left_detailed
+    /// [`contains_right`]: BiBTreeMap::contains_right
+    pub fn update_by_left<Q>(&mut self, left: &Q, new_right: R) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.update_by_left_detailed(left, new_right)
+            .map(|(old_right, _)| old_right)
+    }
+
+    /// Replaces the right value paired with the given left value, returning
+    /// the old right value and the pair, if any, that was overwritten by the
+    /// new right value.
+    ///
+    /// This behaves exactly like [`update_by_left`], but also returns the
+    /// pair that previously contained `new_right`. The returned
+    /// [`Overwritten`] is either `Neither`, if `new_right` wasn't paired with
+    /// a different left value, or `Right` with the removed pair.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(
+    ///     bimap.update_by_left_detailed(&'a', 3),
+    ///     Some((1, Overwritten::Neither))
+    /// );
+    /// assert_eq!(bimap.update_by_left_detailed(&'c', 4), None);
+    /// assert_eq!(
+    ///     bimap.update_by_left_detailed(&'a', 2),
+    ///     Some((3, Overwritten::Right('b', 2)))
+    /// );
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    ///
+    /// [`update_by_left`]: BiBTreeMap::update_by_left
+    pub fn update_by_left_detailed<Q>(
+        &mut self,
+        left: &Q,
+        new_right: R,
+    ) -> Option<(R, Overwritten<L, R>)>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (left, old_right) = self.remove_by_left(left)?;
+        let overwritten = match self.remove_by_right(&new_right) {
+            Some((l, r)) => Overwritten::Right(l, r),
+            None => Overwritten::Neither,
+        };
+        self.insert_unchecked(left, new_right);
+        Some((old_right, overwritten))
+    }
+
+    /// Replaces the pair containing the given left value with a new pair,
+    /// returning the removed pair and the pairs that inserting the new pair
+    /// overwrote.
+    ///
+    /// This is useful for renames where both values change at once. If the
+    /// bimap doesn't contain `old_left`, nothing happens and `None` is
+    /// returned, so no new left value is added. Otherwise the old pair is
+    /// removed and the new pair is inserted as with [`insert`]: if `new_left`
+    /// or `new_right` is already paired with another value, that pair is
+    /// removed as well to maintain the bijection and returned in the
+    /// [`Overwritten`]. Check [`contains_left`] and [`contains_right`] first
+    /// to avoid removing them.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert("alice", 1);
+    /// bimap.insert("bob", 2);
+    ///
+    /// assert_eq!(
+    ///     bimap.replace_pair("alice", "carol", 3),
+    ///     Some((("alice", 1), Overwritten::Neither))
+    /// );
+    /// assert_eq!(bimap.get_by_left("carol"), Some(&3));
+    /// assert!(!bimap.contains_left("alice"));
+    /// assert!(!bimap.contains_right(&1));
+    ///
+    /// // missing left values are not replaced
+    /// assert_eq!(bimap.replace_pair("dave", "erin", 4), None);
+    /// assert!(!bimap.contains_left("erin"));
+    ///
+    /// // the pair ("bob", 2) is removed because 2 is now paired with "erin"
+    /// assert_eq!(
+    ///     bimap.replace_pair("carol", "erin", 2),
+    ///     Some((("carol", 3), Overwritten::Right("bob", 2)))
+    /// );
+    /// assert_eq!(bimap.get_by_right(&2), Some(&"erin"));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    /// [`contains_left`]: BiBTreeMap::contains_left
+    /// [`contains_right`]: BiBTreeMap::contains_right
+    pub fn replace_pair<Q>(
+        &mut self,
+        old_left: &Q,
+        new_left: L,
+        new_right: R,
+    ) -> Option<((L, R), Overwritten<L, R>)>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let removed = self.remove_by_left(old_left)?;
+        let overwritten = self.insert(new_left, new_right);
+        Some((removed, overwritten))
+    }
+
+    /// Applies a batch of operations to the bimap, either all of them or none
+    /// of them.
+    ///
+    /// The operations run in order, each one seeing the effects of those
+    /// before it, so a batch may for example insert a pair and then replace
+    /// its left value. The whole batch is checked before the bimap is
+    /// touched: if any operation would fail, as described on [`BatchOp`], a
+    /// [`BatchError`] with that operation and the reason is returned and the
+    /// bimap is left exactly as it was. The check keeps a temporary map of
+    /// the values touched by the batch and doesn't count towards
+    /// [`lookup_stats`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BatchOp, BiBTreeMap, ModifyError};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert("alice", 1);
+    /// bimap.insert("bob", 2);
+    ///
+    /// // the renamed user takes over the id of the removed one
+    /// let batch = vec![
+    ///     BatchOp::RemoveByLeft("bob"),
+    ///     BatchOp::ReplaceLeft { old: "alice", new: "carol" },
+    ///     BatchOp::ReplaceRight { old: 1, new: 2 },
+    /// ];
+    /// assert_eq!(bimap.apply_batch(batch), Ok(()));
+    /// assert_eq!(bimap.get_by_left("carol"), Some(&2));
+    /// assert_eq!(bimap.len(), 1);
+    ///
+    /// // a failing operation rejects the whole batch
+    /// let batch = vec![BatchOp::Insert("dave", 3), BatchOp::RemoveByRight(1)];
+    /// let error = bimap.apply_batch(batch).unwrap_err();
+    /// assert_eq!((error.index, error.reason), (1, ModifyError::NotFound));
+    /// assert!(!bimap.contains_left("dave"));
+    /// ```
+    ///
+    /// [`lookup_stats`]: BiBTreeMap::lookup_stats
+    pub fn apply_batch<I>(&mut self, ops: I) -> Result<(), BatchError<L, R>>
+    where
+        I: IntoIterator<Item = BatchOp<L, R>>,
+    {
+        let mut ops: Vec<_> = ops.into_iter().collect();
+        if let Err((index, reason)) = self.check_batch(&ops) {
+            let op = ops.swap_remove(index);
+            return Err(BatchError { index, op, reason });
+        }
+        // unwraps are safe because the batch has been checked
+        for op in ops {
+            match op {
+                BatchOp::Insert(left, right) => self.insert_unchecked(left, right),
+                BatchOp::RemoveByLeft(left) => {
+                    self.remove_by_left(&left).unwrap();
+                }
+                BatchOp::RemoveByRight(right) => {
+                    self.remove_by_right(&right).unwrap();
+                }
+                BatchOp::ReplaceLeft { old, new } => {
+                    let (_, right) = self.remove_by_left(&old).unwrap();
+                    self.insert_unchecked(new, right);
+                }
+                BatchOp::ReplaceRight { old, new } => {
+                    let (left, _) = self.remove_by_right(&old).unwrap();
+                    self.insert_unchecked(left, new);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Begins a transaction on the bimap, which undoes the changes made
+    /// through it unless it is committed.
+    ///
+    /// See the [`transaction`](crate::transaction) module for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut tx = bimap.begin();
+    /// tx.insert('b', 2);
+    /// tx.remove_by_left(&'a');
+    /// drop(tx);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert!(!bimap.contains_left(&'b'));
+    ///
+    /// let mut tx = bimap.begin();
+    /// tx.insert('b', 2);
+    /// tx.commit();
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn begin(&mut self) -> Transaction<'_, Self, L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        Transaction::new(self)
+    }
+
+    /// Runs the closure in a transaction on the bimap, keeping its changes if
+    /// it returns `Ok` and undoing them if it returns `Err` or panics.
+    ///
+    /// The closure's result is returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert("alice", 1);
+    ///
+    /// // rename alice, but only if the new name is free
+    /// let rename = |bimap: &mut BiBTreeMap<_, _>, new| {
+    ///     bimap.transaction(|tx| {
+    ///         let (_, id) = tx.remove_by_left(&"alice").ok_or("no alice")?;
+    ///         if tx.insert(new, id).did_overwrite() {
+    ///             return Err("name taken");
+    ///         }
+    ///         Ok(id)
+    ///     })
+    /// };
+    ///
+    /// bimap.insert("bob", 2);
+    /// assert_eq!(rename(&mut bimap, "bob"), Err("name taken"));
+    /// assert_eq!(bimap.get_by_left(&"alice"), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&"bob"), Some(&2));
+    ///
+    /// assert_eq!(rename(&mut bimap, "carol"), Ok(1));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&"carol"));
+    /// ```
+    pub fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        L: Clone,
+        R: Clone,
+        F: FnOnce(&mut Transaction<'_, Self, L, R>) -> Result<T, E>,
+    {
+        let mut tx = self.begin();
+        let result = f(&mut tx);
+        if result.is_ok() {
+            tx.commit();
+        }
+        result
+    }
+
+    /// Modifies the right value paired with the given left value in place,
+    /// moving it to its new position in the bimap's right-ordered tree.
+    ///
+    /// The pair is taken out of the bimap while the closure modifies the right
+    /// value, and put back with the modified value afterwards, also when the
+    /// closure panics. Neither value is cloned. If the modified value is
+    /// already paired with a different left value, the pair can't be put
+    /// back: the original right value no longer exists, so the pair is
+    /// dropped and [`ModifyError::Collision`] is returned. If the left value
+    /// isn't present, [`ModifyError::NotFound`] is returned and the bimap is
+    /// left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, ModifyError};
+    ///
+    /// // task ids paired with their priorities
+    /// let mut tasks = BiBTreeMap::new();
+    /// tasks.insert("build", 1);
+    /// tasks.insert("test", 2);
+    /// tasks.insert("deploy", 3);
+    ///
+    /// assert_eq!(tasks.modify_right_by_left("build", |p| *p += 10), Ok(()));
+    /// let by_priority: Vec<_> = tasks.right_values().collect();
+    /// assert_eq!(by_priority, [&2, &3, &11]);
+    ///
+    /// // "test" is dropped because priority 3 is already taken by "deploy"
+    /// let result = tasks.modify_right_by_left("test", |p| *p = 3);
+    /// assert_eq!(result, Err(ModifyError::Collision));
+    /// assert!(!tasks.contains_left("test"));
+    /// assert_eq!(tasks.get_by_right(&3), Some(&"deploy"));
+    /// ```
+    pub fn modify_right_by_left<Q, F>(&mut self, left: &Q, f: F) -> Result<(), ModifyError>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: FnOnce(&mut R),
+    {
+        let pair = self.remove_by_left(left).ok_or(ModifyError::NotFound)?;
+        let mut guard = ModifyGuard {
+            bimap: self,
+            pair: Some(pair),
+        };
+        // unwrap is safe because the pair is only taken by restore
+        f(&mut guard.pair.as_mut().unwrap().1);
+        guard.restore()
+    }
+
+    /// Modifies the left value paired with the given right value in place,
+    /// moving it to its new position in the bimap's left-ordered tree.
+    ///
+    /// The pair is taken out of the bimap while the closure modifies the left
+    /// value, and put back with the modified value afterwards, also when the
+    /// closure panics. Neither value is cloned. If the modified value is
+    /// already paired with a different right value, the pair can't be put
+    /// back: the original left value no longer exists, so the pair is
+    /// dropped and [`ModifyError::Collision`] is returned. If the right value
+    /// isn't present, [`ModifyError::NotFound`] is returned and the bimap is
+    /// left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, ModifyError};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert(1, 'a');
+    /// bimap.insert(2, 'b');
+    ///
+    /// assert_eq!(bimap.modify_left_by_right(&'a', |l| *l = 5), Ok(()));
+    /// let lefts: Vec<_> = bimap.left_values().collect();
+    /// assert_eq!(lefts, [&2, &5]);
+    ///
+    /// let result = bimap.modify_left_by_right(&'z', |l| *l = 0);
+    /// assert_eq!(result, Err(ModifyError::NotFound));
+    /// ```
+    pub fn modify_left_by_right<Q, F>(&mut self, right: &Q, f: F) -> Result<(), ModifyError>
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: FnOnce(&mut L),
+    {
+        let pair = self.remove_by_right(right).ok_or(ModifyError::NotFound)?;
+        let mut guard = ModifyGuard {
+            bimap: self,
+            pair: Some(pair),
+        };
+        // unwrap is safe because the pair is only taken by restore
+        f(&mut guard.pair.as_mut().unwrap().0);
+        guard.restore()
+    }
+
+    /// Inserts every left-right pair from the iterator into the bimap,
+    /// returning the result of each insertion in order.
+    ///
+    /// This is the same as the [`Extend`] implementation, which calls
+    /// [`insert`] for every pair, except that the [`Overwritten`] values are
+    /// collected instead of discarded. Pairs from the iterator can overwrite
+    /// earlier pairs from the same iterator.
+    ///
+    /// # Examples
     ///
-    /// // ('a', 2) already exists, so inserting ('a', 2) overwrites the pair.
-    /// // the previous left-right pair ('a', 2) is returned.
-    /// assert_eq!(bimap.insert('a', 2), Overwritten::Pair('a', 2));
-    /// assert_eq!(bimap.len(), 1); // {'a' <> 2}
     /// ```
-    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
-        let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
-            (None, None) => Overwritten::Neither,
-            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
-            (Some(l_pair), None) => {
-                // since remove_by_left() was called first, it's possible the right value was
-                // removed if a duplicate pair is being inserted
-                if l_pair.1 == right {
-                    Overwritten::Pair(l_pair.0, l_pair.1)
-                } else {
-                    Overwritten::Left(l_pair.0, l_pair.1)
-                }
-            }
-            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
-        };
-        self.insert_unchecked(left, right);
-        retval
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let overwritten = bimap.extend_detailed(vec![('b', 2), ('a', 3), ('c', 2)]);
+    /// assert_eq!(
+    ///     overwritten,
+    ///     vec![
+    ///         Overwritten::Neither,
+    ///         Overwritten::Left('a', 1),
+    ///         Overwritten::Right('b', 2),
+    ///     ]
+    /// );
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn extend_detailed<I>(&mut self, iter: I) -> Vec<Overwritten<L, R>>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        iter.into_iter().map(|(l, r)| self.insert(l, r)).collect()
     }
 
-    /// Inserts the given left-right pair into the bimap without overwriting any
-    /// existing values.
+    /// Inserts every left-right pair from the iterator that doesn't conflict
+    /// with the bimap, returning the rejected pairs in the order they were
+    /// encountered.
     ///
-    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
-    /// If either value exists in the map, `Err((left, right)` is returned
-    /// with the attempted left-right pair and the map is unchanged.
+    /// Each pair is inserted with [`insert_no_overwrite`], so a pair whose
+    /// left or right value is already present is skipped entirely and the
+    /// existing pairs are never overwritten. Pairs from the iterator can be
+    /// rejected because of earlier pairs from the same iterator.
     ///
     /// # Examples
     ///
@@ -471,17 +2015,119 @@ where
     /// use bimap::BiBTreeMap;
     ///
     /// let mut bimap = BiBTreeMap::new();
-    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
-    /// assert_eq!(bimap.insert_no_overwrite('b', 2), Ok(()));
-    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
-    /// assert_eq!(bimap.insert_no_overwrite('c', 2), Err(('c', 2)));
+    /// bimap.insert('a', 1);
+    ///
+    /// let rejected = bimap.extend_no_overwrite(vec![('b', 2), ('a', 3), ('c', 2), ('d', 4)]);
+    /// assert_eq!(rejected, vec![('a', 3), ('c', 2)]);
+    /// assert_eq!(bimap.len(), 3);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
     /// ```
-    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
-        if self.contains_left(&left) || self.contains_right(&right) {
-            Err((left, right))
-        } else {
-            self.insert_unchecked(left, right);
+    ///
+    /// [`insert_no_overwrite`]: BiBTreeMap::insert_no_overwrite
+    pub fn extend_no_overwrite<I>(&mut self, iter: I) -> Vec<(L, R)>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        iter.into_iter()
+            .filter_map(|(l, r)| self.insert_no_overwrite(l, r).err())
+            .collect()
+    }
+
+    /// Inserts a copy of every left-right pair in the slice into the bimap.
+    ///
+    /// Like [`Vec::extend_from_slice`], this is a convenience for `Copy`
+    /// pairs that avoids having to own or clone them first. Each pair is
+    /// inserted with [`insert`], so it overwrites any existing pairs with the
+    /// same left or right value, including earlier pairs from the same slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert(1, 10);
+    ///
+    /// bimap.extend_from_slice(&[(2, 20), (1, 11), (3, 20)]);
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_left(&1), Some(&11));
+    /// assert_eq!(bimap.get_by_left(&2), None);
+    /// assert_eq!(bimap.get_by_right(&20), Some(&3));
+    /// ```
+    ///
+    /// [`insert`]: BiBTreeMap::insert
+    pub fn extend_from_slice(&mut self, pairs: &[(L, R)])
+    where
+        L: Copy,
+        R: Copy,
+    {
+        for &(left, right) in pairs {
+            self.insert(left, right);
+        }
+    }
+
+    /// Checks that the two internal maps of the bimap are mirror images of each
+    /// other, returning every inconsistency found.
+    ///
+    /// The bimap's own methods always keep the maps consistent, so this only
+    /// fails if there is a bug in this crate. It takes O(n) lookups, which is
+    /// cheap enough to call after every batch of operations in debug builds or
+    /// fuzz targets. An error lists the differing lengths first, then every
+    /// pair of the left-to-right map that is missing or mismatched in the
+    /// right-to-left map, then vice versa.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 1);
+    /// bimap.remove_by_left(&'b');
+    /// debug_assert_eq!(bimap.check_invariants(), Ok(()));
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), Vec<InvariantViolation<&L, &R>>> {
+        let mut violations = Vec::new();
+        if self.left2right.len() != self.right2left.len() {
+            violations.push(InvariantViolation::Len {
+                left2right: self.left2right.len(),
+                right2left: self.right2left.len(),
+            });
+        }
+        for (left, right) in &self.left2right {
+            match self.right2left.get(right) {
+                None => violations.push(InvariantViolation::MissingRight {
+                    left: &*left.0,
+                    right: &*right.0,
+                }),
+                Some(found) if found != left => violations.push(InvariantViolation::WrongLeft {
+                    left: &*left.0,
+                    right: &*right.0,
+                    found: &*found.0,
+                }),
+                Some(_) => {}
+            }
+        }
+        for (right, left) in &self.right2left {
+            match self.left2right.get(left) {
+                None => violations.push(InvariantViolation::MissingLeft {
+                    left: &*left.0,
+                    right: &*right.0,
+                }),
+                Some(found) if found != right => violations.push(InvariantViolation::WrongRight {
+                    left: &*left.0,
+                    right: &*right.0,
+                    found: &*found.0,
+                }),
+                Some(_) => {}
+            }
+        }
+        if violations.is_empty() {
             Ok(())
+        } else {
+            Err(violations)
         }
     }
 
@@ -492,6 +2138,88 @@ where
         let right_rc = Ref(Rc::new(right));
         self.left2right.insert(left.clone(), right_rc.clone());
         self.right2left.insert(right_rc, left);
+        self.bump_generation();
+    }
+
+    /// Checks a batch for `apply_batch` without changing the bimap,
+    /// returning the position of the first failing operation and the reason.
+    fn check_batch(&self, ops: &[BatchOp<L, R>]) -> Result<(), (usize, ModifyError)> {
+        /// Returns the current partner of a value, looking at the changes
+        /// made by the batch first.
+        fn partner<'a, T, U>(
+            changed: &BTreeMap<&'a T, Option<&'a U>>,
+            map: &'a BTreeMap<Ref<T>, Ref<U>>,
+            value: &'a T,
+        ) -> Option<&'a U>
+        where
+            T: Ord,
+        {
+            match changed.get(value) {
+                Some(partner) => *partner,
+                None => map.get(Wrapper::wrap(value)).map(|p| &*p.0),
+            }
+        }
+
+        // the values changed by the operations checked so far, mapped to their
+        // new partners or to None if they have been removed
+        let mut lefts: BTreeMap<&L, Option<&R>> = BTreeMap::new();
+        let mut rights: BTreeMap<&R, Option<&L>> = BTreeMap::new();
+        let right_of = |lefts: &BTreeMap<_, _>, left| partner(lefts, &self.left2right, left);
+        let left_of = |rights: &BTreeMap<_, _>, right| partner(rights, &self.right2left, right);
+        for (index, op) in ops.iter().enumerate() {
+            match op {
+                BatchOp::Insert(left, right) => {
+                    if right_of(&lefts, left).is_some() || left_of(&rights, right).is_some() {
+                        return Err((index, ModifyError::Collision));
+                    }
+                    lefts.insert(left, Some(right));
+                    rights.insert(right, Some(left));
+                }
+                BatchOp::RemoveByLeft(left) => {
+                    let right = right_of(&lefts, left).ok_or((index, ModifyError::NotFound))?;
+                    lefts.insert(left, None);
+                    rights.insert(right, None);
+                }
+                BatchOp::RemoveByRight(right) => {
+                    let left = left_of(&rights, right).ok_or((index, ModifyError::NotFound))?;
+                    lefts.insert(left, None);
+                    rights.insert(right, None);
+                }
+                BatchOp::ReplaceLeft { old, new } => {
+                    let right = right_of(&lefts, old).ok_or((index, ModifyError::NotFound))?;
+                    if new != old && right_of(&lefts, new).is_some() {
+                        return Err((index, ModifyError::Collision));
+                    }
+                    lefts.insert(old, None);
+                    lefts.insert(new, Some(right));
+                    rights.insert(right, Some(new));
+                }
+                BatchOp::ReplaceRight { old, new } => {
+                    let left = left_of(&rights, old).ok_or((index, ModifyError::NotFound))?;
+                    if new != old && left_of(&rights, new).is_some() {
+                        return Err((index, ModifyError::Collision));
+                    }
+                    rights.insert(old, None);
+                    rights.insert(new, Some(left));
+                    lefts.insert(left, Some(new));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the slice contains exactly the pairs of the bimap,
+    /// each one once.
+    fn eq_pairs(&self, pairs: &[(L, R)]) -> bool {
+        if pairs.len() != self.len() {
+            return false;
+        }
+        // the lengths match, so the slice holds every pair exactly once if
+        // all of its pairs are in the bimap and its left values are distinct
+        let mut seen = BTreeSet::new();
+        pairs
+            .iter()
+            .all(|(l, r)| self.get_by_left(l) == Some(r) && seen.insert(l))
     }
 
     /// Creates an iterator over the left-right pairs lying within a range of
@@ -569,6 +2297,137 @@ where
     }
 }
 
+/// Rebuilds the right-to-left map of a bimap from its left-to-right map when
+/// dropped, removing any pairs whose right value is already taken.
+struct ReindexGuard<'a, L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    bimap: &'a mut BiBTreeMap<L, R>,
+}
+
+impl<'a, L, R> Drop for ReindexGuard<'a, L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn drop(&mut self) {
+        let left2right = &mut self.bimap.left2right;
+        let right2left = &mut self.bimap.right2left;
+        right2left.clear();
+        let mut duplicates = Vec::new();
+        for (l, r) in left2right.iter() {
+            if right2left.contains_key(r) {
+                duplicates.push(l.clone());
+            } else {
+                right2left.insert(r.clone(), l.clone());
+            }
+        }
+        for l in duplicates {
+            left2right.remove(&l);
+        }
+    }
+}
+
+/// Puts a pair taken out of a bimap by `modify_right_by_left` or
+/// `modify_left_by_right` back when dropped, so that a panic while modifying
+/// one of its values doesn't lose the pair.
+struct ModifyGuard<'a, L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    bimap: &'a mut BiBTreeMap<L, R>,
+    pair: Option<(L, R)>,
+}
+
+impl<'a, L, R> ModifyGuard<'a, L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Puts the pair back into the bimap, or drops it if either value is now
+    /// paired with another value.
+    fn restore(&mut self) -> Result<(), ModifyError> {
+        let (left, right) = match self.pair.take() {
+            Some(pair) => pair,
+            None => return Ok(()),
+        };
+        if self.bimap.contains_left(&left) || self.bimap.contains_right(&right) {
+            return Err(ModifyError::Collision);
+        }
+        self.bimap.insert_unchecked(left, right);
+        Ok(())
+    }
+}
+
+impl<'a, L, R> Drop for ModifyGuard<'a, L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+impl<L, R> crate::sealed::Sealed for BiBTreeMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+}
+
+impl<L, R> BiMapLike<L, R> for BiBTreeMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    type Iter<'a>
+        = Iter<'a, L, R>
+    where
+        Self: 'a,
+        L: 'a,
+        R: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter(&self) -> Iter<'_, L, R> {
+        self.iter()
+    }
+
+    fn get_by_left(&self, left: &L) -> Option<&R> {
+        self.get_by_left(left)
+    }
+
+    fn get_by_right(&self, right: &R) -> Option<&L> {
+        self.get_by_right(right)
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn contains_right(&self, right: &R) -> bool {
+        self.contains_right(right)
+    }
+
+    fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        self.insert(left, right)
+    }
+
+    fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        self.remove_by_left(left)
+    }
+
+    fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        self.remove_by_right(right)
+    }
+}
+
 impl<L, R> Clone for BiBTreeMap<L, R>
 where
     L: Clone + Ord,
@@ -610,16 +2469,47 @@ where
     }
 }
 
-impl<L, R> Default for BiBTreeMap<L, R>
-where
-    L: Ord,
-    R: Ord,
-{
+/// Creates an empty `BiBTreeMap`.
+///
+/// Like `BTreeMap`, this doesn't require the value types to implement `Ord`.
+impl<L, R> Default for BiBTreeMap<L, R> {
     fn default() -> BiBTreeMap<L, R> {
         BiBTreeMap {
             left2right: BTreeMap::default(),
             right2left: BTreeMap::default(),
+            generation: 0,
+            stats: Counters::new(),
+        }
+    }
+}
+
+/// Formats the bimap as `{left <-> right, ...}`, in ascending order of the
+/// left values.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+///
+/// let mut bimap = BiBTreeMap::new();
+/// bimap.insert('b', 2);
+/// bimap.insert('a', 1);
+/// assert_eq!(bimap.to_string(), "{a <-> 1, b <-> 2}");
+/// ```
+impl<L, R> fmt::Display for BiBTreeMap<L, R>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, (left, right)) in self.left2right.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} <-> {}", left.0, right.0)?;
         }
+        f.write_str("}")
     }
 }
 
@@ -630,6 +2520,50 @@ where
 {
 }
 
+/// Converts a `BiHashMap` into a `BiBTreeMap` with the same left-right pairs.
+///
+/// Since the pairs of a bimap are already unique, this skips the overwrite
+/// checks of [`insert`] and builds both trees in bulk from sorted pairs,
+/// which is faster than collecting the `BiHashMap` into a `BiBTreeMap`.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiBTreeMap, BiHashMap};
+///
+/// let mut hash = BiHashMap::new();
+/// hash.insert('b', 2);
+/// hash.insert('a', 1);
+///
+/// let btree = BiBTreeMap::from(hash);
+/// assert_eq!(btree.iter().collect::<Vec<_>>(), [(&'a', &1), (&'b', &2)]);
+/// ```
+///
+/// [`insert`]: BiBTreeMap::insert
+#[cfg(feature = "std")]
+impl<L, R, LS, RS> From<crate::BiHashMap<L, R, LS, RS>> for BiBTreeMap<L, R>
+where
+    L: Ord + Hash,
+    R: Ord + Hash,
+{
+    fn from(bimap: crate::BiHashMap<L, R, LS, RS>) -> BiBTreeMap<L, R> {
+        let pairs: Vec<(Ref<L>, Ref<R>)> = bimap
+            .into_iter()
+            .map(|(l, r)| (Ref(Rc::new(l)), Ref(Rc::new(r))))
+            .collect();
+        // collecting into a BTreeMap sorts the pairs and then builds the tree
+        // bottom-up
+        let right2left = pairs.iter().map(|(l, r)| (r.clone(), l.clone())).collect();
+        let left2right = pairs.into_iter().collect();
+        BiBTreeMap {
+            left2right,
+            right2left,
+            generation: 0,
+            stats: Counters::new(),
+        }
+    }
+}
+
 impl<L, R> FromIterator<(L, R)> for BiBTreeMap<L, R>
 where
     L: Ord,
@@ -687,6 +2621,27 @@ where
     }
 }
 
+/// Compares the left-right pairs of the bimaps lexicographically in ascending
+/// left order, just like `BTreeMap` does with its entries. This is consistent
+/// with `PartialEq`, and a bimap whose pairs are a prefix of another's
+/// compares less.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+/// use std::collections::BTreeSet;
+/// use std::iter::FromIterator;
+///
+/// let mut configs = BTreeSet::new();
+/// configs.insert(BiBTreeMap::from_iter(vec![('a', 2)]));
+/// configs.insert(BiBTreeMap::from_iter(vec![('a', 1), ('b', 2)]));
+/// configs.insert(BiBTreeMap::from_iter(vec![('a', 1)]));
+///
+/// let firsts: Vec<_> = configs.iter().map(|c| c.get_by_left(&'a')).collect();
+/// assert_eq!(firsts, vec![Some(&1), Some(&1), Some(&2)]);
+/// assert_eq!(configs.iter().next().unwrap().len(), 1);
+/// ```
 impl<L, R> Ord for BiBTreeMap<L, R>
 where
     L: Ord,
@@ -707,16 +2662,76 @@ where
     }
 }
 
+/// Compares the bimap's left-right pairs with the pairs of an array,
+/// regardless of their order.
+///
+/// The bimap is equal to the array if the array contains exactly the pairs
+/// of the bimap, each one once.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+///
+/// let mut bimap = BiBTreeMap::new();
+/// bimap.insert('a', 1);
+/// bimap.insert('b', 2);
+///
+/// assert_eq!(bimap, [('b', 2), ('a', 1)]);
+/// assert_ne!(bimap, [('a', 2), ('b', 1)]);
+/// assert_ne!(bimap, [('a', 1), ('a', 1)]);
+/// ```
+impl<L, R, const N: usize> PartialEq<[(L, R); N]> for BiBTreeMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn eq(&self, other: &[(L, R); N]) -> bool {
+        self.eq_pairs(other)
+    }
+}
+
+/// Compares the bimap's left-right pairs with the pairs of a slice,
+/// regardless of their order.
+///
+/// The bimap is equal to the slice if the slice contains exactly the pairs
+/// of the bimap, each one once.
+impl<'a, L, R> PartialEq<&'a [(L, R)]> for BiBTreeMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn eq(&self, other: &&'a [(L, R)]) -> bool {
+        self.eq_pairs(other)
+    }
+}
+
 impl<L, R> PartialOrd for BiBTreeMap<L, R>
 where
     L: Ord,
     R: Ord,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.left2right.partial_cmp(&other.left2right)
+        Some(self.cmp(other))
     }
 }
 
+/// Hashes the number of pairs followed by every left-right pair in ascending
+/// left order, which is consistent with `PartialEq`: equal bimaps hash
+/// equally, regardless of the order their pairs were inserted in.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+/// use std::collections::HashSet;
+/// use std::iter::FromIterator;
+///
+/// let mut seen = HashSet::new();
+/// seen.insert(BiBTreeMap::from_iter(vec![('a', 1), ('b', 2)]));
+/// assert!(seen.contains(&BiBTreeMap::from_iter(vec![('b', 2), ('a', 1)])));
+/// assert!(!seen.contains(&BiBTreeMap::from_iter(vec![('a', 2), ('b', 1)])));
+/// ```
 impl<L, R> Hash for BiBTreeMap<L, R>
 where
     L: Hash,
@@ -917,12 +2932,75 @@ impl<'a, L, R> FusedIterator for RightRange<'a, L, R> {}
 impl<'a, L, R> Iterator for RightRange<'a, L, R> {
     type Item = (&'a L, &'a R);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(r, l)| (&*l.0, &*r.0))
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(r, l)| (&*l.0, &*r.0))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A pair taken out of a `BiBTreeMap` that is removed for good unless it is
+/// kept.
+///
+/// The lease dereferences to the right value of the pair. When the lease is
+/// dropped, the pair stays removed; calling [`keep`](Lease::keep) puts it
+/// back into the bimap instead.
+///
+/// This struct is created by the [`lease_by_left`] method of `BiBTreeMap`.
+///
+/// [`lease_by_left`]: BiBTreeMap::lease_by_left
+pub struct Lease<'a, L, R> {
+    bimap: &'a mut BiBTreeMap<L, R>,
+    left: L,
+    right: R,
+}
+
+impl<'a, L, R> Lease<'a, L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Returns the left value of the leased pair.
+    pub fn left(&self) -> &L {
+        &self.left
+    }
+
+    /// Puts the leased pair back into the bimap.
+    ///
+    /// Like any insertion, this advances the bimap's generation.
+    pub fn keep(self) {
+        let Lease { bimap, left, right } = self;
+        // the bimap was borrowed since the pair was taken out, so neither
+        // value can have been inserted in the meantime
+        bimap.insert_unchecked(left, right);
+    }
+
+    /// Removes the leased pair for good and returns it.
+    pub fn take(self) -> (L, R) {
+        (self.left, self.right)
+    }
+}
+
+impl<'a, L, R> fmt::Debug for Lease<'a, L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Lease")
+            .field("left", &self.left)
+            .field("right", &self.right)
+            .finish()
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+impl<'a, L, R> Deref for Lease<'a, L, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.right
     }
 }
 
@@ -984,11 +3062,39 @@ mod tests {
         assert_eq!("{'a' <> 1, 'b' <> 2}", format!("{:?}", bimap));
     }
 
+    #[test]
+    fn display() {
+        use alloc::string::ToString;
+
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.to_string(), "{}");
+
+        bimap.insert("two", 2);
+        assert_eq!(bimap.to_string(), "{two <-> 2}");
+
+        bimap.insert("three", 3);
+        bimap.insert("one", 1);
+        assert_eq!(bimap.to_string(), "{one <-> 1, three <-> 3, two <-> 2}");
+    }
+
     #[test]
     fn default() {
         let _ = BiBTreeMap::<char, i32>::default();
     }
 
+    #[test]
+    fn default_without_ord() {
+        // neither `Ord` nor `Eq`
+        struct Opaque;
+
+        #[derive(Default)]
+        struct Wrapper {
+            bimap: BiBTreeMap<Opaque, core::marker::PhantomData<Opaque>>,
+        }
+
+        let _ = Wrapper::default().bimap;
+    }
+
     #[test]
     fn eq() {
         let mut bimap = BiBTreeMap::new();
@@ -1028,84 +3134,674 @@ mod tests {
     fn into_iter() {
         let mut bimap = BiBTreeMap::new();
         bimap.insert('a', 3);
-        bimap.insert('b', 2);
-        bimap.insert('c', 1);
-        let pairs = bimap.into_iter().collect::<Vec<_>>();
-        assert_eq!(pairs, vec![('a', 3), ('b', 2), ('c', 1)]);
+        bimap.insert('b', 2);
+        bimap.insert('c', 1);
+        let pairs = bimap.into_iter().collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('a', 3), ('b', 2), ('c', 1)]);
+    }
+
+    #[test]
+    fn into_iter_ref() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+        bimap.insert('c', 1);
+        let pairs = (&bimap).into_iter().collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(&'a', &3), (&'b', &2), (&'c', &1)]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+        bimap.extend(vec![('c', 3), ('b', 1), ('a', 4)]);
+        let mut bimap2 = BiBTreeMap::new();
+        bimap2.insert('a', 4);
+        bimap2.insert('b', 1);
+        bimap2.insert('c', 3);
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn extend_detailed() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+        let overwritten = bimap.extend_detailed(vec![('c', 3), ('b', 1), ('a', 4), ('b', 1)]);
+        assert_eq!(
+            overwritten,
+            vec![
+                Overwritten::Right('a', 3),
+                Overwritten::Left('b', 2),
+                Overwritten::Neither,
+                Overwritten::Unchanged('b', 1),
+            ]
+        );
+        let mut bimap2 = BiBTreeMap::new();
+        bimap2.extend(vec![
+            ('a', 3),
+            ('b', 2),
+            ('c', 3),
+            ('b', 1),
+            ('a', 4),
+            ('b', 1),
+        ]);
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn extend_no_overwrite() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        let rejected = bimap.extend_no_overwrite(vec![
+            // conflicts with existing pairs
+            ('a', 3),
+            ('c', 2),
+            ('a', 2),
+            ('a', 1),
+            // accepted
+            ('d', 4),
+            // conflict with the accepted pair from the same batch
+            ('d', 5),
+            ('e', 4),
+            // accepted
+            ('f', 6),
+        ]);
+        assert_eq!(
+            rejected,
+            vec![('a', 3), ('c', 2), ('a', 2), ('a', 1), ('d', 5), ('e', 4)]
+        );
+
+        let mut expected = BiBTreeMap::new();
+        expected.insert('a', 1);
+        expected.insert('b', 2);
+        expected.insert('d', 4);
+        expected.insert('f', 6);
+        assert_eq!(bimap, expected);
+        assert!(bimap.extend_no_overwrite(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn extend_from_slice() {
+        let pairs = [(1u32, 10u32), (2, 20), (1, 30), (3, 20)];
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(4, 10);
+        bimap.extend_from_slice(&pairs);
+
+        let mut expected = BiBTreeMap::new();
+        expected.insert(4, 10);
+        expected.extend(pairs.iter().copied());
+        assert_eq!(bimap, expected);
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_left(&1), Some(&30));
+        assert_eq!(bimap.get_by_left(&3), Some(&20));
+    }
+
+    #[test]
+    fn modify_right_by_left() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 10);
+        bimap.insert('b', 20);
+        bimap.insert('c', 30);
+
+        assert_eq!(bimap.modify_right_by_left(&'a', |r| *r = 25), Ok(()));
+        assert_eq!(bimap.get_by_left(&'a'), Some(&25));
+        assert_eq!(bimap.get_by_right(&25), Some(&'a'));
+        assert_eq!(bimap.get_by_right(&10), None);
+        assert_eq!(
+            bimap.right_range(..).collect::<Vec<_>>(),
+            vec![(&'b', &20), (&'a', &25), (&'c', &30)]
+        );
+        assert_eq!(bimap.first_by_right(), Some((&'b', &20)));
+
+        // leaving the value unchanged is not a collision
+        assert_eq!(bimap.modify_right_by_left(&'b', |_| {}), Ok(()));
+
+        let snapshot = bimap.clone();
+        let generation = bimap.generation();
+        assert_eq!(
+            bimap.modify_right_by_left(&'z', |r| *r = 0),
+            Err(ModifyError::NotFound)
+        );
+        assert_eq!(bimap, snapshot);
+        assert_eq!(bimap.generation(), generation);
+
+        // a colliding pair can't be put back, so it is dropped
+        assert_eq!(
+            bimap.modify_right_by_left(&'a', |r| *r = 30),
+            Err(ModifyError::Collision)
+        );
+        assert!(bimap.generation() > generation);
+        assert_eq!(bimap, [('b', 20), ('c', 30)]);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn modify_left_by_right() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(1, 'x');
+        bimap.insert(2, 'y');
+        bimap.insert(3, 'z');
+
+        assert_eq!(bimap.modify_left_by_right(&'x', |l| *l = 4), Ok(()));
+        assert_eq!(
+            bimap.iter().collect::<Vec<_>>(),
+            vec![(&2, &'y'), (&3, &'z'), (&4, &'x')]
+        );
+        assert_eq!(
+            bimap.modify_left_by_right(&'y', |l| *l = 3),
+            Err(ModifyError::Collision)
+        );
+        assert!(!bimap.contains_right(&'y'));
+        assert_eq!(bimap.get_by_left(&3), Some(&'z'));
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn modify_right_by_left_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        // the pair is put back with the value as the closure left it
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bimap.modify_right_by_left(&'a', |r| {
+                *r = 3;
+                panic!("oops");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap, [('a', 3), ('b', 2)]);
+        assert_eq!(bimap.first_by_right(), Some((&'b', &2)));
+        assert!(bimap.check_invariants().is_ok());
+
+        // unless the value collides with another pair
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bimap.modify_right_by_left(&'a', |r| {
+                *r = 2;
+                panic!("oops");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap, [('b', 2)]);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn update_by_left_detailed() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        // updating to the current right value doesn't overwrite anything
+        assert_eq!(
+            bimap.update_by_left_detailed(&'a', 1),
+            Some((1, Overwritten::Neither))
+        );
+        assert_eq!(bimap.len(), 2);
+
+        assert_eq!(
+            bimap.update_by_left_detailed(&'b', 1),
+            Some((2, Overwritten::Right('a', 1)))
+        );
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_right(&1), Some(&'b'));
+        assert_eq!(bimap.update_by_left_detailed(&'a', 5), None);
+        assert!(!bimap.contains_right(&5));
+    }
+
+    #[test]
+    fn replace_pair() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        // replacing a pair with itself keeps it
+        assert_eq!(
+            bimap.replace_pair(&'a', 'a', 1),
+            Some((('a', 1), Overwritten::Neither))
+        );
+        assert_eq!(bimap.len(), 3);
+
+        // keeping the left value only changes the right value
+        assert_eq!(
+            bimap.replace_pair(&'a', 'a', 4),
+            Some((('a', 1), Overwritten::Neither))
+        );
+        assert_eq!(bimap.get_by_left(&'a'), Some(&4));
+        assert!(!bimap.contains_right(&1));
+
+        // the new pair overwrites the pairs of both 'b' and 3
+        assert_eq!(
+            bimap.replace_pair(&'a', 'b', 3),
+            Some((('a', 4), Overwritten::Both(('b', 2), ('c', 3))))
+        );
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'b'), Some(&3));
+
+        assert_eq!(bimap.replace_pair(&'z', 'y', 9), None);
+        assert_eq!(bimap.len(), 1);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn what_would_overwrite() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        // each report matches what insert then returns
+        for &(l, r) in &[('d', 4), ('a', 4), ('d', 1), ('b', 2), ('a', 2), ('c', 1)] {
+            let expected = match bimap.what_would_overwrite(&l, &r) {
+                Overwritten::Neither => Overwritten::Neither,
+                Overwritten::Left(l, r) => Overwritten::Left(*l, *r),
+                Overwritten::Right(l, r) => Overwritten::Right(*l, *r),
+                Overwritten::Pair(l, r) => Overwritten::Pair(*l, *r),
+                Overwritten::Unchanged(l, r) => Overwritten::Unchanged(*l, *r),
+                Overwritten::Both((l1, r1), (l2, r2)) => Overwritten::Both((*l1, *r1), (*l2, *r2)),
+            };
+            assert_eq!(bimap.clone().insert(l, r), expected);
+        }
+        assert_eq!(bimap.len(), 3);
+    }
+
+    #[test]
+    fn insert_unchanged_pair() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let generation = bimap.generation();
+
+        // re-inserting an existing pair reports it without evicting anything
+        let unchanged = bimap.insert('a', 1);
+        assert_eq!(unchanged, Overwritten::Unchanged('a', 1));
+        assert!(!unchanged.did_overwrite());
+        assert_eq!(bimap.generation(), generation);
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+        assert!(bimap.check_invariants().is_ok());
+
+        // a pair that only shares its left value is still overwritten
+        assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+        assert!(bimap.generation() > generation);
+    }
+
+    #[test]
+    fn cmp() {
+        let bimap = BiBTreeMap::from_iter(vec![('a', 2)]);
+        let bimap2 = BiBTreeMap::from_iter(vec![('b', 1)]);
+
+        assert_eq!(bimap.partial_cmp(&bimap2), Some(Ordering::Less));
+        assert_eq!(bimap.cmp(&bimap2), Ordering::Less);
+
+        assert_eq!(bimap2.partial_cmp(&bimap), Some(Ordering::Greater));
+        assert_eq!(bimap2.cmp(&bimap), Ordering::Greater);
+
+        assert_eq!(bimap.cmp(&bimap), Ordering::Equal);
+        assert_eq!(bimap2.cmp(&bimap2), Ordering::Equal);
+    }
+
+    #[test]
+    fn cmp_lexicographic() {
+        let empty = BiBTreeMap::<char, i32>::new();
+        let a1 = BiBTreeMap::from_iter(vec![('a', 1)]);
+        let a1_b2 = BiBTreeMap::from_iter(vec![('a', 1), ('b', 2)]);
+        let a1_b3 = BiBTreeMap::from_iter(vec![('b', 3), ('a', 1)]);
+        let a2 = BiBTreeMap::from_iter(vec![('a', 2)]);
+        let b0 = BiBTreeMap::from_iter(vec![('b', 0)]);
+
+        // prefixes compare less
+        assert!(empty < a1);
+        assert!(a1 < a1_b2);
+        // the first differing pair decides, on the right value if the left
+        // values are equal
+        assert!(a1_b2 < a1_b3);
+        assert!(a1_b3 < a2);
+        assert!(a2 < b0);
+
+        // total order laws
+        let samples = [&empty, &a1, &a1_b2, &a1_b3, &a2, &b0];
+        for x in samples.iter() {
+            assert_eq!(x.cmp(x), Ordering::Equal);
+            for y in samples.iter() {
+                assert_eq!(x.cmp(y), y.cmp(x).reverse());
+                assert_eq!(x.cmp(y) == Ordering::Equal, x == y);
+                assert_eq!(x.partial_cmp(y), Some(x.cmp(y)));
+                for z in samples.iter() {
+                    if x <= y && y <= z {
+                        assert!(x <= z);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iter() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+        let pairs = bimap.iter().map(|(c, i)| (*c, *i)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('a', 1), ('b', 2), ('c', 3)]);
+    }
+
+    #[test]
+    fn iter_rev() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let mut iter = bimap.iter();
+        assert_eq!(iter.next_back(), Some((&'c', &3)));
+        assert_eq!(iter.next_back(), Some((&'b', &2)));
+        assert_eq!(iter.next_back(), Some((&'a', &1)));
+    }
+
+    #[test]
+    fn insert_and_get() {
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(*bimap.insert_and_get('a', 1), 1);
+        assert_eq!(*bimap.insert_and_get_left('b', 2), 'b');
+
+        // overwrites both pairs
+        let right = bimap.insert_and_get('a', 2);
+        assert_eq!(*right, 2);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+        assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+        assert!(!bimap.contains_left(&'b'));
+        assert!(!bimap.contains_right(&1));
+
+        // the returned reference points into the bimap
+        let left: *const char = bimap.insert_and_get_left('c', 2);
+        assert!(core::ptr::eq(left, bimap.get_by_right(&2).unwrap()));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn check_invariants() {
+        use crate::InvariantViolation::*;
+
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        assert_eq!(bimap.check_invariants(), Ok(()));
+
+        // a reverse entry is missing
+        bimap.right2left.remove(Wrapper::wrap(&2));
+        assert_eq!(
+            bimap.check_invariants(),
+            Err(vec![
+                Len {
+                    left2right: 2,
+                    right2left: 1,
+                },
+                MissingRight {
+                    left: &'b',
+                    right: &2,
+                },
+            ])
+        );
+
+        // a reverse entry points to the wrong left value
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.right2left.insert(Ref(Rc::new(1)), Ref(Rc::new('c')));
+        assert_eq!(
+            bimap.check_invariants(),
+            Err(vec![
+                WrongLeft {
+                    left: &'a',
+                    right: &1,
+                    found: &'c',
+                },
+                MissingLeft {
+                    left: &'c',
+                    right: &1,
+                },
+            ])
+        );
+
+        // a forward entry points to the wrong right value
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.left2right.insert(Ref(Rc::new('a')), Ref(Rc::new(2)));
+        assert_eq!(
+            bimap.check_invariants(),
+            Err(vec![
+                MissingRight {
+                    left: &'a',
+                    right: &2,
+                },
+                WrongRight {
+                    left: &'a',
+                    right: &1,
+                    found: &2,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn bimap_like() {
+        fn exercise<M: BiMapLike<char, i32>>(bimap: &mut M) {
+            assert!(bimap.is_empty());
+            assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+            assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+            assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+            assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+            assert_eq!(bimap.len(), 2);
+            assert_eq!(bimap.iter().len(), 2);
+            assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+            assert_eq!(bimap.get_by_right(&3), Some(&'c'));
+            assert!(bimap.contains_left(&'c'));
+            assert!(!bimap.contains_right(&1));
+            assert_eq!(bimap.remove_by_left(&'a'), Some(('a', 2)));
+            assert_eq!(bimap.remove_by_right(&3), Some(('c', 3)));
+            assert_eq!(bimap.remove_by_right(&3), None);
+            assert!(bimap.is_empty());
+        }
+
+        exercise(&mut BiBTreeMap::new());
+    }
+
+    #[test]
+    fn iter_exact_size_fused() {
+        fn check<I>(mut iter: I, len: usize)
+        where
+            I: DoubleEndedIterator + ExactSizeIterator + FusedIterator,
+        {
+            for remaining in (0..=len).rev() {
+                assert_eq!(iter.len(), remaining);
+                assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+                // alternate between both ends
+                let item = if remaining % 2 == 0 {
+                    iter.next()
+                } else {
+                    iter.next_back()
+                };
+                assert_eq!(item.is_some(), remaining > 0);
+            }
+            assert!(iter.next().is_none());
+            assert!(iter.next_back().is_none());
+            assert_eq!(iter.len(), 0);
+        }
+
+        let bimap: BiBTreeMap<u32, char> = (0..26).zip('a'..='z').collect();
+        assert_eq!(bimap.iter().len(), bimap.len());
+        check(bimap.iter(), bimap.len());
+        check(bimap.left_values(), bimap.len());
+        check(bimap.right_values(), bimap.len());
+        check(bimap.clone().into_iter(), bimap.len());
+        check(BiBTreeMap::<u32, char>::new().iter(), 0);
+    }
+
+    #[test]
+    fn iter_rev_pairs() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 1);
+        bimap.insert('c', 2);
+
+        let pairs = bimap.iter().rev().collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(&'c', &2), (&'b', &1), (&'a', &3)]);
+
+        let lefts = bimap.left_values().rev().collect::<Vec<_>>();
+        assert_eq!(lefts, vec![&'c', &'b', &'a']);
+        let rights = bimap.right_values().rev().collect::<Vec<_>>();
+        assert_eq!(rights, vec![&3, &2, &1]);
+
+        // both ends can be consumed from the same iterator
+        let mut iter = bimap.iter();
+        assert_eq!(iter.next(), Some((&'a', &3)));
+        assert_eq!(iter.next_back(), Some((&'c', &2)));
+        assert_eq!(iter.next_back(), Some((&'b', &1)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
     }
 
     #[test]
-    fn into_iter_ref() {
+    fn into_iter_rev() {
         let mut bimap = BiBTreeMap::new();
-        bimap.insert('a', 3);
+        bimap.insert('a', 1);
         bimap.insert('b', 2);
-        bimap.insert('c', 1);
-        let pairs = (&bimap).into_iter().collect::<Vec<_>>();
-        assert_eq!(pairs, vec![(&'a', &3), (&'b', &2), (&'c', &1)]);
+        bimap.insert('c', 3);
+
+        let mut iter = bimap.into_iter();
+        assert_eq!(iter.next_back(), Some(('c', 3)));
+        assert_eq!(iter.next_back(), Some(('b', 2)));
+        assert_eq!(iter.next_back(), Some(('a', 1)));
     }
 
     #[test]
-    fn extend() {
+    fn first_last_by_right() {
         let mut bimap = BiBTreeMap::new();
-        bimap.insert('a', 3);
-        bimap.insert('b', 2);
-        bimap.extend(vec![('c', 3), ('b', 1), ('a', 4)]);
-        let mut bimap2 = BiBTreeMap::new();
-        bimap2.insert('a', 4);
-        bimap2.insert('b', 1);
-        bimap2.insert('c', 3);
-        assert_eq!(bimap, bimap2);
+        assert_eq!(bimap.first_by_right(), None);
+        assert_eq!(bimap.last_by_right(), None);
+
+        bimap.insert('a', 1);
+        assert_eq!(bimap.first_by_right(), Some((&'a', &1)));
+        assert_eq!(bimap.last_by_right(), Some((&'a', &1)));
+
+        for (i, c) in ('b'..='j').enumerate() {
+            bimap.insert(c, -(i as i32));
+        }
+        assert_eq!(bimap.first_by_right(), Some((&'j', &-8)));
+        assert_eq!(bimap.last_by_right(), Some((&'a', &1)));
     }
 
     #[test]
-    fn cmp() {
-        let bimap = BiBTreeMap::from_iter(vec![('a', 2)]);
-        let bimap2 = BiBTreeMap::from_iter(vec![('b', 1)]);
+    fn pop_by_right() {
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.pop_first_by_right(), None);
+        assert_eq!(bimap.pop_last_by_right(), None);
 
-        assert_eq!(bimap.partial_cmp(&bimap2), Some(Ordering::Less));
-        assert_eq!(bimap.cmp(&bimap2), Ordering::Less);
+        bimap.insert('a', 3);
+        bimap.insert('b', 1);
+        bimap.insert('c', 4);
+        bimap.insert('d', 2);
 
-        assert_eq!(bimap2.partial_cmp(&bimap), Some(Ordering::Greater));
-        assert_eq!(bimap2.cmp(&bimap), Ordering::Greater);
+        assert_eq!(bimap.pop_first_by_right(), Some(('b', 1)));
+        assert_eq!(bimap.pop_last_by_right(), Some(('c', 4)));
+        assert_eq!(bimap.len(), 2);
+        assert!(!bimap.contains_left(&'b'));
+        assert!(!bimap.contains_left(&'c'));
+        assert_eq!(bimap.first_by_right(), Some((&'d', &2)));
+        assert_eq!(bimap.last_by_right(), Some((&'a', &3)));
 
-        assert_eq!(bimap.cmp(&bimap), Ordering::Equal);
-        assert_eq!(bimap2.cmp(&bimap2), Ordering::Equal);
+        // owned values without Copy are returned intact
+        use alloc::string::String;
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(String::from("x"), String::from("2"));
+        bimap.insert(String::from("y"), String::from("1"));
+        assert_eq!(
+            bimap.pop_first_by_right(),
+            Some((String::from("y"), String::from("1")))
+        );
     }
 
     #[test]
-    fn iter() {
+    fn peek_and_pop() {
         let mut bimap = BiBTreeMap::new();
-        bimap.insert('a', 1);
+        assert_eq!(bimap.peek(), None);
+        assert_eq!(bimap.pop(), None);
+
+        bimap.insert('c', 1);
+        bimap.insert('a', 3);
         bimap.insert('b', 2);
-        bimap.insert('c', 3);
-        let pairs = bimap.iter().map(|(c, i)| (*c, *i)).collect::<Vec<_>>();
-        assert_eq!(pairs, vec![('a', 1), ('b', 2), ('c', 3)]);
+
+        // look at the front of the worklist before deciding to take it
+        while let Some((&left, &right)) = bimap.peek() {
+            assert_eq!(bimap.peek(), Some((&left, &right)));
+            assert_eq!(bimap.pop(), Some((left, right)));
+            assert!(!bimap.contains_right(&right));
+        }
+        assert!(bimap.is_empty());
+
+        bimap.insert('c', 1);
+        bimap.insert('a', 3);
+        assert_eq!(bimap.pop(), Some(('a', 3)));
+        assert_eq!(bimap.peek(), Some((&'c', &1)));
     }
 
     #[test]
-    fn iter_rev() {
+    fn lease_by_left() {
         let mut bimap = BiBTreeMap::new();
         bimap.insert('a', 1);
         bimap.insert('b', 2);
-        bimap.insert('c', 3);
+        assert!(bimap.lease_by_left(&'z').is_none());
 
-        let mut iter = bimap.iter();
-        assert_eq!(iter.next_back(), Some((&'c', &3)));
-        assert_eq!(iter.next_back(), Some((&'b', &2)));
-        assert_eq!(iter.next_back(), Some((&'a', &1)));
+        let lease = bimap.lease_by_left(&'a').unwrap();
+        assert_eq!((lease.left(), *lease), (&'a', 1));
+        lease.keep();
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+        assert!(bimap.check_invariants().is_ok());
+
+        let generation = bimap.generation();
+        {
+            let lease = bimap.lease_by_left(&'a').unwrap();
+            assert_eq!(*lease, 1);
+        }
+        assert!(bimap.generation() > generation);
+        assert!(!bimap.contains_left(&'a'));
+        assert!(!bimap.contains_right(&1));
+
+        let lease = bimap.lease_by_left(&'b').unwrap();
+        assert_eq!(lease.take(), ('b', 2));
+        assert!(bimap.is_empty());
+        assert!(bimap.check_invariants().is_ok());
     }
 
     #[test]
-    fn into_iter_rev() {
+    fn fold_pairs() {
         let mut bimap = BiBTreeMap::new();
         bimap.insert('a', 1);
         bimap.insert('b', 2);
         bimap.insert('c', 3);
 
-        let mut iter = bimap.into_iter();
-        assert_eq!(iter.next_back(), Some(('c', 3)));
-        assert_eq!(iter.next_back(), Some(('b', 2)));
-        assert_eq!(iter.next_back(), Some(('a', 1)));
+        let count = bimap.fold_pairs(0, |acc, _l, _r| acc + 1);
+        assert_eq!(count, 3);
+        let pairs = bimap.fold_pairs(Vec::new(), |mut acc, l, r| {
+            acc.push((*l, *r));
+            acc
+        });
+        assert_eq!(pairs, vec![('a', 1), ('b', 2), ('c', 3)]);
+        assert_eq!(
+            BiBTreeMap::<char, i32>::new().fold_pairs(7, |acc, _l, _r| acc + 1),
+            7
+        );
     }
 
     #[test]
@@ -1246,12 +3942,40 @@ mod tests {
         assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
         assert_eq!(bimap.insert('a', 2), Overwritten::Left('a', 1));
         assert_eq!(bimap.insert('b', 2), Overwritten::Right('a', 2));
-        assert_eq!(bimap.insert('b', 2), Overwritten::Pair('b', 2));
+        assert_eq!(bimap.insert('b', 2), Overwritten::Unchanged('b', 2));
 
         assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
         assert_eq!(bimap.insert('b', 3), Overwritten::Both(('b', 2), ('c', 3)));
     }
 
+    #[test]
+    fn insert_returns_evicted_pairs() {
+        use alloc::boxed::Box;
+
+        // boxes aren't cloned, so matching addresses show that the evicted
+        // values are moved out of the bimap
+        let (a, one) = (Box::new('a'), Box::new(1));
+        let (b, two) = (Box::new('b'), Box::new(2));
+        let addresses = [&*a as *const char, &*b as *const char];
+        let (one_addr, two_addr) = (&*one as *const i32, &*two as *const i32);
+
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert(a, one);
+        bimap.insert(b, two);
+        match bimap.insert(Box::new('a'), Box::new(2)) {
+            Overwritten::Both((l1, r1), (l2, r2)) => {
+                assert_eq!((*l1, *r1, *l2, *r2), ('a', 1, 'b', 2));
+                assert_eq!([&*l1 as *const char, &*l2 as *const char], addresses);
+                assert_eq!(
+                    (&*r1 as *const i32, &*r2 as *const i32),
+                    (one_addr, two_addr)
+                );
+            }
+            other => panic!("expected Overwritten::Both, got {:?}", other),
+        }
+        assert_eq!(bimap.len(), 1);
+    }
+
     #[test]
     fn insert_no_overwrite() {
         let mut bimap = BiBTreeMap::new();
@@ -1261,8 +3985,310 @@ mod tests {
         assert!(bimap.insert_no_overwrite('b', 1).is_err());
     }
 
+    #[test]
+    fn insert_no_overwrite_leaves_map_unchanged() {
+        use alloc::string::{String, ToString};
+
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.insert_no_overwrite("a".to_string(), 1), Ok(()));
+        assert_eq!(bimap.insert_no_overwrite("b".to_string(), 2), Ok(()));
+        assert_eq!(bimap.get_by_left("a"), Some(&1));
+        assert_eq!(bimap.get_by_right(&2).map(String::as_str), Some("b"));
+
+        let snapshot = bimap.clone();
+        let generation = bimap.generation();
+        let rejected = [
+            // only the left value exists
+            ("a", 3),
+            // only the right value exists
+            ("c", 1),
+            // both values exist, but in different pairs
+            ("a", 2),
+            // the exact pair already exists
+            ("b", 2),
+        ];
+        for &(left, right) in &rejected {
+            assert_eq!(
+                bimap.insert_no_overwrite(left.to_string(), right),
+                Err((left.to_string(), right))
+            );
+            assert_eq!(bimap, snapshot);
+            assert_eq!(bimap.generation(), generation);
+            assert!(bimap.check_invariants().is_ok());
+        }
+    }
+
+    #[test]
+    fn try_insert() {
+        use alloc::string::ToString;
+
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.try_insert('a', 1), Ok((&'a', &1)));
+        assert_eq!(bimap.try_insert('b', 2), Ok((&'b', &2)));
+        let snapshot = bimap.clone();
+
+        // left conflict
+        let error = bimap.try_insert('a', 3).unwrap_err();
+        assert_eq!(error.pair, ('a', 3));
+        assert_eq!(error.left_conflict, Some((&'a', &1)));
+        assert_eq!(error.right_conflict, None);
+        assert_eq!(error.to_string(), "left value already present in bimap");
+
+        // right conflict
+        let error = bimap.try_insert('c', 2).unwrap_err();
+        assert_eq!(error.pair, ('c', 2));
+        assert_eq!(error.left_conflict, None);
+        assert_eq!(error.right_conflict, Some((&'b', &2)));
+        assert_eq!(error.to_string(), "right value already present in bimap");
+
+        // conflicts with two different pairs
+        let error = bimap.try_insert('a', 2).unwrap_err();
+        assert_eq!(error.pair, ('a', 2));
+        assert_eq!(error.left_conflict, Some((&'a', &1)));
+        assert_eq!(error.right_conflict, Some((&'b', &2)));
+        assert_eq!(
+            error.to_string(),
+            "left and right values already present in bimap"
+        );
+
+        // the exact pair is already present
+        let error = bimap.try_insert('b', 2).unwrap_err();
+        assert_eq!(error.left_conflict, error.right_conflict);
+
+        assert_eq!(bimap, snapshot);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn retain_mut_right() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+        bimap.insert('d', 4);
+
+        bimap.retain_mut_right(|_l, r| {
+            *r = 10 - *r;
+            *r % 2 == 0
+        });
+        let pairs = bimap.iter().map(|(l, r)| (*l, *r)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('b', 8), ('d', 6)]);
+        let pairs = bimap
+            .right_range(..)
+            .map(|(l, r)| (*l, *r))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('d', 6), ('b', 8)]);
+    }
+
+    #[test]
+    fn retain_mut_right_collision() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        bimap.retain_mut_right(|&l, r| {
+            if l != 'a' {
+                *r = 0;
+            }
+            true
+        });
+        let pairs = bimap.iter().map(|(l, r)| (*l, *r)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('a', 1), ('b', 0)]);
+        assert_eq!(bimap.right2left.len(), 2);
+    }
+
+    #[test]
+    fn range_replace_right() {
+        let mut bimap: BiBTreeMap<i32, i32> = (0..10).map(|i| (i, i)).collect();
+        let generation = bimap.generation();
+
+        let mut visited = Vec::new();
+        bimap.range_replace_right(3..6, |&l, r| {
+            visited.push(l);
+            *r = 100 - *r;
+        });
+        assert_eq!(visited, vec![3, 4, 5]);
+        assert!(bimap.generation() > generation);
+        assert_eq!(bimap.get_by_left(&4), Some(&96));
+        assert_eq!(bimap.get_by_right(&95), Some(&5));
+        assert_eq!(bimap.get_by_right(&4), None);
+        assert_eq!(bimap.get_by_right(&6), Some(&6));
+        let lefts = bimap.right_range(..).map(|(l, _)| *l).collect::<Vec<_>>();
+        assert_eq!(lefts, vec![0, 1, 2, 6, 7, 8, 9, 5, 4, 3]);
+        assert!(bimap.check_invariants().is_ok());
+
+        // swapping right values within the range doesn't collide
+        bimap.range_replace_right(..2, |_, r| *r = 1 - *r);
+        assert_eq!(bimap.get_by_right(&0), Some(&1));
+        assert_eq!(bimap.get_by_right(&1), Some(&0));
+        assert!(bimap.check_invariants().is_ok());
+
+        // an empty range changes nothing
+        bimap.range_replace_right(20.., |_, _| unreachable!());
+        assert_eq!(bimap.len(), 10);
+    }
+
+    #[cfg(all(feature = "std", debug_assertions))]
+    #[test]
+    fn range_replace_right_collision() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bimap.range_replace_right('b'..='c', |_, r| *r = 1);
+        }));
+        assert!(result.is_err());
+        // the reverse map has been rebuilt, keeping the smallest left value
+        let pairs = bimap.iter().map(|(l, r)| (*l, *r)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('a', 1)]);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn retain_keeps_first_k_in_left_order() {
+        let mut bimap = BiBTreeMap::new();
+        for (l, r) in [(5, 'a'), (1, 'e'), (4, 'b'), (2, 'd'), (3, 'c')] {
+            bimap.insert(l, r);
+        }
+
+        let mut count = 0;
+        let mut visited = Vec::new();
+        bimap.retain(|&l, _| {
+            visited.push(l);
+            count += 1;
+            count <= 3
+        });
+        assert_eq!(visited, vec![1, 2, 3, 4, 5]);
+        let pairs = bimap.iter().map(|(l, r)| (*l, *r)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(1, 'e'), (2, 'd'), (3, 'c')]);
+        assert!(!bimap.contains_right(&'a'));
+        assert!(!bimap.contains_right(&'b'));
+    }
+
+    #[test]
+    fn retain_count() {
+        let mut bimap: BiBTreeMap<i32, i32> = (0..10).map(|i| (i, -i)).collect();
+        assert_eq!(bimap.retain_count(|&l, _| l % 3 == 0), 6);
+        assert_eq!(bimap.len(), 4);
+        assert!(bimap.check_invariants().is_ok());
+        assert_eq!(bimap.retain_count(|_, _| true), 0);
+        assert_eq!(bimap.retain_count(|_, _| false), 4);
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn retain_intersection_and_remove_all() {
+        let live: BiBTreeMap<char, i32> = vec![('a', 1), ('b', 2), ('c', 3), ('d', 4)]
+            .into_iter()
+            .collect();
+        // ('a', 1) matches, 'b' and 3 are paired with other values, 'd' is
+        // missing and ('e', 5) isn't in the live bimap
+        let snapshot: BiBTreeMap<char, i32> = vec![('a', 1), ('b', 7), ('x', 3), ('e', 5)]
+            .into_iter()
+            .collect();
+
+        let mut intersection = live.clone();
+        intersection.retain_intersection(&snapshot);
+        assert_eq!(intersection.iter().collect::<Vec<_>>(), [(&'a', &1)]);
+        assert!(intersection.check_invariants().is_ok());
+
+        let mut difference = live.clone();
+        difference.remove_all(&snapshot);
+        assert_eq!(difference.len(), 3);
+        assert!(!difference.contains_left(&'a'));
+        assert!(!difference.contains_right(&1));
+        assert!(difference.check_invariants().is_ok());
+
+        let mut same = live.clone();
+        same.retain_intersection(&live);
+        assert_eq!(same, live);
+        same.remove_all(&live);
+        assert!(same.is_empty());
+    }
+
+    #[test]
+    fn retain_mut_right_calls_f_in_order() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('c', 1);
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+
+        let mut visited = Vec::new();
+        bimap.retain_mut_right(|&l, _r| {
+            visited.push(l);
+            true
+        });
+        assert_eq!(visited, vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn new_in_static() {
+        use std::sync::Mutex;
+
+        static BIMAP: Mutex<BiBTreeMap<u8, char>> = Mutex::new(BiBTreeMap::new());
+
+        let mut bimap = BIMAP.lock().unwrap();
+        assert!(bimap.is_empty());
+        bimap.insert(1, 'a');
+        assert_eq!(bimap.get_by_right(&'a'), Some(&1));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_hash() {
+        use crate::BiHashMap;
+
+        let hash: BiHashMap<_, _> = (0..100).rev().map(|i| (i, 99 - i)).collect();
+        let btree = BiBTreeMap::from(hash);
+        assert_eq!(btree.len(), 100);
+        assert!(btree.left_values().copied().eq(0..100));
+        assert!(btree.right_values().copied().eq(0..100));
+        for (l, r) in &btree {
+            assert_eq!(btree.get_by_right(r), Some(l));
+        }
+
+        assert!(BiBTreeMap::from(BiHashMap::<u8, u8>::new()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_consistent_with_eq() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let bimap = BiBTreeMap::from_iter(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+        let mut bimap2 = BiBTreeMap::new();
+        bimap2.insert(3, 'c');
+        bimap2.insert(2, 'z');
+        bimap2.insert(1, 'a');
+        bimap2.insert(2, 'b');
+        assert_eq!(bimap, bimap2);
+        assert_eq!(hash_of(&bimap), hash_of(&bimap2));
+
+        bimap2.remove_by_left(&3);
+        assert_ne!(hash_of(&bimap), hash_of(&bimap2));
+        assert_eq!(
+            hash_of(&BiBTreeMap::<u8, u8>::new()),
+            hash_of(&BiBTreeMap::<u8, u8>::new())
+        );
+    }
+
     #[test]
     #[cfg(feature = "std")]
+    // the lookup counters of the `stats` feature don't take part in hashing
+    // or equality, so bimaps are fine as keys
+    #[allow(clippy::mutable_key_type)]
     fn hash() {
         use core::iter::{self, FromIterator};
         use std::collections::HashSet;
@@ -1286,4 +4312,216 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn into_btreesets() {
+        use alloc::string::{String, ToString};
+
+        let bimap = (0..10)
+            .map(|i| (i.to_string(), 10 - i))
+            .collect::<BiBTreeMap<String, _>>();
+        let lefts = bimap.clone().into_left_btreeset();
+        assert!(lefts.iter().eq(bimap.left_values()));
+        let rights = bimap.into_right_btreeset();
+        assert!(rights.into_iter().eq(1..=10));
+    }
+
+    #[test]
+    fn to_maps() {
+        use alloc::string::{String, ToString};
+
+        let bimap = (0..10)
+            .map(|i| (i.to_string(), 10 - i))
+            .collect::<BiBTreeMap<String, _>>();
+        assert!(bimap.to_btree_map().iter().eq(bimap.iter()));
+        assert!(bimap.to_btree_map_by_right().iter().eq(bimap
+            .right_values()
+            .map(|r| (r, bimap.get_by_right(r).unwrap()))));
+        #[cfg(feature = "std")]
+        {
+            let forward = bimap.to_hash_map();
+            let backward = bimap.to_hash_map_by_right();
+            for (l, r) in &bimap {
+                assert_eq!(forward.get(l), Some(r));
+                assert_eq!(backward.get(r), Some(l));
+            }
+        }
+    }
+
+    #[test]
+    fn generation() {
+        let mut bimap = BiBTreeMap::new();
+        let mut last = bimap.generation();
+        let mut changed = |bimap: &BiBTreeMap<char, i32>| {
+            let changed = bimap.generation() > last;
+            last = bimap.generation();
+            changed
+        };
+        assert!(!changed(&bimap));
+
+        bimap.insert('a', 1);
+        assert!(changed(&bimap));
+        bimap.insert('a', 2);
+        assert!(changed(&bimap));
+        assert!(bimap.insert_no_overwrite('b', 3).is_ok());
+        assert!(changed(&bimap));
+        assert!(bimap.insert_no_overwrite('b', 4).is_err());
+        assert!(!changed(&bimap));
+        bimap.extend(vec![('c', 5), ('d', 6), ('e', 7)]);
+        assert!(changed(&bimap));
+
+        assert_eq!(bimap.remove_by_left(&'z'), None);
+        assert_eq!(bimap.remove_by_right(&0), None);
+        assert!(bimap.contains_left(&'a'));
+        assert_eq!(bimap.first_by_right(), Some((&'a', &2)));
+        assert!(!changed(&bimap));
+
+        assert!(bimap.remove_by_left(&'a').is_some());
+        assert!(changed(&bimap));
+        assert!(bimap.remove_by_right(&3).is_some());
+        assert!(changed(&bimap));
+        assert!(bimap.pop_first_by_right().is_some());
+        assert!(changed(&bimap));
+        assert!(bimap.pop_last_by_right().is_some());
+        assert!(changed(&bimap));
+        bimap.retain(|_, _| true);
+        assert!(changed(&bimap));
+        bimap.retain_mut_right(|_, r| {
+            *r += 1;
+            true
+        });
+        assert!(changed(&bimap));
+        bimap.clear();
+        assert!(changed(&bimap));
+        assert_eq!(bimap.pop_first_by_right(), None);
+        assert!(!changed(&bimap));
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn lookup_stats() {
+        use crate::LookupStats;
+
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        assert_eq!(bimap.lookup_stats(), LookupStats::default());
+
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_left(&'z'), None);
+        assert!(bimap.contains_left(&'b'));
+        assert_eq!(bimap.try_get_by_right(&2), Ok(&'b'));
+        assert!(!bimap.contains_right(&9));
+        assert_eq!(
+            bimap.lookup_stats(),
+            LookupStats {
+                by_left: 3,
+                by_right: 2,
+                hits: 3,
+                misses: 2,
+            }
+        );
+
+        // clones start counting from scratch
+        let clone = bimap.clone();
+        assert_eq!(clone.lookup_stats(), LookupStats::default());
+
+        bimap.reset_lookup_stats();
+        assert_eq!(bimap.lookup_stats(), LookupStats::default());
+    }
+
+    #[test]
+    fn apply_batch() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        // every operation applies, in order
+        let batch = vec![
+            BatchOp::RemoveByRight(2),
+            BatchOp::Insert('b', 3),
+            BatchOp::ReplaceRight { old: 1, new: 2 },
+            BatchOp::ReplaceLeft { old: 'a', new: 'a' },
+        ];
+        assert_eq!(bimap.apply_batch(batch), Ok(()));
+        assert_eq!(bimap, [('a', 2), ('b', 3)]);
+
+        // later operations see the pairs inserted by earlier ones
+        let batch = vec![
+            BatchOp::Insert('c', 4),
+            BatchOp::ReplaceLeft { old: 'c', new: 'd' },
+            BatchOp::RemoveByLeft('a'),
+        ];
+        assert_eq!(bimap.apply_batch(batch), Ok(()));
+        assert_eq!(bimap, [('b', 3), ('d', 4)]);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn apply_batch_conflict() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let before = bimap.clone();
+        let generation = bimap.generation();
+
+        let failing = [
+            (BatchOp::Insert('z', 3), ModifyError::Collision),
+            (BatchOp::RemoveByLeft('b'), ModifyError::NotFound),
+            (BatchOp::RemoveByRight(9), ModifyError::NotFound),
+            (
+                BatchOp::ReplaceLeft { old: 'c', new: 'a' },
+                ModifyError::Collision,
+            ),
+            (
+                BatchOp::ReplaceRight { old: 2, new: 3 },
+                ModifyError::NotFound,
+            ),
+        ];
+        for (op, reason) in failing {
+            // the removal and insertion before the failing operation are
+            // never applied
+            let batch = vec![BatchOp::RemoveByLeft('b'), BatchOp::Insert('c', 3), op];
+            let error = bimap.apply_batch(batch).unwrap_err();
+            assert_eq!(
+                error,
+                BatchError {
+                    index: 2,
+                    op,
+                    reason
+                }
+            );
+            assert_eq!(bimap, before);
+            assert_eq!(bimap.generation(), generation);
+        }
+
+        assert_eq!(
+            bimap.apply_batch(vec![BatchOp::RemoveByLeft('z')]),
+            Err(BatchError {
+                index: 0,
+                op: BatchOp::RemoveByLeft('z'),
+                reason: ModifyError::NotFound,
+            })
+        );
+        assert_eq!(bimap, before);
+    }
+
+    #[test]
+    fn take() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let generation = bimap.generation();
+
+        let pairs = bimap.take();
+        assert_eq!(pairs, [('a', 1), ('b', 2)]);
+        assert!(bimap.is_empty());
+        assert!(bimap.generation() > generation);
+        assert!(bimap.check_invariants().is_ok());
+        assert!(bimap.take().is_empty());
+
+        // the bimap is usable after being emptied
+        bimap.insert('c', 3);
+        assert_eq!(bimap.get_by_left(&'c'), Some(&3));
+    }
 }
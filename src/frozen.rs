@@ -0,0 +1,610 @@
+//! An immutable bimap laid out for fast, compact lookups.
+//!
+//! A [`FrozenBiMap`] is created from a finished [`BiHashMap`] with
+//! [`BiHashMap::freeze`]. It stores the left values and the right values in
+//! two parallel slices, exactly as large as needed, and finds them through two
+//! open-addressing tables of indices into those slices, one for each
+//! direction. Compared to a `BiHashMap` this needs less memory and fewer
+//! pointer indirections per lookup, and since it can't be modified, it is
+//! `Send` and `Sync` whenever its values and hashers are, so one table can be
+//! shared by many threads behind an `Arc`.
+//!
+//! Since the values are stored densely, [`left_values`] and [`right_values`]
+//! return them as plain slices, which can be scanned quickly or handed to
+//! code that expects contiguous memory, such as an FFI call.
+//!
+//! Use [`thaw`](FrozenBiMap::thaw) to turn it back into a `BiHashMap`.
+//!
+//! [`left_values`]: FrozenBiMap::left_values
+//! [`right_values`]: FrozenBiMap::right_values
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiHashMap;
+//! use std::{sync::Arc, thread};
+//!
+//! let mut bimap = BiHashMap::new();
+//! for (i, name) in ["zero", "one", "two", "three"].iter().enumerate() {
+//!     bimap.insert(i, *name);
+//! }
+//! let frozen = Arc::new(bimap.freeze());
+//!
+//! let handles: Vec<_> = (0..4)
+//!     .map(|i| {
+//!         let frozen = Arc::clone(&frozen);
+//!         thread::spawn(move || *frozen.get_by_left(&i).unwrap())
+//!     })
+//!     .collect();
+//! let names: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+//! assert_eq!(names, ["zero", "one", "two", "three"]);
+//! assert_eq!(frozen.get_by_right(&"two"), Some(&2));
+//! ```
+
+use crate::{mem::hash_one, BiHashMap};
+use std::{
+    borrow::Borrow,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{FromIterator, FusedIterator, Zip},
+    slice,
+};
+
+/// Marks a slot of an index table that holds no pair.
+const EMPTY: usize = usize::MAX;
+
+/// An immutable bimap optimized for lookups, created by
+/// [`BiHashMap::freeze`].
+///
+/// The pairs are iterated in the order in which the original `BiHashMap`
+/// iterated them.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::frozen
+#[derive(Clone)]
+pub struct FrozenBiMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    // the pair at index i is (lefts[i], rights[i])
+    lefts: Box<[L]>,
+    rights: Box<[R]>,
+    // open-addressing tables with linear probing, holding indices into lefts
+    // and rights
+    left_slots: Box<[usize]>,
+    right_slots: Box<[usize]>,
+    left_hasher: LS,
+    right_hasher: RS,
+}
+
+impl<L, R, LS, RS> FrozenBiMap<L, R, LS, RS> {
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let bimap: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// assert_eq!(bimap.freeze().len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.lefts.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::new();
+    /// assert!(bimap.freeze().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.lefts.is_empty()
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap.
+    ///
+    /// The iterator element type is `(&'a L, &'a R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let bimap: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let frozen = bimap.freeze();
+    ///
+    /// for (left, right) in frozen.iter() {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.lefts.iter().zip(self.rights.iter()),
+        }
+    }
+
+    /// Returns the left values in the bimap as a contiguous slice.
+    ///
+    /// The order of the values is unspecified, but it is the same order in
+    /// which [`iter`] yields the pairs, so `left_values()[i]` and
+    /// `right_values()[i]` always form a pair, and it doesn't change for the
+    /// lifetime of the frozen bimap.
+    ///
+    /// [`iter`]: FrozenBiMap::iter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let bimap: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let frozen = bimap.freeze();
+    /// let lefts = frozen.left_values();
+    /// assert_eq!(lefts.len(), 2);
+    /// assert!(lefts.contains(&'a') && lefts.contains(&'b'));
+    /// ```
+    pub fn left_values(&self) -> &[L] {
+        &self.lefts
+    }
+
+    /// Returns the right values in the bimap as a contiguous slice.
+    ///
+    /// The order of the values is unspecified, but it is the same order in
+    /// which [`iter`] yields the pairs, so `left_values()[i]` and
+    /// `right_values()[i]` always form a pair, and it doesn't change for the
+    /// lifetime of the frozen bimap.
+    ///
+    /// [`iter`]: FrozenBiMap::iter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let bimap: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+    /// let frozen = bimap.freeze();
+    /// let total: i32 = frozen.right_values().iter().sum();
+    /// assert_eq!(total, 3);
+    /// ```
+    pub fn right_values(&self) -> &[R] {
+        &self.rights
+    }
+}
+
+impl<L, R, LS, RS> FrozenBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Creates a frozen bimap from pairs whose left values and right values
+    /// are known to be unique.
+    pub(crate) fn from_unique_pairs(pairs: Vec<(L, R)>, left_hasher: LS, right_hasher: RS) -> Self {
+        let (lefts, rights): (Vec<L>, Vec<R>) = pairs.into_iter().unzip();
+        let left_slots = build_slots(&left_hasher, lefts.iter());
+        let right_slots = build_slots(&right_hasher, rights.iter());
+        FrozenBiMap {
+            lefts: lefts.into_boxed_slice(),
+            rights: rights.into_boxed_slice(),
+            left_slots,
+            right_slots,
+            left_hasher,
+            right_hasher,
+        }
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// let frozen = bimap.freeze();
+    /// assert_eq!(frozen.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(frozen.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left).map(|index| &self.rights[index])
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// let frozen = bimap.freeze();
+    /// assert_eq!(frozen.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(frozen.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_right(right).map(|index| &self.lefts[index])
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// let frozen = bimap.freeze();
+    /// assert!(frozen.contains_left(&'a'));
+    /// assert!(!frozen.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_left(left).is_some()
+    }
+
+    /// Returns `true` if the map contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// let frozen = bimap.freeze();
+    /// assert!(frozen.contains_right(&1));
+    /// assert!(!frozen.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_right(right).is_some()
+    }
+
+    /// Consumes the frozen bimap and returns a mutable `BiHashMap` with the
+    /// same pairs and hashers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// let frozen = bimap.clone().freeze();
+    ///
+    /// let mut thawed = frozen.thaw();
+    /// assert_eq!(thawed, bimap);
+    /// thawed.insert('b', 2);
+    /// ```
+    pub fn thaw(self) -> BiHashMap<L, R, LS, RS> {
+        let mut bimap =
+            BiHashMap::with_capacity_and_hashers(self.len(), self.left_hasher, self.right_hasher);
+        let pairs = self
+            .lefts
+            .into_vec()
+            .into_iter()
+            .zip(self.rights.into_vec());
+        for (left, right) in pairs {
+            bimap.insert(left, right);
+        }
+        bimap
+    }
+
+    fn find_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = hash_one(&self.left_hasher, left);
+        probe(&self.left_slots, hash, |index| {
+            self.lefts[index].borrow() == left
+        })
+    }
+
+    fn find_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = hash_one(&self.right_hasher, right);
+        probe(&self.right_slots, hash, |index| {
+            self.rights[index].borrow() == right
+        })
+    }
+}
+
+/// Builds an index table for the given unique values, with room to spare so
+/// that probe sequences stay short.
+fn build_slots<'a, T, S, I>(hasher: &S, values: I) -> Box<[usize]>
+where
+    T: Hash + 'a,
+    S: BuildHasher,
+    I: ExactSizeIterator<Item = &'a T>,
+{
+    // keep the load factor at or below 3/4
+    let len = values.len();
+    let mut slots = vec![EMPTY; (len + len / 3 + 1).next_power_of_two()].into_boxed_slice();
+    let mask = slots.len() - 1;
+    for (index, value) in values.enumerate() {
+        let mut slot = hash_one(hasher, value) as usize & mask;
+        while slots[slot] != EMPTY {
+            slot = (slot + 1) & mask;
+        }
+        slots[slot] = index;
+    }
+    slots
+}
+
+/// Finds the index of the pair that `matches` accepts, starting at the slot
+/// for `hash` and stopping at the first empty slot.
+fn probe<F>(slots: &[usize], hash: u64, matches: F) -> Option<usize>
+where
+    F: Fn(usize) -> bool,
+{
+    // there is always at least one empty slot, so the loop terminates
+    let mask = slots.len() - 1;
+    let mut slot = hash as usize & mask;
+    loop {
+        match slots[slot] {
+            EMPTY => return None,
+            index if matches(index) => return Some(index),
+            _ => slot = (slot + 1) & mask,
+        }
+    }
+}
+
+impl<L, R, LS, RS> fmt::Debug for FrozenBiMap<L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct EntryDebugger<'a, L, R> {
+            left: &'a L,
+            right: &'a R,
+        }
+        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+        where
+            L: fmt::Debug,
+            R: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.left.fmt(f)?;
+                write!(f, " <> ")?;
+                self.right.fmt(f)
+            }
+        }
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Eq for FrozenBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+}
+
+/// Collects the pairs into a `BiHashMap` and freezes it, so later pairs
+/// overwrite earlier conflicting ones as with [`BiHashMap::insert`].
+impl<L, R, LS, RS> FromIterator<(L, R)> for FrozenBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone + Default,
+    RS: BuildHasher + Clone + Default,
+{
+    fn from_iter<I>(iter: I) -> FrozenBiMap<L, R, LS, RS>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        BiHashMap::from_iter(iter).freeze()
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for &'a FrozenBiMap<L, R, LS, RS> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+impl<L, R, LS, RS> PartialEq for FrozenBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(l, r)| other.get_by_left(l) == Some(r))
+    }
+}
+
+/// An iterator over the left-right pairs in a `FrozenBiMap`.
+///
+/// This struct is created by the [`iter`] method of `FrozenBiMap`.
+///
+/// [`iter`]: FrozenBiMap::iter
+#[derive(Debug, Clone)]
+pub struct Iter<'a, L, R> {
+    inner: Zip<slice::Iter<'a, L>, slice::Iter<'a, R>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a deterministic sequence of pseudo-random numbers.
+    fn random_numbers(seed: u64, count: usize) -> Vec<u64> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                // xorshift64
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lookup_parity() {
+        for (seed, len) in [(1, 0), (2, 1), (3, 7), (4, 100), (5, 2000)] {
+            let numbers = random_numbers(seed, 2 * len);
+            // small domains so that some inserts overwrite earlier pairs
+            let domain = 2 * len as u64 + 1;
+            let bimap: BiHashMap<u64, String> = numbers
+                .chunks(2)
+                .map(|pair| (pair[0] % domain, (pair[1] % domain).to_string()))
+                .collect();
+            let frozen = bimap.clone().freeze();
+            assert_eq!(frozen.len(), bimap.len());
+
+            for probe in random_numbers(seed + 100, 200)
+                .into_iter()
+                .map(|n| n % domain)
+                .chain(bimap.left_values().copied())
+            {
+                assert_eq!(frozen.get_by_left(&probe), bimap.get_by_left(&probe));
+                assert_eq!(frozen.contains_left(&probe), bimap.contains_left(&probe));
+                let right = probe.to_string();
+                assert_eq!(frozen.get_by_right(&*right), bimap.get_by_right(&*right));
+                assert_eq!(
+                    frozen.contains_right(&*right),
+                    bimap.contains_right(&*right)
+                );
+            }
+            for (l, r) in &frozen {
+                assert_eq!(bimap.get_by_left(l), Some(r));
+            }
+
+            assert_eq!(frozen.thaw(), bimap);
+        }
+    }
+
+    #[test]
+    fn iter_matches_original() {
+        let bimap: BiHashMap<_, _> = (0..50).map(|i| (i, -i)).collect();
+        let pairs: Vec<_> = bimap.iter().map(|(&l, &r)| (l, r)).collect();
+        let frozen = bimap.freeze();
+        assert_eq!(
+            frozen.iter().map(|(&l, &r)| (l, r)).collect::<Vec<_>>(),
+            pairs
+        );
+        assert_eq!(frozen.left_values().len(), 50);
+        assert_eq!(frozen.right_values().len(), 50);
+    }
+
+    #[test]
+    fn value_slices() {
+        let bimap: BiHashMap<_, _> = (0..100).map(|i| (i, i.to_string())).collect();
+        let frozen = bimap.freeze();
+        let lefts = frozen.left_values();
+        let rights = frozen.right_values();
+        assert_eq!(lefts.len(), 100);
+        assert_eq!(rights.len(), 100);
+        for (i, (l, r)) in frozen.iter().enumerate() {
+            assert_eq!(&lefts[i], l);
+            assert_eq!(&rights[i], r);
+            assert_eq!(frozen.get_by_left(&lefts[i]), Some(&rights[i]));
+        }
+        let mut sorted = lefts.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        let frozen = bimap.freeze();
+        let clone = frozen.clone();
+        assert_eq!(frozen, clone);
+        assert_eq!(format!("{:?}", clone), "{'a' <> 1}");
+
+        let mut other = BiHashMap::new();
+        other.insert('a', 2);
+        assert_ne!(frozen, other.freeze());
+    }
+
+    #[test]
+    fn from_iter() {
+        let frozen: FrozenBiMap<char, i32> =
+            vec![('a', 1), ('b', 2), ('a', 2)].into_iter().collect();
+        assert_eq!(frozen.len(), 1);
+        assert_eq!(frozen.get_by_left(&'a'), Some(&2));
+        assert_eq!(frozen.get_by_right(&1), None);
+    }
+
+    #[test]
+    fn send_sync() {
+        fn check<T: Send + Sync>() {}
+        check::<FrozenBiMap<String, u32>>();
+    }
+}
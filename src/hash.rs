@@ -2,14 +2,17 @@
 
 use crate::{
     mem::{Ref, Wrapper},
-    Overwritten,
+    stats::Counters,
+    BatchError, BatchOp, BiBTreeMap, BiMapLike, FrozenBiMap, InvariantViolation, KeyNotFound,
+    MapMismatch, ModifyError, OccupiedError, OverwriteReport, Overwritten, Transaction,
 };
 use std::{
     borrow::Borrow,
-    collections::{hash_map, HashMap},
+    collections::{btree_set, hash_map, BTreeMap, BTreeSet, HashMap, HashSet, TryReserveError},
     fmt,
-    hash::{BuildHasher, Hash},
+    hash::{BuildHasher, Hash, Hasher},
     iter::{Extend, FromIterator, FusedIterator},
+    ops::{Bound, Deref},
     rc::Rc,
 };
 
@@ -21,6 +24,10 @@ use std::{
 pub struct BiHashMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
     left2right: HashMap<Ref<L>, Ref<R>, LS>,
     right2left: HashMap<Ref<R>, Ref<L>, RS>,
+    generation: u64,
+    stats: Counters,
+    auto_shrink: bool,
+    prefix_index: PrefixIndex<L>,
 }
 
 impl<L, R> BiHashMap<L, R, hash_map::RandomState, hash_map::RandomState>
@@ -41,11 +48,19 @@ where
         Self {
             left2right: HashMap::new(),
             right2left: HashMap::new(),
+            generation: 0,
+            stats: Counters::new(),
+            auto_shrink: false,
+            prefix_index: PrefixIndex::disabled(),
         }
     }
 
     /// Creates a new empty `BiHashMap` with the given capacity.
     ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -58,6 +73,10 @@ where
         Self {
             left2right: HashMap::with_capacity(capacity),
             right2left: HashMap::with_capacity(capacity),
+            generation: 0,
+            stats: Counters::new(),
+            auto_shrink: false,
+            prefix_index: PrefixIndex::disabled(),
         }
     }
 }
@@ -106,20 +125,81 @@ where
     /// Returns a lower bound on the number of left-right pairs the `BiHashMap`
     /// can store without reallocating memory.
     ///
+    /// The capacity is the smaller of the capacities of the two internal maps.
+    /// Both maps always hold the same number of values, so inserting pairs
+    /// while [`len`] is less than `capacity` never reallocates or rehashes
+    /// either map, even when the inserts overwrite existing pairs. Reserving
+    /// space up front with [`with_capacity`] or [`reserve`] therefore makes a
+    /// burst of inserts up to the capacity free of rehashing pauses.
+    ///
+    /// Note that removing pairs may lower the capacity reported afterwards.
+    ///
     /// # Examples
     ///
     /// ```
     /// use bimap::BiHashMap;
     ///
-    /// let bimap = BiHashMap::<char, i32>::with_capacity(10);
-    /// assert!(bimap.capacity() >= 10);
+    /// let mut bimap = BiHashMap::<u32, u32>::with_capacity(10);
+    /// let capacity = bimap.capacity();
+    /// assert!(capacity >= 10);
+    ///
+    /// for i in 0..capacity as u32 {
+    ///     bimap.insert(i, i);
+    /// }
+    /// assert_eq!(bimap.capacity(), capacity);
     /// ```
+    ///
+    /// [`len`]: BiHashMap::len
+    /// [`with_capacity`]: BiHashMap::with_capacity
+    /// [`reserve`]: BiHashMap::reserve
     pub fn capacity(&self) -> usize {
         self.left2right.capacity().min(self.right2left.capacity())
     }
 
+    /// Returns the bimap's generation, a counter that changes whenever the
+    /// left-right pairs may have changed.
+    ///
+    /// Every method that inserts, removes or modifies pairs, including
+    /// `clear` and `retain`, advances the generation, while lookups and
+    /// capacity changes leave it unchanged. Comparing generations is a cheap
+    /// way to tell whether a cache derived from the bimap is stale. The
+    /// counter starts at zero and only increases, but its exact value is not
+    /// specified; clones and deserialized bimaps start their own count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// let generation = bimap.generation();
+    ///
+    /// // lookups don't change the generation
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.generation(), generation);
+    ///
+    /// // mutations do
+    /// bimap.remove_by_left(&'a');
+    /// assert!(bimap.generation() > generation);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Advances the generation after a mutation.
+    fn bump_generation(&mut self) {
+        self.generation += 1;
+    }
+
     /// Removes all left-right pairs from the bimap.
     ///
+    /// The allocated memory of both sides is kept for reuse, so refilling the
+    /// bimap with as many pairs as before doesn't reallocate, which makes
+    /// clearing a bimap cheaper than replacing it with a new one when it's
+    /// recycled. This holds even when [`set_auto_shrink`] is enabled. Call
+    /// [`shrink_to_fit`] afterwards to release the memory instead.
+    ///
     /// # Examples
     ///
     /// ```
@@ -129,12 +209,62 @@ where
     /// bimap.insert('a', 1);
     /// bimap.insert('b', 2);
     /// bimap.insert('c', 3);
+    /// let capacity = bimap.capacity();
+    ///
     /// bimap.clear();
     /// assert!(bimap.len() == 0);
+    /// assert_eq!(bimap.capacity(), capacity);
     /// ```
+    ///
+    /// [`set_auto_shrink`]: BiHashMap::set_auto_shrink
+    /// [`shrink_to_fit`]: BiHashMap::shrink_to_fit
     pub fn clear(&mut self) {
         self.left2right.clear();
         self.right2left.clear();
+        self.prefix_index.clear();
+        self.bump_generation();
+    }
+
+    /// Removes all left-right pairs from the bimap and returns them, in
+    /// arbitrary order.
+    ///
+    /// Like [`clear`], this keeps the allocated memory of both sides for
+    /// reuse. The returned vector is allocated up front with room for exactly
+    /// the pairs of the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let capacity = bimap.capacity();
+    ///
+    /// let mut pairs = bimap.take();
+    /// pairs.sort();
+    /// assert_eq!(pairs, [('a', 1), ('b', 2)]);
+    /// assert!(bimap.is_empty());
+    /// assert_eq!(bimap.capacity(), capacity);
+    /// ```
+    ///
+    /// [`clear`]: BiHashMap::clear
+    pub fn take(&mut self) -> Vec<(L, R)> {
+        let mut pairs = Vec::with_capacity(self.len());
+        // drop the reverse references first, so that the values are uniquely
+        // owned
+        self.right2left.clear();
+        // unwraps are safe because right2left has been cleared
+        pairs.extend(self.left2right.drain().map(|(l, r)| {
+            (
+                Rc::try_unwrap(l.0).ok().unwrap(),
+                Rc::try_unwrap(r.0).ok().unwrap(),
+            )
+        }));
+        self.prefix_index.clear();
+        self.bump_generation();
+        pairs
     }
 
     /// Creates an iterator over the left-right pairs in the bimap in arbitrary
@@ -211,6 +341,91 @@ where
             inner: self.right2left.iter(),
         }
     }
+
+    /// Folds every left-right pair in the bimap into an accumulator by
+    /// applying an operation, returning the final result.
+    ///
+    /// Pairs are visited in arbitrary order. This is equivalent to
+    /// `iter().fold()` but passes the left and right values as separate
+    /// arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// let sum = bimap.fold_pairs(0, |acc, _left, right| acc + right);
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn fold_pairs<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &L, &R) -> B,
+    {
+        let mut f = f;
+        self.iter().fold(init, |acc, (l, r)| f(acc, l, r))
+    }
+
+    /// Returns a value whose `Debug` output lists the bimap's pairs in
+    /// ascending order of their left values.
+    ///
+    /// The `Debug` implementation of `BiHashMap` itself prints pairs in
+    /// arbitrary order, which can differ between two equal bimaps. The sorted
+    /// output is stable, which makes it well suited to assertion messages and
+    /// snapshot tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('c', 3);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(
+    ///     format!("{:?}", bimap.debug_sorted()),
+    ///     "{'a' <> 1, 'b' <> 2, 'c' <> 3}"
+    /// );
+    /// ```
+    pub fn debug_sorted(&self) -> impl fmt::Debug + '_
+    where
+        L: fmt::Debug + Ord,
+        R: fmt::Debug,
+    {
+        SortedDebugger { bimap: self }
+    }
+
+    /// Returns a value whose `Display` output lists the bimap's pairs in
+    /// ascending order of their left values.
+    ///
+    /// This is the sorted counterpart of the `Display` implementation of
+    /// `BiHashMap`, which prints pairs in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('c', 3);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.display_sorted().to_string(), "{a <-> 1, b <-> 2, c <-> 3}");
+    /// ```
+    pub fn display_sorted(&self) -> impl fmt::Display + '_
+    where
+        L: fmt::Display + Ord,
+        R: fmt::Display,
+    {
+        SortedDisplayer { bimap: self }
+    }
 }
 
 impl<L, R, LS, RS> BiHashMap<L, R, LS, RS>
@@ -238,6 +453,10 @@ where
         Self {
             left2right: HashMap::with_hasher(hash_builder_left),
             right2left: HashMap::with_hasher(hash_builder_right),
+            generation: 0,
+            stats: Counters::new(),
+            auto_shrink: false,
+            prefix_index: PrefixIndex::disabled(),
         }
     }
 
@@ -245,6 +464,10 @@ where
     /// `hash_builder_left` to hash left values and `hash_builder_right` to
     /// hash right values.
     ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -264,13 +487,151 @@ where
         Self {
             left2right: HashMap::with_capacity_and_hasher(capacity, hash_builder_left),
             right2left: HashMap::with_capacity_and_hasher(capacity, hash_builder_right),
+            generation: 0,
+            stats: Counters::new(),
+            auto_shrink: false,
+            prefix_index: PrefixIndex::disabled(),
+        }
+    }
+
+    /// Creates a `BiHashMap` from a forward map and its reverse map, after
+    /// checking that they are exact inverses of each other.
+    ///
+    /// The bimap uses the hashers of the given maps. Since the bimap shares
+    /// each value between its two directions, the maps can't be adopted as
+    /// they are: the pairs are moved out of `forward` into the bimap, and
+    /// `reverse` is only used for validation. This still saves checking
+    /// every pair for conflicts like [`insert`] does.
+    ///
+    /// # Errors
+    ///
+    /// If the maps have different lengths, or `reverse` doesn't map every
+    /// right value of `forward` back to its left value, an error describing
+    /// the first inconsistency found is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, MapMismatch};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut forward = HashMap::new();
+    /// let mut reverse = HashMap::new();
+    /// forward.insert('a', 1);
+    /// reverse.insert(1, 'a');
+    /// forward.insert('b', 2);
+    /// reverse.insert(2, 'c');
+    ///
+    /// let result = BiHashMap::from_maps(forward.clone(), reverse.clone());
+    /// assert_eq!(result, Err(MapMismatch::WrongLeft));
+    ///
+    /// reverse.insert(2, 'b');
+    /// let bimap = BiHashMap::from_maps(forward, reverse).unwrap();
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'b'));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn from_maps(
+        forward: HashMap<L, R, LS>,
+        reverse: HashMap<R, L, RS>,
+    ) -> Result<Self, MapMismatch>
+    where
+        LS: Clone,
+        RS: Clone,
+    {
+        if forward.len() != reverse.len() {
+            return Err(MapMismatch::Len {
+                forward: forward.len(),
+                reverse: reverse.len(),
+            });
+        }
+        for (l, r) in &forward {
+            match reverse.get(r) {
+                Some(left) if left == l => {}
+                Some(_) => return Err(MapMismatch::WrongLeft),
+                None => return Err(MapMismatch::MissingRight),
+            }
+        }
+        let mut bimap = Self::with_capacity_and_hashers(
+            forward.len(),
+            forward.hasher().clone(),
+            reverse.hasher().clone(),
+        );
+        drop(reverse);
+        for (l, r) in forward {
+            bimap.insert_unchecked(l, r);
         }
+        Ok(bimap)
+    }
+
+    /// Returns a reference to the bimap's left [`BuildHasher`].
+    ///
+    /// Hashing a left value with this builder produces the same hash that the
+    /// bimap uses internally, which can be used to precompute hashes for
+    /// custom interning schemes.
+    ///
+    /// Note that this bimap can't look up values by a precomputed hash, since
+    /// the standard library's `HashMap` doesn't support it on stable Rust. The
+    /// `BiHashMap` of the [`hashbrown`](crate::hashbrown) module, behind the
+    /// `hashbrown` feature, can with `get_by_left_hashed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    /// use bimap::BiHashMap;
+    ///
+    /// fn hash(builder: &RandomState, value: char) -> u64 {
+    ///     let mut hasher = builder.build_hasher();
+    ///     value.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let s_left = RandomState::new();
+    /// let expected = hash(&s_left, 'a');
+    /// let bimap = BiHashMap::<char, i32>::with_hashers(s_left, RandomState::new());
+    /// assert_eq!(hash(bimap.left_hasher(), 'a'), expected);
+    /// ```
+    pub fn left_hasher(&self) -> &LS {
+        self.left2right.hasher()
+    }
+
+    /// Returns a reference to the bimap's right [`BuildHasher`].
+    ///
+    /// Hashing a right value with this builder produces the same hash that
+    /// the bimap uses internally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    /// use bimap::BiHashMap;
+    ///
+    /// fn hash(builder: &RandomState, value: i32) -> u64 {
+    ///     let mut hasher = builder.build_hasher();
+    ///     value.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let s_right = RandomState::new();
+    /// let expected = hash(&s_right, 42);
+    /// let bimap = BiHashMap::<char, i32>::with_hashers(RandomState::new(), s_right);
+    /// assert_eq!(hash(bimap.right_hasher(), 42), expected);
+    /// ```
+    pub fn right_hasher(&self) -> &RS {
+        self.right2left.hasher()
     }
 
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the `BiHashMap`. The collection may reserve more space to avoid
     /// frequent reallocations.
     ///
+    /// Both internal maps reserve the same amount up front, so inserting
+    /// `additional` pairs with new left and right values afterwards doesn't
+    /// reallocate or rehash either of them.
+    ///
     /// # Panics
     ///
     /// Panics if the new allocation size overflows [`usize`].
@@ -289,6 +650,35 @@ where
         self.right2left.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the `BiHashMap`. The collection may reserve more space to
+    /// avoid frequent reallocations.
+    ///
+    /// Unlike [`reserve`], this doesn't panic if the new capacity overflows
+    /// [`usize`] or the allocator reports a failure.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned. The capacity of either side may still have grown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// bimap.try_reserve(10).unwrap();
+    /// assert!(bimap.capacity() >= 10);
+    /// assert!(bimap.try_reserve(usize::MAX).is_err());
+    /// ```
+    ///
+    /// [`reserve`]: BiHashMap::reserve
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.left2right.try_reserve(additional)?;
+        self.right2left.try_reserve(additional)
+    }
+
     /// Shrinks the capacity of the bimap as much as possible. It will drop
     /// down as much as possible while maintaining the internal rules
     /// and possibly leaving some space in accordance with the resize policy.
@@ -336,6 +726,185 @@ where
         self.right2left.shrink_to(min_capacity);
     }
 
+    /// Returns whether the bimap shrinks automatically after removals.
+    ///
+    /// See [`set_auto_shrink`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// assert!(!bimap.auto_shrink());
+    /// bimap.set_auto_shrink(true);
+    /// assert!(bimap.auto_shrink());
+    /// ```
+    ///
+    /// [`set_auto_shrink`]: BiHashMap::set_auto_shrink
+    pub fn auto_shrink(&self) -> bool {
+        self.auto_shrink
+    }
+
+    /// Enables or disables shrinking the bimap automatically after removals.
+    ///
+    /// Like `HashMap`, a bimap never gives back memory on its own, so a bimap
+    /// that once held many pairs keeps its peak capacity. With auto-shrinking
+    /// enabled, every method that removes pairs (`remove_by_left`,
+    /// `remove_by_right`, `retain`, `retain_mut_right`, and overwriting
+    /// inserts and updates) checks afterwards whether fewer than a quarter of
+    /// the capacity is in use, and if so shrinks the bimap to twice its
+    /// current length. The gap between the two thresholds means a bimap that
+    /// was just shrunk has room to grow again before it reallocates, so
+    /// alternating inserts and removals don't thrash. `clear` is not
+    /// affected; call [`shrink_to_fit`] after it to release the memory.
+    ///
+    /// Shrinking rehashes every pair, so it trades occasional O(n) removals
+    /// for lower memory use. Because a shrink only happens once the bimap has
+    /// become sparse, its cost is amortized over the removals that made it
+    /// so. Capacity reserved with [`with_capacity`] or [`reserve`] is
+    /// given up by the first removal that finds the bimap sparse.
+    ///
+    /// Auto-shrinking is disabled by default. Clones inherit the setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+    /// bimap.set_auto_shrink(true);
+    /// bimap.retain(|&l, _| l < 10);
+    /// assert!(bimap.capacity() < 40);
+    /// ```
+    ///
+    /// [`shrink_to_fit`]: BiHashMap::shrink_to_fit
+    /// [`with_capacity`]: BiHashMap::with_capacity
+    /// [`reserve`]: BiHashMap::reserve
+    pub fn set_auto_shrink(&mut self, enabled: bool) {
+        self.auto_shrink = enabled;
+    }
+
+    /// Shrinks the bimap if auto-shrinking is enabled and less than a quarter
+    /// of its capacity is in use.
+    fn shrink_if_sparse(&mut self) {
+        if self.auto_shrink && self.len() < self.capacity() / 4 {
+            self.shrink_to(self.len() * 2);
+        }
+    }
+
+    /// Returns the bimap with a sorted index of its left values, which
+    /// enables prefix queries with [`left_prefix`].
+    ///
+    /// The index is a `BTreeSet` holding a copy of every left value's string
+    /// form, kept up to date by every method that adds or removes pairs. It
+    /// roughly doubles the memory taken by the left strings, plus a few words
+    /// of tree overhead per pair, and adds an O(log n) index update with a
+    /// string allocation to every insertion and an O(log n) update to every
+    /// removal. Lookups by exact value still go through the hash maps and are
+    /// not affected. Clones keep the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new().with_prefix_index();
+    /// bimap.insert(String::from("apple"), 1);
+    /// bimap.insert(String::from("apricot"), 2);
+    /// bimap.insert(String::from("banana"), 3);
+    ///
+    /// let ap: Vec<_> = bimap.left_prefix("ap").map(|(_, r)| *r).collect();
+    /// assert_eq!(ap, [1, 2]);
+    /// assert_eq!(bimap.get_by_left("banana"), Some(&3));
+    /// ```
+    ///
+    /// [`left_prefix`]: BiHashMap::left_prefix
+    pub fn with_prefix_index(mut self) -> Self
+    where
+        L: Borrow<str>,
+    {
+        let mut prefix_index = PrefixIndex {
+            key: Some(<L as Borrow<str>>::borrow),
+            keys: BTreeSet::new(),
+        };
+        for left in self.left2right.keys() {
+            prefix_index.insert(&left.0);
+        }
+        self.prefix_index = prefix_index;
+        self
+    }
+
+    /// Returns `true` if the bimap keeps a prefix index of its left values,
+    /// and `false` otherwise.
+    ///
+    /// See [`with_prefix_index`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let bimap = BiHashMap::<&str, i32>::new();
+    /// assert!(!bimap.has_prefix_index());
+    /// assert!(bimap.with_prefix_index().has_prefix_index());
+    /// ```
+    ///
+    /// [`with_prefix_index`]: BiHashMap::with_prefix_index
+    pub fn has_prefix_index(&self) -> bool {
+        self.prefix_index.key.is_some()
+    }
+
+    /// Creates an iterator over the left-right pairs whose left value starts
+    /// with the given prefix, in ascending order of the left values.
+    ///
+    /// The iterator element type is `(&L, &R)`. Finding the first pair takes
+    /// O(log n) time, as in a `BTreeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bimap was not created with [`with_prefix_index`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new().with_prefix_index();
+    /// bimap.insert("car", 'c');
+    /// bimap.insert("cart", 't');
+    /// bimap.insert("cat", 'a');
+    /// bimap.insert("dog", 'd');
+    ///
+    /// let lefts: Vec<_> = bimap.left_prefix("car").map(|(l, _)| *l).collect();
+    /// assert_eq!(lefts, ["car", "cart"]);
+    /// assert_eq!(bimap.left_prefix("").count(), 4);
+    /// assert_eq!(bimap.left_prefix("cow").next(), None);
+    /// ```
+    ///
+    /// [`with_prefix_index`]: BiHashMap::with_prefix_index
+    pub fn left_prefix(&self, prefix: &str) -> LeftPrefix<'_, L, R, LS>
+    where
+        L: Borrow<str>,
+    {
+        assert!(
+            self.has_prefix_index(),
+            "left_prefix requires a bimap created with with_prefix_index"
+        );
+        let end = prefix_end(prefix);
+        let end = match &end {
+            Some(end) => Bound::Excluded(end.as_str()),
+            None => Bound::Unbounded,
+        };
+        LeftPrefix {
+            keys: self
+                .prefix_index
+                .keys
+                .range::<str, _>((Bound::Included(prefix), end)),
+            left2right: &self.left2right,
+        }
+    }
+
     /// Returns a reference to the right value corresponding to the given left
     /// value.
     ///
@@ -357,7 +926,9 @@ where
         L: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.left2right.get(Wrapper::wrap(left)).map(|r| &*r.0)
+        let right = self.left2right.get(Wrapper::wrap(left)).map(|r| &*r.0);
+        self.stats.record_left(right.is_some());
+        right
     }
 
     /// Returns a reference to the left value corresponding to the given right
@@ -381,11 +952,15 @@ where
         R: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.right2left.get(Wrapper::wrap(right)).map(|l| &*l.0)
+        let left = self.right2left.get(Wrapper::wrap(right)).map(|l| &*l.0);
+        self.stats.record_right(left.is_some());
+        left
     }
 
-    /// Returns `true` if the bimap contains the given left value and `false`
-    /// otherwise.
+    /// Returns a clone of the right value corresponding to the given left
+    /// value, converted into `T`.
+    ///
+    /// This is a shorthand for `get_by_left(..).cloned().map(T::from)`.
     ///
     /// The input may be any borrowed form of the bimap's left type, but `Eq`
     /// and `Hash` on the borrowed form *must* match those for the left type.
@@ -396,16 +971,149 @@ where
     /// use bimap::BiHashMap;
     ///
     /// let mut bimap = BiHashMap::new();
-    /// bimap.insert('a', 1);
-    /// assert!(bimap.contains_left(&'a'));
-    /// assert!(!bimap.contains_left(&'b'));
+    /// bimap.insert('a', 1u8);
+    /// let right: Option<u32> = bimap.get_by_left_as(&'a');
+    /// assert_eq!(right, Some(1));
+    /// assert_eq!(bimap.get_by_left_as::<u32, _>(&'z'), None);
     /// ```
-    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    pub fn get_by_left_as<T, Q>(&self, left: &Q) -> Option<T>
     where
         L: Borrow<Q>,
+        R: Clone,
+        T: From<R>,
         Q: Eq + Hash + ?Sized,
     {
-        self.left2right.contains_key(Wrapper::wrap(left))
+        self.get_by_left(left).cloned().map(T::from)
+    }
+
+    /// Returns a clone of the left value corresponding to the given right
+    /// value, converted into `T`.
+    ///
+    /// This is a shorthand for `get_by_right(..).cloned().map(T::from)`.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert(b'a', 1);
+    /// let left: Option<u32> = bimap.get_by_right_as(&1);
+    /// assert_eq!(left, Some(97));
+    /// assert_eq!(bimap.get_by_right_as::<u32, _>(&2), None);
+    /// ```
+    pub fn get_by_right_as<T, Q>(&self, right: &Q) -> Option<T>
+    where
+        R: Borrow<Q>,
+        L: Clone,
+        T: From<L>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_by_right(right).cloned().map(T::from)
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value, or a [`KeyNotFound`] error if the left value is not in the
+    /// bimap.
+    ///
+    /// This is the same as [`get_by_left`](Self::get_by_left), but works with
+    /// the `?` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, KeyNotFound};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.try_get_by_left(&'a'), Ok(&1));
+    /// assert_eq!(bimap.try_get_by_left(&'z'), Err(KeyNotFound));
+    /// ```
+    pub fn try_get_by_left<Q>(&self, left: &Q) -> Result<&R, KeyNotFound>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_by_left(left).ok_or(KeyNotFound)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value, or a [`KeyNotFound`] error if the right value is not in the
+    /// bimap.
+    ///
+    /// This is the same as [`get_by_right`](Self::get_by_right), but works
+    /// with the `?` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, KeyNotFound};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.try_get_by_right(&1), Ok(&'a'));
+    /// assert_eq!(bimap.try_get_by_right(&2), Err(KeyNotFound));
+    /// ```
+    pub fn try_get_by_right<Q>(&self, right: &Q) -> Result<&L, KeyNotFound>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_by_right(right).ok_or(KeyNotFound)
+    }
+
+    /// Returns an arbitrary left-right pair from the bimap without removing
+    /// it, or `None` if the bimap is empty.
+    ///
+    /// Which pair is returned is unspecified, but as long as the bimap is not
+    /// modified in between, it is the pair that [`pop`] would remove.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(bimap.peek(), None);
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let (&left, &right) = bimap.peek().unwrap();
+    /// assert_eq!(bimap.pop(), Some((left, right)));
+    /// ```
+    ///
+    /// [`pop`]: BiHashMap::pop
+    pub fn peek(&self) -> Option<(&L, &R)> {
+        self.left2right.iter().next().map(|(l, r)| (&*l.0, &*r.0))
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let found = self.left2right.contains_key(Wrapper::wrap(left));
+        self.stats.record_left(found);
+        found
     }
 
     /// Returns `true` if the map contains the given right value and `false`
@@ -429,7 +1137,69 @@ where
         R: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.right2left.contains_key(Wrapper::wrap(right))
+        let found = self.right2left.contains_key(Wrapper::wrap(right));
+        self.stats.record_right(found);
+        found
+    }
+
+    /// Returns the number of lookups performed on the bimap so far, and how
+    /// many of them found a pair.
+    ///
+    /// Calls to [`get_by_left`], [`get_by_right`], [`contains_left`] and
+    /// [`contains_right`] are counted, including calls made by other methods
+    /// such as `try_get_by_left` and `insert_no_overwrite`. A cloned bimap
+    /// starts with fresh counters.
+    ///
+    /// This method is only available with the `stats` feature. Without it,
+    /// lookups are not counted and the bimap carries no counters.
+    ///
+    /// [`get_by_left`]: BiHashMap::get_by_left
+    /// [`get_by_right`]: BiHashMap::get_by_right
+    /// [`contains_left`]: BiHashMap::contains_left
+    /// [`contains_right`]: BiHashMap::contains_right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// bimap.get_by_left(&'a');
+    /// bimap.get_by_left(&'b');
+    /// bimap.contains_right(&1);
+    ///
+    /// let stats = bimap.lookup_stats();
+    /// assert_eq!(stats.by_left, 2);
+    /// assert_eq!(stats.by_right, 1);
+    /// assert_eq!(stats.hits, 2);
+    /// assert_eq!(stats.misses, 1);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn lookup_stats(&self) -> crate::LookupStats {
+        self.stats.snapshot()
+    }
+
+    /// Resets all lookup counters of the bimap to zero.
+    ///
+    /// This method is only available with the `stats` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LookupStats};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.get_by_left(&'a');
+    ///
+    /// bimap.reset_lookup_stats();
+    /// assert_eq!(bimap.lookup_stats(), LookupStats::default());
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn reset_lookup_stats(&self) {
+        self.stats.reset();
     }
 
     /// Removes the left-right pair corresponding to the given left value.
@@ -458,15 +1228,19 @@ where
         L: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.left2right.remove(Wrapper::wrap(left)).map(|right_rc| {
+        let pair = self.left2right.remove(Wrapper::wrap(left)).map(|right_rc| {
+            self.bump_generation();
             // unwrap is safe because we know right2left contains the key (it's a bimap)
             let left_rc = self.right2left.remove(&right_rc).unwrap();
+            self.prefix_index.remove(&left_rc.0);
             // at this point we can safely unwrap because the other pointers are gone
             (
                 Rc::try_unwrap(left_rc.0).ok().unwrap(),
                 Rc::try_unwrap(right_rc.0).ok().unwrap(),
             )
-        })
+        });
+        self.shrink_if_sparse();
+        pair
     }
 
     /// Removes the left-right pair corresponding to the given right value.
@@ -495,23 +1269,172 @@ where
         R: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.right2left.remove(Wrapper::wrap(right)).map(|left_rc| {
+        let pair = self.right2left.remove(Wrapper::wrap(right)).map(|left_rc| {
+            self.bump_generation();
             // unwrap is safe because we know left2right contains the key (it's a bimap)
             let right_rc = self.left2right.remove(&left_rc).unwrap();
+            self.prefix_index.remove(&left_rc.0);
             // at this point we can safely unwrap because the other pointers are gone
             (
                 Rc::try_unwrap(left_rc.0).ok().unwrap(),
                 Rc::try_unwrap(right_rc.0).ok().unwrap(),
             )
+        });
+        self.shrink_if_sparse();
+        pair
+    }
+
+    /// Takes the pair of the given left value out of the bimap and returns a
+    /// [`Lease`] on it, or `None` if the bimap doesn't contain the left value.
+    ///
+    /// The lease dereferences to the right value. Dropping it removes the
+    /// pair for good, while [`Lease::keep`] puts the pair back. This supports
+    /// a "take it out, and put it back only if committed" pattern. The
+    /// bimap stays mutably borrowed while the lease exists, so no other
+    /// changes can happen in between.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// // committed: the pair goes back into the bimap
+    /// let lease = bimap.lease_by_left(&'a').unwrap();
+    /// assert_eq!(*lease, 1);
+    /// lease.keep();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    ///
+    /// // not committed: the pair is removed
+    /// let lease = bimap.lease_by_left(&'b').unwrap();
+    /// assert_eq!(*lease, 2);
+    /// drop(lease);
+    /// assert!(!bimap.contains_left(&'b'));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn lease_by_left<Q>(&mut self, left: &Q) -> Option<Lease<'_, L, R, LS, RS>>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (left, right) = self.remove_by_left(left)?;
+        Some(Lease {
+            bimap: self,
+            left,
+            right,
         })
     }
 
+    /// Removes and returns an arbitrary left-right pair from the bimap, or
+    /// `None` if the bimap is empty.
+    ///
+    /// The removed pair is the one returned by [`peek`].
+    ///
+    /// Finding the pair scans the table from its start, so each call takes
+    /// time proportional to the capacity rather than constant time, and
+    /// emptying a bimap with repeated calls is quadratic. Use [`take`] or
+    /// [`into_iter`] to remove all of the pairs instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let (left, right) = bimap.pop().unwrap();
+    /// assert!(!bimap.contains_left(&left));
+    /// assert!(!bimap.contains_right(&right));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    ///
+    /// [`peek`]: BiHashMap::peek
+    /// [`take`]: BiHashMap::take
+    /// [`into_iter`]: BiHashMap::into_iter
+    pub fn pop(&mut self) -> Option<(L, R)> {
+        let left = self.left2right.keys().next()?.clone();
+        self.bump_generation();
+        // unwrap is safe because the left value was just taken from left2right
+        let right_rc = self.left2right.remove(&left).unwrap();
+        // unwrap is safe because we know right2left contains the key (it's a bimap)
+        let left_rc = self.right2left.remove(&right_rc).unwrap();
+        self.prefix_index.remove(&left_rc.0);
+        drop(left);
+        self.shrink_if_sparse();
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Returns the pairs that inserting the given left-right pair would
+    /// overwrite, without modifying the bimap.
+    ///
+    /// The returned [`OverwriteReport`] has the same variant that
+    /// [`insert`](BiHashMap::insert) would return, but borrows the pairs from
+    /// the bimap instead of removing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.what_would_overwrite(&'c', &3), Overwritten::Neither);
+    /// assert_eq!(bimap.what_would_overwrite(&'a', &3), Overwritten::Left(&'a', &1));
+    /// assert_eq!(bimap.what_would_overwrite(&'c', &2), Overwritten::Right(&'b', &2));
+    /// assert_eq!(bimap.what_would_overwrite(&'a', &1), Overwritten::Unchanged(&'a', &1));
+    /// assert_eq!(
+    ///     bimap.what_would_overwrite(&'a', &2),
+    ///     Overwritten::Both((&'a', &1), (&'b', &2))
+    /// );
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn what_would_overwrite(&self, left: &L, right: &R) -> OverwriteReport<'_, L, R> {
+        let by_left = self
+            .left2right
+            .get_key_value(Wrapper::wrap(left))
+            .map(|(l, r)| (&*l.0, &*r.0));
+        let by_right = self
+            .right2left
+            .get_key_value(Wrapper::wrap(right))
+            .map(|(r, l)| (&*l.0, &*r.0));
+        match (by_left, by_right) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) => Overwritten::Left(l, r),
+            (None, Some((l, r))) => Overwritten::Right(l, r),
+            // both lookups found the same pair if it is already present
+            (Some(l_pair), Some(r_pair)) if core::ptr::eq(l_pair.0, r_pair.0) => {
+                Overwritten::Unchanged(l_pair.0, l_pair.1)
+            }
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        }
+    }
+
     /// Inserts the given left-right pair into the bimap.
     ///
     /// Returns an enum `Overwritten` representing any left-right pairs that
     /// were overwritten by the call to `insert`. The example below details
     /// all possible enum variants that can be returned.
     ///
+    /// If the bimap already contains exactly the given pair, it is left
+    /// untouched and `Overwritten::Unchanged` hands the given values back, so
+    /// idempotent re-inserts don't have to rebuild the pair. The values
+    /// stored in the bimap are then the ones inserted first, which only
+    /// matters for types whose equal values can still be told apart.
+    ///
     /// # Warnings
     ///
     /// Somewhat paradoxically, calling `insert()` can actually reduce the size
@@ -550,24 +1473,20 @@ where
     /// assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 4), ('c', 2)));
     /// assert_eq!(bimap.len(), 1); // {'a' <> 2} // bimap is smaller than before!
     ///
-    /// // ('a', 2) already exists, so inserting ('a', 2) overwrites the pair.
-    /// // the previous left-right pair ('a', 2) is returned.
-    /// assert_eq!(bimap.insert('a', 2), Overwritten::Pair('a', 2));
+    /// // ('a', 2) already exists, so inserting ('a', 2) leaves the bimap as it is.
+    /// // the given left-right pair ('a', 2) is returned.
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Unchanged('a', 2));
     /// assert_eq!(bimap.len(), 1); // {'a' <> 2}
     /// ```
     pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        if matches!(self.left2right.get(Wrapper::wrap(&left)), Some(r) if *r.0 == right) {
+            // the pair is already present, so neither map needs to change
+            return Overwritten::Unchanged(left, right);
+        }
         let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
             (None, None) => Overwritten::Neither,
             (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
-            (Some(l_pair), None) => {
-                // since remove_by_left() was called first, it's possible the right value was
-                // removed if a duplicate pair is being inserted
-                if l_pair.1 == right {
-                    Overwritten::Pair(l_pair.0, l_pair.1)
-                } else {
-                    Overwritten::Left(l_pair.0, l_pair.1)
-                }
-            }
+            (Some(l_pair), None) => Overwritten::Left(l_pair.0, l_pair.1),
             (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
         };
         self.insert_unchecked(left, right);
@@ -578,7 +1497,7 @@ where
     /// existing values.
     ///
     /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
-    /// If either value exists in the map, `Err((left, right)` is returned
+    /// If either value exists in the map, `Err((left, right))` is returned
     /// with the attempted left-right pair and the map is unchanged.
     ///
     /// # Examples
@@ -601,10 +1520,13 @@ where
         }
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Tries to insert the given left-right pair into the bimap, returning
+    /// references to the inserted values on success.
     ///
-    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
-    /// &r)` returns `false`.
+    /// Like [`insert_no_overwrite`], this never overwrites existing pairs. If
+    /// the left or right value is already present, the bimap is unchanged and
+    /// an [`OccupiedError`] is returned that holds the rejected pair along
+    /// with references to the existing pairs it conflicts with.
     ///
     /// # Examples
     ///
@@ -612,81 +1534,1650 @@ where
     /// use bimap::BiHashMap;
     ///
     /// let mut bimap = BiHashMap::new();
-    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.try_insert('a', 1).unwrap(), (&'a', &1));
     /// bimap.insert('b', 2);
-    /// bimap.insert('c', 3);
-    /// bimap.retain(|&l, &r| r >= 2);
-    /// assert_eq!(bimap.len(), 2);
-    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
-    /// assert_eq!(bimap.get_by_left(&'c'), Some(&3));
-    /// assert_eq!(bimap.get_by_left(&'a'), None);
+    ///
+    /// let error = bimap.try_insert('a', 2).unwrap_err();
+    /// assert_eq!(error.pair, ('a', 2));
+    /// assert_eq!(error.left_conflict, Some((&'a', &1)));
+    /// assert_eq!(error.right_conflict, Some((&'b', &2)));
     /// ```
-    pub fn retain<F>(&mut self, f: F)
-    where
-        F: FnMut(&L, &R) -> bool,
-    {
-        let mut f = f;
-        let right2left = &mut self.right2left;
-        self.left2right.retain(|l, r| {
-            let to_retain = f(&l.0, &r.0);
-            if !to_retain {
-                right2left.remove(r);
-            }
-            to_retain
-        });
-    }
-
-    /// Inserts the given left-right pair into the bimap without checking if the
-    /// pair already exists.
-    fn insert_unchecked(&mut self, left: L, right: R) {
+    ///
+    /// [`insert_no_overwrite`]: BiHashMap::insert_no_overwrite
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(&L, &R), OccupiedError<'_, L, R>> {
+        if self.left2right.contains_key(Wrapper::wrap(&left))
+            || self.right2left.contains_key(Wrapper::wrap(&right))
+        {
+            let left_conflict = self
+                .left2right
+                .get_key_value(Wrapper::wrap(&left))
+                .map(|(l, r)| (&*l.0, &*r.0));
+            let right_conflict = self
+                .right2left
+                .get_key_value(Wrapper::wrap(&right))
+                .map(|(r, l)| (&*l.0, &*r.0));
+            return Err(OccupiedError {
+                pair: (left, right),
+                left_conflict,
+                right_conflict,
+            });
+        }
         let left = Ref(Rc::new(left));
         let right = Ref(Rc::new(right));
+        self.prefix_index.insert(&left.0);
         self.left2right.insert(left.clone(), right.clone());
-        self.right2left.insert(right, left);
+        self.right2left.insert(right, left.clone());
+        self.bump_generation();
+        // unwrap is safe because the pair was just inserted
+        let (l, r) = self.left2right.get_key_value(&left).unwrap();
+        Ok((&*l.0, &*r.0))
     }
-}
 
-impl<L, R, LS, RS> Clone for BiHashMap<L, R, LS, RS>
-where
-    L: Clone + Eq + Hash,
-    R: Clone + Eq + Hash,
-    LS: BuildHasher + Clone,
-    RS: BuildHasher + Clone,
-{
-    fn clone(&self) -> BiHashMap<L, R, LS, RS> {
-        let mut new_bimap = BiHashMap::with_capacity_and_hashers(
-            self.capacity(),
-            self.left2right.hasher().clone(),
-            self.right2left.hasher().clone(),
-        );
-        for (l, r) in self.iter() {
-            new_bimap.insert(l.clone(), r.clone());
-        }
-        new_bimap
+    /// Inserts the given left-right pair into the bimap and returns a reference
+    /// to the stored right value.
+    ///
+    /// Any pairs that conflict with the new pair are removed and dropped, just
+    /// like with [`insert`]. The reference is taken while inserting, so this
+    /// saves the lookup of calling [`get_by_left`] afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let right = bimap.insert_and_get('a', 2);
+    /// assert_eq!(*right, 2);
+    /// assert_eq!(bimap.get_by_right(&1), None);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`get_by_left`]: BiHashMap::get_by_left
+    pub fn insert_and_get(&mut self, left: L, right: R) -> &R {
+        self.remove_by_left(&left);
+        self.remove_by_right(&right);
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.prefix_index.insert(&left.0);
+        self.right2left.insert(right.clone(), left.clone());
+        self.bump_generation();
+        &self.left2right.entry(left).or_insert(right).0
     }
-}
 
-impl<L, R, LS, RS> fmt::Debug for BiHashMap<L, R, LS, RS>
-where
-    L: fmt::Debug,
-    R: fmt::Debug,
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        struct EntryDebugger<'a, L, R> {
-            left: &'a L,
-            right: &'a R,
-        }
-        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+    /// Inserts the given left-right pair into the bimap and returns a reference
+    /// to the stored left value.
+    ///
+    /// This is the same as [`insert_and_get`], but for the left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let left = bimap.insert_and_get_left('b', 1);
+    /// assert_eq!(*left, 'b');
+    /// assert_eq!(bimap.get_by_left(&'a'), None);
+    /// ```
+    ///
+    /// [`insert_and_get`]: BiHashMap::insert_and_get
+    pub fn insert_and_get_left(&mut self, left: L, right: R) -> &L {
+        self.remove_by_left(&left);
+        self.remove_by_right(&right);
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.prefix_index.insert(&left.0);
+        self.left2right.insert(left.clone(), right.clone());
+        self.bump_generation();
+        &self.right2left.entry(right).or_insert(left).0
+    }
+
+    /// Replaces the right value paired with the given left value, returning
+    /// the old right value.
+    ///
+    /// If the bimap doesn't contain the left value, nothing happens and `None`
+    /// is returned. Unlike [`insert`], this never adds a new left value.
+    ///
+    /// If `new_right` is already paired with a different left value, that
+    /// pair is removed to maintain the bijection, just like with [`insert`].
+    /// Use [`update_by_left_detailed`] instead to get the removed pair back,
+    /// or check [`contains_right`] first to avoid removing it.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.update_by_left(&'a', 3), Some(1));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&3));
+    /// assert_eq!(bimap.get_by_right(&1), None);
+    ///
+    /// // missing left values are not inserted
+    /// assert_eq!(bimap.update_by_left(&'c', 4), None);
+    /// assert!(!bimap.contains_left(&'c'));
+    ///
+    /// // the pair ('b', 2) is removed because 2 is now paired with 'a'
+    /// assert_eq!(bimap.update_by_left(&'a', 2), Some(3));
+    /// assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`update_by_left_detailed`]: BiHashMap::update_by_left_detailed
+    /// [`contains_right`]: BiHashMap::contains_right
+    pub fn update_by_left<Q>(&mut self, left: &Q, new_right: R) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.update_by_left_detailed(left, new_right)
+            .map(|(old_right, _)| old_right)
+    }
+
+    /// Replaces the right value paired with the given left value, returning
+    /// the old right value and the pair, if any, that was overwritten by the
+    /// new right value.
+    ///
+    /// This behaves exactly like [`update_by_left`], but also returns the
+    /// pair that previously contained `new_right`. The returned
+    /// [`Overwritten`] is either `Neither`, if `new_right` wasn't paired with
+    /// a different left value, or `Right` with the removed pair.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(
+    ///     bimap.update_by_left_detailed(&'a', 3),
+    ///     Some((1, Overwritten::Neither))
+    /// );
+    /// assert_eq!(bimap.update_by_left_detailed(&'c', 4), None);
+    /// assert_eq!(
+    ///     bimap.update_by_left_detailed(&'a', 2),
+    ///     Some((3, Overwritten::Right('b', 2)))
+    /// );
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    ///
+    /// [`update_by_left`]: BiHashMap::update_by_left
+    pub fn update_by_left_detailed<Q>(
+        &mut self,
+        left: &Q,
+        new_right: R,
+    ) -> Option<(R, Overwritten<L, R>)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (left, old_right) = self.remove_by_left(left)?;
+        let overwritten = match self.remove_by_right(&new_right) {
+            Some((l, r)) => Overwritten::Right(l, r),
+            None => Overwritten::Neither,
+        };
+        self.insert_unchecked(left, new_right);
+        Some((old_right, overwritten))
+    }
+
+    /// Replaces the pair containing the given left value with a new pair,
+    /// returning the removed pair and the pairs that inserting the new pair
+    /// overwrote.
+    ///
+    /// This is useful for renames where both values change at once. If the
+    /// bimap doesn't contain `old_left`, nothing happens and `None` is
+    /// returned, so no new left value is added. Otherwise the old pair is
+    /// removed and the new pair is inserted as with [`insert`]: if `new_left`
+    /// or `new_right` is already paired with another value, that pair is
+    /// removed as well to maintain the bijection and returned in the
+    /// [`Overwritten`]. Check [`contains_left`] and [`contains_right`] first
+    /// to avoid removing them.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert("alice", 1);
+    /// bimap.insert("bob", 2);
+    ///
+    /// assert_eq!(
+    ///     bimap.replace_pair("alice", "carol", 3),
+    ///     Some((("alice", 1), Overwritten::Neither))
+    /// );
+    /// assert_eq!(bimap.get_by_left("carol"), Some(&3));
+    /// assert!(!bimap.contains_left("alice"));
+    /// assert!(!bimap.contains_right(&1));
+    ///
+    /// // missing left values are not replaced
+    /// assert_eq!(bimap.replace_pair("dave", "erin", 4), None);
+    /// assert!(!bimap.contains_left("erin"));
+    ///
+    /// // the pair ("bob", 2) is removed because 2 is now paired with "erin"
+    /// assert_eq!(
+    ///     bimap.replace_pair("carol", "erin", 2),
+    ///     Some((("carol", 3), Overwritten::Right("bob", 2)))
+    /// );
+    /// assert_eq!(bimap.get_by_right(&2), Some(&"erin"));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`contains_left`]: BiHashMap::contains_left
+    /// [`contains_right`]: BiHashMap::contains_right
+    pub fn replace_pair<Q>(
+        &mut self,
+        old_left: &Q,
+        new_left: L,
+        new_right: R,
+    ) -> Option<((L, R), Overwritten<L, R>)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let removed = self.remove_by_left(old_left)?;
+        let overwritten = self.insert(new_left, new_right);
+        Some((removed, overwritten))
+    }
+
+    /// Applies a batch of operations to the bimap, either all of them or none
+    /// of them.
+    ///
+    /// The operations run in order, each one seeing the effects of those
+    /// before it, so a batch may for example insert a pair and then replace
+    /// its left value. The whole batch is checked before the bimap is
+    /// touched: if any operation would fail, as described on [`BatchOp`], a
+    /// [`BatchError`] with that operation and the reason is returned and the
+    /// bimap is left exactly as it was. The check keeps a temporary map of
+    /// the values touched by the batch and doesn't count towards
+    /// [`lookup_stats`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BatchOp, BiHashMap, ModifyError};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert("alice", 1);
+    /// bimap.insert("bob", 2);
+    ///
+    /// // the renamed user takes over the id of the removed one
+    /// let batch = vec![
+    ///     BatchOp::RemoveByLeft("bob"),
+    ///     BatchOp::ReplaceLeft { old: "alice", new: "carol" },
+    ///     BatchOp::ReplaceRight { old: 1, new: 2 },
+    /// ];
+    /// assert_eq!(bimap.apply_batch(batch), Ok(()));
+    /// assert_eq!(bimap.get_by_left("carol"), Some(&2));
+    /// assert_eq!(bimap.len(), 1);
+    ///
+    /// // a failing operation rejects the whole batch
+    /// let batch = vec![BatchOp::Insert("dave", 3), BatchOp::RemoveByRight(1)];
+    /// let error = bimap.apply_batch(batch).unwrap_err();
+    /// assert_eq!((error.index, error.reason), (1, ModifyError::NotFound));
+    /// assert!(!bimap.contains_left("dave"));
+    /// ```
+    ///
+    /// [`lookup_stats`]: BiHashMap::lookup_stats
+    pub fn apply_batch<I>(&mut self, ops: I) -> Result<(), BatchError<L, R>>
+    where
+        I: IntoIterator<Item = BatchOp<L, R>>,
+    {
+        let mut ops: Vec<_> = ops.into_iter().collect();
+        if let Err((index, reason)) = self.check_batch(&ops) {
+            let op = ops.swap_remove(index);
+            return Err(BatchError { index, op, reason });
+        }
+        // unwraps are safe because the batch has been checked
+        for op in ops {
+            match op {
+                BatchOp::Insert(left, right) => self.insert_unchecked(left, right),
+                BatchOp::RemoveByLeft(left) => {
+                    self.remove_by_left(&left).unwrap();
+                }
+                BatchOp::RemoveByRight(right) => {
+                    self.remove_by_right(&right).unwrap();
+                }
+                BatchOp::ReplaceLeft { old, new } => {
+                    let (_, right) = self.remove_by_left(&old).unwrap();
+                    self.insert_unchecked(new, right);
+                }
+                BatchOp::ReplaceRight { old, new } => {
+                    let (left, _) = self.remove_by_right(&old).unwrap();
+                    self.insert_unchecked(left, new);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Begins a transaction on the bimap, which undoes the changes made
+    /// through it unless it is committed.
+    ///
+    /// See the [`transaction`](crate::transaction) module for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut tx = bimap.begin();
+    /// tx.insert('b', 2);
+    /// tx.remove_by_left(&'a');
+    /// drop(tx);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert!(!bimap.contains_left(&'b'));
+    ///
+    /// let mut tx = bimap.begin();
+    /// tx.insert('b', 2);
+    /// tx.commit();
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn begin(&mut self) -> Transaction<'_, Self, L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        Transaction::new(self)
+    }
+
+    /// Runs the closure in a transaction on the bimap, keeping its changes if
+    /// it returns `Ok` and undoing them if it returns `Err` or panics.
+    ///
+    /// The closure's result is returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert("alice", 1);
+    ///
+    /// // rename alice, but only if the new name is free
+    /// let rename = |bimap: &mut BiHashMap<_, _>, new| {
+    ///     bimap.transaction(|tx| {
+    ///         let (_, id) = tx.remove_by_left(&"alice").ok_or("no alice")?;
+    ///         if tx.insert(new, id).did_overwrite() {
+    ///             return Err("name taken");
+    ///         }
+    ///         Ok(id)
+    ///     })
+    /// };
+    ///
+    /// bimap.insert("bob", 2);
+    /// assert_eq!(rename(&mut bimap, "bob"), Err("name taken"));
+    /// assert_eq!(bimap.get_by_left(&"alice"), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&"bob"), Some(&2));
+    ///
+    /// assert_eq!(rename(&mut bimap, "carol"), Ok(1));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&"carol"));
+    /// ```
+    pub fn transaction<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        L: Clone,
+        R: Clone,
+        F: FnOnce(&mut Transaction<'_, Self, L, R>) -> Result<T, E>,
+    {
+        let mut tx = self.begin();
+        let result = f(&mut tx);
+        if result.is_ok() {
+            tx.commit();
+        }
+        result
+    }
+
+    /// Modifies the right value paired with the given left value in place,
+    /// keeping the bimap's reverse index up to date.
+    ///
+    /// The pair is taken out of the bimap while the closure modifies the right
+    /// value, and put back with the modified value afterwards, also when the
+    /// closure panics. If the modified value is already paired with a
+    /// different left value, the pair can't be put back: the original right
+    /// value no longer exists, so the pair is dropped and
+    /// [`ModifyError::Collision`] is returned. If the left value isn't
+    /// present, [`ModifyError::NotFound`] is returned and the bimap is left
+    /// unchanged.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, ModifyError};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', String::from("one"));
+    /// bimap.insert('b', String::from("two"));
+    ///
+    /// assert_eq!(bimap.modify_right_by_left(&'a', |r| r.push('!')), Ok(()));
+    /// assert_eq!(bimap.get_by_right("one!"), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right("one"), None);
+    ///
+    /// // ('a', "one!") is dropped because "two" is already paired with 'b'
+    /// let result = bimap.modify_right_by_left(&'a', |r| *r = String::from("two"));
+    /// assert_eq!(result, Err(ModifyError::Collision));
+    /// assert!(!bimap.contains_left(&'a'));
+    /// assert_eq!(bimap.get_by_right("two"), Some(&'b'));
+    ///
+    /// let result = bimap.modify_right_by_left(&'z', |r| r.clear());
+    /// assert_eq!(result, Err(ModifyError::NotFound));
+    /// ```
+    pub fn modify_right_by_left<Q, F>(&mut self, left: &Q, f: F) -> Result<(), ModifyError>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        F: FnOnce(&mut R),
+    {
+        let (left, right_rc) = self
+            .left2right
+            .remove_entry(Wrapper::wrap(left))
+            .ok_or(ModifyError::NotFound)?;
+        // unwrap is safe because we know right2left contains the key (it's a bimap)
+        self.right2left.remove(&right_rc).unwrap();
+        self.bump_generation();
+        // at this point we can safely unwrap because the other pointer is gone
+        let right = Rc::try_unwrap(right_rc.0).ok().unwrap();
+        let mut guard = ModifyGuard {
+            bimap: self,
+            pair: Some((left, right)),
+        };
+        // unwrap is safe because the pair is only taken by restore
+        f(&mut guard.pair.as_mut().unwrap().1);
+        guard.restore()
+    }
+
+    /// Exchanges the right values paired with the two given left values,
+    /// returning `true` if both left values are present and `false`
+    /// otherwise.
+    ///
+    /// The two right values simply trade owners, so no pairs are added or
+    /// removed and the bimap stays a bijection. If either left value is
+    /// missing, the bimap is unchanged. Swapping a left value with itself
+    /// leaves the pairs unchanged.
+    ///
+    /// The inputs may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert!(bimap.swap_rights(&'a', &'b'));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'b'));
+    /// assert!(!bimap.swap_rights(&'a', &'z'));
+    /// ```
+    pub fn swap_rights<Q>(&mut self, left_a: &Q, left_b: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let ((a, a_right), (b, b_right)) = match (
+            self.left2right.get_key_value(Wrapper::wrap(left_a)),
+            self.left2right.get_key_value(Wrapper::wrap(left_b)),
+        ) {
+            (Some((a, a_right)), Some((b, b_right))) => {
+                ((a.clone(), a_right.clone()), (b.clone(), b_right.clone()))
+            }
+            _ => return false,
+        };
+        self.left2right.insert(a.clone(), b_right.clone());
+        self.left2right.insert(b.clone(), a_right.clone());
+        self.right2left.insert(a_right, b);
+        self.right2left.insert(b_right, a);
+        self.bump_generation();
+        true
+    }
+
+    /// Exchanges the left values paired with the two given right values,
+    /// returning `true` if both right values are present and `false`
+    /// otherwise.
+    ///
+    /// The two left values simply trade owners, so no pairs are added or
+    /// removed and the bimap stays a bijection. If either right value is
+    /// missing, the bimap is unchanged. Swapping a right value with itself
+    /// leaves the pairs unchanged.
+    ///
+    /// The inputs may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert!(bimap.swap_lefts(&1, &2));
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'b'));
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+    /// assert!(!bimap.swap_lefts(&1, &3));
+    /// ```
+    pub fn swap_lefts<Q>(&mut self, right_a: &Q, right_b: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let ((a, a_left), (b, b_left)) = match (
+            self.right2left.get_key_value(Wrapper::wrap(right_a)),
+            self.right2left.get_key_value(Wrapper::wrap(right_b)),
+        ) {
+            (Some((a, a_left)), Some((b, b_left))) => {
+                ((a.clone(), a_left.clone()), (b.clone(), b_left.clone()))
+            }
+            _ => return false,
+        };
+        self.right2left.insert(a.clone(), b_left.clone());
+        self.right2left.insert(b.clone(), a_left.clone());
+        self.left2right.insert(a_left, b);
+        self.left2right.insert(b_left, a);
+        self.bump_generation();
+        true
+    }
+
+    /// Inserts every left-right pair from the iterator into the bimap,
+    /// returning the result of each insertion in order.
+    ///
+    /// This is the same as the [`Extend`] implementation, which calls
+    /// [`insert`] for every pair, except that the [`Overwritten`] values are
+    /// collected instead of discarded. Pairs from the iterator can overwrite
+    /// earlier pairs from the same iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let overwritten = bimap.extend_detailed(vec![('b', 2), ('a', 3), ('c', 2)]);
+    /// assert_eq!(
+    ///     overwritten,
+    ///     vec![
+    ///         Overwritten::Neither,
+    ///         Overwritten::Left('a', 1),
+    ///         Overwritten::Right('b', 2),
+    ///     ]
+    /// );
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn extend_detailed<I>(&mut self, iter: I) -> Vec<Overwritten<L, R>>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        iter.into_iter().map(|(l, r)| self.insert(l, r)).collect()
+    }
+
+    /// Inserts every left-right pair from the iterator that doesn't conflict
+    /// with the bimap, returning the rejected pairs in the order they were
+    /// encountered.
+    ///
+    /// Each pair is inserted with [`insert_no_overwrite`], so a pair whose
+    /// left or right value is already present is skipped entirely and the
+    /// existing pairs are never overwritten. Pairs from the iterator can be
+    /// rejected because of earlier pairs from the same iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let rejected = bimap.extend_no_overwrite(vec![('b', 2), ('a', 3), ('c', 2), ('d', 4)]);
+    /// assert_eq!(rejected, vec![('a', 3), ('c', 2)]);
+    /// assert_eq!(bimap.len(), 3);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    ///
+    /// [`insert_no_overwrite`]: BiHashMap::insert_no_overwrite
+    pub fn extend_no_overwrite<I>(&mut self, iter: I) -> Vec<(L, R)>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        iter.into_iter()
+            .filter_map(|(l, r)| self.insert_no_overwrite(l, r).err())
+            .collect()
+    }
+
+    /// Inserts a copy of every left-right pair in the slice into the bimap.
+    ///
+    /// Like [`Vec::extend_from_slice`], this is a convenience for `Copy`
+    /// pairs that avoids having to own or clone them first. Each pair is
+    /// inserted with [`insert`], so it overwrites any existing pairs with the
+    /// same left or right value, including earlier pairs from the same slice.
+    /// Capacity for all of the pairs is reserved up front, so inserting them
+    /// reallocates at most once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert(1, 10);
+    ///
+    /// bimap.extend_from_slice(&[(2, 20), (1, 11), (3, 20)]);
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_left(&1), Some(&11));
+    /// assert_eq!(bimap.get_by_left(&2), None);
+    /// assert_eq!(bimap.get_by_right(&20), Some(&3));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    pub fn extend_from_slice(&mut self, pairs: &[(L, R)])
+    where
+        L: Copy,
+        R: Copy,
+    {
+        self.reserve(pairs.len());
+        for &(left, right) in pairs {
+            self.insert(left, right);
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &r)` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.retain(|&l, &r| r >= 2);
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// assert_eq!(bimap.get_by_left(&'c'), Some(&3));
+    /// assert_eq!(bimap.get_by_left(&'a'), None);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        self.bump_generation();
+        let mut f = f;
+        let right2left = &mut self.right2left;
+        let prefix_index = &mut self.prefix_index;
+        self.left2right.retain(|l, r| {
+            let to_retain = f(&l.0, &r.0);
+            if !to_retain {
+                right2left.remove(r);
+                prefix_index.remove(&l.0);
+            }
+            to_retain
+        });
+        self.shrink_if_sparse();
+    }
+
+    /// Retains only the left-right pairs that are also in `other`.
+    ///
+    /// A pair is kept if `other` contains the same left value paired with the
+    /// same right value, so this turns the bimap into the intersection of
+    /// both bimaps without allocating a new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut live: BiHashMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// let snapshot: BiHashMap<_, _> = vec![('a', 1), ('b', 5), ('d', 4)].into_iter().collect();
+    ///
+    /// live.retain_intersection(&snapshot);
+    /// assert_eq!(live.len(), 1);
+    /// assert_eq!(live.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn retain_intersection(&mut self, other: &Self) {
+        self.retain(|l, r| other.get_by_left(l) == Some(r));
+    }
+
+    /// Removes all left-right pairs that are also in `other`.
+    ///
+    /// A pair is removed if `other` contains the same left value paired with
+    /// the same right value, so this turns the bimap into the difference of
+    /// both bimaps without allocating a new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut live: BiHashMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// let stale: BiHashMap<_, _> = vec![('a', 1), ('b', 5), ('d', 4)].into_iter().collect();
+    ///
+    /// live.remove_all(&stale);
+    /// assert_eq!(live.len(), 2);
+    /// assert_eq!(live.get_by_left(&'a'), None);
+    /// assert_eq!(live.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn remove_all(&mut self, other: &Self) {
+        self.retain(|l, r| other.get_by_left(l) != Some(r));
+    }
+
+    /// Retains only the elements specified by the predicate and returns the
+    /// number of left-right pairs that were removed.
+    ///
+    /// This is the same as [`retain`], which returns `()` like the method of
+    /// the same name on the standard maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<char, i32> = ('a'..='e').zip(1..).collect();
+    /// assert_eq!(bimap.retain_count(|_, &r| r % 2 == 1), 2);
+    /// assert_eq!(bimap.len(), 3);
+    /// assert_eq!(bimap.retain_count(|_, _| true), 0);
+    /// ```
+    ///
+    /// [`retain`]: BiHashMap::retain
+    pub fn retain_count<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        let len = self.len();
+        self.retain(f);
+        len - self.len()
+    }
+
+    /// Removes the pairs for which the predicate returns `true` and returns
+    /// them in arbitrary order.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn extract_where<F>(&mut self, mut f: F) -> Vec<(L, R)>
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        self.bump_generation();
+        let mut removed = Vec::new();
+        let right2left = &mut self.right2left;
+        let prefix_index = &mut self.prefix_index;
+        self.left2right.retain(|l, r| {
+            let to_remove = f(&l.0, &r.0);
+            if to_remove {
+                right2left.remove(r);
+                prefix_index.remove(&l.0);
+                removed.push((l.clone(), r.clone()));
+            }
+            !to_remove
+        });
+        self.shrink_if_sparse();
+        // unwrap is safe because the bimap no longer holds the removed pairs
+        removed
+            .into_iter()
+            .map(|(l, r)| {
+                (
+                    Rc::try_unwrap(l.0).ok().unwrap(),
+                    Rc::try_unwrap(r.0).ok().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    /// Retains only the elements specified by the predicate, allowing the
+    /// predicate to modify the right values of the retained pairs.
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &mut r)` returns `false`. Right values are re-indexed after every pair
+    /// has been visited.
+    ///
+    /// If modifying the right values causes two retained pairs to share a
+    /// right value, only one of them is kept and the others are dropped.
+    /// Which pair is kept is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.retain_mut_right(|&l, r| {
+    ///     *r *= 10;
+    ///     l != 'b'
+    /// });
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&10));
+    /// assert_eq!(bimap.get_by_right(&30), Some(&'c'));
+    /// assert_eq!(bimap.get_by_right(&3), None);
+    /// ```
+    pub fn retain_mut_right<F>(&mut self, f: F)
+    where
+        F: FnMut(&L, &mut R) -> bool,
+    {
+        self.bump_generation();
+        let mut f = f;
+        // drop the reverse references so that the right values are uniquely
+        // owned, then rebuild the reverse map once the sweep has finished (or
+        // panicked)
+        self.right2left.clear();
+        let guard = ReindexGuard { bimap: self };
+        let prefix_index = &mut guard.bimap.prefix_index;
+        guard.bimap.left2right.retain(|l, r| {
+            // unwrap is safe because right2left has been cleared
+            let right = Rc::get_mut(&mut r.0).unwrap();
+            let to_retain = f(&l.0, right);
+            if !to_retain {
+                prefix_index.remove(&l.0);
+            }
+            to_retain
+        });
+        drop(guard);
+        self.shrink_if_sparse();
+    }
+
+    /// Consumes the bimap and returns its left values as a `HashSet`, dropping
+    /// the right values.
+    ///
+    /// The set uses a clone of the bimap's left hasher. Its storage has a
+    /// different layout than the bimap's, so it is allocated anew with exactly
+    /// enough capacity for the left values, and the bimap's allocations are
+    /// freed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let lefts: HashSet<char> = bimap.into_left_hashset();
+    /// assert_eq!(lefts, ['a', 'b'].iter().copied().collect());
+    /// ```
+    pub fn into_left_hashset(self) -> HashSet<L, LS>
+    where
+        LS: Clone,
+    {
+        let BiHashMap {
+            left2right,
+            right2left,
+            ..
+        } = self;
+        // drop the right values and the reverse references first, so that
+        // the left values are uniquely owned
+        drop(right2left);
+        let mut set =
+            HashSet::with_capacity_and_hasher(left2right.len(), left2right.hasher().clone());
+        // unwrap is safe because right2left has been dropped
+        set.extend(
+            left2right
+                .into_keys()
+                .map(|l| Rc::try_unwrap(l.0).ok().unwrap()),
+        );
+        set
+    }
+
+    /// Consumes the bimap and returns its right values as a `HashSet`,
+    /// dropping the left values.
+    ///
+    /// The set uses a clone of the bimap's right hasher. Its storage has a
+    /// different layout than the bimap's, so it is allocated anew with exactly
+    /// enough capacity for the right values, and the bimap's allocations are
+    /// freed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let rights: HashSet<i32> = bimap.into_right_hashset();
+    /// assert_eq!(rights, [1, 2].iter().copied().collect());
+    /// ```
+    pub fn into_right_hashset(self) -> HashSet<R, RS>
+    where
+        RS: Clone,
+    {
+        let BiHashMap {
+            left2right,
+            right2left,
+            ..
+        } = self;
+        // drop the left values and the forward references first, so that the
+        // right values are uniquely owned
+        drop(left2right);
+        let mut set =
+            HashSet::with_capacity_and_hasher(right2left.len(), right2left.hasher().clone());
+        // unwrap is safe because left2right has been dropped
+        set.extend(
+            right2left
+                .into_keys()
+                .map(|r| Rc::try_unwrap(r.0).ok().unwrap()),
+        );
+        set
+    }
+
+    /// Returns a `HashMap` from left values to right values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// The map uses a clone of the bimap's left hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let map: HashMap<char, i32> = bimap.to_hash_map();
+    /// assert_eq!(map[&'a'], 1);
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    pub fn to_hash_map(&self) -> HashMap<L, R, LS>
+    where
+        L: Clone,
+        R: Clone,
+        LS: Clone,
+    {
+        let mut map =
+            HashMap::with_capacity_and_hasher(self.len(), self.left2right.hasher().clone());
+        map.extend(self.iter().map(|(l, r)| (l.clone(), r.clone())));
+        map
+    }
+
+    /// Returns a `HashMap` from right values to left values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// The map uses a clone of the bimap's right hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let map: HashMap<i32, char> = bimap.to_hash_map_by_right();
+    /// assert_eq!(map[&1], 'a');
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    pub fn to_hash_map_by_right(&self) -> HashMap<R, L, RS>
+    where
+        L: Clone,
+        R: Clone,
+        RS: Clone,
+    {
+        let mut map =
+            HashMap::with_capacity_and_hasher(self.len(), self.right2left.hasher().clone());
+        map.extend(self.iter().map(|(l, r)| (r.clone(), l.clone())));
+        map
+    }
+
+    /// Returns a `BTreeMap` from left values to right values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('b', 1);
+    /// bimap.insert('a', 2);
+    /// let map: BTreeMap<char, i32> = bimap.to_btree_map();
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [('a', 2), ('b', 1)]);
+    /// ```
+    pub fn to_btree_map(&self) -> BTreeMap<L, R>
+    where
+        L: Clone + Ord,
+        R: Clone,
+    {
+        self.iter().map(|(l, r)| (l.clone(), r.clone())).collect()
+    }
+
+    /// Returns a `BTreeMap` from right values to left values, cloning the
+    /// pairs and leaving the bimap untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 2);
+    /// bimap.insert('b', 1);
+    /// let map: BTreeMap<i32, char> = bimap.to_btree_map_by_right();
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 'b'), (2, 'a')]);
+    /// ```
+    pub fn to_btree_map_by_right(&self) -> BTreeMap<R, L>
+    where
+        L: Clone,
+        R: Clone + Ord,
+    {
+        self.iter().map(|(l, r)| (r.clone(), l.clone())).collect()
+    }
+
+    /// Consumes the bimap and returns an immutable [`FrozenBiMap`] with the
+    /// same pairs, laid out for fast lookups with as little memory as
+    /// possible.
+    ///
+    /// The frozen bimap keeps the bimap's hashers. Use
+    /// [`FrozenBiMap::thaw`] to get a mutable `BiHashMap` back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let frozen = bimap.freeze();
+    /// assert_eq!(frozen.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(frozen.get_by_right(&2), Some(&'b'));
+    /// ```
+    pub fn freeze(self) -> FrozenBiMap<L, R, LS, RS>
+    where
+        LS: Clone,
+        RS: Clone,
+    {
+        let BiHashMap {
+            left2right,
+            right2left,
+            ..
+        } = self;
+        let right_hasher = right2left.hasher().clone();
+        // drop the reverse references first, so that the values are uniquely
+        // owned
+        drop(right2left);
+        let left_hasher = left2right.hasher().clone();
+        // unwrap is safe because right2left has been dropped
+        let pairs = left2right
+            .into_iter()
+            .map(|(l, r)| {
+                (
+                    Rc::try_unwrap(l.0).ok().unwrap(),
+                    Rc::try_unwrap(r.0).ok().unwrap(),
+                )
+            })
+            .collect();
+        FrozenBiMap::from_unique_pairs(pairs, left_hasher, right_hasher)
+    }
+
+    /// Clones the bimap into a new bimap with as little capacity as its
+    /// pairs need.
+    ///
+    /// The default [`clone`](Clone::clone) keeps the capacity of the
+    /// original, so that the clone can grow back to the same size without
+    /// reallocating. For a bimap that used to be much larger than it is now,
+    /// that capacity is mostly unused memory, which adds up when a snapshot
+    /// is kept around for a long time. `clone_compact` allocates only what
+    /// `len()` pairs need instead, at the cost of reallocating once the
+    /// clone grows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, -i)).collect();
+    /// bimap.retain(|&l, _| l < 10);
+    ///
+    /// let compact = bimap.clone_compact();
+    /// assert_eq!(compact, bimap);
+    /// assert!(compact.capacity() < 100);
+    /// assert!(bimap.clone().capacity() >= 1000);
+    /// ```
+    pub fn clone_compact(&self) -> Self
+    where
+        L: Clone,
+        R: Clone,
+        LS: Clone,
+        RS: Clone,
+    {
+        let mut new_bimap = BiHashMap::with_capacity_and_hashers(
+            self.len(),
+            self.left2right.hasher().clone(),
+            self.right2left.hasher().clone(),
+        );
+        new_bimap.auto_shrink = self.auto_shrink;
+        new_bimap.prefix_index = self.prefix_index.empty_copy();
+        for (l, r) in self.iter() {
+            new_bimap.insert_unchecked(l.clone(), r.clone());
+        }
+        new_bimap
+    }
+
+    /// Checks that the two internal maps of the bimap are mirror images of each
+    /// other, returning every inconsistency found.
+    ///
+    /// The bimap's own methods always keep the maps consistent, so this only
+    /// fails if there is a bug in this crate. It takes O(n) lookups, which is
+    /// cheap enough to call after every batch of operations in debug builds or
+    /// fuzz targets. An error lists the differing lengths first, then every
+    /// pair of the left-to-right map that is missing or mismatched in the
+    /// right-to-left map, then vice versa.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 1);
+    /// bimap.remove_by_left(&'b');
+    /// debug_assert_eq!(bimap.check_invariants(), Ok(()));
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), Vec<InvariantViolation<&L, &R>>> {
+        let mut violations = Vec::new();
+        if self.left2right.len() != self.right2left.len() {
+            violations.push(InvariantViolation::Len {
+                left2right: self.left2right.len(),
+                right2left: self.right2left.len(),
+            });
+        }
+        for (left, right) in &self.left2right {
+            match self.right2left.get(right) {
+                None => violations.push(InvariantViolation::MissingRight {
+                    left: &*left.0,
+                    right: &*right.0,
+                }),
+                Some(found) if found != left => violations.push(InvariantViolation::WrongLeft {
+                    left: &*left.0,
+                    right: &*right.0,
+                    found: &*found.0,
+                }),
+                Some(_) => {}
+            }
+        }
+        for (right, left) in &self.right2left {
+            match self.left2right.get(left) {
+                None => violations.push(InvariantViolation::MissingLeft {
+                    left: &*left.0,
+                    right: &*right.0,
+                }),
+                Some(found) if found != right => violations.push(InvariantViolation::WrongRight {
+                    left: &*left.0,
+                    right: &*right.0,
+                    found: &*found.0,
+                }),
+                Some(_) => {}
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if the
+    /// pair already exists.
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.prefix_index.insert(&left.0);
+        self.left2right.insert(left.clone(), right.clone());
+        self.right2left.insert(right, left);
+        self.bump_generation();
+    }
+
+    /// Checks a batch for `apply_batch` without changing the bimap,
+    /// returning the position of the first failing operation and the reason.
+    fn check_batch(&self, ops: &[BatchOp<L, R>]) -> Result<(), (usize, ModifyError)> {
+        /// Returns the current partner of a value, looking at the changes
+        /// made by the batch first.
+        fn partner<'a, T, U, S>(
+            changed: &HashMap<&'a T, Option<&'a U>>,
+            map: &'a HashMap<Ref<T>, Ref<U>, S>,
+            value: &'a T,
+        ) -> Option<&'a U>
         where
-            L: fmt::Debug,
-            R: fmt::Debug,
+            T: Eq + Hash,
+            S: BuildHasher,
         {
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                self.left.fmt(f)?;
-                write!(f, " <> ")?;
-                self.right.fmt(f)
+            match changed.get(value) {
+                Some(partner) => *partner,
+                None => map.get(Wrapper::wrap(value)).map(|p| &*p.0),
+            }
+        }
+
+        // the values changed by the operations checked so far, mapped to their
+        // new partners or to None if they have been removed
+        let mut lefts: HashMap<&L, Option<&R>> = HashMap::new();
+        let mut rights: HashMap<&R, Option<&L>> = HashMap::new();
+        let right_of = |lefts: &HashMap<_, _>, left| partner(lefts, &self.left2right, left);
+        let left_of = |rights: &HashMap<_, _>, right| partner(rights, &self.right2left, right);
+        for (index, op) in ops.iter().enumerate() {
+            match op {
+                BatchOp::Insert(left, right) => {
+                    if right_of(&lefts, left).is_some() || left_of(&rights, right).is_some() {
+                        return Err((index, ModifyError::Collision));
+                    }
+                    lefts.insert(left, Some(right));
+                    rights.insert(right, Some(left));
+                }
+                BatchOp::RemoveByLeft(left) => {
+                    let right = right_of(&lefts, left).ok_or((index, ModifyError::NotFound))?;
+                    lefts.insert(left, None);
+                    rights.insert(right, None);
+                }
+                BatchOp::RemoveByRight(right) => {
+                    let left = left_of(&rights, right).ok_or((index, ModifyError::NotFound))?;
+                    lefts.insert(left, None);
+                    rights.insert(right, None);
+                }
+                BatchOp::ReplaceLeft { old, new } => {
+                    let right = right_of(&lefts, old).ok_or((index, ModifyError::NotFound))?;
+                    if new != old && right_of(&lefts, new).is_some() {
+                        return Err((index, ModifyError::Collision));
+                    }
+                    lefts.insert(old, None);
+                    lefts.insert(new, Some(right));
+                    rights.insert(right, Some(new));
+                }
+                BatchOp::ReplaceRight { old, new } => {
+                    let left = left_of(&rights, old).ok_or((index, ModifyError::NotFound))?;
+                    if new != old && left_of(&rights, new).is_some() {
+                        return Err((index, ModifyError::Collision));
+                    }
+                    rights.insert(old, None);
+                    rights.insert(new, Some(left));
+                    lefts.insert(left, Some(new));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the slice contains exactly the pairs of the bimap,
+    /// each one once.
+    fn eq_pairs(&self, pairs: &[(L, R)]) -> bool {
+        if pairs.len() != self.len() {
+            return false;
+        }
+        // the lengths match, so the slice holds every pair exactly once if
+        // all of its pairs are in the bimap and its left values are distinct
+        let mut seen = HashSet::with_capacity(pairs.len());
+        pairs
+            .iter()
+            .all(|(l, r)| self.get_by_left(l) == Some(r) && seen.insert(l))
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<L, R, LS, RS> BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a uniformly random left-right pair from the bimap, or `None`
+    /// if the bimap is empty.
+    ///
+    /// The backing `HashMap`s don't support indexing, so this takes O(n) time
+    /// to walk to a random position. The same `rng` state always selects the
+    /// same pair from the same bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rand::{rngs::SmallRng, SeedableRng};
+    ///
+    /// let mut rng = SmallRng::seed_from_u64(42);
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(bimap.random_pair(&mut rng), None);
+    ///
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// let (left, right) = bimap.random_pair(&mut rng).unwrap();
+    /// assert_eq!(bimap.get_by_left(left), Some(right));
+    /// ```
+    pub fn random_pair<G>(&self, rng: &mut G) -> Option<(&L, &R)>
+    where
+        G: rand::Rng + ?Sized,
+    {
+        self.random_left_ref(rng)
+            .and_then(|left| self.left2right.get_key_value(left))
+            .map(|(l, r)| (&*l.0, &*r.0))
+    }
+
+    /// Removes a uniformly random left-right pair from the bimap and returns
+    /// it, or returns `None` if the bimap is empty.
+    ///
+    /// Like [`random_pair`], this takes O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rand::{rngs::SmallRng, SeedableRng};
+    ///
+    /// let mut rng = SmallRng::seed_from_u64(42);
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let (left, right) = bimap.remove_random(&mut rng).unwrap();
+    /// assert_eq!(bimap.len(), 1);
+    /// assert!(!bimap.contains_left(&left));
+    /// assert!(!bimap.contains_right(&right));
+    /// ```
+    ///
+    /// [`random_pair`]: BiHashMap::random_pair
+    pub fn remove_random<G>(&mut self, rng: &mut G) -> Option<(L, R)>
+    where
+        G: rand::Rng + ?Sized,
+    {
+        let left = self.random_left_ref(rng)?.clone();
+        self.bump_generation();
+        // unwrap is safe because the left value was just taken from left2right
+        let right_rc = self.left2right.remove(&left).unwrap();
+        // unwrap is safe because we know right2left contains the key (it's a bimap)
+        let left_rc = self.right2left.remove(&right_rc).unwrap();
+        self.prefix_index.remove(&left_rc.0);
+        drop(left);
+        self.shrink_if_sparse();
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Creates an iterator over `amount` distinct left-right pairs chosen
+    /// uniformly at random from the bimap, in random order.
+    ///
+    /// If `amount` is larger than the number of pairs in the bimap, every
+    /// pair is returned. Creating the iterator takes O(n) time and
+    /// allocates O(n) memory, regardless of `amount`.
+    ///
+    /// The iterator element type is `(&L, &R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rand::{rngs::SmallRng, SeedableRng};
+    ///
+    /// let mut rng = SmallRng::seed_from_u64(42);
+    /// let bimap: BiHashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    ///
+    /// let sample: Vec<_> = bimap.sample_iter(&mut rng, 3).collect();
+    /// assert_eq!(sample.len(), 3);
+    /// for (left, right) in sample {
+    ///     assert_eq!(*right, left * 10);
+    /// }
+    /// ```
+    pub fn sample_iter<G>(&self, rng: &mut G, amount: usize) -> SampleIter<'_, L, R>
+    where
+        G: rand::Rng + ?Sized,
+    {
+        let pairs: Vec<_> = self.iter().collect();
+        let amount = amount.min(pairs.len());
+        let indices = rand::seq::index::sample(rng, pairs.len(), amount);
+        SampleIter {
+            inner: indices
+                .into_iter()
+                .map(|i| pairs[i])
+                .collect::<Vec<_>>()
+                .into_iter(),
+        }
+    }
+
+    /// Returns the left value at a uniformly random position in the
+    /// left-to-right map.
+    fn random_left_ref<G>(&self, rng: &mut G) -> Option<&Ref<L>>
+    where
+        G: rand::Rng + ?Sized,
+    {
+        use rand::RngExt;
+
+        if self.is_empty() {
+            return None;
+        }
+        let index = rng.random_range(0..self.len());
+        self.left2right.keys().nth(index)
+    }
+}
+
+/// Rebuilds the right-to-left map of a bimap from its left-to-right map when
+/// dropped, removing any pairs whose right value is already taken.
+struct ReindexGuard<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    bimap: &'a mut BiHashMap<L, R, LS, RS>,
+}
+
+impl<'a, L, R, LS, RS> Drop for ReindexGuard<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn drop(&mut self) {
+        let left2right = &mut self.bimap.left2right;
+        let right2left = &mut self.bimap.right2left;
+        right2left.clear();
+        let mut duplicates = Vec::new();
+        for (l, r) in left2right.iter() {
+            if right2left.contains_key(r) {
+                duplicates.push(l.clone());
+            } else {
+                right2left.insert(r.clone(), l.clone());
             }
         }
+        for l in duplicates {
+            left2right.remove(&l);
+            self.bimap.prefix_index.remove(&l.0);
+        }
+    }
+}
+
+/// Puts a pair taken out of a bimap by `modify_right_by_left` back when
+/// dropped, so that a panic while modifying the right value doesn't lose the
+/// pair.
+struct ModifyGuard<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    bimap: &'a mut BiHashMap<L, R, LS, RS>,
+    pair: Option<(Ref<L>, R)>,
+}
+
+impl<'a, L, R, LS, RS> ModifyGuard<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Puts the pair back into the bimap, or drops it if its right value is
+    /// now paired with another left value.
+    fn restore(&mut self) -> Result<(), ModifyError> {
+        let (left, right) = match self.pair.take() {
+            Some(pair) => pair,
+            None => return Ok(()),
+        };
+        if self.bimap.right2left.contains_key(Wrapper::wrap(&right)) {
+            self.bimap.prefix_index.remove(&left.0);
+            self.bimap.shrink_if_sparse();
+            return Err(ModifyError::Collision);
+        }
+        let right = Ref(Rc::new(right));
+        self.bimap.left2right.insert(left.clone(), right.clone());
+        self.bimap.right2left.insert(right, left);
+        Ok(())
+    }
+}
+
+impl<'a, L, R, LS, RS> Drop for ModifyGuard<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+impl<L, R, LS, RS> crate::sealed::Sealed for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+}
+
+impl<L, R, LS, RS> BiMapLike<L, R> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Iter<'a>
+        = Iter<'a, L, R>
+    where
+        Self: 'a,
+        L: 'a,
+        R: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter(&self) -> Iter<'_, L, R> {
+        self.iter()
+    }
+
+    fn get_by_left(&self, left: &L) -> Option<&R> {
+        self.get_by_left(left)
+    }
+
+    fn get_by_right(&self, right: &R) -> Option<&L> {
+        self.get_by_right(right)
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn contains_right(&self, right: &R) -> bool {
+        self.contains_right(right)
+    }
+
+    fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        self.insert(left, right)
+    }
+
+    fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        self.remove_by_left(left)
+    }
+
+    fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        self.remove_by_right(right)
+    }
+}
+
+impl<L, R, LS, RS> Clone for BiHashMap<L, R, LS, RS>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+{
+    fn clone(&self) -> BiHashMap<L, R, LS, RS> {
+        let mut new_bimap = BiHashMap::with_capacity_and_hashers(
+            self.capacity(),
+            self.left2right.hasher().clone(),
+            self.right2left.hasher().clone(),
+        );
+        new_bimap.auto_shrink = self.auto_shrink;
+        new_bimap.prefix_index = self.prefix_index.empty_copy();
+        for (l, r) in self.iter() {
+            new_bimap.insert(l.clone(), r.clone());
+        }
+        new_bimap
+    }
+}
+
+/// Displays the bimap's pairs in arbitrary order, as `{left <> right, ...}`.
+///
+/// Use [`debug_sorted`](BiHashMap::debug_sorted) for output that doesn't
+/// depend on the iteration order, e.g. in test assertions.
+impl<L, R, LS, RS> fmt::Debug for BiHashMap<L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_set()
             .entries(
                 self.left2right
@@ -697,19 +3188,133 @@ where
     }
 }
 
+/// Formats a single pair as `left <> right`
+struct EntryDebugger<'a, L, R> {
+    left: &'a L,
+    right: &'a R,
+}
+
+impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.left.fmt(f)?;
+        write!(f, " <> ")?;
+        self.right.fmt(f)
+    }
+}
+
+/// Formats a bimap's pairs in ascending order of their left values
+struct SortedDebugger<'a, L, R, LS, RS> {
+    bimap: &'a BiHashMap<L, R, LS, RS>,
+}
+
+impl<'a, L, R, LS, RS> fmt::Debug for SortedDebugger<'a, L, R, LS, RS>
+where
+    L: fmt::Debug + Ord,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut entries = self
+            .bimap
+            .left2right
+            .iter()
+            .map(|(left, right)| EntryDebugger {
+                left: &*left.0,
+                right: &*right.0,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by(|a, b| a.left.cmp(b.left));
+        f.debug_set().entries(entries).finish()
+    }
+}
+
+/// Creates an empty `BiHashMap` with the default hashers.
+///
+/// Like `HashMap`, this only requires the hashers to implement `Default`, so
+/// `#[derive(Default)]` works on structs containing bimaps with custom hashers
+/// or with value types that aren't `Hash`.
 impl<L, R, LS, RS> Default for BiHashMap<L, R, LS, RS>
 where
-    L: Eq + Hash,
-    R: Eq + Hash,
-    LS: BuildHasher + Default,
-    RS: BuildHasher + Default,
+    LS: Default,
+    RS: Default,
 {
     fn default() -> BiHashMap<L, R, LS, RS> {
         BiHashMap {
             left2right: HashMap::default(),
             right2left: HashMap::default(),
+            generation: 0,
+            stats: Counters::new(),
+            auto_shrink: false,
+            prefix_index: PrefixIndex::disabled(),
+        }
+    }
+}
+
+/// Formats the bimap as `{left <-> right, ...}`.
+///
+/// The pairs are listed in arbitrary order, which can differ between two
+/// equal bimaps. Use [`display_sorted`](BiHashMap::display_sorted) to list
+/// them in ascending order of their left values instead.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiHashMap;
+///
+/// let mut bimap = BiHashMap::new();
+/// bimap.insert("apple", 1);
+/// assert_eq!(bimap.to_string(), "{apple <-> 1}");
+/// ```
+impl<L, R, LS, RS> fmt::Display for BiHashMap<L, R, LS, RS>
+where
+    L: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        display_pairs(f, self.left2right.iter().map(|(l, r)| (&*l.0, &*r.0)))
+    }
+}
+
+/// Formats a bimap's pairs in ascending order of their left values
+struct SortedDisplayer<'a, L, R, LS, RS> {
+    bimap: &'a BiHashMap<L, R, LS, RS>,
+}
+
+impl<'a, L, R, LS, RS> fmt::Display for SortedDisplayer<'a, L, R, LS, RS>
+where
+    L: fmt::Display + Ord,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pairs = self
+            .bimap
+            .left2right
+            .iter()
+            .map(|(l, r)| (&*l.0, &*r.0))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        display_pairs(f, pairs)
+    }
+}
+
+/// Writes pairs as `{left <-> right, ...}`
+fn display_pairs<'a, L, R, I>(f: &mut fmt::Formatter, pairs: I) -> fmt::Result
+where
+    L: fmt::Display + 'a,
+    R: fmt::Display + 'a,
+    I: IntoIterator<Item = (&'a L, &'a R)>,
+{
+    f.write_str("{")?;
+    for (i, (left, right)) in pairs.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
         }
+        write!(f, "{} <-> {}", left, right)?;
     }
+    f.write_str("}")
 }
 
 impl<L, R, LS, RS> Eq for BiHashMap<L, R, LS, RS>
@@ -721,6 +3326,94 @@ where
 {
 }
 
+/// Hashes the bimap independently of its iteration order, so that equal
+/// bimaps hash equally regardless of the order their pairs were inserted in
+/// or the hashers they use.
+///
+/// Every left-right pair is hashed on its own with a fixed-seed
+/// [`DefaultHasher`], and the resulting 64-bit hashes are combined with
+/// wrapping addition, which is commutative. The number of pairs and the
+/// combined value are then written to the given hasher. Bimaps that differ in
+/// any pair collide only if their sums happen to match.
+///
+/// The hash is stable for the lifetime of a program, which is all `Hash`
+/// requires, but it is not guaranteed to be stable across Rust versions or
+/// versions of this crate, so it must not be persisted.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiHashMap;
+/// use std::collections::HashSet;
+///
+/// let mut bimap = BiHashMap::new();
+/// bimap.insert('a', 1);
+/// bimap.insert('b', 2);
+///
+/// let mut seen = HashSet::new();
+/// seen.insert(bimap);
+///
+/// let mut bimap2 = BiHashMap::new();
+/// bimap2.insert('b', 2);
+/// bimap2.insert('a', 1);
+/// assert!(seen.contains(&bimap2));
+/// ```
+///
+/// [`DefaultHasher`]: hash_map::DefaultHasher
+impl<L, R, LS, RS> Hash for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let sum = self.iter().fold(0u64, |sum, pair| {
+            let mut hasher = hash_map::DefaultHasher::new();
+            pair.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        state.write_usize(self.len());
+        state.write_u64(sum);
+    }
+}
+
+/// Converts a `BiBTreeMap` into a `BiHashMap` with the same left-right pairs.
+///
+/// Since the pairs of a bimap are already unique, this allocates the hash
+/// maps once and skips the overwrite checks of [`insert`], which is faster
+/// than collecting the `BiBTreeMap` into a `BiHashMap`.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiBTreeMap, BiHashMap};
+///
+/// let mut btree = BiBTreeMap::new();
+/// btree.insert('a', 1);
+/// btree.insert('b', 2);
+///
+/// let hash: BiHashMap<_, _> = btree.into();
+/// assert_eq!(hash.len(), 2);
+/// assert_eq!(hash.get_by_right(&2), Some(&'b'));
+/// ```
+///
+/// [`insert`]: BiHashMap::insert
+impl<L, R, LS, RS> From<BiBTreeMap<L, R>> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Ord,
+    R: Eq + Hash + Ord,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn from(bimap: BiBTreeMap<L, R>) -> BiHashMap<L, R, LS, RS> {
+        let mut new_bimap =
+            BiHashMap::with_capacity_and_hashers(bimap.len(), LS::default(), RS::default());
+        for (l, r) in bimap {
+            new_bimap.insert_unchecked(l, r);
+        }
+        new_bimap
+    }
+}
+
 impl<L, R, LS, RS> FromIterator<(L, R)> for BiHashMap<L, R, LS, RS>
 where
     L: Eq + Hash,
@@ -733,14 +3426,11 @@ where
         I: IntoIterator<Item = (L, R)>,
     {
         let iter = iter.into_iter();
-        let mut bimap = match iter.size_hint() {
-            (lower, None) => {
-                BiHashMap::with_capacity_and_hashers(lower, LS::default(), RS::default())
-            }
-            (_, Some(upper)) => {
-                BiHashMap::with_capacity_and_hashers(upper, LS::default(), RS::default())
-            }
-        };
+        // only the lower bound is reserved, since an iterator like
+        // `(0..usize::MAX).filter(..)` may yield far fewer pairs than its upper
+        // bound, which could overflow the capacity
+        let mut bimap =
+            BiHashMap::with_capacity_and_hashers(iter.size_hint().0, LS::default(), RS::default());
         for (left, right) in iter {
             bimap.insert(left, right);
         }
@@ -802,6 +3492,98 @@ where
     }
 }
 
+/// Compares the bimaps' left-right pairs, regardless of their order.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiBTreeMap, BiHashMap};
+///
+/// let mut hash = BiHashMap::new();
+/// hash.insert('b', 2);
+/// hash.insert('a', 1);
+/// let mut btree = BiBTreeMap::new();
+/// btree.insert('a', 1);
+/// btree.insert('b', 2);
+///
+/// assert_eq!(hash, btree);
+/// assert_eq!(btree, hash);
+/// ```
+impl<L, R, LS, RS> PartialEq<BiBTreeMap<L, R>> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Ord,
+    R: Eq + Hash + Ord,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &BiBTreeMap<L, R>) -> bool {
+        // both are bijections, so every pair of the smaller one being in the
+        // other means they're equal
+        self.len() == other.len() && other.iter().all(|(l, r)| self.get_by_left(l) == Some(r))
+    }
+}
+
+/// Compares the bimaps' left-right pairs, regardless of their order.
+impl<L, R, LS, RS> PartialEq<BiHashMap<L, R, LS, RS>> for BiBTreeMap<L, R>
+where
+    L: Eq + Hash + Ord,
+    R: Eq + Hash + Ord,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &BiHashMap<L, R, LS, RS>) -> bool {
+        other == self
+    }
+}
+
+/// Compares the bimap's left-right pairs with the pairs of an array,
+/// regardless of their order.
+///
+/// The bimap is equal to the array if the array contains exactly the pairs
+/// of the bimap, each one once.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiHashMap;
+///
+/// let mut bimap = BiHashMap::new();
+/// bimap.insert('a', 1);
+/// bimap.insert('b', 2);
+///
+/// assert_eq!(bimap, [('b', 2), ('a', 1)]);
+/// assert_ne!(bimap, [('a', 2), ('b', 1)]);
+/// assert_ne!(bimap, [('a', 1), ('a', 1)]);
+/// ```
+impl<L, R, LS, RS, const N: usize> PartialEq<[(L, R); N]> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &[(L, R); N]) -> bool {
+        self.eq_pairs(other)
+    }
+}
+
+/// Compares the bimap's left-right pairs with the pairs of a slice,
+/// regardless of their order.
+///
+/// The bimap is equal to the slice if the slice contains exactly the pairs
+/// of the bimap, each one once.
+impl<'a, L, R, LS, RS> PartialEq<&'a [(L, R)]> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    fn eq(&self, other: &&'a [(L, R)]) -> bool {
+        self.eq_pairs(other)
+    }
+}
+
 /// An owning iterator over the left-right pairs in a `BiHashMap`.
 pub struct IntoIter<L, R> {
     inner: hash_map::IntoIter<Ref<L>, Ref<R>>,
@@ -892,17 +3674,242 @@ pub struct RightValues<'a, L, R> {
 
 impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
 
-impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(r, _)| &*r.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over randomly chosen left-right pairs in a `BiHashMap`.
+///
+/// This struct is created by the [`sample_iter`] method of `BiHashMap`.
+///
+/// [`sample_iter`]: BiHashMap::sample_iter
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone)]
+pub struct SampleIter<'a, L, R> {
+    inner: std::vec::IntoIter<(&'a L, &'a R)>,
+}
+
+#[cfg(feature = "rand")]
+impl<'a, L, R> ExactSizeIterator for SampleIter<'a, L, R> {}
+
+#[cfg(feature = "rand")]
+impl<'a, L, R> FusedIterator for SampleIter<'a, L, R> {}
+
+#[cfg(feature = "rand")]
+impl<'a, L, R> Iterator for SampleIter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A sorted copy of the string forms of the left values, kept by bimaps
+/// created with `with_prefix_index`.
+struct PrefixIndex<L> {
+    // returns the string form of a left value, or None if the index is
+    // disabled and keys stays empty
+    key: Option<fn(&L) -> &str>,
+    keys: BTreeSet<Box<str>>,
+}
+
+impl<L> PrefixIndex<L> {
+    const fn disabled() -> Self {
+        PrefixIndex {
+            key: None,
+            keys: BTreeSet::new(),
+        }
+    }
+
+    /// Returns an empty index that is enabled if this one is.
+    fn empty_copy(&self) -> Self {
+        PrefixIndex {
+            key: self.key,
+            keys: BTreeSet::new(),
+        }
+    }
+
+    fn insert(&mut self, left: &L) {
+        if let Some(key) = self.key {
+            self.keys.insert(key(left).into());
+        }
+    }
+
+    fn remove(&mut self, left: &L) {
+        if let Some(key) = self.key {
+            self.keys.remove(key(left));
+        }
+    }
+
+    fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+/// Returns the smallest string that is greater than every string starting
+/// with `prefix`, or `None` if there is no such string.
+fn prefix_end(prefix: &str) -> Option<String> {
+    // strings are ordered by their chars, so incrementing the last char that
+    // can be incremented gives the bound
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        // the surrogate range holds no chars
+        let next = match last {
+            '\u{d7ff}' => Some('\u{e000}'),
+            _ => char::from_u32(last as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
+/// An iterator over the left-right pairs in a `BiHashMap` whose left value
+/// starts with a prefix, in ascending order of the left values.
+///
+/// This struct is created by the [`left_prefix`] method of `BiHashMap`.
+///
+/// [`left_prefix`]: BiHashMap::left_prefix
+#[derive(Debug)]
+pub struct LeftPrefix<'a, L, R, LS> {
+    keys: btree_set::Range<'a, Box<str>>,
+    left2right: &'a HashMap<Ref<L>, Ref<R>, LS>,
+}
+
+impl<'a, L, R, LS> Clone for LeftPrefix<'a, L, R, LS> {
+    fn clone(&self) -> Self {
+        LeftPrefix {
+            keys: self.keys.clone(),
+            left2right: self.left2right,
+        }
+    }
+}
+
+impl<'a, L, R, LS> LeftPrefix<'a, L, R, LS>
+where
+    L: Eq + Hash + Borrow<str>,
+    LS: BuildHasher,
+{
+    fn pair(&self, key: &str) -> (&'a L, &'a R) {
+        // unwrap is safe because the index holds exactly the left values
+        let (l, r) = self.left2right.get_key_value(Wrapper::wrap(key)).unwrap();
+        (&*l.0, &*r.0)
+    }
+}
+
+impl<'a, L, R, LS> DoubleEndedIterator for LeftPrefix<'a, L, R, LS>
+where
+    L: Eq + Hash + Borrow<str>,
+    LS: BuildHasher,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.pair(key))
+    }
+}
+
+impl<'a, L, R, LS> FusedIterator for LeftPrefix<'a, L, R, LS>
+where
+    L: Eq + Hash + Borrow<str>,
+    LS: BuildHasher,
+{
+}
+
+impl<'a, L, R, LS> Iterator for LeftPrefix<'a, L, R, LS>
+where
+    L: Eq + Hash + Borrow<str>,
+    LS: BuildHasher,
+{
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.pair(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+/// A pair taken out of a `BiHashMap` that is removed for good unless it is
+/// kept.
+///
+/// The lease dereferences to the right value of the pair. When the lease is
+/// dropped, the pair stays removed; calling [`keep`](Lease::keep) puts it
+/// back into the bimap instead.
+///
+/// This struct is created by the [`lease_by_left`] method of `BiHashMap`.
+///
+/// [`lease_by_left`]: BiHashMap::lease_by_left
+pub struct Lease<'a, L, R, LS, RS> {
+    bimap: &'a mut BiHashMap<L, R, LS, RS>,
+    left: L,
+    right: R,
+}
+
+impl<'a, L, R, LS, RS> Lease<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns the left value of the leased pair.
+    pub fn left(&self) -> &L {
+        &self.left
+    }
+
+    /// Puts the leased pair back into the bimap.
+    ///
+    /// Like any insertion, this advances the bimap's generation.
+    pub fn keep(self) {
+        let Lease { bimap, left, right } = self;
+        // the bimap was borrowed since the pair was taken out, so neither
+        // value can have been inserted in the meantime
+        bimap.insert_unchecked(left, right);
+    }
 
-impl<'a, L, R> Iterator for RightValues<'a, L, R> {
-    type Item = &'a R;
+    /// Removes the leased pair for good and returns it.
+    pub fn take(self) -> (L, R) {
+        (self.left, self.right)
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(r, _)| &*r.0)
+impl<'a, L, R, LS, RS> fmt::Debug for Lease<'a, L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Lease")
+            .field("left", &self.left)
+            .field("right", &self.right)
+            .finish()
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.inner.size_hint()
+impl<'a, L, R, LS, RS> Deref for Lease<'a, L, R, LS, RS> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.right
     }
 }
 
@@ -928,6 +3935,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mem::hash_one;
 
     #[test]
     fn clone() {
@@ -952,6 +3960,26 @@ mod tests {
         bimap.remove_by_right(&2);
     }
 
+    #[test]
+    fn clone_compact() {
+        let mut bimap: BiHashMap<_, _> = (0..1000).map(|i| (i, -i)).collect();
+        bimap.retain(|&l, _| l % 100 == 0);
+        let capacity = bimap.capacity();
+
+        let compact = bimap.clone_compact();
+        assert_eq!(compact, bimap);
+        assert_eq!(compact.auto_shrink(), bimap.auto_shrink());
+        assert!(compact.capacity() >= compact.len());
+        assert!(compact.capacity() < capacity / 10);
+        assert_eq!(bimap.capacity(), capacity);
+        assert!(bimap.clone().capacity() >= capacity);
+
+        // the clone is deep
+        let mut compact = compact;
+        compact.insert(0, 1);
+        assert_eq!(bimap.get_by_left(&0), Some(&0));
+    }
+
     #[test]
     fn debug() {
         let mut bimap = BiHashMap::new();
@@ -967,11 +3995,82 @@ mod tests {
         assert!(formatted == expected1 || formatted == expected2);
     }
 
+    #[test]
+    fn display() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(bimap.to_string(), "{}");
+        assert_eq!(bimap.display_sorted().to_string(), "{}");
+
+        bimap.insert("one", 1);
+        assert_eq!(bimap.to_string(), "{one <-> 1}");
+
+        bimap.insert("two", 2);
+        let formatted = bimap.to_string();
+        assert!(formatted == "{one <-> 1, two <-> 2}" || formatted == "{two <-> 2, one <-> 1}");
+
+        bimap.insert("three", 3);
+        assert_eq!(
+            bimap.display_sorted().to_string(),
+            "{one <-> 1, three <-> 3, two <-> 2}"
+        );
+    }
+
+    #[test]
+    fn debug_sorted() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!("{}", format!("{:?}", bimap.debug_sorted()));
+
+        let mut bimap2 = BiHashMap::new();
+        for i in 0..20 {
+            bimap.insert(i, -i);
+            bimap2.insert(19 - i, i - 19);
+        }
+        let formatted = format!("{:?}", bimap.debug_sorted());
+        assert_eq!(formatted, format!("{:?}", bimap2.debug_sorted()));
+        assert!(formatted.starts_with("{0 <> 0, 1 <> -1, 2 <> -2,"));
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert('b', 2);
+        bimap.insert('a', 1);
+        assert_eq!(
+            "{\n    'a' <> 1,\n    'b' <> 2,\n}",
+            format!("{:#?}", bimap.debug_sorted())
+        );
+    }
+
     #[test]
     fn default() {
         let _ = BiHashMap::<char, i32>::default();
     }
 
+    #[test]
+    fn default_custom_hasher() {
+        #[derive(Clone, Copy, Default)]
+        struct ZeroSizedState;
+
+        impl BuildHasher for ZeroSizedState {
+            type Hasher = hash_map::DefaultHasher;
+
+            fn build_hasher(&self) -> Self::Hasher {
+                hash_map::DefaultHasher::new()
+            }
+        }
+
+        // neither `Hash` nor `Eq`
+        struct Opaque;
+
+        #[derive(Default)]
+        struct Registry {
+            names: BiHashMap<u32, String, ZeroSizedState, ZeroSizedState>,
+            opaque: BiHashMap<Opaque, std::marker::PhantomData<Opaque>>,
+        }
+
+        let mut registry = Registry::default();
+        let _ = registry.opaque;
+        registry.names.insert(1, "one".to_string());
+        assert_eq!(registry.names.get_by_right("one"), Some(&1));
+    }
+
     #[test]
     fn eq() {
         let mut bimap = BiHashMap::new();
@@ -1042,6 +4141,391 @@ mod tests {
         assert_eq!(bimap, bimap2);
     }
 
+    #[test]
+    fn eq_btree() {
+        let hash: BiHashMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+        let mut btree: BiBTreeMap<_, _> = vec![('c', 3), ('b', 2), ('a', 1)].into_iter().collect();
+        assert!(hash == btree);
+        assert!(btree == hash);
+
+        // same left values with different right values
+        btree.insert('a', 4);
+        assert!(hash != btree);
+        assert!(btree != hash);
+
+        // different lengths
+        btree.insert('a', 1);
+        btree.insert('d', 4);
+        assert!(hash != btree);
+        assert!(btree != hash);
+        btree.remove_by_left(&'d');
+        assert!(hash == btree);
+        btree.remove_by_left(&'c');
+        assert!(hash != btree);
+        assert!(btree != hash);
+
+        assert!(BiHashMap::<u8, u8>::new() == BiBTreeMap::new());
+    }
+
+    #[test]
+    fn from_btree() {
+        let btree: BiBTreeMap<_, _> = (0..100).map(|i| (i, -i)).collect();
+        let hash = BiHashMap::<_, _>::from(btree.clone());
+        assert_eq!(hash, btree);
+        assert!(hash.capacity() >= 100);
+        for i in 0..100 {
+            assert_eq!(hash.get_by_right(&-i), Some(&i));
+        }
+
+        // and back again
+        let roundtrip = BiBTreeMap::from(hash);
+        assert_eq!(roundtrip, btree);
+
+        let empty = BiHashMap::<u8, u8>::from(BiBTreeMap::new());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn eq_pairs() {
+        let hash: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+        let btree: BiBTreeMap<_, _> = hash.iter().map(|(l, r)| (*l, *r)).collect();
+        let cases: [(&[(char, i32)], bool); 6] = [
+            (&[('a', 1), ('b', 2)], true),
+            (&[('b', 2), ('a', 1)], true),
+            (&[('a', 1), ('b', 3)], false),
+            (&[('a', 1)], false),
+            (&[('a', 1), ('a', 1)], false),
+            (&[('a', 1), ('b', 2), ('c', 3)], false),
+        ];
+        for (pairs, expected) in cases.iter().copied() {
+            assert_eq!(hash == pairs, expected, "{:?}", pairs);
+            assert_eq!(btree == pairs, expected, "{:?}", pairs);
+        }
+        assert_eq!(hash, [('b', 2), ('a', 1)]);
+        assert_eq!(btree, [('b', 2), ('a', 1)]);
+        assert_ne!(hash, [('a', 1); 2]);
+        assert_ne!(btree, [('a', 2), ('b', 1)]);
+        assert_eq!(BiHashMap::<u8, u8>::new(), []);
+        assert_eq!(BiBTreeMap::<u8, u8>::new(), []);
+    }
+
+    #[test]
+    fn hash_independent_of_order() {
+        fn hash_of<T: Hash>(value: &T) -> u64 {
+            let mut hasher = hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let pairs = (0..100).map(|i| (i, i.to_string())).collect::<Vec<_>>();
+        let bimap = pairs.iter().cloned().collect::<BiHashMap<_, _>>();
+        let mut bimap2 = BiHashMap::with_capacity(1000);
+        for (l, r) in pairs.iter().rev().cloned() {
+            bimap2.insert(l, r);
+        }
+        assert_eq!(bimap, bimap2);
+        assert_eq!(hash_of(&bimap), hash_of(&bimap2));
+
+        // a single different pair changes the hash
+        bimap2.insert(100, String::from("100"));
+        assert_ne!(hash_of(&bimap), hash_of(&bimap2));
+        bimap2.remove_by_left(&100);
+        bimap2.insert(42, String::from("x"));
+        assert_ne!(hash_of(&bimap), hash_of(&bimap2));
+
+        // the same values in different pairs hash differently
+        let swapped: BiHashMap<_, _> = vec![('a', 2), ('b', 1)].into_iter().collect();
+        let original: BiHashMap<_, _> = vec![('a', 1), ('b', 2)].into_iter().collect();
+        assert_ne!(hash_of(&swapped), hash_of(&original));
+        let single: BiHashMap<_, _> = vec![(0u8, 0u8)].into_iter().collect();
+        assert_ne!(hash_of(&BiHashMap::<u8, u8>::new()), hash_of(&single));
+    }
+
+    #[test]
+    fn extend_detailed() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+        let overwritten = bimap.extend_detailed(vec![('c', 3), ('b', 1), ('a', 4), ('b', 1)]);
+        assert_eq!(
+            overwritten,
+            vec![
+                Overwritten::Right('a', 3),
+                Overwritten::Left('b', 2),
+                Overwritten::Neither,
+                Overwritten::Unchanged('b', 1),
+            ]
+        );
+        let mut bimap2 = BiHashMap::new();
+        bimap2.extend(vec![
+            ('a', 3),
+            ('b', 2),
+            ('c', 3),
+            ('b', 1),
+            ('a', 4),
+            ('b', 1),
+        ]);
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn extend_no_overwrite() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        let rejected = bimap.extend_no_overwrite(vec![
+            // conflicts with existing pairs
+            ('a', 3),
+            ('c', 2),
+            ('a', 2),
+            ('a', 1),
+            // accepted
+            ('d', 4),
+            // conflict with the accepted pair from the same batch
+            ('d', 5),
+            ('e', 4),
+            // accepted
+            ('f', 6),
+        ]);
+        assert_eq!(
+            rejected,
+            vec![('a', 3), ('c', 2), ('a', 2), ('a', 1), ('d', 5), ('e', 4)]
+        );
+
+        let mut expected = BiHashMap::new();
+        expected.insert('a', 1);
+        expected.insert('b', 2);
+        expected.insert('d', 4);
+        expected.insert('f', 6);
+        assert_eq!(bimap, expected);
+        assert!(bimap.extend_no_overwrite(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn extend_from_slice() {
+        let pairs = [(1u32, 10u32), (2, 20), (1, 30), (3, 20)];
+        let mut bimap = BiHashMap::new();
+        bimap.insert(4, 10);
+        bimap.extend_from_slice(&pairs);
+
+        let mut expected = BiHashMap::new();
+        expected.insert(4, 10);
+        expected.extend(pairs.iter().copied());
+        assert_eq!(bimap, expected);
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_left(&1), Some(&30));
+        assert_eq!(bimap.get_by_left(&3), Some(&20));
+
+        let mut empty = BiHashMap::<u32, u32>::new();
+        empty.extend_from_slice(&pairs);
+        assert!(empty.capacity() >= pairs.len());
+    }
+
+    #[test]
+    fn modify_right_by_left() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', vec![1]);
+        bimap.insert('b', vec![2]);
+
+        let generation = bimap.generation();
+        assert_eq!(bimap.modify_right_by_left(&'a', |r| r.push(3)), Ok(()));
+        assert_eq!(bimap.get_by_left(&'a'), Some(&vec![1, 3]));
+        assert_eq!(bimap.get_by_right(&vec![1, 3]), Some(&'a'));
+        assert_eq!(bimap.get_by_right(&vec![1]), None);
+        assert!(bimap.generation() > generation);
+
+        // leaving the value unchanged is not a collision
+        assert_eq!(bimap.modify_right_by_left(&'b', |_| {}), Ok(()));
+        assert_eq!(bimap.get_by_right(&vec![2]), Some(&'b'));
+
+        let snapshot = bimap.clone();
+        let generation = bimap.generation();
+        assert_eq!(
+            bimap.modify_right_by_left(&'c', |r| r.clear()),
+            Err(ModifyError::NotFound)
+        );
+        assert_eq!(bimap, snapshot);
+        assert_eq!(bimap.generation(), generation);
+
+        // a colliding pair can't be put back, so it is dropped
+        assert_eq!(
+            bimap.modify_right_by_left(&'a', |r| *r = vec![2]),
+            Err(ModifyError::Collision)
+        );
+        assert!(bimap.generation() > generation);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_right(&vec![2]), Some(&'b'));
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn modify_right_by_left_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', vec![1]);
+        bimap.insert('b', vec![2]);
+
+        // the pair is put back with the value as the closure left it
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bimap.modify_right_by_left(&'a', |r| {
+                r.push(3);
+                panic!("oops");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.get_by_left(&'a'), Some(&vec![1, 3]));
+        assert_eq!(bimap.get_by_right(&vec![1, 3]), Some(&'a'));
+        assert!(bimap.check_invariants().is_ok());
+
+        // unless the value collides with another pair
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bimap.modify_right_by_left(&'a', |r| {
+                *r = vec![2];
+                panic!("oops");
+            })
+        }));
+        assert!(result.is_err());
+        assert!(!bimap.contains_left(&'a'));
+        assert_eq!(bimap.get_by_right(&vec![2]), Some(&'b'));
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn swap_rights_and_lefts() {
+        let mut bimap: BiHashMap<char, i32> =
+            vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+
+        assert!(bimap.swap_rights(&'a', &'c'));
+        let expected: BiHashMap<char, i32> =
+            vec![('a', 3), ('b', 2), ('c', 1)].into_iter().collect();
+        assert_eq!(bimap, expected);
+        assert!(bimap.check_invariants().is_ok());
+
+        assert!(bimap.swap_lefts(&2, &3));
+        let expected: BiHashMap<char, i32> =
+            vec![('a', 2), ('b', 3), ('c', 1)].into_iter().collect();
+        assert_eq!(bimap, expected);
+        assert!(bimap.check_invariants().is_ok());
+
+        // swapping with itself
+        assert!(bimap.swap_rights(&'b', &'b'));
+        assert!(bimap.swap_lefts(&1, &1));
+        assert_eq!(bimap, expected);
+
+        // missing values
+        let generation = bimap.generation();
+        assert!(!bimap.swap_rights(&'a', &'z'));
+        assert!(!bimap.swap_rights(&'z', &'a'));
+        assert!(!bimap.swap_lefts(&4, &5));
+        assert_eq!(bimap, expected);
+        assert_eq!(bimap.generation(), generation);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn update_by_left_detailed() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        // updating to the current right value doesn't overwrite anything
+        assert_eq!(
+            bimap.update_by_left_detailed(&'a', 1),
+            Some((1, Overwritten::Neither))
+        );
+        assert_eq!(bimap.len(), 2);
+
+        assert_eq!(
+            bimap.update_by_left_detailed(&'b', 1),
+            Some((2, Overwritten::Right('a', 1)))
+        );
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_right(&1), Some(&'b'));
+        assert_eq!(bimap.update_by_left_detailed(&'a', 5), None);
+        assert!(!bimap.contains_right(&5));
+    }
+
+    #[test]
+    fn replace_pair() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        // replacing a pair with itself keeps it
+        assert_eq!(
+            bimap.replace_pair(&'a', 'a', 1),
+            Some((('a', 1), Overwritten::Neither))
+        );
+        assert_eq!(bimap.len(), 3);
+
+        // keeping the left value only changes the right value
+        assert_eq!(
+            bimap.replace_pair(&'a', 'a', 4),
+            Some((('a', 1), Overwritten::Neither))
+        );
+        assert_eq!(bimap.get_by_left(&'a'), Some(&4));
+        assert!(!bimap.contains_right(&1));
+
+        // the new pair overwrites the pairs of both 'b' and 3
+        assert_eq!(
+            bimap.replace_pair(&'a', 'b', 3),
+            Some((('a', 4), Overwritten::Both(('b', 2), ('c', 3))))
+        );
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'b'), Some(&3));
+
+        assert_eq!(bimap.replace_pair(&'z', 'y', 9), None);
+        assert_eq!(bimap.len(), 1);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn what_would_overwrite() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        // each report matches what insert then returns
+        for &(l, r) in &[('d', 4), ('a', 4), ('d', 1), ('b', 2), ('a', 2), ('c', 1)] {
+            let expected = match bimap.what_would_overwrite(&l, &r) {
+                Overwritten::Neither => Overwritten::Neither,
+                Overwritten::Left(l, r) => Overwritten::Left(*l, *r),
+                Overwritten::Right(l, r) => Overwritten::Right(*l, *r),
+                Overwritten::Pair(l, r) => Overwritten::Pair(*l, *r),
+                Overwritten::Unchanged(l, r) => Overwritten::Unchanged(*l, *r),
+                Overwritten::Both((l1, r1), (l2, r2)) => Overwritten::Both((*l1, *r1), (*l2, *r2)),
+            };
+            assert_eq!(bimap.clone().insert(l, r), expected);
+        }
+        assert_eq!(bimap.len(), 3);
+    }
+
+    #[test]
+    fn insert_unchanged_pair() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let generation = bimap.generation();
+
+        // re-inserting an existing pair reports it without evicting anything
+        let unchanged = bimap.insert('a', 1);
+        assert_eq!(unchanged, Overwritten::Unchanged('a', 1));
+        assert!(!unchanged.did_overwrite());
+        assert_eq!(bimap.generation(), generation);
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+        assert!(bimap.check_invariants().is_ok());
+
+        // a pair that only shares its left value is still overwritten
+        assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+        assert!(bimap.generation() > generation);
+    }
+
     #[test]
     fn iter() {
         let mut bimap = BiHashMap::new();
@@ -1053,6 +4537,156 @@ mod tests {
         assert_eq!(pairs, vec![('a', 1), ('b', 2), ('c', 3)]);
     }
 
+    #[test]
+    fn insert_and_get() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(*bimap.insert_and_get('a', 1), 1);
+        assert_eq!(*bimap.insert_and_get_left('b', 2), 'b');
+
+        // overwrites both pairs
+        let right = bimap.insert_and_get('a', 2);
+        assert_eq!(*right, 2);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+        assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+        assert!(!bimap.contains_left(&'b'));
+        assert!(!bimap.contains_right(&1));
+
+        // the returned reference points into the bimap
+        let left: *const char = bimap.insert_and_get_left('c', 2);
+        assert!(core::ptr::eq(left, bimap.get_by_right(&2).unwrap()));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn check_invariants() {
+        use crate::InvariantViolation::*;
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        assert_eq!(bimap.check_invariants(), Ok(()));
+
+        // a reverse entry is missing
+        bimap.right2left.remove(Wrapper::wrap(&2));
+        assert_eq!(
+            bimap.check_invariants(),
+            Err(vec![
+                Len {
+                    left2right: 2,
+                    right2left: 1,
+                },
+                MissingRight {
+                    left: &'b',
+                    right: &2,
+                },
+            ])
+        );
+
+        // a reverse entry points to the wrong left value
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.right2left.insert(Ref(Rc::new(1)), Ref(Rc::new('c')));
+        assert_eq!(
+            bimap.check_invariants(),
+            Err(vec![
+                WrongLeft {
+                    left: &'a',
+                    right: &1,
+                    found: &'c',
+                },
+                MissingLeft {
+                    left: &'c',
+                    right: &1,
+                },
+            ])
+        );
+
+        // a forward entry points to the wrong right value
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.left2right.insert(Ref(Rc::new('a')), Ref(Rc::new(2)));
+        assert_eq!(
+            bimap.check_invariants(),
+            Err(vec![
+                MissingRight {
+                    left: &'a',
+                    right: &2,
+                },
+                WrongRight {
+                    left: &'a',
+                    right: &1,
+                    found: &2,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn bimap_like() {
+        fn exercise<M: BiMapLike<char, i32>>(bimap: &mut M) {
+            assert!(bimap.is_empty());
+            assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+            assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+            assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+            assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+            assert_eq!(bimap.len(), 2);
+            assert_eq!(bimap.iter().len(), 2);
+            assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+            assert_eq!(bimap.get_by_right(&3), Some(&'c'));
+            assert!(bimap.contains_left(&'c'));
+            assert!(!bimap.contains_right(&1));
+            assert_eq!(bimap.remove_by_left(&'a'), Some(('a', 2)));
+            assert_eq!(bimap.remove_by_right(&3), Some(('c', 3)));
+            assert_eq!(bimap.remove_by_right(&3), None);
+            assert!(bimap.is_empty());
+        }
+
+        exercise(&mut BiHashMap::new());
+    }
+
+    #[test]
+    fn iter_exact_size_fused() {
+        fn check<I: ExactSizeIterator + FusedIterator>(mut iter: I, len: usize) {
+            for remaining in (0..=len).rev() {
+                assert_eq!(iter.len(), remaining);
+                assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+                assert_eq!(iter.next().is_some(), remaining > 0);
+            }
+            assert!(iter.next().is_none());
+            assert_eq!(iter.len(), 0);
+        }
+
+        let bimap: BiHashMap<u32, char> = (0..26).zip('a'..='z').collect();
+        assert_eq!(bimap.iter().len(), bimap.len());
+        check(bimap.iter(), bimap.len());
+        check(bimap.left_values(), bimap.len());
+        check(bimap.right_values(), bimap.len());
+        check(bimap.clone().into_iter(), bimap.len());
+        check(BiHashMap::<u32, char>::new().iter(), 0);
+    }
+
+    #[test]
+    fn fold_pairs() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let count = bimap.fold_pairs(0, |acc, _l, _r| acc + 1);
+        assert_eq!(count, 3);
+        let mut pairs = bimap.fold_pairs(Vec::new(), |mut acc, l, r| {
+            acc.push((*l, *r));
+            acc
+        });
+        pairs.sort();
+        assert_eq!(pairs, vec![('a', 1), ('b', 2), ('c', 3)]);
+        assert_eq!(
+            BiHashMap::<char, i32>::new().fold_pairs(7, |acc, _l, _r| acc + 1),
+            7
+        );
+    }
+
     #[test]
     fn left_values() {
         let mut bimap = BiHashMap::new();
@@ -1081,6 +4715,76 @@ mod tests {
         assert!(bimap.capacity() >= 10);
     }
 
+    #[test]
+    fn insert_up_to_capacity() {
+        let mut bimap = BiHashMap::<u32, u32>::with_capacity(100);
+        let capacity = bimap.capacity();
+        let left_capacity = bimap.left2right.capacity();
+        let right_capacity = bimap.right2left.capacity();
+        for i in 0..capacity as u32 {
+            bimap.insert(i, !i);
+        }
+        assert_eq!(bimap.len(), capacity);
+        assert_eq!(bimap.capacity(), capacity);
+        assert_eq!(bimap.left2right.capacity(), left_capacity);
+        assert_eq!(bimap.right2left.capacity(), right_capacity);
+
+        // reserving on a non-empty bimap gives both maps the same headroom
+        bimap.reserve(capacity);
+        let capacity = bimap.capacity();
+        assert!(capacity >= 2 * bimap.len());
+        for i in bimap.len() as u32..capacity as u32 {
+            bimap.insert(i, !i);
+        }
+        assert_eq!(bimap.capacity(), capacity);
+    }
+
+    #[test]
+    fn from_maps() {
+        let forward: HashMap<_, _> = (0..100).map(|i| (i, i.to_string())).collect();
+        let reverse: HashMap<_, _> = (0..100).map(|i| (i.to_string(), i)).collect();
+        let bimap = BiHashMap::from_maps(forward.clone(), reverse.clone()).unwrap();
+        assert_eq!(bimap.len(), 100);
+        assert_eq!(bimap.right2left.len(), 100);
+        assert_eq!(
+            bimap,
+            (0..100)
+                .map(|i| (i, i.to_string()))
+                .collect::<BiHashMap<_, _>>()
+        );
+
+        let mut short = reverse.clone();
+        short.remove("7");
+        assert_eq!(
+            BiHashMap::from_maps(forward.clone(), short.clone()),
+            Err(MapMismatch::Len {
+                forward: 100,
+                reverse: 99
+            })
+        );
+
+        short.insert(String::from("x"), 7);
+        assert_eq!(
+            BiHashMap::from_maps(forward.clone(), short),
+            Err(MapMismatch::MissingRight)
+        );
+
+        let mut wrong = reverse;
+        wrong.insert(String::from("7"), 8);
+        assert_eq!(
+            BiHashMap::from_maps(forward, wrong),
+            Err(MapMismatch::WrongLeft)
+        );
+
+        // a forward map that isn't injective can't have an exact inverse
+        let forward: HashMap<_, _> = vec![('a', 1), ('b', 1)].into_iter().collect();
+        let reverse: HashMap<_, _> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+        assert_eq!(
+            BiHashMap::from_maps(forward, reverse),
+            Err(MapMismatch::WrongLeft)
+        );
+    }
+
     #[test]
     fn with_hashers() {
         let s_left = hash_map::RandomState::new();
@@ -1091,6 +4795,15 @@ mod tests {
         assert_eq!(Some(&42), bimap.get_by_left(&'a'));
     }
 
+    #[test]
+    fn hashers() {
+        let s_left = hash_map::RandomState::new();
+        let s_right = hash_map::RandomState::new();
+        let bimap = BiHashMap::<char, i32>::with_hashers(s_left.clone(), s_right.clone());
+        assert_eq!(hash_one(bimap.left_hasher(), &'a'), hash_one(&s_left, &'a'));
+        assert_eq!(hash_one(bimap.right_hasher(), &1), hash_one(&s_right, &1));
+    }
+
     #[test]
     fn reserve() {
         let mut bimap = BiHashMap::<char, i32>::new();
@@ -1104,6 +4817,24 @@ mod tests {
         assert!(bimap.capacity() >= 10);
     }
 
+    #[test]
+    fn reserve_avoids_rehash() {
+        let mut bimap = BiHashMap::<u32, u32>::new();
+        bimap.insert(0, 0);
+        bimap.reserve(1000);
+        let capacities = (bimap.left2right.capacity(), bimap.right2left.capacity());
+        assert!(capacities.0 >= 1001 && capacities.1 >= 1001);
+
+        for i in 1..=1000 {
+            bimap.insert(i, i * 2);
+            assert_eq!(
+                (bimap.left2right.capacity(), bimap.right2left.capacity()),
+                capacities
+            );
+        }
+        assert_eq!(bimap.len(), 1001);
+    }
+
     #[test]
     fn shrink_to_fit() {
         let mut bimap = BiHashMap::<char, i32>::with_capacity(100);
@@ -1147,6 +4878,41 @@ mod tests {
         assert!(bimap.capacity() >= 2);
     }
 
+    #[test]
+    fn auto_shrink() {
+        let mut bimap: BiHashMap<u32, u32> = (0..1000).map(|i| (i, i)).collect();
+        let peak = bimap.capacity();
+
+        // disabled by default, so removals keep the table
+        for i in 0..900 {
+            bimap.remove_by_left(&i);
+        }
+        assert!(bimap.capacity() > peak / 2);
+
+        bimap.set_auto_shrink(true);
+        bimap.remove_by_right(&900);
+        assert_eq!(bimap.len(), 99);
+        assert!(bimap.capacity() >= 198);
+        assert!(bimap.capacity() < 4 * 99);
+        assert!(bimap
+            .left_values()
+            .all(|l| bimap.get_by_right(l) == Some(l)));
+
+        // no further shrinking while at least a quarter is in use
+        for i in 901..920 {
+            bimap.remove_by_left(&i);
+        }
+        assert_eq!(bimap.len(), 80);
+        assert!(bimap.capacity() >= 160);
+
+        bimap.retain_mut_right(|&l, _| l < 925);
+        assert_eq!(bimap.len(), 5);
+        assert!(bimap.capacity() < 20);
+
+        let clone = bimap.clone();
+        assert!(clone.auto_shrink());
+    }
+
     #[test]
     fn clear() {
         let mut bimap = vec![('a', 1)].into_iter().collect::<BiHashMap<_, _>>();
@@ -1159,6 +4925,36 @@ mod tests {
         assert!(bimap.is_empty());
     }
 
+    #[test]
+    fn clear_keeps_capacity() {
+        let mut bimap = BiHashMap::with_capacity(100);
+        for round in 0..3 {
+            let capacity = bimap.capacity();
+            for i in 0..100 {
+                bimap.insert(i, i + round);
+            }
+            assert_eq!(bimap.capacity(), capacity);
+            bimap.clear();
+            assert!(bimap.is_empty());
+            assert_eq!(bimap.capacity(), capacity);
+        }
+    }
+
+    #[test]
+    fn clear_with_auto_shrink() {
+        let mut bimap = BiHashMap::with_capacity(100);
+        bimap.set_auto_shrink(true);
+        for i in 0..100 {
+            bimap.insert(i, i);
+        }
+        let capacity = bimap.capacity();
+        let generation = bimap.generation();
+        bimap.clear();
+        assert!(bimap.is_empty());
+        assert_eq!(bimap.capacity(), capacity);
+        assert!(bimap.generation() > generation);
+    }
+
     #[test]
     fn get_contains() {
         let bimap = vec![('a', 1)].into_iter().collect::<BiHashMap<_, _>>();
@@ -1183,12 +4979,40 @@ mod tests {
         assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
         assert_eq!(bimap.insert('a', 2), Overwritten::Left('a', 1));
         assert_eq!(bimap.insert('b', 2), Overwritten::Right('a', 2));
-        assert_eq!(bimap.insert('b', 2), Overwritten::Pair('b', 2));
+        assert_eq!(bimap.insert('b', 2), Overwritten::Unchanged('b', 2));
 
         assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
         assert_eq!(bimap.insert('b', 3), Overwritten::Both(('b', 2), ('c', 3)));
     }
 
+    #[test]
+    fn insert_returns_evicted_pairs() {
+        use alloc::boxed::Box;
+
+        // boxes aren't cloned, so matching addresses show that the evicted
+        // values are moved out of the bimap
+        let (a, one) = (Box::new('a'), Box::new(1));
+        let (b, two) = (Box::new('b'), Box::new(2));
+        let addresses = [&*a as *const char, &*b as *const char];
+        let (one_addr, two_addr) = (&*one as *const i32, &*two as *const i32);
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert(a, one);
+        bimap.insert(b, two);
+        match bimap.insert(Box::new('a'), Box::new(2)) {
+            Overwritten::Both((l1, r1), (l2, r2)) => {
+                assert_eq!((*l1, *r1, *l2, *r2), ('a', 1, 'b', 2));
+                assert_eq!([&*l1 as *const char, &*l2 as *const char], addresses);
+                assert_eq!(
+                    (&*r1 as *const i32, &*r2 as *const i32),
+                    (one_addr, two_addr)
+                );
+            }
+            other => panic!("expected Overwritten::Both, got {:?}", other),
+        }
+        assert_eq!(bimap.len(), 1);
+    }
+
     #[test]
     fn insert_no_overwrite() {
         let mut bimap = BiHashMap::new();
@@ -1198,6 +5022,137 @@ mod tests {
         assert!(bimap.insert_no_overwrite('b', 1).is_err());
     }
 
+    #[test]
+    fn insert_no_overwrite_leaves_map_unchanged() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(bimap.insert_no_overwrite("a".to_string(), 1), Ok(()));
+        assert_eq!(bimap.insert_no_overwrite("b".to_string(), 2), Ok(()));
+        assert_eq!(bimap.get_by_left("a"), Some(&1));
+        assert_eq!(bimap.get_by_right(&2).map(String::as_str), Some("b"));
+
+        let snapshot = bimap.clone();
+        let generation = bimap.generation();
+        let capacity = bimap.capacity();
+        let rejected = [
+            // only the left value exists
+            ("a", 3),
+            // only the right value exists
+            ("c", 1),
+            // both values exist, but in different pairs
+            ("a", 2),
+            // the exact pair already exists
+            ("b", 2),
+        ];
+        for &(left, right) in &rejected {
+            assert_eq!(
+                bimap.insert_no_overwrite(left.to_string(), right),
+                Err((left.to_string(), right))
+            );
+            assert_eq!(bimap, snapshot);
+            assert_eq!(bimap.generation(), generation);
+            assert_eq!(bimap.capacity(), capacity);
+            assert!(bimap.check_invariants().is_ok());
+        }
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(bimap.try_insert('a', 1), Ok((&'a', &1)));
+        assert_eq!(bimap.try_insert('b', 2), Ok((&'b', &2)));
+        let snapshot = bimap.clone();
+
+        // left conflict
+        let error = bimap.try_insert('a', 3).unwrap_err();
+        assert_eq!(error.pair, ('a', 3));
+        assert_eq!(error.left_conflict, Some((&'a', &1)));
+        assert_eq!(error.right_conflict, None);
+        assert_eq!(error.to_string(), "left value already present in bimap");
+
+        // right conflict
+        let error = bimap.try_insert('c', 2).unwrap_err();
+        assert_eq!(error.pair, ('c', 2));
+        assert_eq!(error.left_conflict, None);
+        assert_eq!(error.right_conflict, Some((&'b', &2)));
+        assert_eq!(error.to_string(), "right value already present in bimap");
+
+        // conflicts with two different pairs
+        let error = bimap.try_insert('a', 2).unwrap_err();
+        assert_eq!(error.pair, ('a', 2));
+        assert_eq!(error.left_conflict, Some((&'a', &1)));
+        assert_eq!(error.right_conflict, Some((&'b', &2)));
+        assert_eq!(
+            error.to_string(),
+            "left and right values already present in bimap"
+        );
+
+        // the exact pair is already present
+        let error = bimap.try_insert('b', 2).unwrap_err();
+        assert_eq!(error.left_conflict, error.right_conflict);
+
+        assert_eq!(bimap, snapshot);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn retain_mut_right() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+        bimap.insert('d', 4);
+
+        bimap.retain_mut_right(|_l, r| {
+            *r += 10;
+            *r % 2 == 0
+        });
+        let mut pairs = bimap.iter().map(|(l, r)| (*l, *r)).collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, vec![('b', 12), ('d', 14)]);
+        assert_eq!(bimap.get_by_right(&12), Some(&'b'));
+        assert_eq!(bimap.get_by_right(&14), Some(&'d'));
+        assert_eq!(bimap.get_by_right(&2), None);
+    }
+
+    #[test]
+    fn retain_mut_right_collision() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        bimap.retain_mut_right(|_l, r| {
+            *r = 0;
+            true
+        });
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.right2left.len(), 1);
+        let left = *bimap.get_by_right(&0).unwrap();
+        assert_eq!(bimap.get_by_left(&left), Some(&0));
+    }
+
+    #[test]
+    fn retain_mut_right_panic() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bimap.retain_mut_right(|&l, _r| {
+                if l == 'b' {
+                    panic!();
+                }
+                true
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap.left2right.len(), bimap.right2left.len());
+        for (l, r) in bimap.iter() {
+            assert_eq!(bimap.get_by_right(r), Some(l));
+        }
+    }
+
     #[test]
     fn retain_calls_f_once() {
         let mut bimap = BiHashMap::new();
@@ -1214,4 +5169,550 @@ mod tests {
         assert_eq!(bimap.len(), 1);
         assert_eq!(i, 3);
     }
+
+    #[test]
+    fn retain_count() {
+        let mut bimap: BiHashMap<i32, i32> = (0..10).map(|i| (i, -i)).collect();
+        assert_eq!(bimap.retain_count(|&l, _| l % 3 == 0), 6);
+        assert_eq!(bimap.len(), 4);
+        assert!(bimap.check_invariants().is_ok());
+        assert_eq!(bimap.retain_count(|_, _| true), 0);
+        assert_eq!(bimap.retain_count(|_, _| false), 4);
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn retain_intersection_and_remove_all() {
+        let live: BiHashMap<char, i32> = vec![('a', 1), ('b', 2), ('c', 3), ('d', 4)]
+            .into_iter()
+            .collect();
+        // ('a', 1) matches, 'b' and 3 are paired with other values, 'd' is
+        // missing and ('e', 5) isn't in the live bimap
+        let snapshot: BiHashMap<char, i32> = vec![('a', 1), ('b', 7), ('x', 3), ('e', 5)]
+            .into_iter()
+            .collect();
+
+        let mut intersection = live.clone();
+        intersection.retain_intersection(&snapshot);
+        assert_eq!(intersection.iter().collect::<Vec<_>>(), [(&'a', &1)]);
+        assert!(intersection.check_invariants().is_ok());
+
+        let mut difference = live.clone();
+        difference.remove_all(&snapshot);
+        assert_eq!(difference.len(), 3);
+        assert!(!difference.contains_left(&'a'));
+        assert!(!difference.contains_right(&1));
+        assert!(difference.check_invariants().is_ok());
+
+        let mut same = live.clone();
+        same.retain_intersection(&live);
+        assert_eq!(same, live);
+        same.remove_all(&live);
+        assert!(same.is_empty());
+    }
+
+    #[test]
+    fn into_hashsets() {
+        use fnv::FnvBuildHasher;
+
+        let mut bimap = BiHashMap::<_, _, FnvBuildHasher, FnvBuildHasher>::default();
+        for i in 0..10 {
+            bimap.insert(i.to_string(), vec![i; i]);
+        }
+        let lefts = bimap.clone().into_left_hashset();
+        assert_eq!(lefts.len(), 10);
+        assert!(lefts.contains("7"));
+        let rights = bimap.into_right_hashset();
+        assert_eq!(rights.len(), 10);
+        assert!(rights.contains(&vec![3, 3, 3]));
+    }
+
+    #[test]
+    fn to_maps() {
+        let bimap: BiHashMap<_, _> = (0..10).map(|i| (i.to_string(), 10 - i)).collect();
+        let forward = bimap.to_hash_map();
+        let backward = bimap.to_hash_map_by_right();
+        assert_eq!(forward.len(), 10);
+        assert_eq!(backward.len(), 10);
+        for (l, r) in &bimap {
+            assert_eq!(forward.get(l), Some(r));
+            assert_eq!(backward.get(r), Some(l));
+        }
+        assert!(bimap
+            .to_btree_map()
+            .into_iter()
+            .eq(forward.clone().into_iter().collect::<BTreeMap<_, _>>()));
+        assert!(bimap
+            .to_btree_map_by_right()
+            .into_values()
+            .eq((1..=10).map(|r| bimap.get_by_right(&r).unwrap().clone())));
+        // the bimap is left untouched
+        assert_eq!(bimap.len(), 10);
+    }
+
+    #[test]
+    fn try_reserve_overflow() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        for &additional in &[usize::MAX, usize::MAX - 1, usize::MAX / 2 + 1] {
+            assert!(bimap.try_reserve(additional).is_err());
+        }
+        // the bimap is still usable after a failed reservation
+        assert!(bimap.try_reserve(10).is_ok());
+        bimap.insert('b', 2);
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn reserve_overflow() {
+        let mut bimap = BiHashMap::<char, i32>::new();
+        bimap.reserve(usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn with_capacity_overflow() {
+        BiHashMap::<char, i32>::with_capacity(usize::MAX);
+    }
+
+    #[test]
+    fn from_iter_huge_size_hint() {
+        let iter = (0..usize::MAX).take_while(|&i| i < 3).map(|i| (i, i));
+        assert_eq!(iter.size_hint(), (0, Some(usize::MAX)));
+        let bimap: BiHashMap<usize, usize> = iter.collect();
+        assert_eq!(bimap.len(), 3);
+    }
+
+    #[test]
+    fn generation() {
+        let mut bimap = BiHashMap::new();
+        let mut last = bimap.generation();
+        let mut changed = |bimap: &BiHashMap<char, i32>| {
+            let changed = bimap.generation() > last;
+            last = bimap.generation();
+            changed
+        };
+        assert!(!changed(&bimap));
+
+        bimap.insert('a', 1);
+        assert!(changed(&bimap));
+        bimap.insert('a', 2);
+        assert!(changed(&bimap));
+        assert!(bimap.insert_no_overwrite('b', 3).is_ok());
+        assert!(changed(&bimap));
+        assert!(bimap.insert_no_overwrite('b', 4).is_err());
+        assert!(!changed(&bimap));
+        bimap.extend(vec![('c', 5)]);
+        assert!(changed(&bimap));
+
+        assert_eq!(bimap.remove_by_left(&'z'), None);
+        assert_eq!(bimap.remove_by_right(&0), None);
+        assert!(bimap.contains_left(&'a'));
+        assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+        bimap.reserve(100);
+        bimap.shrink_to_fit();
+        assert!(!changed(&bimap));
+
+        assert!(bimap.remove_by_left(&'a').is_some());
+        assert!(changed(&bimap));
+        assert!(bimap.remove_by_right(&3).is_some());
+        assert!(changed(&bimap));
+        bimap.retain(|_, _| true);
+        assert!(changed(&bimap));
+        bimap.retain_mut_right(|_, r| {
+            *r += 1;
+            true
+        });
+        assert!(changed(&bimap));
+        bimap.clear();
+        assert!(changed(&bimap));
+    }
+
+    #[test]
+    fn update_by_left() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert(String::from("a"), 1);
+        bimap.insert(String::from("b"), 2);
+
+        // updating to the current right value keeps the pair
+        assert_eq!(bimap.update_by_left("a", 1), Some(1));
+        assert_eq!(bimap.get_by_right(&1).map(String::as_str), Some("a"));
+        assert_eq!(bimap.len(), 2);
+
+        // colliding with another pair removes it
+        assert_eq!(bimap.update_by_left("b", 1), Some(2));
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left("b"), Some(&1));
+        assert_eq!(bimap.get_by_right(&1).map(String::as_str), Some("b"));
+        assert!(!bimap.contains_left("a"));
+        assert!(!bimap.contains_right(&2));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_pair_is_deterministic() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        let bimap: BiHashMap<_, _> = (0..100).map(|i| (i, -i)).collect();
+        let draw = |seed| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| *bimap.random_pair(&mut rng).unwrap().0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_pair_is_uniform() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(1);
+        let bimap: BiHashMap<_, _> = (0..10usize).map(|i| (i, i.to_string())).collect();
+        let mut counts = [0; 10];
+        for _ in 0..10_000 {
+            let (l, r) = bimap.random_pair(&mut rng).unwrap();
+            assert_eq!(*r, l.to_string());
+            counts[*l] += 1;
+        }
+        // chi-squared statistic with 9 degrees of freedom, far below the
+        // 0.001 critical value of 27.88 for a uniform distribution
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&count| (f64::from(count) - 1000.0).powi(2) / 1000.0)
+            .sum();
+        assert!(chi_squared < 27.88, "{:?}", counts);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn remove_random() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut bimap: BiHashMap<_, _> = (0..50).map(|i| (i, i + 100)).collect();
+        let mut removed = Vec::new();
+        while let Some((l, r)) = bimap.remove_random(&mut rng) {
+            assert_eq!(r, l + 100);
+            assert!(!bimap.contains_left(&l));
+            assert!(!bimap.contains_right(&r));
+            assert_eq!(bimap.right2left.len(), bimap.len());
+            removed.push(l);
+        }
+        assert!(bimap.is_empty());
+        // the pairs are removed in a random order
+        assert_ne!(removed, (0..50).collect::<Vec<_>>());
+        removed.sort_unstable();
+        assert_eq!(removed, (0..50).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sample_iter() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        let bimap: BiHashMap<_, _> = (0..20).map(|i| (i, i * 2)).collect();
+        let sample = |seed, amount| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            bimap
+                .sample_iter(&mut rng, amount)
+                .map(|(l, r)| (*l, *r))
+                .collect::<Vec<_>>()
+        };
+
+        let pairs = sample(5, 8);
+        assert_eq!(pairs, sample(5, 8));
+        assert_eq!(pairs.len(), 8);
+        let lefts = pairs.iter().map(|(l, _)| *l).collect::<HashSet<_>>();
+        assert_eq!(lefts.len(), 8);
+        assert!(pairs.iter().all(|(l, r)| *r == l * 2));
+
+        // asking for too many pairs returns all of them
+        let mut all = sample(5, 100);
+        all.sort_unstable();
+        assert_eq!(all, (0..20).map(|i| (i, i * 2)).collect::<Vec<_>>());
+
+        let mut rng = SmallRng::seed_from_u64(5);
+        assert_eq!(BiHashMap::<u8, u8>::new().sample_iter(&mut rng, 3).len(), 0);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn lookup_stats() {
+        use crate::LookupStats;
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        assert_eq!(bimap.lookup_stats(), LookupStats::default());
+
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_left(&'z'), None);
+        assert!(bimap.contains_left(&'b'));
+        assert_eq!(bimap.try_get_by_right(&2), Ok(&'b'));
+        assert!(!bimap.contains_right(&9));
+        assert_eq!(
+            bimap.lookup_stats(),
+            LookupStats {
+                by_left: 3,
+                by_right: 2,
+                hits: 3,
+                misses: 2,
+            }
+        );
+
+        // clones start counting from scratch
+        let clone = bimap.clone();
+        assert_eq!(clone.lookup_stats(), LookupStats::default());
+
+        bimap.reset_lookup_stats();
+        assert_eq!(bimap.lookup_stats(), LookupStats::default());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn lookup_stats_across_threads() {
+        use crate::LookupStats;
+        use std::thread;
+
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let bimap: BiHashMap<u32, u32> = (0..100).map(|i| (i, i)).collect();
+        assert_sync(&bimap);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for i in 0..200 {
+                        bimap.get_by_left(&i);
+                        bimap.get_by_right(&i);
+                    }
+                });
+            }
+        });
+        assert_eq!(
+            bimap.lookup_stats(),
+            LookupStats {
+                by_left: 800,
+                by_right: 800,
+                hits: 800,
+                misses: 800,
+            }
+        );
+    }
+
+    #[test]
+    fn peek_and_pop() {
+        let mut bimap: BiHashMap<_, _> = (0..50).map(|i| (i, i * 10)).collect();
+        let mut popped = Vec::new();
+        while let Some((&left, &right)) = bimap.peek() {
+            let len = bimap.len();
+            assert_eq!(bimap.peek(), Some((&left, &right)));
+            assert_eq!(bimap.pop(), Some((left, right)));
+            assert_eq!(bimap.len(), len - 1);
+            assert!(!bimap.contains_right(&right));
+            popped.push(left);
+        }
+        assert_eq!(bimap.pop(), None);
+        popped.sort_unstable();
+        assert_eq!(popped, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lease_by_left() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        assert!(bimap.lease_by_left(&'z').is_none());
+
+        let lease = bimap.lease_by_left(&'a').unwrap();
+        assert_eq!((lease.left(), *lease), (&'a', 1));
+        lease.keep();
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+        assert!(bimap.check_invariants().is_ok());
+
+        let generation = bimap.generation();
+        {
+            let lease = bimap.lease_by_left(&'a').unwrap();
+            assert_eq!(*lease, 1);
+        }
+        assert!(bimap.generation() > generation);
+        assert!(!bimap.contains_left(&'a'));
+        assert!(!bimap.contains_right(&1));
+
+        let lease = bimap.lease_by_left(&'b').unwrap();
+        assert_eq!(lease.take(), ('b', 2));
+        assert!(bimap.is_empty());
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn prefix_index() {
+        fn prefixed(bimap: &BiHashMap<String, i32>, prefix: &str) -> Vec<i32> {
+            bimap.left_prefix(prefix).map(|(_, r)| *r).collect()
+        }
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert(String::from("ab"), 1);
+        let mut bimap = bimap.with_prefix_index();
+        bimap.insert(String::from("abc"), 2);
+        bimap.insert(String::from("b"), 3);
+        bimap.insert(String::from("a"), 4);
+        assert_eq!(prefixed(&bimap, "a"), [4, 1, 2]);
+        assert_eq!(prefixed(&bimap, "ab"), [1, 2]);
+        assert_eq!(prefixed(&bimap, "b"), [3]);
+        assert_eq!(prefixed(&bimap, ""), [4, 1, 2, 3]);
+        assert_eq!(
+            bimap.left_prefix("a").next_back(),
+            Some((&"abc".to_string(), &2))
+        );
+
+        // overwriting and removing pairs keeps the index in sync
+        bimap.insert(String::from("abd"), 2);
+        bimap.remove_by_right(&3);
+        assert_eq!(prefixed(&bimap, "ab"), [1, 2]);
+        assert!(prefixed(&bimap, "abc").is_empty());
+        bimap.retain(|l, _| l.len() > 1);
+        assert_eq!(prefixed(&bimap, ""), [1, 2]);
+        bimap.retain_mut_right(|_, r| {
+            *r += 10;
+            *r > 11
+        });
+        assert_eq!(prefixed(&bimap, ""), [12]);
+
+        let clone = bimap.clone();
+        assert!(clone.has_prefix_index());
+        assert_eq!(prefixed(&clone, "abd"), [12]);
+        bimap.clear();
+        assert!(prefixed(&bimap, "").is_empty());
+        assert_eq!(prefixed(&clone, ""), [12]);
+    }
+
+    #[test]
+    fn prefix_index_char_bounds() {
+        let mut bimap = BiHashMap::new().with_prefix_index();
+        bimap.insert("a\u{d7ff}", 1);
+        bimap.insert("a\u{d7ff}b", 2);
+        bimap.insert("a\u{e000}", 3);
+        bimap.insert("a\u{10ffff}", 4);
+        bimap.insert("b", 5);
+        let rights = |prefix| {
+            bimap
+                .left_prefix(prefix)
+                .map(|(_, r)| *r)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rights("a\u{d7ff}"), [1, 2]);
+        assert_eq!(rights("a\u{10ffff}"), [4]);
+        assert!(rights("\u{10ffff}").is_empty());
+        assert_eq!(rights("a"), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn left_prefix_without_index() {
+        let bimap = BiHashMap::<&str, i32>::new();
+        bimap.left_prefix("a");
+    }
+
+    #[test]
+    fn apply_batch() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+
+        // every operation applies, in order
+        let batch = vec![
+            BatchOp::RemoveByRight(2),
+            BatchOp::Insert('b', 3),
+            BatchOp::ReplaceRight { old: 1, new: 2 },
+            BatchOp::ReplaceLeft { old: 'a', new: 'a' },
+        ];
+        assert_eq!(bimap.apply_batch(batch), Ok(()));
+        assert_eq!(bimap, [('a', 2), ('b', 3)]);
+
+        // later operations see the pairs inserted by earlier ones
+        let batch = vec![
+            BatchOp::Insert('c', 4),
+            BatchOp::ReplaceLeft { old: 'c', new: 'd' },
+            BatchOp::RemoveByLeft('a'),
+        ];
+        assert_eq!(bimap.apply_batch(batch), Ok(()));
+        assert_eq!(bimap, [('b', 3), ('d', 4)]);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn apply_batch_conflict() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let before = bimap.clone();
+        let generation = bimap.generation();
+
+        let failing = [
+            (BatchOp::Insert('z', 3), ModifyError::Collision),
+            (BatchOp::RemoveByLeft('b'), ModifyError::NotFound),
+            (BatchOp::RemoveByRight(9), ModifyError::NotFound),
+            (
+                BatchOp::ReplaceLeft { old: 'c', new: 'a' },
+                ModifyError::Collision,
+            ),
+            (
+                BatchOp::ReplaceRight { old: 2, new: 3 },
+                ModifyError::NotFound,
+            ),
+        ];
+        for (op, reason) in failing {
+            // the removal and insertion before the failing operation are
+            // never applied
+            let batch = vec![BatchOp::RemoveByLeft('b'), BatchOp::Insert('c', 3), op];
+            let error = bimap.apply_batch(batch).unwrap_err();
+            assert_eq!(
+                error,
+                BatchError {
+                    index: 2,
+                    op,
+                    reason
+                }
+            );
+            assert_eq!(bimap, before);
+            assert_eq!(bimap.generation(), generation);
+        }
+
+        assert_eq!(
+            bimap.apply_batch(vec![BatchOp::RemoveByLeft('z')]),
+            Err(BatchError {
+                index: 0,
+                op: BatchOp::RemoveByLeft('z'),
+                reason: ModifyError::NotFound,
+            })
+        );
+        assert_eq!(bimap, before);
+    }
+
+    #[test]
+    fn take() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let generation = bimap.generation();
+
+        let mut pairs = bimap.take();
+        pairs.sort_unstable();
+        assert_eq!(pairs, [('a', 1), ('b', 2)]);
+        assert!(bimap.is_empty());
+        assert!(bimap.generation() > generation);
+        assert!(bimap.check_invariants().is_ok());
+        assert!(bimap.take().is_empty());
+
+        // the bimap is usable after being emptied
+        bimap.insert('c', 3);
+        assert_eq!(bimap.get_by_left(&'c'), Some(&3));
+    }
 }
@@ -0,0 +1,1435 @@
+//! A bimap backed by two `hashbrown::HashMap`s with a custom allocator.
+//!
+//! The [`BiHashMap`] in this module has the same core API as
+//! [`crate::BiHashMap`], but stores its pairs in [hashbrown] maps. This makes
+//! it available without the standard library, and lets the hash tables use a
+//! custom [`Allocator`] such as an arena.
+//!
+//! Cargo features must be additive, so enabling the `hashbrown` feature adds
+//! this separate type instead of changing the backing of
+//! [`crate::BiHashMap`]. Note that only the hash tables are allocated with
+//! the custom allocator: the left and right values themselves are still
+//! stored in reference-counted boxes from the global allocator.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::hashbrown::{BiHashMap, Global};
+//!
+//! let mut ports = BiHashMap::new_in(Global);
+//! ports.insert("http", 80);
+//! ports.insert("https", 443);
+//!
+//! assert_eq!(ports.get_by_left(&"https"), Some(&443));
+//! assert_eq!(ports.get_by_right(&80), Some(&"http"));
+//! ```
+//!
+//! [hashbrown]: https://docs.rs/hashbrown/
+
+use crate::{
+    mem::{Ref, Wrapper},
+    BiMapLike, Overwritten,
+};
+use alloc::rc::Rc;
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{Extend, FromIterator, FusedIterator},
+};
+use hashbrown::{
+    hash_map::{self, RawEntryMut},
+    DefaultHashBuilder, HashMap,
+};
+
+pub use allocator_api2::alloc::{Allocator, Global};
+
+/// A bimap backed by two `hashbrown::HashMap`s.
+///
+/// See the [module-level documentation](self) for more details and examples.
+pub struct BiHashMap<
+    L,
+    R,
+    LS = DefaultHashBuilder,
+    RS = DefaultHashBuilder,
+    A: Allocator + Clone = Global,
+> {
+    left2right: HashMap<Ref<L>, Ref<R>, LS, A>,
+    right2left: HashMap<Ref<R>, Ref<L>, RS, A>,
+}
+
+impl<L, R> BiHashMap<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `BiHashMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates a new empty `BiHashMap` with the given capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<L, R, A> BiHashMap<L, R, DefaultHashBuilder, DefaultHashBuilder, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    A: Allocator + Clone,
+{
+    /// Creates an empty `BiHashMap` that allocates its hash tables with the
+    /// given allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::{BiHashMap, Global};
+    ///
+    /// let bimap = BiHashMap::<char, i32>::new_in(Global);
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_hashers_in(
+            DefaultHashBuilder::default(),
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
+
+    /// Creates a new empty `BiHashMap` with the given capacity that allocates
+    /// its hash tables with the given allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::{BiHashMap, Global};
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_capacity_in(10, Global);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::with_capacity_and_hashers_in(
+            capacity,
+            DefaultHashBuilder::default(),
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
+}
+
+impl<L, R, LS, RS> BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Creates a new empty `BiHashMap` using `hash_builder_left` to hash left
+    /// values and `hash_builder_right` to hash right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    /// use hashbrown::DefaultHashBuilder;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_hashers(
+    ///     DefaultHashBuilder::default(),
+    ///     DefaultHashBuilder::default(),
+    /// );
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        Self::with_hashers_in(hash_builder_left, hash_builder_right, Global)
+    }
+
+    /// Creates a new empty `BiHashMap` with the given capacity, using
+    /// `hash_builder_left` to hash left values and `hash_builder_right` to
+    /// hash right values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    /// use hashbrown::DefaultHashBuilder;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_capacity_and_hashers(
+    ///     10,
+    ///     DefaultHashBuilder::default(),
+    ///     DefaultHashBuilder::default(),
+    /// );
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity_and_hashers(
+        capacity: usize,
+        hash_builder_left: LS,
+        hash_builder_right: RS,
+    ) -> Self {
+        Self::with_capacity_and_hashers_in(capacity, hash_builder_left, hash_builder_right, Global)
+    }
+}
+
+impl<L, R, LS, RS, A> BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+    /// Creates a new empty `BiHashMap` with the given hashers that allocates
+    /// its hash tables with the given allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::{BiHashMap, Global};
+    /// use hashbrown::DefaultHashBuilder;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_hashers_in(
+    ///     DefaultHashBuilder::default(),
+    ///     DefaultHashBuilder::default(),
+    ///     Global,
+    /// );
+    /// ```
+    pub fn with_hashers_in(hash_builder_left: LS, hash_builder_right: RS, alloc: A) -> Self {
+        Self {
+            left2right: HashMap::with_hasher_in(hash_builder_left, alloc.clone()),
+            right2left: HashMap::with_hasher_in(hash_builder_right, alloc),
+        }
+    }
+
+    /// Creates a new empty `BiHashMap` with the given capacity and hashers
+    /// that allocates its hash tables with the given allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::{BiHashMap, Global};
+    /// use hashbrown::DefaultHashBuilder;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_capacity_and_hashers_in(
+    ///     10,
+    ///     DefaultHashBuilder::default(),
+    ///     DefaultHashBuilder::default(),
+    ///     Global,
+    /// );
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity_and_hashers_in(
+        capacity: usize,
+        hash_builder_left: LS,
+        hash_builder_right: RS,
+        alloc: A,
+    ) -> Self {
+        Self {
+            left2right: HashMap::with_capacity_and_hasher_in(
+                capacity,
+                hash_builder_left,
+                alloc.clone(),
+            ),
+            right2left: HashMap::with_capacity_and_hasher_in(capacity, hash_builder_right, alloc),
+        }
+    }
+
+    /// Returns a reference to the allocator of the bimap's hash tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::{BiHashMap, Global};
+    ///
+    /// let bimap = BiHashMap::<char, i32>::new_in(Global);
+    /// let _: &Global = bimap.allocator();
+    /// ```
+    pub fn allocator(&self) -> &A {
+        self.left2right.allocator()
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.left2right.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert!(bimap.is_empty());
+    /// bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.left2right.is_empty()
+    }
+
+    /// Returns a lower bound on the number of left-right pairs the bimap can
+    /// store without reallocating memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let bimap = BiHashMap::<char, i32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.left2right.capacity().min(self.right2left.capacity())
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `BiHashMap`.
+    ///
+    /// Both internal maps reserve the same amount up front, so inserting
+    /// `additional` pairs with new left and right values afterwards doesn't
+    /// reallocate or rehash either of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::new();
+    /// bimap.reserve(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.left2right.reserve(additional);
+        self.right2left.reserve(additional);
+    }
+
+    /// Shrinks the capacity of the bimap as much as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::<char, i32>::with_capacity(100);
+    /// bimap.insert('a', 1);
+    /// bimap.shrink_to_fit();
+    /// assert!(bimap.capacity() >= 1);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.left2right.shrink_to_fit();
+        self.right2left.shrink_to_fit();
+    }
+
+    /// Removes all left-right pairs from the bimap, keeping the allocated
+    /// memory for reuse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.clear();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.left2right.clear();
+        self.right2left.clear();
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap in arbitrary
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// for (left, right) in bimap.iter() {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.left2right.iter(),
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap in arbitrary
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// for left in bimap.left_values() {
+    ///     println!("{}", left);
+    /// }
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: self.left2right.iter(),
+        }
+    }
+
+    /// Creates an iterator over the right values in the bimap in arbitrary
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// for right in bimap.right_values() {
+    ///     println!("{}", right);
+    /// }
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.right2left.iter(),
+        }
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.left2right.get(Wrapper::wrap(left)).map(|r| &*r.0)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.right2left.get(Wrapper::wrap(right)).map(|l| &*l.0)
+    }
+
+    /// Returns a reference to the bimap's left [`BuildHasher`].
+    ///
+    /// Hashing a left value with this builder produces the hash that
+    /// [`get_by_left_hashed`] expects.
+    ///
+    /// [`get_by_left_hashed`]: BiHashMap::get_by_left_hashed
+    pub fn left_hasher(&self) -> &LS {
+        self.left2right.hasher()
+    }
+
+    /// Returns a reference to the bimap's right [`BuildHasher`].
+    ///
+    /// Hashing a right value with this builder produces the hash that
+    /// [`get_by_right_hashed`] expects.
+    ///
+    /// [`get_by_right_hashed`]: BiHashMap::get_by_right_hashed
+    pub fn right_hasher(&self) -> &RS {
+        self.right2left.hasher()
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value, using a hash of the left value computed beforehand.
+    ///
+    /// This doesn't hash `left` again, so a caller that already hashed it,
+    /// for example to intern it in another table built with the same hasher,
+    /// only pays for the hash once. `hash` must be the hash of `left` produced
+    /// by [`left_hasher`]; with any other hash, the pair may not be found.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    /// use core::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert("alice", 1);
+    ///
+    /// let mut hasher = bimap.left_hasher().build_hasher();
+    /// "alice".hash(&mut hasher);
+    /// let hash = hasher.finish();
+    /// assert_eq!(bimap.get_by_left_hashed(hash, "alice"), Some(&1));
+    /// ```
+    ///
+    /// [`left_hasher`]: BiHashMap::left_hasher
+    pub fn get_by_left_hashed<Q>(&self, hash: u64, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.left2right
+            .raw_entry()
+            .from_hash(hash, |l| (*l.0).borrow() == left)
+            .map(|(_, r)| &*r.0)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value, using a hash of the right value computed beforehand.
+    ///
+    /// `hash` must be the hash of `right` produced by [`right_hasher`]; with
+    /// any other hash, the pair may not be found. See
+    /// [`get_by_left_hashed`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    /// use core::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', "one");
+    ///
+    /// let mut hasher = bimap.right_hasher().build_hasher();
+    /// "one".hash(&mut hasher);
+    /// let hash = hasher.finish();
+    /// assert_eq!(bimap.get_by_right_hashed(hash, "one"), Some(&'a'));
+    /// ```
+    ///
+    /// [`right_hasher`]: BiHashMap::right_hasher
+    /// [`get_by_left_hashed`]: BiHashMap::get_by_left_hashed
+    pub fn get_by_right_hashed<Q>(&self, hash: u64, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.right2left
+            .raw_entry()
+            .from_hash(hash, |r| (*r.0).borrow() == right)
+            .map(|(_, l)| &*l.0)
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.left2right.contains_key(Wrapper::wrap(left))
+    }
+
+    /// Returns `true` if the map contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.right2left.contains_key(Wrapper::wrap(right))
+    }
+
+    /// Removes the left-right pair corresponding to the given left value.
+    ///
+    /// Returns the previous left-right pair if the map contained the left
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_left(&'b'), None);
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.left2right.remove(Wrapper::wrap(left)).map(|right_rc| {
+            // unwrap is safe because we know right2left contains the key (it's a bimap)
+            let left_rc = self.right2left.remove(&right_rc).unwrap();
+            // at this point we can safely unwrap because the other pointers are gone
+            (
+                Rc::try_unwrap(left_rc.0).ok().unwrap(),
+                Rc::try_unwrap(right_rc.0).ok().unwrap(),
+            )
+        })
+    }
+
+    /// Removes the left-right pair corresponding to the given right value.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_right(&2), None);
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.right2left.remove(Wrapper::wrap(right)).map(|left_rc| {
+            // unwrap is safe because we know left2right contains the key (it's a bimap)
+            let right_rc = self.left2right.remove(&left_rc).unwrap();
+            // at this point we can safely unwrap because the other pointers are gone
+            (
+                Rc::try_unwrap(left_rc.0).ok().unwrap(),
+                Rc::try_unwrap(right_rc.0).ok().unwrap(),
+            )
+        })
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`, exactly like
+    /// [`crate::BiHashMap::insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hashbrown::BiHashMap, Overwritten};
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Unchanged('a', 2));
+    /// assert_eq!(bimap.len(), 1);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        if matches!(self.left2right.get(Wrapper::wrap(&left)), Some(r) if *r.0 == right) {
+            // the pair is already present, so neither map needs to change
+            return Overwritten::Unchanged(left, right);
+        }
+        let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
+            (None, None) => Overwritten::Neither,
+            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
+            (Some(l_pair), None) => Overwritten::Left(l_pair.0, l_pair.1),
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        };
+        self.insert_unchecked(left, right);
+        retval
+    }
+
+    /// Inserts the given left-right pair into the bimap, using hashes of both
+    /// values computed beforehand.
+    ///
+    /// Behaves exactly like [`insert`], but doesn't hash `left` and `right`
+    /// again. `left_hash` and `right_hash` must be the hashes of `left` and
+    /// `right` produced by [`left_hasher`] and [`right_hasher`]; with any other
+    /// hash, the bimap may end up with duplicate values. Pairs overwritten by
+    /// the call are still hashed once to remove their other side.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{hashbrown::BiHashMap, Overwritten};
+    /// use core::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// fn hash<T: Hash>(builder: &impl BuildHasher, value: &T) -> u64 {
+    ///     let mut hasher = builder.build_hasher();
+    ///     value.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let left_hash = hash(bimap.left_hasher(), &'b');
+    /// let right_hash = hash(bimap.right_hasher(), &1);
+    /// assert_eq!(
+    ///     bimap.insert_hashed(left_hash, right_hash, 'b', 1),
+    ///     Overwritten::Right('a', 1)
+    /// );
+    /// assert_eq!(bimap.get_by_left_hashed(left_hash, &'b'), Some(&1));
+    /// ```
+    ///
+    /// [`insert`]: BiHashMap::insert
+    /// [`left_hasher`]: BiHashMap::left_hasher
+    /// [`right_hasher`]: BiHashMap::right_hasher
+    pub fn insert_hashed(
+        &mut self,
+        left_hash: u64,
+        right_hash: u64,
+        left: L,
+        right: R,
+    ) -> Overwritten<L, R> {
+        if matches!(self.get_by_left_hashed(left_hash, &left), Some(r) if *r == right) {
+            // the pair is already present, so neither map needs to change
+            return Overwritten::Unchanged(left, right);
+        }
+        let retval = match (
+            self.remove_by_left_hashed(left_hash, &left),
+            self.remove_by_right_hashed(right_hash, &right),
+        ) {
+            (None, None) => Overwritten::Neither,
+            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
+            (Some(l_pair), None) => Overwritten::Left(l_pair.0, l_pair.1),
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        };
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        // both entries are vacant since any previous pairs were removed above
+        if let RawEntryMut::Vacant(entry) = self
+            .left2right
+            .raw_entry_mut()
+            .from_hash(left_hash, |l| l == &left)
+        {
+            entry.insert_hashed_nocheck(left_hash, left.clone(), right.clone());
+        }
+        if let RawEntryMut::Vacant(entry) = self
+            .right2left
+            .raw_entry_mut()
+            .from_hash(right_hash, |r| r == &right)
+        {
+            entry.insert_hashed_nocheck(right_hash, right, left);
+        }
+        retval
+    }
+
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right))` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
+    /// assert_eq!(bimap.insert_no_overwrite('c', 1), Err(('c', 1)));
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            Err((left, right))
+        } else {
+            self.insert_unchecked(left, right);
+            Ok(())
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &r)` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::hashbrown::BiHashMap;
+    ///
+    /// let mut bimap = BiHashMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.retain(|&l, &r| r >= 2);
+    /// assert_eq!(bimap.len(), 2);
+    /// assert_eq!(bimap.get_by_left(&'a'), None);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        let mut f = f;
+        let right2left = &mut self.right2left;
+        self.left2right.retain(|l, r| {
+            let to_retain = f(&l.0, &r.0);
+            if !to_retain {
+                right2left.remove(r);
+            }
+            to_retain
+        });
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if the
+    /// pair already exists.
+    /// Removes the pair with the given left value, found by its precomputed
+    /// hash.
+    fn remove_by_left_hashed(&mut self, hash: u64, left: &L) -> Option<(L, R)> {
+        match self
+            .left2right
+            .raw_entry_mut()
+            .from_hash(hash, |l| *l.0 == *left)
+        {
+            RawEntryMut::Occupied(entry) => {
+                let (left_rc, right_rc) = entry.remove_entry();
+                // unwrap is safe because we know right2left contains the key (it's a bimap)
+                self.right2left.remove(&right_rc).unwrap();
+                // at this point we can safely unwrap because the other pointers are gone
+                Some((
+                    Rc::try_unwrap(left_rc.0).ok().unwrap(),
+                    Rc::try_unwrap(right_rc.0).ok().unwrap(),
+                ))
+            }
+            RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    /// Removes the pair with the given right value, found by its precomputed
+    /// hash.
+    fn remove_by_right_hashed(&mut self, hash: u64, right: &R) -> Option<(L, R)> {
+        match self
+            .right2left
+            .raw_entry_mut()
+            .from_hash(hash, |r| *r.0 == *right)
+        {
+            RawEntryMut::Occupied(entry) => {
+                let (right_rc, left_rc) = entry.remove_entry();
+                // unwrap is safe because we know left2right contains the key (it's a bimap)
+                self.left2right.remove(&left_rc).unwrap();
+                // at this point we can safely unwrap because the other pointers are gone
+                Some((
+                    Rc::try_unwrap(left_rc.0).ok().unwrap(),
+                    Rc::try_unwrap(right_rc.0).ok().unwrap(),
+                ))
+            }
+            RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        let left = Ref(Rc::new(left));
+        let right = Ref(Rc::new(right));
+        self.left2right.insert(left.clone(), right.clone());
+        self.right2left.insert(right, left);
+    }
+}
+
+impl<L, R, LS, RS, A> crate::sealed::Sealed for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+}
+
+impl<L, R, LS, RS, A> BiMapLike<L, R> for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+    type Iter<'a>
+        = Iter<'a, L, R>
+    where
+        Self: 'a,
+        L: 'a,
+        R: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter(&self) -> Iter<'_, L, R> {
+        self.iter()
+    }
+
+    fn get_by_left(&self, left: &L) -> Option<&R> {
+        self.get_by_left(left)
+    }
+
+    fn get_by_right(&self, right: &R) -> Option<&L> {
+        self.get_by_right(right)
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn contains_right(&self, right: &R) -> bool {
+        self.contains_right(right)
+    }
+
+    fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        self.insert(left, right)
+    }
+
+    fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        self.remove_by_left(left)
+    }
+
+    fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        self.remove_by_right(right)
+    }
+}
+
+impl<L, R, LS, RS, A> Clone for BiHashMap<L, R, LS, RS, A>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+    A: Allocator + Clone,
+{
+    fn clone(&self) -> BiHashMap<L, R, LS, RS, A> {
+        let mut new_bimap = BiHashMap::with_capacity_and_hashers_in(
+            self.capacity(),
+            self.left2right.hasher().clone(),
+            self.right2left.hasher().clone(),
+            self.allocator().clone(),
+        );
+        for (l, r) in self.iter() {
+            new_bimap.insert_unchecked(l.clone(), r.clone());
+        }
+        new_bimap
+    }
+}
+
+impl<L, R, LS, RS, A> fmt::Debug for BiHashMap<L, R, LS, RS, A>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+    A: Allocator + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct EntryDebugger<'a, L, R> {
+            left: &'a L,
+            right: &'a R,
+        }
+        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+        where
+            L: fmt::Debug,
+            R: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.left.fmt(f)?;
+                write!(f, " <> ")?;
+                self.right.fmt(f)
+            }
+        }
+        f.debug_set()
+            .entries(self.left2right.iter().map(|(left, right)| EntryDebugger {
+                left: &*left.0,
+                right: &*right.0,
+            }))
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS, A> Default for BiHashMap<L, R, LS, RS, A>
+where
+    LS: Default,
+    RS: Default,
+    A: Allocator + Clone + Default,
+{
+    fn default() -> BiHashMap<L, R, LS, RS, A> {
+        let alloc = A::default();
+        BiHashMap {
+            left2right: HashMap::with_hasher_in(LS::default(), alloc.clone()),
+            right2left: HashMap::with_hasher_in(RS::default(), alloc),
+        }
+    }
+}
+
+impl<L, R, LS, RS, A> Eq for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+}
+
+impl<L, R, LS, RS, A> FromIterator<(L, R)> for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+    A: Allocator + Clone + Default,
+{
+    fn from_iter<I>(iter: I) -> BiHashMap<L, R, LS, RS, A>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiHashMap::default();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+impl<'a, L, R, LS, RS, A> IntoIterator for &'a BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+impl<L, R, LS, RS, A> IntoIterator for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    A: Allocator + Clone,
+{
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R, A>;
+
+    fn into_iter(self) -> IntoIter<L, R, A> {
+        IntoIter {
+            inner: self.left2right.into_iter(),
+        }
+    }
+}
+
+impl<L, R, LS, RS, A> Extend<(L, R)> for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+impl<L, R, LS, RS, A> PartialEq for BiHashMap<L, R, LS, RS, A>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+    A: Allocator + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.left2right == other.left2right
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiHashMap`.
+pub struct IntoIter<L, R, A: Allocator = Global> {
+    inner: hash_map::IntoIter<Ref<L>, Ref<R>, A>,
+}
+
+impl<L, R, A: Allocator> ExactSizeIterator for IntoIter<L, R, A> {}
+
+impl<L, R, A: Allocator> FusedIterator for IntoIter<L, R, A> {}
+
+impl<L, R, A: Allocator> Iterator for IntoIter<L, R, A> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // unwraps are safe because right2left is gone
+        self.inner.next().map(|(l, r)| {
+            (
+                Rc::try_unwrap(l.0).ok().unwrap(),
+                Rc::try_unwrap(r.0).ok().unwrap(),
+            )
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiHashMap`.
+///
+/// This struct is created by the [`iter`] method of `BiHashMap`.
+///
+/// [`iter`]: BiHashMap::iter
+#[derive(Debug, Clone)]
+pub struct Iter<'a, L, R> {
+    inner: hash_map::Iter<'a, Ref<L>, Ref<R>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (&*l.0, &*r.0))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `BiHashMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiHashMap`.
+///
+/// [`left_values`]: BiHashMap::left_values
+#[derive(Debug, Clone)]
+pub struct LeftValues<'a, L, R> {
+    inner: hash_map::Iter<'a, Ref<L>, Ref<R>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, _)| &*l.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `BiHashMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiHashMap`.
+///
+/// [`right_values`]: BiHashMap::right_values
+#[derive(Debug, Clone)]
+pub struct RightValues<'a, L, R> {
+    inner: hash_map::Iter<'a, Ref<R>, Ref<L>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(r, _)| &*r.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+// safe because internal Rcs are not exposed by the api and the reference
+// counts only change in methods with &mut self
+unsafe impl<L, R, LS, RS, A> Send for BiHashMap<L, R, LS, RS, A>
+where
+    L: Send,
+    R: Send,
+    LS: Send,
+    RS: Send,
+    A: Allocator + Clone + Send,
+{
+}
+unsafe impl<L, R, LS, RS, A> Sync for BiHashMap<L, R, LS, RS, A>
+where
+    L: Sync,
+    R: Sync,
+    LS: Sync,
+    RS: Sync,
+    A: Allocator + Clone + Sync,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mem::hash_one;
+    use alloc::vec::Vec;
+    use allocator_api2::alloc::AllocError;
+    use core::{alloc::Layout, cell::Cell, ptr::NonNull};
+
+    /// Counts the allocations made through it, sharing the count between
+    /// clones.
+    #[derive(Clone, Default)]
+    struct CountingAlloc {
+        count: Rc<Cell<usize>>,
+    }
+
+    unsafe impl Allocator for CountingAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.count.set(self.count.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+        assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+        assert_eq!(bimap.insert('b', 2), Overwritten::Unchanged('b', 2));
+        assert_eq!(bimap.insert('c', 1), Overwritten::Right('a', 1));
+        assert_eq!(bimap.insert('b', 3), Overwritten::Left('b', 2));
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_left(&'c'), Some(&1));
+        assert_eq!(bimap.get_by_right(&3), Some(&'b'));
+        assert!(!bimap.contains_left(&'a'));
+        assert_eq!(bimap.insert_no_overwrite('d', 3), Err(('d', 3)));
+
+        assert_eq!(bimap.remove_by_right(&1), Some(('c', 1)));
+        assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 3)));
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn hashed_lookups() {
+        let mut bimap = BiHashMap::new();
+        for i in 0..100 {
+            bimap.insert(i, i * 2);
+        }
+        for i in 0..100 {
+            let left_hash = hash_one(bimap.left_hasher(), &i);
+            let right_hash = hash_one(bimap.right_hasher(), &(i * 2));
+            assert_eq!(bimap.get_by_left_hashed(left_hash, &i), Some(&(i * 2)));
+            assert_eq!(bimap.get_by_right_hashed(right_hash, &(i * 2)), Some(&i));
+        }
+        let hash = hash_one(bimap.left_hasher(), &100);
+        assert_eq!(bimap.get_by_left_hashed(hash, &100), None);
+    }
+
+    #[test]
+    fn hashed_insert() {
+        let mut bimap = BiHashMap::new();
+        let insert = |bimap: &mut BiHashMap<char, i32>, left, right| {
+            let left_hash = hash_one(bimap.left_hasher(), &left);
+            let right_hash = hash_one(bimap.right_hasher(), &right);
+            bimap.insert_hashed(left_hash, right_hash, left, right)
+        };
+        assert_eq!(insert(&mut bimap, 'a', 1), Overwritten::Neither);
+        assert_eq!(insert(&mut bimap, 'b', 2), Overwritten::Neither);
+        assert_eq!(insert(&mut bimap, 'b', 2), Overwritten::Unchanged('b', 2));
+        assert_eq!(insert(&mut bimap, 'c', 1), Overwritten::Right('a', 1));
+        assert_eq!(insert(&mut bimap, 'b', 3), Overwritten::Left('b', 2));
+        assert_eq!(
+            insert(&mut bimap, 'c', 3),
+            Overwritten::Both(('c', 1), ('b', 3))
+        );
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'c'), Some(&3));
+        assert_eq!(bimap.get_by_right(&3), Some(&'c'));
+
+        for i in 0..100 {
+            insert(&mut bimap, char::from(b'0' + (i % 10) as u8), i);
+        }
+        assert_eq!(bimap.len(), 10);
+        for i in 90..100 {
+            let left = char::from(b'0' + (i % 10) as u8);
+            assert_eq!(bimap.get_by_left(&left), Some(&i));
+            assert_eq!(bimap.get_by_right(&i), Some(&left));
+        }
+    }
+
+    #[test]
+    fn custom_allocator() {
+        let alloc = CountingAlloc::default();
+        let mut bimap = BiHashMap::with_capacity_in(10, alloc.clone());
+        // one table for each direction
+        assert_eq!(alloc.count.get(), 2);
+
+        for i in 0..10 {
+            bimap.insert(i, i * 2);
+        }
+        assert_eq!(alloc.count.get(), 2);
+        bimap.retain(|&l, _| l % 2 == 0);
+        assert_eq!(bimap.len(), 5);
+
+        let clone = bimap.clone();
+        assert_eq!(clone, bimap);
+        assert_eq!(alloc.count.get(), 4);
+
+        let mut pairs: Vec<_> = clone.into_iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![(0, 0), (2, 4), (4, 8), (6, 12), (8, 16)]);
+    }
+
+    #[test]
+    fn iterators() {
+        let bimap: BiHashMap<char, i32> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+        assert_eq!(bimap.iter().len(), 3);
+        let mut lefts: Vec<_> = bimap.left_values().copied().collect();
+        lefts.sort();
+        assert_eq!(lefts, ['a', 'b', 'c']);
+        let mut rights: Vec<_> = bimap.right_values().copied().collect();
+        rights.sort();
+        assert_eq!(rights, [1, 2, 3]);
+        for (l, r) in &bimap {
+            assert_eq!(bimap.get_by_right(r), Some(l));
+        }
+    }
+
+    #[test]
+    fn debug() {
+        let mut bimap = BiHashMap::new();
+        assert_eq!(format!("{:?}", bimap), "{}");
+        bimap.insert('a', 1);
+        assert_eq!(format!("{:?}", bimap), "{'a' <> 1}");
+    }
+
+    #[test]
+    fn bimap_like() {
+        fn swap<M: BiMapLike<char, i32>>(bimap: &mut M) {
+            let (l, r) = bimap.remove_by_left(&'a').unwrap();
+            bimap.insert(l, r + 1);
+        }
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        swap(&mut bimap);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+    }
+}
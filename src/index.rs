@@ -6,6 +6,7 @@ use crate::{
 };
 use std::{
     borrow::Borrow,
+    cmp::Ordering,
     fmt,
     collections::hash_map,
     hash::{BuildHasher, Hash},
@@ -19,12 +20,42 @@ use indexmap::{
 
 /// A bimap backed by two `IndexMap`s.
 ///
+/// Unlike the other bimaps, a `BiIndexMap` remembers the order in which its
+/// pairs were inserted: iteration follows that order, and pairs can be
+/// accessed by their position with [`get_index`]. Removing a pair with
+/// [`shift_remove_by_left`] keeps the order of the remaining pairs, while
+/// [`swap_remove_by_left`] is faster but moves the last pair into the gap.
+/// See [`insert_full`] for exactly when the index of a pair can change.
+///
 /// See the [module-level documentation] for more details and examples.
 ///
+/// # Examples
+///
+/// ```
+/// use bimap::BiIndexMap;
+///
+/// let mut bimap = BiIndexMap::new();
+/// bimap.insert("zebra", 26);
+/// bimap.insert("apple", 1);
+/// bimap.insert("mango", 13);
+///
+/// let lefts: Vec<_> = bimap.left_values().copied().collect();
+/// assert_eq!(lefts, ["zebra", "apple", "mango"]);
+/// assert_eq!(bimap.get_index(1), Some((&"apple", &1)));
+/// assert_eq!(bimap.get_index_of_right(&13), Some(2));
+/// ```
+///
 /// [module-level documentation]: crate
+/// [`get_index`]: BiIndexMap::get_index
+/// [`insert_full`]: BiIndexMap::insert_full
+/// [`shift_remove_by_left`]: BiIndexMap::shift_remove_by_left
+/// [`swap_remove_by_left`]: BiIndexMap::swap_remove_by_left
 pub struct BiIndexMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    // both maps hold the pairs in the same order, so a pair has the same
+    // index in each of them
     left2right: IndexMap<Ref<L>, Ref<R>, LS>,
     right2left: IndexMap<Ref<R>, Ref<L>, RS>,
+    generation: u64,
 }
 
 impl<L, R> BiIndexMap<L, R, hash_map::RandomState, hash_map::RandomState>
@@ -45,11 +76,16 @@ where
         Self {
             left2right: IndexMap::new(),
             right2left: IndexMap::new(),
+            generation: 0,
         }
     }
 
     /// Creates a new empty `BiIndexMap` with the given capacity.
     ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -62,6 +98,7 @@ where
         Self {
             left2right: IndexMap::with_capacity(capacity),
             right2left: IndexMap::with_capacity(capacity),
+            generation: 0,
         }
     }
 }
@@ -122,6 +159,42 @@ where
         self.left2right.capacity().min(self.right2left.capacity())
     }
 
+    /// Returns the bimap's generation, a counter that changes whenever the
+    /// left-right pairs may have changed.
+    ///
+    /// Every method that inserts, removes or modifies pairs, including
+    /// `clear` and `retain`, advances the generation, while lookups and
+    /// capacity changes leave it unchanged. Comparing generations is a cheap
+    /// way to tell whether a cache derived from the bimap is stale. The
+    /// counter starts at zero and only increases, but its exact value is not
+    /// specified; clones and deserialized bimaps start their own count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// let generation = bimap.generation();
+    ///
+    /// // lookups don't change the generation
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.generation(), generation);
+    ///
+    /// // mutations do
+    /// bimap.remove_by_left(&'a');
+    /// assert!(bimap.generation() > generation);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Advances the generation after a mutation.
+    fn bump_generation(&mut self) {
+        self.generation += 1;
+    }
+
     /// Removes all left-right pairs from the bimap.
     ///
     /// # Examples
@@ -139,9 +212,10 @@ where
     pub fn clear(&mut self) {
         self.left2right.clear();
         self.right2left.clear();
+        self.bump_generation();
     }
 
-    /// Creates an iterator over the left-right pairs in the bimap in arbitrary
+    /// Creates an iterator over the left-right pairs in the bimap in insertion
     /// order.
     ///
     /// The iterator element type is `(&L, &R)`.
@@ -166,7 +240,7 @@ where
         }
     }
 
-    /// Creates an iterator over the left values in the bimap in arbitrary
+    /// Creates an iterator over the left values in the bimap in insertion
     /// order.
     ///
     /// The iterator element type is `&L`.
@@ -191,7 +265,7 @@ where
         }
     }
 
-    /// Creates an iterator over the right values in the bimap in arbitrary
+    /// Creates an iterator over the right values in the bimap in insertion
     /// order.
     ///
     /// The iterator element type is `&R`.
@@ -215,6 +289,163 @@ where
             inner: self.right2left.iter(),
         }
     }
+
+    /// Returns the left-right pair at the given position in insertion order,
+    /// or `None` if the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.get_index(0), Some((&'a', &1)));
+    /// assert_eq!(bimap.get_index(1), Some((&'b', &2)));
+    /// assert_eq!(bimap.get_index(2), None);
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&L, &R)> {
+        self.left2right
+            .get_index(index)
+            .map(|(l, r)| (&*l.0, &*r.0))
+    }
+
+    /// Reverses the order of the pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// bimap.reverse();
+    /// assert_eq!(bimap.get_index(0), Some((&'c', &3)));
+    /// assert_eq!(bimap.get_index_of_right(&1), Some(2));
+    /// ```
+    pub fn reverse(&mut self) {
+        self.left2right.reverse();
+        self.right2left.reverse();
+        self.bump_generation();
+    }
+
+    /// Swaps the positions of the pairs at the two given indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// bimap.swap_indices(0, 2);
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'c', &3), (&'b', &2), (&'a', &1)]);
+    /// ```
+    pub fn swap_indices(&mut self, a: usize, b: usize) {
+        self.left2right.swap_indices(a, b);
+        self.right2left.swap_indices(a, b);
+        self.bump_generation();
+    }
+
+    /// Moves the pair at index `from` to index `to`, shifting the pairs in
+    /// between by one position to make room.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// bimap.move_index(2, 0);
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'c', &3), (&'a', &1), (&'b', &2)]);
+    /// ```
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        self.left2right.move_index(from, to);
+        self.right2left.move_index(from, to);
+        self.bump_generation();
+    }
+
+    /// Removes the left-right pair at the given index and shifts all pairs
+    /// after it back by one position, preserving their order.
+    ///
+    /// Returns the removed pair, or `None` if the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('a', 1), ('b', 2), ('c', 3)].into_iter().collect();
+    /// assert_eq!(bimap.shift_remove_index(0), Some(('a', 1)));
+    /// assert_eq!(bimap.shift_remove_index(2), None);
+    /// assert_eq!(bimap.get_index_of_right(&3), Some(1));
+    /// ```
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(L, R)> {
+        let (left_rc, right_rc) = self.left2right.shift_remove_index(index)?;
+        self.right2left.shift_remove_index(index);
+        self.bump_generation();
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Sorts the pairs of the bimap by their left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('c', 1), ('a', 3), ('b', 2)].into_iter().collect();
+    /// bimap.sort_by_left();
+    /// let lefts: Vec<_> = bimap.left_values().collect();
+    /// assert_eq!(lefts, [&'a', &'b', &'c']);
+    /// ```
+    pub fn sort_by_left(&mut self)
+    where
+        L: Ord,
+    {
+        // left values are unique, so an unstable sort is deterministic and
+        // puts both maps in the same order
+        self.left2right.sort_unstable_keys();
+        self.right2left.sort_unstable_by(|_, l1, _, l2| l1.cmp(l2));
+        self.bump_generation();
+    }
+
+    /// Sorts the pairs of the bimap by their right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![('c', 1), ('a', 3), ('b', 2)].into_iter().collect();
+    /// bimap.sort_by_right();
+    /// let rights: Vec<_> = bimap.right_values().collect();
+    /// assert_eq!(rights, [&1, &2, &3]);
+    /// ```
+    pub fn sort_by_right(&mut self)
+    where
+        R: Ord,
+    {
+        // right values are unique, so an unstable sort is deterministic and
+        // puts both maps in the same order
+        self.left2right.sort_unstable_by(|_, r1, _, r2| r1.cmp(r2));
+        self.right2left.sort_unstable_keys();
+        self.bump_generation();
+    }
 }
 
 impl<L, R, LS, RS> BiIndexMap<L, R, LS, RS>
@@ -242,6 +473,7 @@ where
         Self {
             left2right: IndexMap::with_hasher(hash_builder_left),
             right2left: IndexMap::with_hasher(hash_builder_right),
+            generation: 0,
         }
     }
 
@@ -249,6 +481,10 @@ where
     /// `hash_builder_left` to hash left values and `hash_builder_right` to
     /// hash right values.
     ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -268,6 +504,7 @@ where
         Self {
             left2right: IndexMap::with_capacity_and_hasher(capacity, hash_builder_left),
             right2left: IndexMap::with_capacity_and_hasher(capacity, hash_builder_right),
+            generation: 0,
         }
     }
 
@@ -436,11 +673,122 @@ where
         self.right2left.contains_key(Wrapper::wrap(right))
     }
 
+    /// Returns the position of the pair containing the given left value, or
+    /// `None` if the bimap doesn't contain it.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.get_index_of_left(&'b'), Some(1));
+    /// assert_eq!(bimap.get_index_of_left(&'c'), None);
+    /// ```
+    pub fn get_index_of_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.left2right.get_index_of(Wrapper::wrap(left))
+    }
+
+    /// Returns the position of the pair containing the given right value, or
+    /// `None` if the bimap doesn't contain it.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.get_index_of_right(&1), Some(0));
+    /// assert_eq!(bimap.get_index_of_right(&3), None);
+    /// ```
+    pub fn get_index_of_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.right2left.get_index_of(Wrapper::wrap(right))
+    }
+
+    /// Returns the position of the pair containing the given left value along
+    /// with the pair itself, or `None` if the bimap doesn't contain it.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.get_full_by_left(&'b'), Some((1, &'b', &2)));
+    /// assert_eq!(bimap.get_full_by_left(&'c'), None);
+    /// ```
+    pub fn get_full_by_left<Q>(&self, left: &Q) -> Option<(usize, &L, &R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.left2right
+            .get_full(Wrapper::wrap(left))
+            .map(|(index, l, r)| (index, &*l.0, &*r.0))
+    }
+
+    /// Returns the position of the pair containing the given right value along
+    /// with the pair itself, or `None` if the bimap doesn't contain it.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// assert_eq!(bimap.get_full_by_right(&1), Some((0, &'a', &1)));
+    /// assert_eq!(bimap.get_full_by_right(&3), None);
+    /// ```
+    pub fn get_full_by_right<Q>(&self, right: &Q) -> Option<(usize, &L, &R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.right2left
+            .get_full(Wrapper::wrap(right))
+            .map(|(index, r, l)| (index, &*l.0, &*r.0))
+    }
+
     /// Removes the left-right pair corresponding to the given left value.
     ///
     /// Returns the previous left-right pair if the map contained the left value
     /// and `None` otherwise.
     ///
+    /// This is the same as [`shift_remove_by_left`], so the remaining pairs
+    /// keep their order.
+    ///
     /// The input may be any borrowed form of the bimap's left type, but `Eq`
     /// and `Hash` on the borrowed form *must* match those for the left type.
     ///
@@ -457,20 +805,14 @@ where
     /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
     /// assert_eq!(bimap.remove_by_left(&'b'), None);
     /// ```
+    ///
+    /// [`shift_remove_by_left`]: BiIndexMap::shift_remove_by_left
     pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
     where
         L: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.left2right.remove(Wrapper::wrap(left)).map(|right_rc| {
-            // unwrap is safe because we know right2left contains the key (it's a bimap)
-            let left_rc = self.right2left.remove(&right_rc).unwrap();
-            // at this point we can safely unwrap because the other pointers are gone
-            (
-                Rc::try_unwrap(left_rc.0).ok().unwrap(),
-                Rc::try_unwrap(right_rc.0).ok().unwrap(),
-            )
-        })
+        self.shift_remove_by_left(left)
     }
 
     /// Removes the left-right pair corresponding to the given right value.
@@ -478,6 +820,9 @@ where
     /// Returns the previous left-right pair if the map contained the right
     /// value and `None` otherwise.
     ///
+    /// This is the same as [`shift_remove_by_right`], so the remaining pairs
+    /// keep their order.
+    ///
     /// The input may be any borrowed form of the bimap's right type, but `Eq`
     /// and `Hash` on the borrowed form *must* match those for the right type.
     ///
@@ -494,20 +839,194 @@ where
     /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
     /// assert_eq!(bimap.remove_by_right(&2), None);
     /// ```
+    ///
+    /// [`shift_remove_by_right`]: BiIndexMap::shift_remove_by_right
     pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
     where
         R: Borrow<Q>,
         Q: Eq + Hash + ?Sized,
     {
-        self.right2left.remove(Wrapper::wrap(right)).map(|left_rc| {
-            // unwrap is safe because we know left2right contains the key (it's a bimap)
-            let right_rc = self.left2right.remove(&left_rc).unwrap();
-            // at this point we can safely unwrap because the other pointers are gone
-            (
-                Rc::try_unwrap(left_rc.0).ok().unwrap(),
-                Rc::try_unwrap(right_rc.0).ok().unwrap(),
-            )
-        })
+        self.shift_remove_by_right(right)
+    }
+
+    /// Removes the left-right pair corresponding to the given left value and
+    /// shifts all pairs after it back by one position, preserving their
+    /// order.
+    ///
+    /// This takes time proportional to the number of pairs after the removed
+    /// one.
+    ///
+    /// Returns the previous left-right pair if the map contained the left
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// assert_eq!(bimap.shift_remove_by_left(&'b'), Some(('b', 2)));
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'c', &3), (&'d', &4)]);
+    /// ```
+    pub fn shift_remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (index, left_rc, right_rc) = self.left2right.shift_remove_full(Wrapper::wrap(left))?;
+        self.bump_generation();
+        // the pair has the same index in both maps, so removing it from
+        // right2left moves the same pairs as in left2right
+        self.right2left.shift_remove_index(index);
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Removes the left-right pair corresponding to the given left value by
+    /// swapping it with the last pair, which takes its position.
+    ///
+    /// This takes constant time but changes the order of the pairs; use
+    /// [`shift_remove_by_left`](BiIndexMap::shift_remove_by_left) to preserve
+    /// it.
+    ///
+    /// Returns the previous left-right pair if the map contained the left
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// assert_eq!(bimap.swap_remove_by_left(&'b'), Some(('b', 2)));
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'d', &4), (&'c', &3)]);
+    /// ```
+    pub fn swap_remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (index, left_rc, right_rc) = self.left2right.swap_remove_full(Wrapper::wrap(left))?;
+        self.bump_generation();
+        // the pair has the same index in both maps, so removing it from
+        // right2left moves the same pairs as in left2right
+        self.right2left.swap_remove_index(index);
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value and
+    /// shifts all pairs after it back by one position, preserving their
+    /// order.
+    ///
+    /// This takes time proportional to the number of pairs after the removed
+    /// one.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// assert_eq!(bimap.shift_remove_by_right(&2), Some(('b', 2)));
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'c', &3), (&'d', &4)]);
+    /// ```
+    pub fn shift_remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (index, right_rc, left_rc) = self.right2left.shift_remove_full(Wrapper::wrap(right))?;
+        self.bump_generation();
+        // the pair has the same index in both maps, so removing it from
+        // left2right moves the same pairs as in right2left
+        self.left2right.shift_remove_index(index);
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value by
+    /// swapping it with the last pair, which takes its position.
+    ///
+    /// This takes constant time but changes the order of the pairs; use
+    /// [`shift_remove_by_right`](BiIndexMap::shift_remove_by_right) to preserve
+    /// it.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap = BiIndexMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.insert('d', 4);
+    ///
+    /// assert_eq!(bimap.swap_remove_by_right(&2), Some(('b', 2)));
+    /// let pairs: Vec<_> = bimap.iter().collect();
+    /// assert_eq!(pairs, [(&'a', &1), (&'d', &4), (&'c', &3)]);
+    /// ```
+    pub fn swap_remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let (index, right_rc, left_rc) = self.right2left.swap_remove_full(Wrapper::wrap(right))?;
+        self.bump_generation();
+        // the pair has the same index in both maps, so removing it from
+        // left2right moves the same pairs as in right2left
+        self.left2right.swap_remove_index(index);
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Rc::try_unwrap(left_rc.0).ok().unwrap(),
+            Rc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
     }
 
     /// Inserts the given left-right pair into the bimap.
@@ -522,6 +1041,11 @@ where
     /// of the bimap! This is because of the invariant that each left value
     /// maps to exactly one right value and vice versa.
     ///
+    /// The pair is always inserted at the end of the bimap. Any overwritten
+    /// pairs are removed as if by [`shift_remove_by_left`], so the remaining
+    /// pairs keep their order, and a pair that is overwritten with new values
+    /// moves to the end.
+    ///
     /// # Examples
     ///
     /// ```
@@ -559,6 +1083,8 @@ where
     /// assert_eq!(bimap.insert('a', 2), Overwritten::Pair('a', 2));
     /// assert_eq!(bimap.len(), 1); // {'a' <> 2}
     /// ```
+    ///
+    /// [`shift_remove_by_left`]: BiIndexMap::shift_remove_by_left
     pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
         let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
             (None, None) => Overwritten::Neither,
@@ -578,11 +1104,70 @@ where
         retval
     }
 
+    /// Inserts the given left-right pair into the bimap and returns its index
+    /// along with any overwritten pairs.
+    ///
+    /// If the bimap already contains exactly this pair, it is left where it
+    /// is: its current index is returned together with
+    /// `Overwritten::Unchanged(left, right)`, and the bimap is unchanged.
+    /// Otherwise this behaves like [`insert`] and the pair is added at the
+    /// end.
+    ///
+    /// This makes `insert_full` suitable for interning values, since looking
+    /// up the index of a value after inserting it needs no second lookup.
+    ///
+    /// # Index stability
+    ///
+    /// Inserting a pair never changes the index of a pair that it doesn't
+    /// overwrite. The index of a pair only changes when:
+    ///
+    /// - a pair before it is removed with [`shift_remove_by_left`],
+    ///   [`remove_by_left`], [`retain`] or their right-hand counterparts, or is
+    ///   overwritten by an insert, which shifts it down by one per removed
+    ///   pair;
+    /// - it is the last pair and another pair is removed with
+    ///   [`swap_remove_by_left`] or [`swap_remove_by_right`], which moves it
+    ///   into the gap;
+    /// - the bimap is reordered with `sort_by_left`, `sort_by_right`,
+    ///   `sort_by`, `reverse`, `swap_indices` or `move_index`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiIndexMap, Overwritten};
+    ///
+    /// let mut interner = BiIndexMap::new();
+    /// assert_eq!(interner.insert_full("foo", 'f'), (0, Overwritten::Neither));
+    /// assert_eq!(interner.insert_full("bar", 'b'), (1, Overwritten::Neither));
+    ///
+    /// // re-inserting an existing pair keeps its index
+    /// assert_eq!(interner.insert_full("foo", 'f'), (0, Overwritten::Unchanged("foo", 'f')));
+    ///
+    /// // overwriting moves the pair to the end
+    /// assert_eq!(interner.insert_full("foo", 'o'), (1, Overwritten::Left("foo", 'f')));
+    /// ```
+    ///
+    /// [`insert`]: BiIndexMap::insert
+    /// [`shift_remove_by_left`]: BiIndexMap::shift_remove_by_left
+    /// [`remove_by_left`]: BiIndexMap::remove_by_left
+    /// [`retain`]: BiIndexMap::retain
+    /// [`swap_remove_by_left`]: BiIndexMap::swap_remove_by_left
+    /// [`swap_remove_by_right`]: BiIndexMap::swap_remove_by_right
+    pub fn insert_full(&mut self, left: L, right: R) -> (usize, Overwritten<L, R>) {
+        if let Some((index, _, r)) = self.left2right.get_full(Wrapper::wrap(&left)) {
+            if *r.0 == right {
+                return (index, Overwritten::Unchanged(left, right));
+            }
+        }
+        let retval = self.insert(left, right);
+        (self.len() - 1, retval)
+    }
+
     /// Inserts the given left-right pair into the bimap without overwriting any
     /// existing values.
     ///
     /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
-    /// If either value exists in the map, `Err((left, right)` is returned
+    /// If either value exists in the map, `Err((left, right))` is returned
     /// with the attempted left-right pair and the map is unchanged.
     ///
     /// # Examples
@@ -608,7 +1193,8 @@ where
     /// Retains only the elements specified by the predicate.
     ///
     /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
-    /// &r)` returns `false`.
+    /// &r)` returns `false`. The pairs are visited in insertion order, and
+    /// the retained pairs keep their order.
     ///
     /// # Examples
     ///
@@ -629,15 +1215,88 @@ where
     where
         F: FnMut(&L, &R) -> bool,
     {
+        self.bump_generation();
         let mut f = f;
-        let right2left = &mut self.right2left;
-        self.left2right.retain(|l, r| {
-            let to_retain = f(&l.0, &r.0);
-            if !to_retain {
-                right2left.remove(r);
-            }
-            to_retain
-        });
+        self.left2right.retain(|l, r| f(&l.0, &r.0));
+        // retain the same pairs in right2left, which keeps both maps in the
+        // same order
+        let left2right = &self.left2right;
+        self.right2left.retain(|_, l| left2right.contains_key(l));
+    }
+
+    /// Sorts the pairs of the bimap with the given comparison function, which
+    /// is passed the left and right values of two pairs.
+    ///
+    /// The sort is stable, so pairs that compare equal keep their relative
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = vec![("bb", 1), ("a", 2), ("cc", 3), ("d", 4)]
+    ///     .into_iter()
+    ///     .collect();
+    /// // shortest left values first, then descending right values
+    /// bimap.sort_by(|l1, r1, l2, r2| l1.len().cmp(&l2.len()).then(r2.cmp(r1)));
+    /// let lefts: Vec<_> = bimap.left_values().copied().collect();
+    /// assert_eq!(lefts, ["d", "a", "cc", "bb"]);
+    /// ```
+    pub fn sort_by<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&L, &R, &L, &R) -> Ordering,
+    {
+        let mut cmp = cmp;
+        self.left2right
+            .sort_by(|l1, r1, l2, r2| cmp(&l1.0, &r1.0, &l2.0, &r2.0));
+        // bring right2left into the new order of left2right
+        let left2right = &self.left2right;
+        self.right2left
+            .sort_by_cached_key(|_, l| left2right.get_index_of(l));
+        self.bump_generation();
+    }
+
+    /// Clones the bimap into a new bimap with as little capacity as its
+    /// pairs need, keeping their order.
+    ///
+    /// The default [`clone`](Clone::clone) keeps the capacity of the
+    /// original, so that the clone can grow back to the same size without
+    /// reallocating. For a bimap that used to be much larger than it is now,
+    /// that capacity is mostly unused memory, which adds up when a snapshot
+    /// is kept around for a long time. `clone_compact` allocates only what
+    /// `len()` pairs need instead, at the cost of reallocating once the
+    /// clone grows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiIndexMap;
+    ///
+    /// let mut bimap: BiIndexMap<_, _> = (0..1000).map(|i| (i, -i)).collect();
+    /// bimap.retain(|&l, _| l < 10);
+    ///
+    /// let compact = bimap.clone_compact();
+    /// assert_eq!(compact, bimap);
+    /// assert!(compact.capacity() < 100);
+    /// assert!(bimap.clone().capacity() >= 1000);
+    /// ```
+    pub fn clone_compact(&self) -> Self
+    where
+        L: Clone,
+        R: Clone,
+        LS: Clone,
+        RS: Clone,
+    {
+        let mut new_bimap = BiIndexMap::with_capacity_and_hashers(
+            self.len(),
+            self.left2right.hasher().clone(),
+            self.right2left.hasher().clone(),
+        );
+        for (l, r) in self.iter() {
+            new_bimap.insert_unchecked(l.clone(), r.clone());
+        }
+        new_bimap
     }
 
     /// Inserts the given left-right pair into the bimap without checking if the
@@ -647,6 +1306,7 @@ where
         let right = Ref(Rc::new(right));
         self.left2right.insert(left.clone(), right.clone());
         self.right2left.insert(right, left);
+        self.bump_generation();
     }
 }
 
@@ -712,6 +1372,7 @@ where
         BiIndexMap {
             left2right: IndexMap::default(),
             right2left: IndexMap::default(),
+            generation: 0,
         }
     }
 }
@@ -737,14 +1398,11 @@ where
         I: IntoIterator<Item = (L, R)>,
     {
         let iter = iter.into_iter();
-        let mut bimap = match iter.size_hint() {
-            (lower, None) => {
-                BiIndexMap::with_capacity_and_hashers(lower, LS::default(), RS::default())
-            }
-            (_, Some(upper)) => {
-                BiIndexMap::with_capacity_and_hashers(upper, LS::default(), RS::default())
-            }
-        };
+        // only the lower bound is reserved, since an iterator like
+        // `(0..usize::MAX).filter(..)` may yield far fewer pairs than its upper
+        // bound, which could overflow the capacity
+        let mut bimap =
+            BiIndexMap::with_capacity_and_hashers(iter.size_hint().0, LS::default(), RS::default());
         for (left, right) in iter {
             bimap.insert(left, right);
         }
@@ -956,6 +1614,26 @@ mod tests {
         bimap.remove_by_right(&2);
     }
 
+    #[test]
+    fn clone_compact() {
+        let mut bimap: BiIndexMap<_, _> = (0..1000).map(|i| (i, -i)).collect();
+        bimap.retain(|&l, _| l % 100 == 0);
+        let capacity = bimap.capacity();
+
+        let compact = bimap.clone_compact();
+        assert_eq!(compact, bimap);
+        assert!(compact.iter().eq(bimap.iter()));
+        assert!(compact.capacity() >= compact.len());
+        assert!(compact.capacity() < capacity / 10);
+        assert_eq!(bimap.capacity(), capacity);
+        assert!(bimap.clone().capacity() >= capacity);
+
+        // the clone is deep
+        let mut compact = compact;
+        compact.insert(0, 1);
+        assert_eq!(bimap.get_by_left(&0), Some(&0));
+    }
+
     #[test]
     fn debug() {
         let mut bimap = BiIndexMap::new();
@@ -1108,6 +1786,20 @@ mod tests {
         assert!(bimap.capacity() >= 10);
     }
 
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn with_capacity_overflow() {
+        BiIndexMap::<char, i32>::with_capacity(usize::MAX);
+    }
+
+    #[test]
+    fn from_iter_huge_size_hint() {
+        let iter = (0..usize::MAX).take_while(|&i| i < 3).map(|i| (i, i));
+        assert_eq!(iter.size_hint(), (0, Some(usize::MAX)));
+        let bimap: BiIndexMap<usize, usize> = iter.collect();
+        assert_eq!(bimap.len(), 3);
+    }
+
     #[test]
     fn shrink_to_fit() {
         let mut bimap = BiIndexMap::<char, i32>::with_capacity(100);
@@ -1218,4 +1910,165 @@ mod tests {
         assert_eq!(bimap.len(), 1);
         assert_eq!(i, 3);
     }
+
+    /// Returns the pairs of the bimap in iteration order, checking that both
+    /// maps agree on it.
+    fn pairs(bimap: &BiIndexMap<char, i32>) -> Vec<(char, i32)> {
+        for (index, (l, r)) in bimap.iter().enumerate() {
+            assert_eq!(bimap.get_index(index), Some((l, r)));
+            assert_eq!(bimap.get_index_of_left(l), Some(index));
+            assert_eq!(bimap.get_index_of_right(r), Some(index));
+        }
+        let rights = bimap.right_values().collect::<Vec<_>>();
+        assert_eq!(rights, bimap.iter().map(|(_, r)| r).collect::<Vec<_>>());
+        bimap.iter().map(|(&l, &r)| (l, r)).collect()
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut bimap = BiIndexMap::new();
+        bimap.insert('c', 3);
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        assert_eq!(pairs(&bimap), vec![('c', 3), ('a', 1), ('b', 2)]);
+        assert_eq!(bimap.get_index(3), None);
+        assert_eq!(bimap.get_index_of_left(&'z'), None);
+        assert_eq!(bimap.get_index_of_right(&9), None);
+
+        // overwritten pairs are removed without disturbing the others, and
+        // the new pair goes to the end
+        bimap.insert('d', 4);
+        assert_eq!(bimap.insert('c', 5), Overwritten::Left('c', 3));
+        assert_eq!(pairs(&bimap), vec![('a', 1), ('b', 2), ('d', 4), ('c', 5)]);
+        assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+        assert_eq!(pairs(&bimap), vec![('d', 4), ('c', 5), ('a', 2)]);
+    }
+
+    #[test]
+    fn shift_remove() {
+        let mut bimap: BiIndexMap<char, i32> = ('a'..='f').zip(1..).collect();
+
+        assert_eq!(bimap.shift_remove_by_left(&'b'), Some(('b', 2)));
+        assert_eq!(
+            pairs(&bimap),
+            vec![('a', 1), ('c', 3), ('d', 4), ('e', 5), ('f', 6)]
+        );
+        assert_eq!(bimap.shift_remove_by_right(&4), Some(('d', 4)));
+        assert_eq!(pairs(&bimap), vec![('a', 1), ('c', 3), ('e', 5), ('f', 6)]);
+        assert_eq!(bimap.remove_by_left(&'a'), Some(('a', 1)));
+        assert_eq!(bimap.remove_by_right(&6), Some(('f', 6)));
+        assert_eq!(pairs(&bimap), vec![('c', 3), ('e', 5)]);
+
+        assert_eq!(bimap.shift_remove_by_left(&'z'), None);
+        assert_eq!(bimap.shift_remove_by_right(&0), None);
+    }
+
+    #[test]
+    fn swap_remove() {
+        let mut bimap: BiIndexMap<char, i32> = ('a'..='f').zip(1..).collect();
+
+        assert_eq!(bimap.swap_remove_by_left(&'b'), Some(('b', 2)));
+        assert_eq!(
+            pairs(&bimap),
+            vec![('a', 1), ('f', 6), ('c', 3), ('d', 4), ('e', 5)]
+        );
+        assert_eq!(bimap.swap_remove_by_right(&1), Some(('a', 1)));
+        assert_eq!(pairs(&bimap), vec![('e', 5), ('f', 6), ('c', 3), ('d', 4)]);
+        // removing the last pair doesn't move any others
+        assert_eq!(bimap.swap_remove_by_left(&'d'), Some(('d', 4)));
+        assert_eq!(pairs(&bimap), vec![('e', 5), ('f', 6), ('c', 3)]);
+
+        assert_eq!(bimap.swap_remove_by_left(&'z'), None);
+        assert_eq!(bimap.swap_remove_by_right(&0), None);
+    }
+
+    #[test]
+    fn retain_keeps_order() {
+        let mut bimap: BiIndexMap<char, i32> =
+            vec![('e', 5), ('a', 1), ('d', 4), ('b', 2), ('c', 3)]
+                .into_iter()
+                .collect();
+        bimap.retain(|_, &r| r != 1 && r != 4);
+        assert_eq!(pairs(&bimap), vec![('e', 5), ('b', 2), ('c', 3)]);
+    }
+
+    #[test]
+    fn sort() {
+        let mut bimap: BiIndexMap<char, i32> =
+            vec![('e', 1), ('a', 4), ('d', 2), ('b', 5), ('c', 3)]
+                .into_iter()
+                .collect();
+        bimap.sort_by_left();
+        assert_eq!(
+            pairs(&bimap),
+            vec![('a', 4), ('b', 5), ('c', 3), ('d', 2), ('e', 1)]
+        );
+        bimap.sort_by_right();
+        assert_eq!(
+            pairs(&bimap),
+            vec![('e', 1), ('d', 2), ('c', 3), ('a', 4), ('b', 5)]
+        );
+        // odd rights first, ties keep their current order
+        bimap.sort_by(|_, r1, _, r2| (r2 % 2).cmp(&(r1 % 2)));
+        assert_eq!(
+            pairs(&bimap),
+            vec![('e', 1), ('c', 3), ('b', 5), ('d', 2), ('a', 4)]
+        );
+        bimap.reverse();
+        assert_eq!(
+            pairs(&bimap),
+            vec![('a', 4), ('d', 2), ('b', 5), ('c', 3), ('e', 1)]
+        );
+    }
+
+    #[test]
+    fn swap_and_move_indices() {
+        let mut bimap: BiIndexMap<char, i32> = vec![('a', 1), ('b', 2), ('c', 3), ('d', 4)]
+            .into_iter()
+            .collect();
+        bimap.swap_indices(1, 3);
+        assert_eq!(pairs(&bimap), vec![('a', 1), ('d', 4), ('c', 3), ('b', 2)]);
+        bimap.move_index(2, 0);
+        assert_eq!(pairs(&bimap), vec![('c', 3), ('a', 1), ('d', 4), ('b', 2)]);
+        bimap.move_index(0, 3);
+        assert_eq!(pairs(&bimap), vec![('a', 1), ('d', 4), ('b', 2), ('c', 3)]);
+        // moving to the same index is a no-op
+        bimap.move_index(1, 1);
+        assert_eq!(pairs(&bimap), vec![('a', 1), ('d', 4), ('b', 2), ('c', 3)]);
+    }
+
+    #[test]
+    fn insert_full() {
+        let mut bimap = BiIndexMap::new();
+        assert_eq!(bimap.insert_full('a', 1), (0, Overwritten::Neither));
+        assert_eq!(bimap.insert_full('b', 2), (1, Overwritten::Neither));
+        assert_eq!(bimap.get_full_by_left(&'a'), Some((0, &'a', &1)));
+        assert_eq!(bimap.get_full_by_right(&2), Some((1, &'b', &2)));
+
+        // re-inserting an existing pair returns its index and changes nothing
+        let generation = bimap.generation();
+        assert_eq!(
+            bimap.insert_full('a', 1),
+            (0, Overwritten::Unchanged('a', 1))
+        );
+        assert_eq!(bimap.generation(), generation);
+        assert_eq!(pairs(&bimap), vec![('a', 1), ('b', 2)]);
+
+        // unrelated inserts don't move existing pairs
+        for (i, l) in ('c'..='z').enumerate() {
+            assert_eq!(
+                bimap.insert_full(l, i as i32 + 3),
+                (i + 2, Overwritten::Neither)
+            );
+            assert_eq!(bimap.get_full_by_left(&'a'), Some((0, &'a', &1)));
+            assert_eq!(bimap.get_full_by_right(&2), Some((1, &'b', &2)));
+        }
+
+        // overwriting removes the old pair and appends the new one
+        assert_eq!(bimap.insert_full('a', 100), (25, Overwritten::Left('a', 1)));
+        assert_eq!(bimap.get_full_by_right(&2), Some((0, &'b', &2)));
+        assert_eq!(bimap.get_full_by_left(&'a'), Some((25, &'a', &100)));
+        assert_eq!(bimap.get_full_by_right(&1), None);
+        pairs(&bimap);
+    }
 }
@@ -0,0 +1,331 @@
+//! A read-only view that resolves lookups across several `BiHashMap`s.
+//!
+//! A [`LayeredBiMap`] holds references to bimaps called layers, ordered from
+//! highest to lowest priority. Each lookup checks the layers in order and
+//! returns the first match, so earlier layers override later ones. This is
+//! the usual shape of configuration overrides: a small bimap of overrides in
+//! front of a large primary bimap.
+//!
+//! Each lookup is resolved independently, so the layers together don't
+//! necessarily form a bijection. For example, if an override maps `'a'` to
+//! `2` and the primary bimap maps `'a'` to `1`, looking up `'a'` by left
+//! gives `2`, while looking up `1` by right still gives `'a'` from the
+//! primary bimap.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{BiHashMap, LayeredBiMap};
+//!
+//! let mut primary = BiHashMap::new();
+//! primary.insert("red", 0xff0000);
+//! primary.insert("green", 0x00ff00);
+//!
+//! let mut overrides = BiHashMap::new();
+//! overrides.insert("red", 0xee0000);
+//!
+//! let mut colors = LayeredBiMap::new();
+//! colors.push(&overrides);
+//! colors.push(&primary);
+//!
+//! assert_eq!(colors.get_by_left(&"red"), Some(&0xee0000));
+//! assert_eq!(colors.get_by_left(&"green"), Some(&0x00ff00));
+//! assert_eq!(colors.get_by_right(&0xee0000), Some(&"red"));
+//! assert_eq!(colors.get_by_left(&"blue"), None);
+//! ```
+
+use crate::BiHashMap;
+use std::{
+    borrow::Borrow,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::FromIterator,
+};
+
+/// A read-only stack of `BiHashMap`s whose lookups return the first match.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::layered
+pub struct LayeredBiMap<'a, L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    layers: Vec<&'a BiHashMap<L, R, LS, RS>>,
+}
+
+impl<'a, L, R, LS, RS> LayeredBiMap<'a, L, R, LS, RS> {
+    /// Creates a `LayeredBiMap` without any layers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::LayeredBiMap;
+    ///
+    /// let layered = LayeredBiMap::<char, i32>::new();
+    /// assert!(layered.layers().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        LayeredBiMap { layers: Vec::new() }
+    }
+
+    /// Adds a layer with a lower priority than all existing layers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LayeredBiMap};
+    ///
+    /// let mut first = BiHashMap::new();
+    /// first.insert('a', 1);
+    /// let mut second = BiHashMap::new();
+    /// second.insert('a', 2);
+    ///
+    /// let mut layered = LayeredBiMap::new();
+    /// layered.push(&first);
+    /// layered.push(&second);
+    /// assert_eq!(layered.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn push(&mut self, layer: &'a BiHashMap<L, R, LS, RS>) {
+        self.layers.push(layer);
+    }
+
+    /// Returns the layers, from highest to lowest priority.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LayeredBiMap};
+    ///
+    /// let primary = BiHashMap::<char, i32>::new();
+    /// let mut layered = LayeredBiMap::new();
+    /// layered.push(&primary);
+    /// assert_eq!(layered.layers().len(), 1);
+    /// ```
+    pub fn layers(&self) -> &[&'a BiHashMap<L, R, LS, RS>] {
+        &self.layers
+    }
+}
+
+impl<'a, L, R, LS, RS> LayeredBiMap<'a, L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    /// Returns a reference to the right value corresponding to the given left
+    /// value in the first layer that contains it.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LayeredBiMap};
+    ///
+    /// let mut primary = BiHashMap::new();
+    /// primary.insert('a', 1);
+    /// primary.insert('b', 2);
+    /// let mut overrides = BiHashMap::new();
+    /// overrides.insert('b', 3);
+    ///
+    /// let layered = vec![&overrides, &primary].into_iter().collect::<LayeredBiMap<_, _>>();
+    /// assert_eq!(layered.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(layered.get_by_left(&'b'), Some(&3));
+    /// assert_eq!(layered.get_by_left(&'c'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&'a R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.layers.iter().find_map(|layer| layer.get_by_left(left))
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value in the first layer that contains it.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LayeredBiMap};
+    ///
+    /// let mut primary = BiHashMap::new();
+    /// primary.insert('a', 1);
+    /// primary.insert('b', 2);
+    /// let mut overrides = BiHashMap::new();
+    /// overrides.insert('c', 2);
+    ///
+    /// let layered = vec![&overrides, &primary].into_iter().collect::<LayeredBiMap<_, _>>();
+    /// assert_eq!(layered.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(layered.get_by_right(&2), Some(&'c'));
+    /// assert_eq!(layered.get_by_right(&3), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&'a L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.layers
+            .iter()
+            .find_map(|layer| layer.get_by_right(right))
+    }
+
+    /// Returns `true` if any layer contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LayeredBiMap};
+    ///
+    /// let mut primary = BiHashMap::new();
+    /// primary.insert('a', 1);
+    /// let layered = vec![&primary].into_iter().collect::<LayeredBiMap<_, _>>();
+    /// assert!(layered.contains_left(&'a'));
+    /// assert!(!layered.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.layers.iter().any(|layer| layer.contains_left(left))
+    }
+
+    /// Returns `true` if any layer contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiHashMap, LayeredBiMap};
+    ///
+    /// let mut primary = BiHashMap::new();
+    /// primary.insert('a', 1);
+    /// let layered = vec![&primary].into_iter().collect::<LayeredBiMap<_, _>>();
+    /// assert!(layered.contains_right(&1));
+    /// assert!(!layered.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.layers.iter().any(|layer| layer.contains_right(right))
+    }
+}
+
+impl<'a, L, R, LS, RS> Clone for LayeredBiMap<'a, L, R, LS, RS> {
+    fn clone(&self) -> Self {
+        LayeredBiMap {
+            layers: self.layers.clone(),
+        }
+    }
+}
+
+impl<'a, L, R, LS, RS> fmt::Debug for LayeredBiMap<'a, L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.layers.iter()).finish()
+    }
+}
+
+impl<'a, L, R, LS, RS> Default for LayeredBiMap<'a, L, R, LS, RS> {
+    fn default() -> Self {
+        LayeredBiMap::new()
+    }
+}
+
+impl<'a, L, R, LS, RS> FromIterator<&'a BiHashMap<L, R, LS, RS>>
+    for LayeredBiMap<'a, L, R, LS, RS>
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a BiHashMap<L, R, LS, RS>>,
+    {
+        LayeredBiMap {
+            layers: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, L, R, LS, RS> Extend<&'a BiHashMap<L, R, LS, RS>> for LayeredBiMap<'a, L, R, LS, RS> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a BiHashMap<L, R, LS, RS>>,
+    {
+        self.layers.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let layered = LayeredBiMap::<char, i32>::new();
+        assert_eq!(layered.get_by_left(&'a'), None);
+        assert_eq!(layered.get_by_right(&1), None);
+        assert!(!layered.contains_left(&'a'));
+        assert!(!layered.contains_right(&1));
+    }
+
+    #[test]
+    fn priority() {
+        let mut low = BiHashMap::new();
+        low.insert('a', 1);
+        low.insert('b', 2);
+        low.insert('c', 3);
+        let mut mid = BiHashMap::new();
+        mid.insert('b', 20);
+        mid.insert('d', 3);
+        let mut high = BiHashMap::new();
+        high.insert('b', 200);
+
+        let mut layered = LayeredBiMap::new();
+        layered.extend(vec![&high, &mid, &low]);
+        assert_eq!(layered.get_by_left(&'a'), Some(&1));
+        assert_eq!(layered.get_by_left(&'b'), Some(&200));
+        assert_eq!(layered.get_by_left(&'c'), Some(&3));
+        assert_eq!(layered.get_by_left(&'d'), Some(&3));
+        // right lookups resolve independently of left lookups
+        assert_eq!(layered.get_by_right(&3), Some(&'d'));
+        assert_eq!(layered.get_by_right(&2), Some(&'b'));
+        assert!(layered.contains_right(&20));
+    }
+
+    #[test]
+    fn outlives_view() {
+        let mut primary = BiHashMap::new();
+        primary.insert(String::from("a"), String::from("b"));
+        let found = {
+            let layered = vec![&primary].into_iter().collect::<LayeredBiMap<_, _>>();
+            layered.get_by_left("a")
+        };
+        assert_eq!(found.map(String::as_str), Some("b"));
+    }
+
+    #[test]
+    fn debug() {
+        let mut primary = BiHashMap::new();
+        primary.insert('a', 1);
+        let layered = vec![&primary, &primary]
+            .into_iter()
+            .collect::<LayeredBiMap<_, _>>();
+        assert_eq!(format!("{:?}", layered), "[{'a' <> 1}, {'a' <> 1}]");
+    }
+}
@@ -79,8 +79,12 @@
 //!
 //! This is especially important when dealing with types that can be equal while
 //! having different data. Unlike a `HashMap` or `BTreeMap`, which [doesn't
-//! update an equal key upon insertion], a bimap updates both the left values
-//! and the right values.
+//! update an equal key upon insertion], a bimap replaces the stored pair
+//! whenever it is overwritten, so an equal left value with different data
+//! takes the place of the old one. The exception is a pair that is equal on
+//! both sides to a pair already in the bimap: nothing is overwritten, the
+//! stored pair is kept as it was, and the given pair is handed back as
+//! [`Overwritten::Unchanged`].
 //!
 //! ```
 //! use bimap::{BiMap, Overwritten};
@@ -149,6 +153,14 @@
 //!     bimap.get_by_right(&100).unwrap().unimportant,
 //!     foo2.unimportant
 //! );
+//!
+//! // inserting a pair that is equal on both sides keeps the stored pair
+//! let overwritten = bimap.insert(foo1, 100);
+//! assert!(matches!(overwritten, Overwritten::Unchanged(foo, 100) if foo.unimportant == 1));
+//! assert_eq!(
+//!     bimap.get_by_right(&100).unwrap().unimportant,
+//!     foo2.unimportant
+//! );
 //! ```
 //!
 //! Note that the `FromIterator` and `Extend` implementations for both
@@ -169,12 +181,125 @@
 //! assert_eq!(bimap.get_by_left(&'c'), Some(&2));
 //! ```
 //!
+//! The methods that can overwrite pairs handle them as follows, identically
+//! for every kind of bimap:
+//!
+//! - [`insert`] removes any pairs that conflict with the new pair and returns
+//!   them as an [`Overwritten`].
+//! - [`insert_no_overwrite`] never removes pairs; it returns the rejected pair
+//!   instead.
+//! - [`update_by_left`] removes the pair that contained the new right value and
+//!   only returns the old right value. Use [`update_by_left_detailed`] to get
+//!   the removed pair back as an [`Overwritten`].
+//! - `Extend` and `FromIterator` call `insert` for every pair and discard the
+//!   results. Use [`extend_detailed`] to get an [`Overwritten`] for every pair.
+//!
+//! ## Newtype values
+//!
+//! Every method that looks up or removes a pair accepts any borrowed form of
+//! the values, just like the maps of the standard library. When the left or
+//! right values are newtypes, implementing [`Borrow`] for the inner type lets
+//! these methods take the inner value directly, so there's no need to wrap it
+//! at every call site. Inserting still requires the newtype, which keeps the
+//! two sides of the bimap from being mixed up.
+//!
+//! ```
+//! use bimap::BiMap;
+//! use std::borrow::Borrow;
+//!
+//! #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+//! struct UserId(u64);
+//!
+//! #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+//! struct Name(String);
+//!
+//! impl Borrow<u64> for UserId {
+//!     fn borrow(&self) -> &u64 {
+//!         &self.0
+//!     }
+//! }
+//!
+//! impl Borrow<str> for Name {
+//!     fn borrow(&self) -> &str {
+//!         &self.0
+//!     }
+//! }
+//!
+//! let mut users = BiMap::new();
+//! users.insert(UserId(1), Name("alice".to_string()));
+//! users.insert(UserId(2), Name("bob".to_string()));
+//!
+//! assert_eq!(users.get_by_right("bob"), Some(&UserId(2)));
+//! assert!(users.contains_left(&1));
+//! assert_eq!(
+//!     users.remove_by_left(&1),
+//!     Some((UserId(1), Name("alice".to_string())))
+//! );
+//! ```
+//!
+//! `Borrow` requires `Eq`, `Hash` and `Ord` to behave the same on the newtype
+//! and on the borrowed type. The derived implementations of a newtype with a
+//! single field do, but a newtype with hand-written implementations that
+//! differ from its field's must not implement `Borrow` this way.
+//!
+//! To also insert and get back the inner values, implement [`Newtype`] for
+//! both sides and use the `_inner` methods of the [`BiMapNewtype`] trait,
+//! which wrap and unwrap the values for you.
+//!
+//! ## Generic code
+//!
+//! The [`BiMapLike`] trait covers the core operations shared by `BiHashMap`
+//! and `BiBTreeMap`, so that helpers can be written once for both kinds of
+//! bimap.
+//!
+//! ## Small bimaps
+//!
+//! [`BiVecMap`] stores its pairs in a single vector and finds them with a
+//! linear scan. It only requires `PartialEq` of its values, so it can hold
+//! types that implement neither `Hash` nor `Ord`, and for a handful of pairs
+//! it avoids the overhead of hashing or tree balancing. It implements
+//! `BiMapLike` as well and is available without the standard library.
+//!
+//! ## Static bimaps
+//!
+//! Tables that are known at compile time can be declared with the
+//! [`static_bimap!`] macro, which builds a [`StaticBiMap`] in a `static`
+//! item. Its indices are sorted during compilation, so lookups in either
+//! direction need no allocation or initialization at run time, and duplicate
+//! values are a compile error. See the [`static_map`] module for details.
+//!
+//! ## Frozen bimaps
+//!
+//! A `BiHashMap` that is no longer modified can be turned into a
+//! [`FrozenBiMap`] with [`freeze`](BiHashMap::freeze). It supports the
+//! lookup half of the API with less memory and faster lookups, and can be
+//! shared between threads. See the [`frozen`] module for details.
+//!
+//! ## Persistent bimaps
+//!
+//! When the `persistent` feature is enabled, [`PersistentBiMap`] is
+//! available. Its `insert` and `remove_*` methods return a new bimap that
+//! shares most of its memory with the old one, and cloning it takes constant
+//! time, so keeping many versions of a large mapping is cheap. See the
+//! [`persistent`] module for details.
+//!
 //! ## `no_std` compatibility
 //!
 //! This crate can be used without the standard library when the `std` feature
 //! is disabled. If you choose to do this, only `BiBTreeMap` is available, not
 //! `BiHashMap`.
 //!
+//! ## indexmap compatibility
+//!
+//! When the `indexmap` feature is enabled, [`BiIndexMap`] is available. It is
+//! backed by two [indexmap] maps, so it iterates over its pairs in insertion
+//! order and supports positional access with
+//! [`get_index`](BiIndexMap::get_index).
+//!
+//! The same feature enables [`BoundedBiMap`], an insertion-ordered bimap with
+//! a maximum length that evicts its oldest or least recently used pair to make
+//! room for new ones. See the [`bounded`] module for details.
+//!
 //! ## serde compatibility
 //!
 //! When the `serde` feature is enabled, implementations of `Serialize` and
@@ -182,13 +307,91 @@
 //! them to be serialized or deserialized painlessly. See the [`serde`] module
 //! for examples and more information.
 //!
+//! ## rkyv compatibility
+//!
+//! When the `rkyv` feature is enabled, bimaps can be archived with [rkyv] for
+//! zero-copy deserialization. See the [`rkyv`] module for
+//! examples and more information.
+//!
+//! ## borsh compatibility
+//!
+//! When the `borsh` feature is enabled, implementations of `BorshSerialize`
+//! and `BorshDeserialize` are provided for [`BiHashMap`] and [`BiBTreeMap`],
+//! with a deterministic encoding. See the [`borsh`] module for
+//! details.
+//!
+//! ## schemars compatibility
+//!
+//! When the `schemars` feature is enabled, implementations of `JsonSchema` are
+//! provided for [`BiHashMap`] and [`BiBTreeMap`], matching their serde
+//! representation. See the [`schemars`] module for details.
+//!
+//! ## speedy compatibility
+//!
+//! When the `speedy` feature is enabled, implementations of `Readable` and
+//! `Writable` are provided for [`BiHashMap`] and [`BiBTreeMap`]. See the
+//! [`speedy`] module for details.
+//!
+//! ## rayon compatibility
+//!
+//! When the `rayon` feature is enabled, bimaps can be collected from parallel
+//! iterators and iterated in parallel. See the [`rayon`] module for details.
+//!
+//! ## arbitrary compatibility
+//!
+//! When the `arbitrary` feature is enabled, implementations of `Arbitrary`
+//! are provided for [`BiHashMap`] and [`BiBTreeMap`] for use in fuzz targets.
+//! See the [`arbitrary`] module for details.
+//!
+//! ## quickcheck compatibility
+//!
+//! When the `quickcheck` feature is enabled, implementations of
+//! `quickcheck::Arbitrary` are provided for [`BiHashMap`] and [`BiBTreeMap`],
+//! including shrinking. See the [`quickcheck`] module for details.
+//!
+//! ## proptest compatibility
+//!
+//! When the `proptest` feature is enabled, the [`proptest`] module provides
+//! strategies for generating and shrinking [`BiHashMap`]s and
+//! [`BiBTreeMap`]s.
+//!
+//! ## rand compatibility
+//!
+//! When the `rand` feature is enabled, [`BiHashMap`] provides
+//! [`random_pair`](BiHashMap::random_pair),
+//! [`remove_random`](BiHashMap::remove_random) and
+//! [`sample_iter`](BiHashMap::sample_iter) for picking uniformly random
+//! pairs.
+//!
+//! ## hashbrown compatibility
+//!
+//! When the `hashbrown` feature is enabled, the [`hashbrown`] module provides
+//! a `BiHashMap` backed by [hashbrown] maps. It works without the standard
+//! library and accepts a custom allocator for its hash tables.
+//!
+//! ## Lookup statistics
+//!
+//! When the `stats` feature is enabled, [`BiHashMap`] and [`BiBTreeMap`]
+//! count their lookups by left and right value and how many of them found a
+//! pair, which helps find hot keys without instrumenting every call site.
+//! The counters are read with `lookup_stats` as a [`LookupStats`]. They are
+//! atomic, so the feature needs a target with 64-bit atomics. Without the
+//! feature, bimaps carry no counters and lookups do no extra work.
+//!
 //! [bijective map]: https://en.wikipedia.org/wiki/Bijection
 //! [doesn't update an equal key upon insertion]:
 //! https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
 //! [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
 //! [`BTreeMap`]: https://doc.rust-lang.org/std/collections/struct.BTreeMap.html
+//! [`Borrow`]: https://doc.rust-lang.org/std/borrow/trait.Borrow.html
 //! [`insert`]: BiHashMap::insert
 //! [`insert_no_overwrite`]: BiHashMap::insert_no_overwrite
+//! [`update_by_left`]: BiHashMap::update_by_left
+//! [`update_by_left_detailed`]: BiHashMap::update_by_left_detailed
+//! [`extend_detailed`]: BiHashMap::extend_detailed
+//! [rkyv]: https://docs.rs/rkyv/
+//! [hashbrown]: https://docs.rs/hashbrown/
+//! [indexmap]: https://docs.rs/indexmap/
 
 // Document everything!
 #![deny(missing_docs)]
@@ -200,20 +403,60 @@
 extern crate alloc;
 
 mod mem;
+mod stats;
+#[cfg(feature = "stats")]
+pub use stats::LookupStats;
 
 pub mod btree;
 pub use btree::BiBTreeMap;
 
+pub mod vec;
+pub use vec::BiVecMap;
+
+pub mod static_map;
+pub use static_map::StaticBiMap;
+
+pub mod transaction;
+pub use transaction::Transaction;
+
 #[cfg(all(feature = "indexmap", feature = "std"))]
 pub mod index;
 #[cfg(all(feature = "indexmap", feature = "std"))]
 pub use index::BiIndexMap;
 
+#[cfg(all(feature = "indexmap", feature = "std"))]
+pub mod bounded;
+#[cfg(all(feature = "indexmap", feature = "std"))]
+pub use bounded::{BoundedBiMap, EvictionPolicy};
+
 #[cfg(feature = "std")]
 pub mod hash;
 #[cfg(feature = "std")]
 pub use hash::BiHashMap;
 
+#[cfg(feature = "std")]
+pub mod frozen;
+#[cfg(feature = "std")]
+pub use frozen::FrozenBiMap;
+
+#[cfg(feature = "std")]
+pub mod layered;
+#[cfg(feature = "std")]
+pub use layered::LayeredBiMap;
+
+#[cfg(feature = "hashbrown")]
+pub mod hashbrown;
+
+#[cfg(feature = "persistent")]
+pub mod persistent;
+#[cfg(feature = "persistent")]
+pub use persistent::PersistentBiMap;
+
+#[cfg(feature = "concurrent")]
+pub mod sharded;
+#[cfg(feature = "concurrent")]
+pub use sharded::ShardedBiMap;
+
 /// Type definition for convenience and compatibility with older versions of
 /// this crate.
 #[cfg(feature = "std")]
@@ -227,9 +470,58 @@ pub type BiMap<L, R> = BiBTreeMap<L, R>;
 #[cfg(all(feature = "serde", feature = "std"))]
 pub mod serde;
 
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+
+#[cfg(feature = "borsh")]
+pub mod borsh;
+
+#[cfg(feature = "schemars")]
+pub mod schemars;
+
+#[cfg(feature = "speedy")]
+pub mod speedy;
+
+#[cfg(feature = "rayon")]
+pub mod rayon;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
 /// The previous left-right pairs, if any, that were overwritten by a call to
 /// the [`insert`](BiHashMap::insert) method of a bimap.
+///
+/// The evicted pairs are moved out of the bimap, not cloned. Use
+/// [`did_overwrite`](Overwritten::did_overwrite) to check whether anything
+/// was evicted, or iterate over the `Overwritten` to handle each evicted pair.
+///
+/// `insert` itself is deliberately not `#[must_use]`: most inserts add a new
+/// pair without overwriting anything, and ignoring the result is fine then.
+/// Where pairs must never be evicted silently, use
+/// [`insert_no_overwrite`](BiHashMap::insert_no_overwrite), which hands the
+/// rejected pair back instead of overwriting anything.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiMap, Overwritten};
+///
+/// let mut bimap = BiMap::new();
+/// bimap.insert('a', 1);
+/// bimap.insert('b', 2);
+///
+/// let overwritten = bimap.insert('a', 2);
+/// assert_eq!(overwritten, Overwritten::Both(('a', 1), ('b', 2)));
+/// assert_eq!(overwritten.into_pairs().count(), 2);
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
 pub enum Overwritten<L, R> {
     /// Neither the left nor the right value previously existed in the bimap.
     Neither,
@@ -242,10 +534,20 @@ pub enum Overwritten<L, R> {
     /// is returned.
     Right(L, R),
 
-    /// The left-right pair already existed in the bimap, and the previous
-    /// left-right pair is returned.
+    /// The left-right pair already existed in the bimap and was removed and
+    /// inserted again, and the previous left-right pair is returned.
+    ///
+    /// Only the bimaps that keep their pairs in insertion order, `BiIndexMap`,
+    /// `BiVecMap` and `BoundedBiMap`, return this variant, since inserting an
+    /// existing pair moves it to the back. The other bimaps leave the pair
+    /// where it is and return [`Unchanged`](Overwritten::Unchanged) instead.
     Pair(L, R),
 
+    /// The left-right pair already existed in the bimap and was left as it
+    /// was, so nothing was overwritten. The given left-right pair is
+    /// returned.
+    Unchanged(L, R),
+
     /// Both the left and the right value existed in the bimap, but as part of
     /// separate pairs. The first tuple is the left-right pair of the
     /// previous left value, and the second is the left-right pair of the
@@ -257,7 +559,8 @@ impl<L, R> Overwritten<L, R> {
     /// Returns a boolean indicating if the `Overwritten` variant implies any
     /// values were overwritten.
     ///
-    /// This method is `true` for all variants other than `Neither`.
+    /// This method is `true` for all variants other than `Neither` and
+    /// `Unchanged`.
     ///
     /// # Examples
     ///
@@ -266,23 +569,867 @@ impl<L, R> Overwritten<L, R> {
     ///
     /// let mut bimap = BiMap::new();
     /// assert!(!bimap.insert('a', 1).did_overwrite());
+    /// assert!(!bimap.insert('a', 1).did_overwrite());
     /// assert!(bimap.insert('a', 2).did_overwrite());
     /// ```
+    #[must_use]
     pub fn did_overwrite(&self) -> bool {
-        !matches!(self, Overwritten::Neither)
+        !matches!(self, Overwritten::Neither | Overwritten::Unchanged(..))
+    }
+
+    /// Creates an iterator over references to the overwritten left-right
+    /// pairs: none for `Neither` and `Unchanged`, two for `Both` and one
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiMap, Overwritten};
+    ///
+    /// let mut bimap = BiMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let overwritten = bimap.insert('a', 2);
+    /// assert_eq!(overwritten.pairs().collect::<Vec<_>>(), [(&'a', &1), (&'b', &2)]);
+    /// ```
+    #[must_use = "iterators are lazy and do nothing unless consumed"]
+    pub fn pairs(&self) -> Pairs<'_, L, R> {
+        let (first, second) = match self {
+            Overwritten::Neither | Overwritten::Unchanged(..) => (None, None),
+            Overwritten::Left(l, r) | Overwritten::Right(l, r) | Overwritten::Pair(l, r) => {
+                (Some((l, r)), None)
+            }
+            Overwritten::Both((l1, r1), (l2, r2)) => (Some((l1, r1)), Some((l2, r2))),
+        };
+        Pairs {
+            inner: first.into_iter().chain(second),
+        }
+    }
+
+    /// Creates an iterator that moves the overwritten left-right pairs out of
+    /// the `Overwritten`, in the same order as [`pairs`](Overwritten::pairs).
+    ///
+    /// `Overwritten` also implements `IntoIterator`, so it can be used
+    /// directly in a `for` loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiMap;
+    ///
+    /// let mut bimap = BiMap::new();
+    /// bimap.insert(String::from("a"), 1);
+    ///
+    /// for (left, right) in bimap.insert(String::from("b"), 1) {
+    ///     // release whatever was attached to the evicted pair
+    ///     assert_eq!((left.as_str(), right), ("a", 1));
+    /// }
+    /// ```
+    #[must_use = "iterators are lazy and do nothing unless consumed"]
+    pub fn into_pairs(self) -> IntoPairs<L, R> {
+        let (first, second) = match self {
+            Overwritten::Neither | Overwritten::Unchanged(..) => (None, None),
+            Overwritten::Left(l, r) | Overwritten::Right(l, r) | Overwritten::Pair(l, r) => {
+                (Some((l, r)), None)
+            }
+            Overwritten::Both(first, second) => (Some(first), Some(second)),
+        };
+        IntoPairs {
+            inner: first.into_iter().chain(second),
+        }
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a Overwritten<L, R> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Pairs<'a, L, R>;
+
+    fn into_iter(self) -> Pairs<'a, L, R> {
+        self.pairs()
     }
 }
 
+impl<L, R> IntoIterator for Overwritten<L, R> {
+    type Item = (L, R);
+    type IntoIter = IntoPairs<L, R>;
+
+    fn into_iter(self) -> IntoPairs<L, R> {
+        self.into_pairs()
+    }
+}
+
+/// The left-right pairs that a call to `insert` would overwrite, borrowed
+/// from the bimap.
+///
+/// This is returned by the `what_would_overwrite` method of
+/// [`BiHashMap`](BiHashMap::what_would_overwrite) and
+/// [`BiBTreeMap`](BiBTreeMap::what_would_overwrite), which inspect the bimap
+/// without modifying it. Each variant means the same as for the
+/// [`Overwritten`] that `insert` would return, so all of its methods are
+/// available as well.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiMap, Overwritten};
+///
+/// let mut bimap = BiMap::new();
+/// bimap.insert('a', 1);
+/// bimap.insert('b', 2);
+///
+/// let report = bimap.what_would_overwrite(&'a', &2);
+/// assert_eq!(report, Overwritten::Both((&'a', &1), (&'b', &2)));
+/// for (l, r) in report {
+///     println!("inserting ('a', 2) would remove ({}, {})", l, r);
+/// }
+/// ```
+pub type OverwriteReport<'a, L, R> = Overwritten<&'a L, &'a R>;
+
+/// An iterator over up to two items.
+type UpToTwo<T> = core::iter::Chain<core::option::IntoIter<T>, core::option::IntoIter<T>>;
+
+/// An iterator over references to the left-right pairs of an `Overwritten`.
+///
+/// This struct is created by the [`pairs`](Overwritten::pairs) method of
+/// `Overwritten`.
+#[derive(Clone, Debug)]
+pub struct Pairs<'a, L, R> {
+    inner: UpToTwo<(&'a L, &'a R)>,
+}
+
+impl<'a, L, R> DoubleEndedIterator for Pairs<'a, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, L, R> core::iter::FusedIterator for Pairs<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Pairs<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An owning iterator over the left-right pairs of an `Overwritten`.
+///
+/// This struct is created by the [`into_pairs`](Overwritten::into_pairs)
+/// method of `Overwritten`.
+#[derive(Clone, Debug)]
+pub struct IntoPairs<L, R> {
+    inner: UpToTwo<(L, R)>,
+}
+
+impl<L, R> DoubleEndedIterator for IntoPairs<L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<L, R> core::iter::FusedIterator for IntoPairs<L, R> {}
+
+impl<L, R> Iterator for IntoPairs<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// The core operations shared by every kind of bimap, for writing code that is
+/// generic over `BiHashMap` and `BiBTreeMap`.
+///
+/// The methods behave exactly like the inherent methods of the same name,
+/// except that lookups and removals take `&L` and `&R` instead of any
+/// borrowed form of the values. The trait is named `BiMapLike` because
+/// [`BiMap`] is already the name of the default bimap type.
+///
+/// This trait is sealed and cannot be implemented outside of this crate, so
+/// that methods can be added to it without breaking changes.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use bimap::{BiBTreeMap, BiHashMap, BiMapLike};
+///
+/// fn is_consistent<L, R, M>(bimap: &M) -> bool
+/// where
+///     L: PartialEq,
+///     R: PartialEq,
+///     M: BiMapLike<L, R>,
+/// {
+///     bimap
+///         .iter()
+///         .all(|(l, r)| bimap.get_by_left(l) == Some(r) && bimap.get_by_right(r) == Some(l))
+/// }
+///
+/// fn rename<L, R, M: BiMapLike<L, R>>(bimap: &mut M, old: &L, new: L) -> bool {
+///     match bimap.remove_by_left(old) {
+///         Some((_, right)) => {
+///             bimap.insert(new, right);
+///             true
+///         }
+///         None => false,
+///     }
+/// }
+///
+/// let mut hash = BiHashMap::new();
+/// hash.insert("one", 1);
+/// assert!(rename(&mut hash, &"one", "uno"));
+/// assert_eq!(hash.get_by_right(&1), Some(&"uno"));
+/// assert!(is_consistent(&hash));
+///
+/// let mut btree = BiBTreeMap::new();
+/// btree.insert("one", 1);
+/// assert!(rename(&mut btree, &"one", "eins"));
+/// assert_eq!(btree.get_by_right(&1), Some(&"eins"));
+/// assert!(is_consistent(&btree));
+/// # }
+/// ```
+pub trait BiMapLike<L, R>: sealed::Sealed {
+    /// The iterator returned by [`iter`](BiMapLike::iter).
+    ///
+    /// This is a generic associated type, so that iterating doesn't need a
+    /// boxed iterator; it is one of the reasons for the crate's MSRV of 1.66.
+    type Iter<'a>: ExactSizeIterator<Item = (&'a L, &'a R)> + core::iter::FusedIterator
+    where
+        Self: 'a,
+        L: 'a,
+        R: 'a;
+
+    /// Returns the number of left-right pairs in the bimap.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    fn get_by_left(&self, left: &L) -> Option<&R>;
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    fn get_by_right(&self, right: &R) -> Option<&L>;
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    fn contains_left(&self, left: &L) -> bool;
+
+    /// Returns `true` if the map contains the given right value and `false`
+    /// otherwise.
+    fn contains_right(&self, right: &R) -> bool;
+
+    /// Inserts the given left-right pair into the bimap, returning any pairs
+    /// that were overwritten.
+    fn insert(&mut self, left: L, right: R) -> Overwritten<L, R>;
+
+    /// Removes the left-right pair corresponding to the given left value.
+    fn remove_by_left(&mut self, left: &L) -> Option<(L, R)>;
+
+    /// Removes the left-right pair corresponding to the given right value.
+    fn remove_by_right(&mut self, right: &R) -> Option<(L, R)>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A single-field wrapper around a value, for bimaps whose left and right
+/// values are newtypes.
+///
+/// Implementing this trait for the newtypes of both sides of a bimap enables
+/// the methods of [`BiMapNewtype`], which take and return the inner values.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::Newtype;
+///
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// struct UserId(u64);
+///
+/// impl Newtype for UserId {
+///     type Inner = u64;
+///
+///     fn from_inner(inner: u64) -> Self {
+///         UserId(inner)
+///     }
+///
+///     fn inner(&self) -> &u64 {
+///         &self.0
+///     }
+///
+///     fn into_inner(self) -> u64 {
+///         self.0
+///     }
+/// }
+///
+/// assert_eq!(UserId::from_inner(7).into_inner(), 7);
+/// ```
+pub trait Newtype {
+    /// The wrapped type.
+    type Inner;
+
+    /// Wraps the given value.
+    fn from_inner(inner: Self::Inner) -> Self;
+
+    /// Returns a reference to the wrapped value.
+    fn inner(&self) -> &Self::Inner;
+
+    /// Unwraps the value.
+    fn into_inner(self) -> Self::Inner;
+}
+
+/// Operations on bimaps of [`Newtype`]s that take and return the inner values,
+/// so that the newtypes don't have to be wrapped and unwrapped at every call
+/// site.
+///
+/// This trait is implemented for every [`BiMapLike`] bimap whose left and
+/// right types both implement `Newtype`. Each method wraps its arguments and
+/// calls the `BiMapLike` method of the same name without the `_inner` suffix.
+/// Lookups build the newtype from an owned inner value; implementing
+/// [`Borrow`] for the newtypes instead lets the inherent methods take a
+/// borrowed inner value, as described in the [crate-level documentation].
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiBTreeMap, BiMapNewtype, Newtype};
+///
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct UserId(u64);
+///
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Port(u16);
+///
+/// # impl Newtype for UserId {
+/// #     type Inner = u64;
+/// #     fn from_inner(inner: u64) -> Self { UserId(inner) }
+/// #     fn inner(&self) -> &u64 { &self.0 }
+/// #     fn into_inner(self) -> u64 { self.0 }
+/// # }
+/// # impl Newtype for Port {
+/// #     type Inner = u16;
+/// #     fn from_inner(inner: u16) -> Self { Port(inner) }
+/// #     fn inner(&self) -> &u16 { &self.0 }
+/// #     fn into_inner(self) -> u16 { self.0 }
+/// # }
+/// let mut ports = BiBTreeMap::new();
+/// ports.insert_inner(1, 8080);
+/// ports.insert(UserId(2), Port(8081));
+///
+/// assert_eq!(ports.get_by_left_inner(1), Some(&8080));
+/// assert_eq!(ports.get_by_right_inner(8081), Some(&2));
+/// assert_eq!(ports.remove_by_left_inner(1), Some((1, 8080)));
+/// assert_eq!(ports.get_by_right(&Port(8080)), None);
+/// ```
+///
+/// [`Borrow`]: core::borrow::Borrow
+/// [crate-level documentation]: crate#newtype-values
+pub trait BiMapNewtype<L, R>: BiMapLike<L, R>
+where
+    L: Newtype,
+    R: Newtype,
+{
+    /// Returns a reference to the inner right value corresponding to the
+    /// given inner left value.
+    fn get_by_left_inner<'a>(&'a self, left: L::Inner) -> Option<&'a R::Inner>
+    where
+        R: 'a,
+    {
+        self.get_by_left(&L::from_inner(left)).map(R::inner)
+    }
+
+    /// Returns a reference to the inner left value corresponding to the given
+    /// inner right value.
+    fn get_by_right_inner<'a>(&'a self, right: R::Inner) -> Option<&'a L::Inner>
+    where
+        L: 'a,
+    {
+        self.get_by_right(&R::from_inner(right)).map(L::inner)
+    }
+
+    /// Wraps the given inner values and inserts them as a left-right pair,
+    /// returning any pairs that were overwritten.
+    fn insert_inner(&mut self, left: L::Inner, right: R::Inner) -> Overwritten<L, R> {
+        self.insert(L::from_inner(left), R::from_inner(right))
+    }
+
+    /// Removes the left-right pair corresponding to the given inner left
+    /// value, returning its inner values.
+    fn remove_by_left_inner(&mut self, left: L::Inner) -> Option<(L::Inner, R::Inner)> {
+        self.remove_by_left(&L::from_inner(left))
+            .map(|(l, r)| (l.into_inner(), r.into_inner()))
+    }
+
+    /// Removes the left-right pair corresponding to the given inner right
+    /// value, returning its inner values.
+    fn remove_by_right_inner(&mut self, right: R::Inner) -> Option<(L::Inner, R::Inner)> {
+        self.remove_by_right(&R::from_inner(right))
+            .map(|(l, r)| (l.into_inner(), r.into_inner()))
+    }
+}
+
+impl<M, L, R> BiMapNewtype<L, R> for M
+where
+    M: BiMapLike<L, R>,
+    L: Newtype,
+    R: Newtype,
+{
+}
+
+/// The error returned by the `try_get_by_left` and `try_get_by_right` methods
+/// of a bimap when the requested value is not present.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiMap, KeyNotFound};
+///
+/// fn lookup(bimap: &BiMap<char, i32>) -> Result<i32, KeyNotFound> {
+///     let right = bimap.try_get_by_left(&'a')?;
+///     Ok(*right + 1)
+/// }
+///
+/// let mut bimap = BiMap::new();
+/// assert_eq!(lookup(&bimap), Err(KeyNotFound));
+/// bimap.insert('a', 1);
+/// assert_eq!(lookup(&bimap), Ok(2));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct KeyNotFound;
+
+impl core::fmt::Display for KeyNotFound {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "key not found in bimap")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyNotFound {}
+
+/// The error returned by [`BiHashMap::from_maps`] when the given maps are not
+/// inverses of each other.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MapMismatch {
+    /// The forward and reverse maps have different numbers of entries.
+    Len {
+        /// The number of entries in the forward map.
+        forward: usize,
+        /// The number of entries in the reverse map.
+        reverse: usize,
+    },
+
+    /// A right value of the forward map is missing from the reverse map.
+    MissingRight,
+
+    /// A right value of the forward map is mapped to a different left value
+    /// by the reverse map.
+    WrongLeft,
+}
+
+impl core::fmt::Display for MapMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            MapMismatch::Len { forward, reverse } => write!(
+                f,
+                "forward map has {} entries but reverse map has {}",
+                forward, reverse
+            ),
+            MapMismatch::MissingRight => {
+                write!(f, "right value of forward map is missing from reverse map")
+            }
+            MapMismatch::WrongLeft => {
+                write!(
+                    f,
+                    "reverse map maps a right value to a different left value"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MapMismatch {}
+
+/// An inconsistency between the two internal maps of a bimap, reported by
+/// `check_invariants`.
+///
+/// Every bimap stores each pair twice, once in a left-to-right map and once
+/// in a right-to-left map. The public API keeps the two maps mirror images
+/// of each other, so `check_invariants` should never find a violation; it
+/// exists to catch bugs in the crate itself, for example when fuzzing.
+///
+/// `check_invariants` borrows the values from the bimap, so `L` and `R` are
+/// typically references.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum InvariantViolation<L, R> {
+    /// The two maps have different numbers of entries.
+    Len {
+        /// The number of entries in the left-to-right map.
+        left2right: usize,
+        /// The number of entries in the right-to-left map.
+        right2left: usize,
+    },
+
+    /// A pair of the left-to-right map whose right value is missing from the
+    /// right-to-left map.
+    MissingRight {
+        /// The left value of the pair.
+        left: L,
+        /// The right value of the pair.
+        right: R,
+    },
+
+    /// A pair of the left-to-right map whose right value is mapped to a
+    /// different left value by the right-to-left map.
+    WrongLeft {
+        /// The left value of the pair.
+        left: L,
+        /// The right value of the pair.
+        right: R,
+        /// The left value the right-to-left map has for `right`.
+        found: L,
+    },
+
+    /// A pair of the right-to-left map whose left value is missing from the
+    /// left-to-right map.
+    MissingLeft {
+        /// The left value of the pair.
+        left: L,
+        /// The right value of the pair.
+        right: R,
+    },
+
+    /// A pair of the right-to-left map whose left value is mapped to a
+    /// different right value by the left-to-right map.
+    WrongRight {
+        /// The left value of the pair.
+        left: L,
+        /// The right value of the pair.
+        right: R,
+        /// The right value the left-to-right map has for `left`.
+        found: R,
+    },
+}
+
+/// The error returned by the `try_insert` method of a bimap when the left or
+/// right value of the pair is already present.
+///
+/// The error gives back the pair that was not inserted, along with
+/// references to the existing pairs it conflicts with. At least one of
+/// `left_conflict` and `right_conflict` is `Some`. If the exact pair is
+/// already in the bimap, both are `Some` and refer to the same pair.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiMap, OccupiedError};
+///
+/// let mut bimap = BiMap::new();
+/// bimap.insert('a', 1);
+///
+/// let error = bimap.try_insert('a', 2).unwrap_err();
+/// assert_eq!(error.pair, ('a', 2));
+/// assert_eq!(error.left_conflict, Some((&'a', &1)));
+/// assert_eq!(error.right_conflict, None);
+/// ```
+#[derive(Debug, Eq, PartialEq)]
+pub struct OccupiedError<'a, L, R> {
+    /// The left-right pair that was not inserted.
+    pub pair: (L, R),
+    /// The existing pair with the same left value, if any.
+    pub left_conflict: Option<(&'a L, &'a R)>,
+    /// The existing pair with the same right value, if any.
+    pub right_conflict: Option<(&'a L, &'a R)>,
+}
+
+impl<'a, L, R> core::fmt::Display for OccupiedError<'a, L, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match (self.left_conflict.is_some(), self.right_conflict.is_some()) {
+            (true, true) => write!(f, "left and right values already present in bimap"),
+            (false, true) => write!(f, "right value already present in bimap"),
+            _ => write!(f, "left value already present in bimap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, L, R> std::error::Error for OccupiedError<'a, L, R>
+where
+    L: core::fmt::Debug,
+    R: core::fmt::Debug,
+{
+}
+
+/// The error returned by the `modify_right_by_left` and
+/// `modify_left_by_right` methods of a bimap when the value could not be
+/// modified, and the reason a [`BatchOp`] failed.
+///
+/// A failed batch leaves the bimap unchanged, as does a `NotFound` error
+/// from the modify methods. A `Collision` error from the modify methods means
+/// the modified pair was dropped, since its original value no longer exists.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ModifyError {
+    /// The given value is not present in the bimap.
+    NotFound,
+
+    /// The modified value is already paired with a different value.
+    Collision,
+}
+
+impl core::fmt::Display for ModifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ModifyError::NotFound => write!(f, "value not found in bimap"),
+            ModifyError::Collision => write!(f, "modified value is already present in bimap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ModifyError {}
+
+/// A single operation in a batch passed to the `apply_batch` method of a
+/// bimap.
+///
+/// Every operation is strict: it fails rather than overwrite or skip
+/// anything, and a failing operation rejects the whole batch.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BatchOp<L, R> {
+    /// Inserts the pair, failing with [`ModifyError::Collision`] if either
+    /// value is already present.
+    Insert(L, R),
+
+    /// Removes the pair with the given left value, failing with
+    /// [`ModifyError::NotFound`] if it isn't present.
+    RemoveByLeft(L),
+
+    /// Removes the pair with the given right value, failing with
+    /// [`ModifyError::NotFound`] if it isn't present.
+    RemoveByRight(R),
+
+    /// Replaces the left value `old` with `new`, keeping its right value.
+    ///
+    /// Fails with [`ModifyError::NotFound`] if `old` isn't present, or with
+    /// [`ModifyError::Collision`] if `new` is already paired with a different
+    /// right value.
+    ReplaceLeft {
+        /// The left value to replace.
+        old: L,
+        /// The left value to replace it with.
+        new: L,
+    },
+
+    /// Replaces the right value `old` with `new`, keeping its left value.
+    ///
+    /// Fails with [`ModifyError::NotFound`] if `old` isn't present, or with
+    /// [`ModifyError::Collision`] if `new` is already paired with a different
+    /// left value.
+    ReplaceRight {
+        /// The right value to replace.
+        old: R,
+        /// The right value to replace it with.
+        new: R,
+    },
+}
+
+/// The error returned by the `apply_batch` method of a bimap when one of the
+/// operations in the batch fails.
+///
+/// The bimap is left exactly as it was before the call.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BatchError, BatchOp, BiMap, ModifyError};
+///
+/// let mut bimap = BiMap::new();
+/// bimap.insert('a', 1);
+///
+/// let error = bimap
+///     .apply_batch(vec![BatchOp::Insert('b', 2), BatchOp::Insert('c', 1)])
+///     .unwrap_err();
+/// assert_eq!(error.index, 1);
+/// assert_eq!(error.op, BatchOp::Insert('c', 1));
+/// assert_eq!(error.reason, ModifyError::Collision);
+/// assert!(!bimap.contains_left(&'b'));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BatchError<L, R> {
+    /// The position of the failed operation in the batch.
+    pub index: usize,
+    /// The failed operation.
+    pub op: BatchOp<L, R>,
+    /// The reason the operation failed.
+    pub reason: ModifyError,
+}
+
+impl<L, R> core::fmt::Display for BatchError<L, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "batch operation {} failed: {}", self.index, self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L, R> std::error::Error for BatchError<L, R>
+where
+    L: core::fmt::Debug,
+    R: core::fmt::Debug,
+{
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn overwritten_pairs() {
+        use alloc::vec::Vec;
+
+        for nothing in [Overwritten::Neither, Overwritten::Unchanged('a', 1)] {
+            assert_eq!(nothing.pairs().count(), 0);
+            assert_eq!(nothing.into_pairs().count(), 0);
+        }
+
+        for single in [
+            Overwritten::Left('a', 1),
+            Overwritten::Right('a', 1),
+            Overwritten::Pair('a', 1),
+        ] {
+            assert_eq!(single.pairs().size_hint(), (1, Some(1)));
+            assert_eq!(single.pairs().collect::<Vec<_>>(), [(&'a', &1)]);
+            assert_eq!(single.into_pairs().collect::<Vec<_>>(), [('a', 1)]);
+        }
+
+        let both = Overwritten::Both(('a', 1), ('b', 2));
+        assert_eq!(both.pairs().size_hint(), (2, Some(2)));
+        assert_eq!(both.pairs().collect::<Vec<_>>(), [(&'a', &1), (&'b', &2)]);
+        assert_eq!(both.pairs().next_back(), Some((&'b', &2)));
+        assert_eq!((&both).into_iter().count(), 2);
+        assert_eq!(both.into_iter().collect::<Vec<_>>(), [('a', 1), ('b', 2)]);
+    }
+
     #[test]
     fn did_overwrite() {
-        assert_eq!(Overwritten::<char, i32>::Neither.did_overwrite(), false);
-        assert_eq!(Overwritten::Left('a', 1).did_overwrite(), true);
-        assert_eq!(Overwritten::Right('a', 1).did_overwrite(), true);
-        assert_eq!(Overwritten::Pair('a', 1).did_overwrite(), true);
-        assert_eq!(Overwritten::Both(('a', 1), ('b', 2)).did_overwrite(), true);
+        assert!(!Overwritten::<char, i32>::Neither.did_overwrite());
+        assert!(!Overwritten::Unchanged('a', 1).did_overwrite());
+        assert!(Overwritten::Left('a', 1).did_overwrite());
+        assert!(Overwritten::Right('a', 1).did_overwrite());
+        assert!(Overwritten::Pair('a', 1).did_overwrite());
+        assert!(Overwritten::Both(('a', 1), ('b', 2)).did_overwrite());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn key_not_found_error() {
+        let error: Box<dyn std::error::Error> = Box::new(KeyNotFound);
+        assert_eq!(error.to_string(), "key not found in bimap");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn map_mismatch_error() {
+        let error: Box<dyn std::error::Error> = Box::new(MapMismatch::Len {
+            forward: 2,
+            reverse: 3,
+        });
+        assert_eq!(
+            error.to_string(),
+            "forward map has 2 entries but reverse map has 3"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn batch_error() {
+        let error: Box<dyn std::error::Error> = Box::new(BatchError {
+            index: 3,
+            op: BatchOp::<char, i32>::RemoveByLeft('a'),
+            reason: ModifyError::NotFound,
+        });
+        assert_eq!(
+            error.to_string(),
+            "batch operation 3 failed: value not found in bimap"
+        );
+    }
+
+    #[test]
+    fn newtype_methods() {
+        use alloc::string::String;
+
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Id(u32);
+
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Name(String);
+
+        impl Newtype for Id {
+            type Inner = u32;
+
+            fn from_inner(inner: u32) -> Self {
+                Id(inner)
+            }
+
+            fn inner(&self) -> &u32 {
+                &self.0
+            }
+
+            fn into_inner(self) -> u32 {
+                self.0
+            }
+        }
+
+        impl Newtype for Name {
+            type Inner = String;
+
+            fn from_inner(inner: String) -> Self {
+                Name(inner)
+            }
+
+            fn inner(&self) -> &String {
+                &self.0
+            }
+
+            fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        let mut bimap = BiBTreeMap::new();
+        assert_eq!(bimap.insert_inner(1, "a".into()), Overwritten::Neither);
+        assert_eq!(
+            bimap.insert_inner(1, "b".into()),
+            Overwritten::Left(Id(1), Name("a".into()))
+        );
+        bimap.insert_inner(2, "c".into());
+
+        assert_eq!(bimap.get_by_left_inner(1).map(String::as_str), Some("b"));
+        assert_eq!(bimap.get_by_right_inner("c".into()), Some(&2));
+        assert_eq!(bimap.get_by_left_inner(3), None);
+        assert_eq!(bimap.remove_by_left_inner(1), Some((1, "b".into())));
+        assert_eq!(
+            bimap.remove_by_right_inner("c".into()),
+            Some((2, "c".into()))
+        );
+        assert!(bimap.is_empty());
     }
 }
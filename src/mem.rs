@@ -1,4 +1,6 @@
 use alloc::rc::Rc;
+#[cfg(any(feature = "std", all(test, feature = "hashbrown")))]
+use core::hash::{BuildHasher, Hash, Hasher};
 use core::{borrow::Borrow, fmt, ops::Bound};
 
 #[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -52,3 +54,55 @@ where
         Wrapper::wrap(q)
     }
 }
+
+/// Thread-safe counterpart of `Ref`, used by the concurrent bimaps.
+#[cfg(feature = "concurrent")]
+#[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SyncRef<T>(pub alloc::sync::Arc<T>);
+
+#[cfg(feature = "concurrent")]
+impl<T> Clone for SyncRef<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(feature = "concurrent")]
+impl<T> fmt::Debug for SyncRef<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(feature = "concurrent")]
+impl<K, Q> Borrow<Wrapper<Q>> for SyncRef<K>
+where
+    K: Borrow<Q>,
+    Q: ?Sized,
+{
+    fn borrow(&self) -> &Wrapper<Q> {
+        // Arc<K>: Borrow<K>
+        let k: &K = self.0.borrow();
+        // K: Borrow<Q>
+        let q: &Q = k.borrow();
+
+        Wrapper::wrap(q)
+    }
+}
+
+/// Hashes a single value with the given hasher.
+///
+/// Equivalent to `BuildHasher::hash_one`, which is newer than the MSRV.
+#[cfg(any(feature = "std", all(test, feature = "hashbrown")))]
+pub fn hash_one<S, T>(hash_builder: &S, value: &T) -> u64
+where
+    S: BuildHasher,
+    T: Hash + ?Sized,
+{
+    let mut hasher = hash_builder.build_hasher();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,1051 @@
+//! A persistent bimap whose snapshots share structure.
+//!
+//! A [`PersistentBiMap`] is never modified in place. Instead,
+//! [`insert`](PersistentBiMap::insert) and the `remove_*` methods return a
+//! new bimap and leave the original untouched. Both bimaps share everything
+//! except the handful of nodes on the paths to the changed pairs, so an edit
+//! takes `O(log n)` time and memory, and cloning a bimap only bumps a
+//! reference count. This makes it cheap to keep every version of a mapping
+//! around, for example to implement undo and redo.
+//!
+//! Internally, each direction is a hash array mapped trie: a tree with up to
+//! 32 children per node, indexed by successive 5-bit fragments of the hash of
+//! a value. Both tries hold pointers to the same pairs, so each pair is stored
+//! only once.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::PersistentBiMap;
+//!
+//! let mut history = vec![PersistentBiMap::new()];
+//! for (i, name) in ["zero", "one", "two"].iter().enumerate() {
+//!     let next = history.last().unwrap().insert(i, *name);
+//!     history.push(next);
+//! }
+//! let next = history.last().unwrap().remove_by_right(&"one");
+//! history.push(next);
+//!
+//! // every version is still intact
+//! assert_eq!(history[2].get_by_left(&1), Some(&"one"));
+//! assert_eq!(history[3].len(), 3);
+//! assert_eq!(history[4].get_by_left(&1), None);
+//! assert_eq!(history[4].get_by_right(&"two"), Some(&2));
+//! ```
+
+use crate::mem::hash_one;
+use std::{
+    borrow::Borrow,
+    collections::hash_map,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::{FromIterator, FusedIterator},
+    mem, slice,
+    sync::Arc,
+};
+
+/// The number of hash bits consumed at each level of a trie.
+const BITS: u32 = 5;
+
+/// A persistent bimap backed by two hash array mapped tries.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::persistent
+pub struct PersistentBiMap<L, R, LS = hash_map::RandomState, RS = hash_map::RandomState> {
+    left2right: Arc<Node<Arc<(L, R)>>>,
+    right2left: Arc<Node<Arc<(L, R)>>>,
+    len: usize,
+    hash_builder_left: LS,
+    hash_builder_right: RS,
+}
+
+impl<L, R> PersistentBiMap<L, R, hash_map::RandomState, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `PersistentBiMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap: PersistentBiMap<char, i32> = PersistentBiMap::new();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hashers(hash_map::RandomState::new(), hash_map::RandomState::new())
+    }
+}
+
+impl<L, R, LS, RS> PersistentBiMap<L, R, LS, RS> {
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    /// assert_eq!(bimap.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new();
+    /// assert!(bimap.is_empty());
+    /// assert!(!bimap.insert('a', 1).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `(&'a L, &'a R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    ///
+    /// for (left, right) in bimap.iter() {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: Items::new(&self.left2right, self.len),
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap in arbitrary
+    /// order.
+    ///
+    /// The iterator element type is `&'a L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    ///
+    /// for left in bimap.left_values() {
+    ///     println!("{}", left);
+    /// }
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: Items::new(&self.left2right, self.len),
+        }
+    }
+
+    /// Creates an iterator over the right values in the bimap in arbitrary
+    /// order.
+    ///
+    /// The iterator element type is `&'a R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    ///
+    /// for right in bimap.right_values() {
+    ///     println!("{}", right);
+    /// }
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: Items::new(&self.right2left, self.len),
+        }
+    }
+}
+
+impl<L, R, LS, RS> PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+{
+    /// Creates an empty `PersistentBiMap` using `hash_builder_left` to hash
+    /// left values and `hash_builder_right` to hash right values.
+    ///
+    /// Every bimap derived from this one uses clones of the same hashers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let bimap = PersistentBiMap::with_hashers(RandomState::new(), RandomState::new());
+    /// assert_eq!(bimap.insert('a', 1).get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn with_hashers(hash_builder_left: LS, hash_builder_right: RS) -> Self {
+        Self {
+            left2right: Arc::new(Node::empty()),
+            right2left: Arc::new(Node::empty()),
+            len: 0,
+            hash_builder_left,
+            hash_builder_right,
+        }
+    }
+
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = hash_one(&self.hash_builder_left, left);
+        self.left2right
+            .get(hash, 0, &|pair| pair.0.borrow() == left)
+            .map(|pair| &pair.1)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = hash_one(&self.hash_builder_right, right);
+        self.right2left
+            .get(hash, 0, &|pair| pair.1.borrow() == right)
+            .map(|pair| &pair.0)
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_by_left(left).is_some()
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let bimap = PersistentBiMap::new().insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_by_right(right).is_some()
+    }
+
+    /// Returns a new bimap that also contains the given left-right pair,
+    /// leaving this bimap unchanged.
+    ///
+    /// Like [`BiHashMap::insert`], any pairs with the same left or right
+    /// value are overwritten, so they are missing from the new bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let old = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    /// let new = old.insert('a', 2);
+    ///
+    /// assert_eq!(new.len(), 1);
+    /// assert_eq!(new.get_by_right(&2), Some(&'a'));
+    /// assert_eq!(old.get_by_right(&2), Some(&'b'));
+    /// ```
+    ///
+    /// [`BiHashMap::insert`]: crate::BiHashMap::insert
+    pub fn insert(&self, left: L, right: R) -> Self {
+        let mut new = self.clone();
+        let left_hash = hash_one(&self.hash_builder_left, &left);
+        let right_hash = hash_one(&self.hash_builder_right, &right);
+        new.remove_left_mut(left_hash, &left);
+        new.remove_right_mut(right_hash, &right);
+        let pair = Arc::new((left, right));
+        Arc::make_mut(&mut new.left2right).insert(left_hash, 0, pair.clone());
+        Arc::make_mut(&mut new.right2left).insert(right_hash, 0, pair);
+        new.len += 1;
+        new
+    }
+
+    /// Returns a new bimap without the pair corresponding to the given left
+    /// value, leaving this bimap unchanged.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let old = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    /// let new = old.remove_by_left(&'a');
+    ///
+    /// assert_eq!(new.get_by_right(&1), None);
+    /// assert_eq!(old.get_by_right(&1), Some(&'a'));
+    /// ```
+    pub fn remove_by_left<Q>(&self, left: &Q) -> Self
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut new = self.clone();
+        new.remove_left_mut(hash_one(&self.hash_builder_left, left), left);
+        new
+    }
+
+    /// Returns a new bimap without the pair corresponding to the given right
+    /// value, leaving this bimap unchanged.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::PersistentBiMap;
+    ///
+    /// let old = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+    /// let new = old.remove_by_right(&1);
+    ///
+    /// assert_eq!(new.get_by_left(&'a'), None);
+    /// assert_eq!(old.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn remove_by_right<Q>(&self, right: &Q) -> Self
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut new = self.clone();
+        new.remove_right_mut(hash_one(&self.hash_builder_right, right), right);
+        new
+    }
+
+    /// Removes the pair with the given left value from both tries, copying
+    /// only the nodes that are shared with other bimaps.
+    fn remove_left_mut<Q>(&mut self, hash: u64, left: &Q)
+    where
+        L: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let is_match = |pair: &Arc<(L, R)>| pair.0.borrow() == left;
+        // look the pair up first so that a miss doesn't copy any nodes
+        if self.left2right.get(hash, 0, &is_match).is_none() {
+            return;
+        }
+        let pair = Arc::make_mut(&mut self.left2right)
+            .remove(hash, 0, &is_match)
+            .unwrap();
+        let right_hash = hash_one(&self.hash_builder_right, &pair.1);
+        Arc::make_mut(&mut self.right2left).remove(right_hash, 0, &|p| Arc::ptr_eq(p, &pair));
+        self.len -= 1;
+    }
+
+    /// Removes the pair with the given right value from both tries, copying
+    /// only the nodes that are shared with other bimaps.
+    fn remove_right_mut<Q>(&mut self, hash: u64, right: &Q)
+    where
+        R: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let is_match = |pair: &Arc<(L, R)>| pair.1.borrow() == right;
+        if self.right2left.get(hash, 0, &is_match).is_none() {
+            return;
+        }
+        let pair = Arc::make_mut(&mut self.right2left)
+            .remove(hash, 0, &is_match)
+            .unwrap();
+        let left_hash = hash_one(&self.hash_builder_left, &pair.0);
+        Arc::make_mut(&mut self.left2right).remove(left_hash, 0, &|p| Arc::ptr_eq(p, &pair));
+        self.len -= 1;
+    }
+}
+
+impl<L, R, LS, RS> Clone for PersistentBiMap<L, R, LS, RS>
+where
+    LS: Clone,
+    RS: Clone,
+{
+    fn clone(&self) -> PersistentBiMap<L, R, LS, RS> {
+        PersistentBiMap {
+            left2right: self.left2right.clone(),
+            right2left: self.right2left.clone(),
+            len: self.len,
+            hash_builder_left: self.hash_builder_left.clone(),
+            hash_builder_right: self.hash_builder_right.clone(),
+        }
+    }
+}
+
+impl<L, R, LS, RS> fmt::Debug for PersistentBiMap<L, R, LS, RS>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct EntryDebugger<'a, L, R> {
+            left: &'a L,
+            right: &'a R,
+        }
+        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+        where
+            L: fmt::Debug,
+            R: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.left.fmt(f)?;
+                write!(f, " <> ")?;
+                self.right.fmt(f)
+            }
+        }
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R, LS, RS> Default for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone + Default,
+    RS: BuildHasher + Clone + Default,
+{
+    fn default() -> PersistentBiMap<L, R, LS, RS> {
+        PersistentBiMap::with_hashers(LS::default(), RS::default())
+    }
+}
+
+impl<L, R, LS, RS> Eq for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+{
+}
+
+impl<L, R, LS, RS> FromIterator<(L, R)> for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone + Default,
+    RS: BuildHasher + Clone + Default,
+{
+    fn from_iter<I>(iter: I) -> PersistentBiMap<L, R, LS, RS>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        iter.into_iter()
+            .fold(PersistentBiMap::default(), |bimap, (l, r)| {
+                bimap.insert(l, r)
+            })
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoIterator for &'a PersistentBiMap<L, R, LS, RS> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+impl<L, R, LS, RS> PartialEq for PersistentBiMap<L, R, LS, RS>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    LS: BuildHasher + Clone,
+    RS: BuildHasher + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // snapshots of the same bimap usually share their root
+        Arc::ptr_eq(&self.left2right, &other.left2right)
+            || (self.len() == other.len()
+                && self.iter().all(|(l, r)| other.get_by_left(l) == Some(r)))
+    }
+}
+
+/// A node of a hash array mapped trie.
+#[derive(Clone)]
+enum Node<T> {
+    /// A node whose `bitmap` has a bit set for each hash fragment that is
+    /// present, with the corresponding entries in the same order.
+    Branch { bitmap: u32, entries: Vec<Entry<T>> },
+    /// A node holding items whose full hashes are all equal.
+    Collision { hash: u64, items: Vec<T> },
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+    Item(u64, T),
+    Node(Arc<Node<T>>),
+}
+
+/// Returns the bit of a branch bitmap for the hash fragment at `shift`.
+fn fragment_bit(hash: u64, shift: u32) -> u32 {
+    1 << ((hash >> shift) & 0x1f)
+}
+
+impl<T: Clone> Node<T> {
+    fn empty() -> Self {
+        Node::Branch {
+            bitmap: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates a node at `shift` holding two items.
+    fn pair(hash_a: u64, a: T, hash_b: u64, b: T, shift: u32) -> Self {
+        if hash_a == hash_b {
+            return Node::Collision {
+                hash: hash_a,
+                items: vec![a, b],
+            };
+        }
+        let mut node = Node::empty();
+        node.insert(hash_a, shift, a);
+        node.insert(hash_b, shift, b);
+        node
+    }
+
+    fn get<F>(&self, hash: u64, shift: u32, is_match: &F) -> Option<&T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        match self {
+            Node::Branch { bitmap, entries } => {
+                let bit = fragment_bit(hash, shift);
+                if bitmap & bit == 0 {
+                    return None;
+                }
+                match &entries[(bitmap & (bit - 1)).count_ones() as usize] {
+                    Entry::Item(h, item) if *h == hash && is_match(item) => Some(item),
+                    Entry::Item(..) => None,
+                    Entry::Node(node) => node.get(hash, shift + BITS, is_match),
+                }
+            }
+            Node::Collision { hash: h, items } if *h == hash => {
+                items.iter().find(|item| is_match(item))
+            }
+            Node::Collision { .. } => None,
+        }
+    }
+
+    /// Inserts an item that is not yet present.
+    fn insert(&mut self, hash: u64, shift: u32, item: T) {
+        match self {
+            Node::Branch { bitmap, entries } => {
+                let bit = fragment_bit(hash, shift);
+                let index = (*bitmap & (bit - 1)).count_ones() as usize;
+                if *bitmap & bit == 0 {
+                    *bitmap |= bit;
+                    entries.insert(index, Entry::Item(hash, item));
+                    return;
+                }
+                match &mut entries[index] {
+                    Entry::Node(node) => Arc::make_mut(node).insert(hash, shift + BITS, item),
+                    Entry::Item(..) => {
+                        // split the slot into a node holding both items
+                        if let Entry::Item(h, old) = entries.remove(index) {
+                            let node = Node::pair(h, old, hash, item, shift + BITS);
+                            entries.insert(index, Entry::Node(Arc::new(node)));
+                        }
+                    }
+                }
+            }
+            Node::Collision { hash: h, items } if *h == hash => items.push(item),
+            Node::Collision { hash: h, .. } => {
+                // the hashes differ in a later fragment, so push the
+                // collision node one level down
+                let bit = fragment_bit(*h, shift);
+                let collision = mem::replace(
+                    self,
+                    Node::Branch {
+                        bitmap: bit,
+                        entries: Vec::new(),
+                    },
+                );
+                if let Node::Branch { entries, .. } = self {
+                    entries.push(Entry::Node(Arc::new(collision)));
+                }
+                self.insert(hash, shift, item);
+            }
+        }
+    }
+
+    fn remove<F>(&mut self, hash: u64, shift: u32, is_match: &F) -> Option<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        match self {
+            Node::Branch { bitmap, entries } => {
+                let bit = fragment_bit(hash, shift);
+                if *bitmap & bit == 0 {
+                    return None;
+                }
+                let index = (*bitmap & (bit - 1)).count_ones() as usize;
+                match &mut entries[index] {
+                    Entry::Item(h, item) if *h == hash && is_match(item) => {
+                        *bitmap &= !bit;
+                        match entries.remove(index) {
+                            Entry::Item(_, item) => Some(item),
+                            Entry::Node(_) => unreachable!(),
+                        }
+                    }
+                    Entry::Item(..) => None,
+                    Entry::Node(node) => {
+                        let removed = Arc::make_mut(node).remove(hash, shift + BITS, is_match)?;
+                        // keep the trie canonical by pulling a lone item up
+                        if let Some(entry) = node.lone_item() {
+                            entries[index] = entry;
+                        }
+                        Some(removed)
+                    }
+                }
+            }
+            Node::Collision { hash: h, items } if *h == hash => {
+                let index = items.iter().position(is_match)?;
+                Some(items.swap_remove(index))
+            }
+            Node::Collision { .. } => None,
+        }
+    }
+
+    /// Returns the only item of a node that has no other items or children.
+    fn lone_item(&self) -> Option<Entry<T>> {
+        match self {
+            Node::Branch { entries, .. } => match entries.as_slice() {
+                [entry @ Entry::Item(..)] => Some(entry.clone()),
+                _ => None,
+            },
+            Node::Collision { hash, items } => match items.as_slice() {
+                [item] => Some(Entry::Item(*hash, item.clone())),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A depth-first iterator over the items of a trie.
+#[derive(Clone)]
+struct Items<'a, T> {
+    stack: Vec<Frame<'a, T>>,
+    remaining: usize,
+}
+
+#[derive(Clone)]
+enum Frame<'a, T> {
+    Entries(slice::Iter<'a, Entry<T>>),
+    Items(slice::Iter<'a, T>),
+}
+
+impl<'a, T> Items<'a, T> {
+    fn new(root: &'a Node<T>, len: usize) -> Self {
+        Items {
+            stack: vec![Frame::of(root)],
+            remaining: len,
+        }
+    }
+}
+
+impl<'a, T> Frame<'a, T> {
+    fn of(node: &'a Node<T>) -> Self {
+        match node {
+            Node::Branch { entries, .. } => Frame::Entries(entries.iter()),
+            Node::Collision { items, .. } => Frame::Items(items.iter()),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Items<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = match self.stack.last_mut()? {
+                Frame::Entries(entries) => match entries.next() {
+                    Some(Entry::Item(_, item)) => Some(item),
+                    Some(Entry::Node(node)) => {
+                        self.stack.push(Frame::of(node));
+                        continue;
+                    }
+                    None => None,
+                },
+                Frame::Items(items) => items.next(),
+            };
+            match next {
+                Some(item) => {
+                    self.remaining -= 1;
+                    return Some(item);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> fmt::Debug for Items<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Items")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An iterator over the left-right pairs in a `PersistentBiMap`.
+///
+/// This struct is created by the [`iter`] method of `PersistentBiMap`.
+///
+/// [`iter`]: PersistentBiMap::iter
+#[derive(Debug, Clone)]
+pub struct Iter<'a, L, R> {
+    inner: Items<'a, Arc<(L, R)>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|pair| (&pair.0, &pair.1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `PersistentBiMap`.
+///
+/// This struct is created by the [`left_values`] method of `PersistentBiMap`.
+///
+/// [`left_values`]: PersistentBiMap::left_values
+#[derive(Debug, Clone)]
+pub struct LeftValues<'a, L, R> {
+    inner: Items<'a, Arc<(L, R)>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|pair| &pair.0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `PersistentBiMap`.
+///
+/// This struct is created by the [`right_values`] method of
+/// `PersistentBiMap`.
+///
+/// [`right_values`]: PersistentBiMap::right_values
+#[derive(Debug, Clone)]
+pub struct RightValues<'a, L, R> {
+    inner: Items<'a, Arc<(L, R)>>,
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|pair| &pair.1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BiHashMap;
+    use std::{
+        collections::HashSet,
+        hash::{BuildHasherDefault, Hasher},
+    };
+
+    /// Collects the addresses of all nodes reachable from a trie.
+    fn collect_nodes<T>(node: &Arc<Node<T>>, seen: &mut HashSet<*const Node<T>>) {
+        if !seen.insert(Arc::as_ptr(node)) {
+            return;
+        }
+        if let Node::Branch { entries, .. } = &**node {
+            for entry in entries {
+                if let Entry::Node(child) = entry {
+                    collect_nodes(child, seen);
+                }
+            }
+        }
+    }
+
+    fn node_count<L, R>(bimaps: &[PersistentBiMap<L, R>]) -> usize {
+        let mut seen = HashSet::new();
+        for bimap in bimaps {
+            collect_nodes(&bimap.left2right, &mut seen);
+            collect_nodes(&bimap.right2left, &mut seen);
+        }
+        seen.len()
+    }
+
+    #[test]
+    fn snapshots_are_unaffected_by_later_edits() {
+        let mut snapshots = vec![PersistentBiMap::new()];
+        for i in 0..200 {
+            let next = snapshots.last().unwrap().insert(i, i * 10);
+            snapshots.push(next);
+        }
+        for i in 0..100 {
+            let next = snapshots.last().unwrap().remove_by_left(&(i * 2));
+            snapshots.push(next);
+        }
+        let next = snapshots.last().unwrap().insert(1, 0);
+        snapshots.push(next);
+
+        for (n, snapshot) in snapshots.iter().enumerate().take(201) {
+            assert_eq!(snapshot.len(), n);
+            for i in 0..200 {
+                let expected = if i < n as i32 { Some(i * 10) } else { None };
+                assert_eq!(snapshot.get_by_left(&i).copied(), expected);
+            }
+        }
+        let last = snapshots.last().unwrap();
+        assert_eq!(last.len(), 100);
+        assert_eq!(last.get_by_left(&1), Some(&0));
+        assert_eq!(last.get_by_right(&10), None);
+        assert_eq!(last.get_by_left(&2), None);
+        assert_eq!(snapshots[300].get_by_left(&1), Some(&10));
+        assert_eq!(snapshots[300].get_by_right(&0), None);
+    }
+
+    #[test]
+    fn snapshots_share_structure() {
+        let base: PersistentBiMap<u32, u32> = (0..10_000).map(|i| (i, i)).collect();
+        let full_copy = node_count(std::slice::from_ref(&base));
+
+        let mut snapshots = vec![base];
+        for i in 0..1000 {
+            let next = snapshots.last().unwrap().insert(i, 20_000 + i);
+            snapshots.push(next);
+        }
+        assert_eq!(snapshots[1000].get_by_left(&999), Some(&20_999));
+        assert_eq!(snapshots[999].get_by_left(&999), Some(&999));
+
+        // each edit copies a few paths instead of the whole trie
+        let total = node_count(&snapshots);
+        assert!(
+            total < full_copy * 10,
+            "{} nodes, {} per copy",
+            total,
+            full_copy
+        );
+    }
+
+    #[test]
+    fn matches_bihashmap() {
+        let mut expected = BiHashMap::new();
+        let mut bimap = PersistentBiMap::new();
+        // xorshift, so that the test is deterministic
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % 64
+        };
+        for _ in 0..2000 {
+            let (l, r) = (next(), next());
+            match next() % 4 {
+                0 => {
+                    expected.remove_by_left(&l);
+                    bimap = bimap.remove_by_left(&l);
+                }
+                1 => {
+                    expected.remove_by_right(&r);
+                    bimap = bimap.remove_by_right(&r);
+                }
+                _ => {
+                    expected.insert(l, r);
+                    bimap = bimap.insert(l, r);
+                }
+            }
+            assert_eq!(bimap.len(), expected.len());
+        }
+        for (l, r) in &expected {
+            assert_eq!(bimap.get_by_left(l), Some(r));
+            assert_eq!(bimap.get_by_right(r), Some(l));
+        }
+        assert_eq!(bimap.iter().count(), expected.len());
+        let mut lefts: Vec<_> = bimap.left_values().copied().collect();
+        lefts.sort_unstable();
+        let mut expected_lefts: Vec<_> = expected.left_values().copied().collect();
+        expected_lefts.sort_unstable();
+        assert_eq!(lefts, expected_lefts);
+    }
+
+    /// A hasher that maps every value to the same hash.
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            42
+        }
+
+        fn write(&mut self, _bytes: &[u8]) {}
+    }
+
+    #[test]
+    fn hash_collisions() {
+        type Colliding = BuildHasherDefault<ConstantHasher>;
+        let mut bimap = PersistentBiMap::with_hashers(Colliding::default(), Colliding::default());
+        for i in 0..10 {
+            bimap = bimap.insert(i, -i);
+        }
+        assert_eq!(bimap.len(), 10);
+        assert_eq!(bimap.get_by_left(&3), Some(&-3));
+        assert_eq!(bimap.get_by_right(&-7), Some(&7));
+
+        let smaller = bimap.insert(3, -7);
+        assert_eq!(smaller.len(), 9);
+        assert_eq!(smaller.get_by_right(&-7), Some(&3));
+        assert!(!smaller.contains_left(&7));
+        assert!(!smaller.contains_right(&-3));
+
+        let mut drained = smaller.clone();
+        for i in 0..10 {
+            drained = drained.remove_by_left(&i);
+        }
+        assert!(drained.is_empty());
+        assert_eq!(smaller.iter().count(), 9);
+    }
+
+    #[test]
+    fn eq_and_debug() {
+        let a = PersistentBiMap::new().insert('a', 1).insert('b', 2);
+        let b = PersistentBiMap::new().insert('b', 2).insert('a', 1);
+        assert_eq!(a, b);
+        assert_ne!(a, b.remove_by_left(&'a'));
+        assert_ne!(a, b.insert('a', 3));
+
+        let single = PersistentBiMap::new().insert('a', 1);
+        assert_eq!(format!("{:?}", single), "{'a' <> 1}");
+    }
+
+    #[test]
+    fn clone_is_shallow() {
+        let bimap: PersistentBiMap<i32, i32> = (0..100).map(|i| (i, i)).collect();
+        let clone = bimap.clone();
+        assert!(Arc::ptr_eq(&bimap.left2right, &clone.left2right));
+        assert!(Arc::ptr_eq(&bimap.right2left, &clone.right2left));
+
+        // a miss doesn't copy anything either
+        let unchanged = bimap.remove_by_left(&1000);
+        assert!(Arc::ptr_eq(&bimap.left2right, &unchanged.left2right));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PersistentBiMap<String, String>>();
+    }
+}
@@ -0,0 +1,367 @@
+//! Strategies for generating `BiHashMap`s and `BiBTreeMap`s with proptest.
+//!
+//! The [`bi_hash_map`] and [`bi_btree_map`] functions are analogous to
+//! `proptest::collection::hash_map` and `proptest::collection::btree_map`:
+//! given a strategy for left values, a strategy for right values and a size
+//! range, they return a strategy producing bimaps.
+//!
+//! Bimaps are generated from a vector of left-right pairs using the
+//! overwriting semantics of `insert`. Like the maps in
+//! `proptest::collection`, bimaps that end up smaller than the minimum size
+//! because of duplicate values are rejected and regenerated, so the value
+//! strategies need to be able to produce enough distinct values.
+//!
+//! Shrinking works on the underlying pairs: a bimap shrinks by removing
+//! pairs and by shrinking individual left and right values, never dropping
+//! below the minimum size.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::proptest::bi_hash_map;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn routes_are_bijective(routes in bi_hash_map(any::<u16>(), "[a-z]{1,8}", 0..32)) {
+//!         for (port, name) in &routes {
+//!             prop_assert_eq!(routes.get_by_right(name), Some(port));
+//!         }
+//!     }
+//! }
+//! routes_are_bijective();
+//! ```
+
+use crate::{BiBTreeMap, BiHashMap};
+use proptest::{
+    collection::{vec, SizeRange, VecStrategy, VecValueTree},
+    strategy::{statics, NewTree, Strategy, ValueTree},
+    test_runner::TestRunner,
+    tuple::TupleValueTree,
+};
+use std::{fmt, hash::Hash};
+
+/// Creates a strategy to generate a `BiHashMap` with left values from
+/// `left`, right values from `right`, and a number of pairs in the range
+/// `size`.
+///
+/// See the [module documentation](self) for details on how duplicate values
+/// are handled.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::proptest::bi_hash_map;
+/// use proptest::{prelude::*, strategy::ValueTree, test_runner::TestRunner};
+///
+/// let strategy = bi_hash_map(0..100u8, any::<char>(), 5..10);
+/// let bimap = strategy.new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert!((5..10).contains(&bimap.len()));
+/// ```
+pub fn bi_hash_map<L: Strategy, R: Strategy>(
+    left: L,
+    right: R,
+    size: impl Into<SizeRange>,
+) -> BiHashMapStrategy<L, R>
+where
+    L::Value: Hash + Eq,
+    R::Value: Hash + Eq,
+{
+    let size = size.into();
+    BiHashMapStrategy(statics::Filter::new(
+        statics::Map::new(vec((left, right), size.clone()), VecToBiHashMap),
+        "BiHashMap minimum size".into(),
+        MinSize(size.start()),
+    ))
+}
+
+/// Creates a strategy to generate a `BiBTreeMap` with left values from
+/// `left`, right values from `right`, and a number of pairs in the range
+/// `size`.
+///
+/// See the [module documentation](self) for details on how duplicate values
+/// are handled.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::proptest::bi_btree_map;
+/// use proptest::{prelude::*, strategy::ValueTree, test_runner::TestRunner};
+///
+/// let strategy = bi_btree_map(any::<i32>(), "[0-9]{3}", 1..=3);
+/// let bimap = strategy.new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert!((1..=3).contains(&bimap.len()));
+/// ```
+pub fn bi_btree_map<L: Strategy, R: Strategy>(
+    left: L,
+    right: R,
+    size: impl Into<SizeRange>,
+) -> BiBTreeMapStrategy<L, R>
+where
+    L::Value: Ord,
+    R::Value: Ord,
+{
+    let size = size.into();
+    BiBTreeMapStrategy(statics::Filter::new(
+        statics::Map::new(vec((left, right), size.clone()), VecToBiBTreeMap),
+        "BiBTreeMap minimum size".into(),
+        MinSize(size.start()),
+    ))
+}
+
+/// Strategy to create `BiHashMap`s, returned by [`bi_hash_map`].
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct BiHashMapStrategy<L, R>(
+    statics::Filter<statics::Map<VecStrategy<(L, R)>, VecToBiHashMap>, MinSize>,
+)
+where
+    L: Strategy,
+    R: Strategy,
+    L::Value: Hash + Eq,
+    R::Value: Hash + Eq;
+
+impl<L, R> Strategy for BiHashMapStrategy<L, R>
+where
+    L: Strategy,
+    R: Strategy,
+    L::Value: Hash + Eq,
+    R::Value: Hash + Eq,
+{
+    type Tree = BiHashMapValueTree<L::Tree, R::Tree>;
+    type Value = BiHashMap<L::Value, R::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.0.new_tree(runner).map(BiHashMapValueTree)
+    }
+}
+
+/// `ValueTree` corresponding to [`BiHashMapStrategy`].
+#[derive(Clone, Debug)]
+pub struct BiHashMapValueTree<L, R>(
+    statics::Filter<statics::Map<PairsTree<L, R>, VecToBiHashMap>, MinSize>,
+)
+where
+    L: ValueTree,
+    R: ValueTree,
+    L::Value: Hash + Eq,
+    R::Value: Hash + Eq;
+
+impl<L, R> ValueTree for BiHashMapValueTree<L, R>
+where
+    L: ValueTree,
+    R: ValueTree,
+    L::Value: Hash + Eq,
+    R::Value: Hash + Eq,
+{
+    type Value = BiHashMap<L::Value, R::Value>;
+
+    fn current(&self) -> Self::Value {
+        self.0.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.0.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.0.complicate()
+    }
+}
+
+/// Strategy to create `BiBTreeMap`s, returned by [`bi_btree_map`].
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct BiBTreeMapStrategy<L, R>(
+    statics::Filter<statics::Map<VecStrategy<(L, R)>, VecToBiBTreeMap>, MinSize>,
+)
+where
+    L: Strategy,
+    R: Strategy,
+    L::Value: Ord,
+    R::Value: Ord;
+
+impl<L, R> Strategy for BiBTreeMapStrategy<L, R>
+where
+    L: Strategy,
+    R: Strategy,
+    L::Value: Ord,
+    R::Value: Ord,
+{
+    type Tree = BiBTreeMapValueTree<L::Tree, R::Tree>;
+    type Value = BiBTreeMap<L::Value, R::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.0.new_tree(runner).map(BiBTreeMapValueTree)
+    }
+}
+
+/// `ValueTree` corresponding to [`BiBTreeMapStrategy`].
+#[derive(Clone, Debug)]
+pub struct BiBTreeMapValueTree<L, R>(
+    statics::Filter<statics::Map<PairsTree<L, R>, VecToBiBTreeMap>, MinSize>,
+)
+where
+    L: ValueTree,
+    R: ValueTree,
+    L::Value: Ord,
+    R::Value: Ord;
+
+impl<L, R> ValueTree for BiBTreeMapValueTree<L, R>
+where
+    L: ValueTree,
+    R: ValueTree,
+    L::Value: Ord,
+    R::Value: Ord,
+{
+    type Value = BiBTreeMap<L::Value, R::Value>;
+
+    fn current(&self) -> Self::Value {
+        self.0.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.0.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.0.complicate()
+    }
+}
+
+/// The value tree of the vector of pairs a bimap is generated from.
+type PairsTree<L, R> = VecValueTree<TupleValueTree<(L, R)>>;
+
+/// Collects a vector of pairs into a `BiHashMap`.
+#[derive(Clone, Copy, Debug)]
+#[doc(hidden)]
+pub struct VecToBiHashMap;
+
+impl<L, R> statics::MapFn<Vec<(L, R)>> for VecToBiHashMap
+where
+    L: fmt::Debug + Hash + Eq,
+    R: fmt::Debug + Hash + Eq,
+{
+    type Output = BiHashMap<L, R>;
+
+    fn apply(&self, pairs: Vec<(L, R)>) -> Self::Output {
+        pairs.into_iter().collect()
+    }
+}
+
+/// Collects a vector of pairs into a `BiBTreeMap`.
+#[derive(Clone, Copy, Debug)]
+#[doc(hidden)]
+pub struct VecToBiBTreeMap;
+
+impl<L, R> statics::MapFn<Vec<(L, R)>> for VecToBiBTreeMap
+where
+    L: fmt::Debug + Ord,
+    R: fmt::Debug + Ord,
+{
+    type Output = BiBTreeMap<L, R>;
+
+    fn apply(&self, pairs: Vec<(L, R)>) -> Self::Output {
+        pairs.into_iter().collect()
+    }
+}
+
+/// Rejects bimaps with fewer pairs than the minimum size.
+#[derive(Clone, Copy, Debug)]
+#[doc(hidden)]
+pub struct MinSize(usize);
+
+impl<L, R> statics::FilterFn<BiHashMap<L, R>> for MinSize
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+{
+    fn apply(&self, bimap: &BiHashMap<L, R>) -> bool {
+        bimap.len() >= self.0
+    }
+}
+
+impl<L, R> statics::FilterFn<BiBTreeMap<L, R>> for MinSize
+where
+    L: Ord,
+    R: Ord,
+{
+    fn apply(&self, bimap: &BiBTreeMap<L, R>) -> bool {
+        bimap.len() >= self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::{prelude::*, test_runner::TestError};
+
+    proptest! {
+        #[test]
+        fn hash_map_is_bijective(bimap in bi_hash_map(0..50u8, 0..50u8, 10..40)) {
+            prop_assert!((10..40).contains(&bimap.len()));
+            for (l, r) in &bimap {
+                prop_assert_eq!(bimap.get_by_left(l), Some(r));
+                prop_assert_eq!(bimap.get_by_right(r), Some(l));
+            }
+        }
+
+        #[test]
+        fn btree_map_is_bijective(bimap in bi_btree_map(0..50u8, 0..50u8, 10..=40)) {
+            prop_assert!((10..=40).contains(&bimap.len()));
+            for (l, r) in &bimap {
+                prop_assert_eq!(bimap.get_by_right(r), Some(l));
+            }
+        }
+
+        #[test]
+        fn generated_bimaps_satisfy_invariants(
+            // few distinct values, so most generated pairs collide
+            hash in bi_hash_map(0..8u8, 0..8u8, 0..8),
+            btree in bi_btree_map(0..8u8, 0..8u8, 0..8),
+        ) {
+            prop_assert!(hash.check_invariants().is_ok());
+            prop_assert!(btree.check_invariants().is_ok());
+            prop_assert_eq!(hash.left_values().count(), hash.right_values().count());
+        }
+
+        #[test]
+        fn compose_with_prop_map(
+            inverse in bi_btree_map(any::<u32>(), any::<i64>(), 1..20)
+                .prop_map(|bimap| bimap.into_iter().map(|(l, r)| (r, l)).collect::<BiBTreeMap<_, _>>())
+        ) {
+            prop_assert!(!inverse.is_empty());
+        }
+    }
+
+    #[test]
+    fn shrinks_to_small_counterexample() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&bi_hash_map(any::<u32>(), any::<u32>(), 0..200), |bimap| {
+            // deliberately fails for any bimap with more than one pair
+            prop_assert!(bimap.len() < 2);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, bimap)) => {
+                assert_eq!(bimap.len(), 2);
+                // individual values are shrunk as well
+                assert!(bimap.left_values().all(|&l| l <= 1));
+                assert!(bimap.right_values().all(|&r| r <= 1));
+            }
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn shrinking_respects_minimum_size() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&bi_btree_map(0..1000u16, 0..1000u16, 5..100), |_| {
+            prop_assert!(false);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, bimap)) => assert_eq!(bimap.len(), 5),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+}
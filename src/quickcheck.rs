@@ -0,0 +1,152 @@
+//! Implementations of `quickcheck::Arbitrary` for `BiHashMap` and
+//! `BiBTreeMap`.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `quickcheck` feature in your dependency
+//! manifest.
+//!
+//! An arbitrary bimap is generated from an arbitrary vector of left-right
+//! pairs using the overwriting semantics of `insert`, so the bimap may have
+//! fewer pairs than were generated.
+//!
+//! Shrinking is based on the pairs of the bimap: a bimap shrinks by removing
+//! pairs and by shrinking individual left and right values. A shrunk value
+//! that collides with another value in the bimap overwrites that pair, so
+//! every shrunk bimap is a valid bimap that is smaller than the original.
+//! When a property fails on a large bimap, quickcheck can therefore reduce it
+//! to the handful of pairs that actually matter.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//! use quickcheck::{quickcheck, TestResult};
+//!
+//! fn prop(bimap: BiBTreeMap<u8, i32>) -> TestResult {
+//!     for (left, right) in &bimap {
+//!         if bimap.get_by_right(right) != Some(left) {
+//!             return TestResult::failed();
+//!         }
+//!     }
+//!     TestResult::passed()
+//! }
+//!
+//! quickcheck(prop as fn(BiBTreeMap<u8, i32>) -> TestResult);
+//! ```
+
+use crate::{BiBTreeMap, BiHashMap};
+use quickcheck::{Arbitrary, Gen};
+use std::hash::{BuildHasher, Hash};
+
+impl<L, R> Arbitrary for BiBTreeMap<L, R>
+where
+    L: Arbitrary + Ord,
+    R: Arbitrary + Ord,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<(L, R)>::arbitrary(g).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let pairs: Vec<(L, R)> = self.iter().map(|(l, r)| (l.clone(), r.clone())).collect();
+        Box::new(pairs.shrink().map(|pairs| pairs.into_iter().collect()))
+    }
+}
+
+impl<L, R, LS, RS> Arbitrary for BiHashMap<L, R, LS, RS>
+where
+    L: Arbitrary + Eq + Hash,
+    R: Arbitrary + Eq + Hash,
+    LS: BuildHasher + Clone + Default + Send + 'static,
+    RS: BuildHasher + Clone + Default + Send + 'static,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<(L, R)>::arbitrary(g).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let pairs: Vec<(L, R)> = self.iter().map(|(l, r)| (l.clone(), r.clone())).collect();
+        Box::new(pairs.shrink().map(|pairs| pairs.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{quickcheck, QuickCheck, TestResult};
+
+    /// Repeatedly takes the first shrink candidate that still fails the
+    /// property, like quickcheck does, and returns the minimal bimap.
+    fn minimize<T, F>(mut value: T, fails: F) -> T
+    where
+        T: Arbitrary,
+        F: Fn(&T) -> bool,
+    {
+        assert!(fails(&value));
+        while let Some(smaller) = value.shrink().find(|candidate| fails(candidate)) {
+            value = smaller;
+        }
+        value
+    }
+
+    #[test]
+    fn inverse_of_inverse_is_identity() {
+        fn prop(bimap: BiHashMap<i32, char>) -> bool {
+            let inverse: BiHashMap<_, _> = bimap.iter().map(|(&l, &r)| (r, l)).collect();
+            let identity: BiHashMap<_, _> = inverse.iter().map(|(&r, &l)| (l, r)).collect();
+            inverse.len() == bimap.len() && identity == bimap
+        }
+        quickcheck(prop as fn(BiHashMap<i32, char>) -> bool);
+
+        fn prop_btree(bimap: BiBTreeMap<i32, char>) -> bool {
+            let inverse: BiBTreeMap<_, _> = bimap.iter().map(|(&l, &r)| (r, l)).collect();
+            let identity: BiBTreeMap<_, _> = inverse.iter().map(|(&r, &l)| (l, r)).collect();
+            inverse.len() == bimap.len() && identity == bimap
+        }
+        quickcheck(prop_btree as fn(BiBTreeMap<i32, char>) -> bool);
+    }
+
+    #[test]
+    fn shrunk_bimaps_are_valid() {
+        fn prop(bimap: BiBTreeMap<u8, u8>) -> TestResult {
+            for smaller in bimap.shrink().take(100) {
+                if smaller.len() > bimap.len() {
+                    return TestResult::failed();
+                }
+                for (l, r) in &smaller {
+                    if smaller.get_by_right(r) != Some(l) {
+                        return TestResult::failed();
+                    }
+                }
+            }
+            TestResult::passed()
+        }
+        QuickCheck::new()
+            .tests(50)
+            .quickcheck(prop as fn(BiBTreeMap<u8, u8>) -> TestResult);
+    }
+
+    #[test]
+    fn shrink_to_minimal_btree() {
+        let bimap: BiBTreeMap<u32, u32> = (0..200).map(|i| (i, 1000 + i)).collect();
+        let minimal = minimize(bimap, |bimap| bimap.len() >= 2);
+        assert_eq!(minimal.len(), 2);
+        // individual values are shrunk as well
+        assert_eq!(minimal.left_values().max(), Some(&1));
+        assert_eq!(minimal.right_values().max(), Some(&1));
+    }
+
+    #[test]
+    fn shrink_to_minimal_hash() {
+        let bimap: BiHashMap<u32, i64> = (0..200).map(|i| (i, -i64::from(i))).collect();
+        let minimal = minimize(bimap, |bimap| bimap.contains_left(&17) && bimap.len() >= 2);
+        assert_eq!(minimal.len(), 2);
+        assert!(minimal.contains_left(&17));
+    }
+
+    #[test]
+    fn shrink_empty() {
+        assert_eq!(BiBTreeMap::<u8, u8>::new().shrink().count(), 0);
+        assert_eq!(BiHashMap::<u8, u8>::new().shrink().count(), 0);
+    }
+}
@@ -0,0 +1,764 @@
+//! Parallel iterator support for `BiHashMap` and `BiBTreeMap` using rayon.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `rayon` feature in your dependency
+//! manifest.
+//!
+//! # Collecting
+//!
+//! Both bimaps implement `FromParallelIterator` and `ParallelExtend`, so a
+//! parallel iterator of left-right pairs can be collected into a bimap or
+//! added to an existing one. Each thread inserts its share of the pairs into
+//! a bimap of its own, and these partial bimaps are then merged, inserting
+//! the pairs of the smaller one into the larger one.
+//!
+//! If no two pairs share a left value or a right value, the result is the
+//! same as collecting the equivalent sequential iterator. Otherwise, which of
+//! the conflicting pairs survive is unspecified: a pair overwrites the pairs
+//! it conflicts with when it is inserted, and the order in which pairs from
+//! different threads are inserted depends on how the work was split. Even
+//! the length of the resulting bimap may differ between runs. Pairs that are
+//! exact duplicates of each other are harmless, since inserting a pair again
+//! doesn't change the bimap. When the input may contain conflicts and the
+//! result must be reproducible, collect it into a `Vec` first and then
+//! collect that sequentially.
+//!
+//! When extending a bimap that already contains pairs, the new pairs are
+//! collected as above and then inserted, overwriting any existing pairs they
+//! conflict with as with `Extend::extend`.
+//!
+//! # Iterating
+//!
+//! Both bimaps can be iterated in parallel, either by reference with
+//! `par_iter`, yielding `(&L, &R)`, or by value with `into_par_iter`,
+//! yielding `(L, R)`. `par_left_values` and `par_right_values` iterate over
+//! just one side. The values of a bimap are reference counted internally and
+//! can't be shared between threads as they are, so these iterators first
+//! gather the pairs into a vector in a quick sequential pass and then split
+//! that vector between threads. This pays off when the work done per pair
+//! outweighs copying a pointer, which is the usual reason to reach for rayon.
+//!
+//! The pairs are processed on several threads at once, so closures like the
+//! one passed to `for_each` see them in no particular order, even for a
+//! `BiBTreeMap`. The iterators are indexed, though, so order-preserving
+//! operations such as `collect` into a `Vec` or `find_first` still see the
+//! pairs in the order of the corresponding sequential iterator: arbitrary
+//! for a `BiHashMap`, and ascending for a `BiBTreeMap`.
+//!
+//! # Retaining
+//!
+//! `par_retain` and `par_extract_if` evaluate their predicate on all pairs
+//! in parallel, which pays off when the predicate is expensive, and then
+//! remove the selected pairs sequentially. The predicate is called exactly
+//! once per pair, so the result is the same as with the sequential `retain`.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiHashMap;
+//! use rayon::prelude::*;
+//!
+//! let bimap: BiHashMap<u32, String> = (0..1000u32)
+//!     .into_par_iter()
+//!     .map(|i| (i, i.to_string()))
+//!     .collect();
+//! assert_eq!(bimap.len(), 1000);
+//! assert_eq!(bimap.get_by_right("42"), Some(&42));
+//!
+//! let total: u32 = bimap.par_iter().map(|(l, _)| l).sum();
+//! assert_eq!(total, 499_500);
+//! ```
+
+use crate::{BiBTreeMap, BiHashMap};
+use rayon::{
+    iter::{
+        plumbing::{Consumer, ProducerCallback, UnindexedConsumer},
+        FromParallelIterator, IndexedParallelIterator, IntoParallelIterator, ParallelExtend,
+        ParallelIterator,
+    },
+    vec,
+};
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash},
+};
+
+impl<L, R, LS, RS> FromParallelIterator<(L, R)> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Send,
+    R: Eq + Hash + Send,
+    LS: BuildHasher + Default + Send,
+    RS: BuildHasher + Default + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        par_collect(par_iter, BiHashMap::len)
+    }
+}
+
+impl<L, R, LS, RS> ParallelExtend<(L, R)> for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Send,
+    R: Eq + Hash + Send,
+    LS: BuildHasher + Default + Send,
+    RS: BuildHasher + Default + Send,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        let pairs: Self = par_collect(par_iter, BiHashMap::len);
+        self.extend(pairs);
+    }
+}
+
+impl<L, R> FromParallelIterator<(L, R)> for BiBTreeMap<L, R>
+where
+    L: Ord + Send,
+    R: Ord + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        par_collect(par_iter, BiBTreeMap::len)
+    }
+}
+
+impl<L, R> ParallelExtend<(L, R)> for BiBTreeMap<L, R>
+where
+    L: Ord + Send,
+    R: Ord + Send,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        let pairs: Self = par_collect(par_iter, BiBTreeMap::len);
+        self.extend(pairs);
+    }
+}
+
+/// Collects the pairs into one bimap per thread and merges them, inserting
+/// the pairs of the smaller bimap into the larger one.
+fn par_collect<M, L, R, I>(par_iter: I, len: fn(&M) -> usize) -> M
+where
+    M: Default + Extend<(L, R)> + IntoIterator<Item = (L, R)> + Send,
+    L: Send,
+    R: Send,
+    I: IntoParallelIterator<Item = (L, R)>,
+{
+    par_iter
+        .into_par_iter()
+        .fold(M::default, |mut bimap, pair| {
+            bimap.extend(Some(pair));
+            bimap
+        })
+        .reduce(M::default, |mut a, mut b| {
+            if len(&a) < len(&b) {
+                std::mem::swap(&mut a, &mut b);
+            }
+            a.extend(b);
+            a
+        })
+}
+
+impl<L, R, LS, RS> BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Sync,
+    R: Eq + Hash + Sync,
+{
+    /// Creates a parallel iterator over the left values in the bimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `&L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiHashMap<_, _> = (0..100u64).map(|i| (i, -(i as i64))).collect();
+    /// let total: u64 = bimap.par_left_values().sum();
+    /// assert_eq!(total, 4950);
+    /// ```
+    pub fn par_left_values(&self) -> ParLeftValues<'_, L> {
+        ParLeftValues {
+            inner: self.left_values().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+
+    /// Creates a parallel iterator over the right values in the bimap in
+    /// arbitrary order.
+    ///
+    /// The iterator element type is `&R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiHashMap<_, _> = (0..100u64).map(|i| (-(i as i64), i)).collect();
+    /// let total: u64 = bimap.par_right_values().sum();
+    /// assert_eq!(total, 4950);
+    /// ```
+    pub fn par_right_values(&self) -> ParRightValues<'_, R> {
+        ParRightValues {
+            inner: self.right_values().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+
+    /// Retains only the pairs for which the predicate returns `true`,
+    /// evaluating the predicate on all pairs in parallel.
+    ///
+    /// The result is the same as with [`retain`](BiHashMap::retain), but
+    /// the predicate is called from several threads in arbitrary order. The
+    /// pairs are removed sequentially afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<_, _> = (0..1000u32).map(|i| (i, i.to_string())).collect();
+    /// bimap.par_retain(|l, r| l % 3 == 0 && !r.ends_with('9'));
+    /// assert_eq!(bimap.len(), 300);
+    /// assert!(bimap.contains_left(&3));
+    /// assert!(!bimap.contains_left(&39));
+    /// ```
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        LS: BuildHasher,
+        RS: BuildHasher,
+        F: Fn(&L, &R) -> bool + Sync,
+    {
+        let rejected = par_select(self.iter(), |l, r| !f(l, r));
+        self.retain(|l, _| !rejected.contains(&address(l)));
+    }
+
+    /// Removes the pairs for which the predicate returns `true` and returns
+    /// them in arbitrary order, evaluating the predicate on all pairs in
+    /// parallel.
+    ///
+    /// The predicate is called from several threads in arbitrary order. The
+    /// pairs are removed sequentially afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiHashMap;
+    ///
+    /// let mut bimap: BiHashMap<_, _> = (0..100u32).map(|i| (i, i * 2)).collect();
+    /// let mut removed = bimap.par_extract_if(|l, _| l % 10 == 0);
+    /// removed.sort();
+    /// assert_eq!(removed[..3], [(0, 0), (10, 20), (20, 40)]);
+    /// assert_eq!(bimap.len(), 90);
+    /// assert!(!bimap.contains_right(&20));
+    /// ```
+    pub fn par_extract_if<F>(&mut self, f: F) -> Vec<(L, R)>
+    where
+        LS: BuildHasher,
+        RS: BuildHasher,
+        F: Fn(&L, &R) -> bool + Sync,
+    {
+        let selected = par_select(self.iter(), f);
+        self.extract_where(|l, _| selected.contains(&address(l)))
+    }
+}
+
+impl<'a, L, R, LS, RS> IntoParallelIterator for &'a BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Sync,
+    R: Eq + Hash + Sync,
+{
+    type Item = (&'a L, &'a R);
+    type Iter = ParIter<'a, L, R>;
+
+    fn into_par_iter(self) -> ParIter<'a, L, R> {
+        ParIter {
+            inner: self.iter().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+}
+
+impl<L, R, LS, RS> IntoParallelIterator for BiHashMap<L, R, LS, RS>
+where
+    L: Eq + Hash + Send,
+    R: Eq + Hash + Send,
+{
+    type Item = (L, R);
+    type Iter = IntoParIter<L, R>;
+
+    fn into_par_iter(self) -> IntoParIter<L, R> {
+        IntoParIter {
+            inner: self.into_iter().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+}
+
+impl<L, R> BiBTreeMap<L, R>
+where
+    L: Ord + Sync,
+    R: Ord + Sync,
+{
+    /// Creates a parallel iterator over the left values in the bimap.
+    ///
+    /// The iterator element type is `&L`. Order-preserving operations see
+    /// the values in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiBTreeMap<_, _> = (0..100u64).map(|i| (i, -(i as i64))).collect();
+    /// let total: u64 = bimap.par_left_values().sum();
+    /// assert_eq!(total, 4950);
+    /// ```
+    pub fn par_left_values(&self) -> ParLeftValues<'_, L> {
+        ParLeftValues {
+            inner: self.left_values().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+
+    /// Creates a parallel iterator over the right values in the bimap.
+    ///
+    /// The iterator element type is `&R`. Order-preserving operations see
+    /// the values in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let bimap: BiBTreeMap<_, _> = (0..100u64).map(|i| (-(i as i64), i)).collect();
+    /// let rights: Vec<_> = bimap.par_right_values().copied().collect();
+    /// assert_eq!(rights, (0..100).collect::<Vec<_>>());
+    /// ```
+    pub fn par_right_values(&self) -> ParRightValues<'_, R> {
+        ParRightValues {
+            inner: self.right_values().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+
+    /// Retains only the pairs for which the predicate returns `true`,
+    /// evaluating the predicate on all pairs in parallel.
+    ///
+    /// The result is the same as with [`retain`](BiBTreeMap::retain), but
+    /// the predicate is called from several threads in arbitrary order. The
+    /// pairs are removed sequentially afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..1000u32).map(|i| (i, i.to_string())).collect();
+    /// bimap.par_retain(|l, r| l % 3 == 0 && !r.ends_with('9'));
+    /// assert_eq!(bimap.len(), 300);
+    /// assert_eq!(bimap.left_values().nth(12), Some(&42));
+    /// ```
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(&L, &R) -> bool + Sync,
+    {
+        let rejected = par_select(self.iter(), |l, r| !f(l, r));
+        self.retain(|l, _| !rejected.contains(&address(l)));
+    }
+
+    /// Removes the pairs for which the predicate returns `true` and returns
+    /// them in ascending order of their left values, evaluating the predicate
+    /// on all pairs in parallel.
+    ///
+    /// The predicate is called from several threads in arbitrary order. The
+    /// pairs are removed sequentially afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap: BiBTreeMap<_, _> = (0..100u32).map(|i| (i, i * 2)).collect();
+    /// let removed = bimap.par_extract_if(|l, _| l % 10 == 0);
+    /// assert_eq!(removed[..3], [(0, 0), (10, 20), (20, 40)]);
+    /// assert_eq!(bimap.len(), 90);
+    /// assert!(!bimap.contains_right(&20));
+    /// ```
+    pub fn par_extract_if<F>(&mut self, f: F) -> Vec<(L, R)>
+    where
+        F: Fn(&L, &R) -> bool + Sync,
+    {
+        let selected = par_select(self.iter(), f);
+        self.extract_where(|l, _| selected.contains(&address(l)))
+    }
+}
+
+impl<'a, L, R> IntoParallelIterator for &'a BiBTreeMap<L, R>
+where
+    L: Ord + Sync,
+    R: Ord + Sync,
+{
+    type Item = (&'a L, &'a R);
+    type Iter = ParIter<'a, L, R>;
+
+    fn into_par_iter(self) -> ParIter<'a, L, R> {
+        ParIter {
+            inner: self.iter().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+}
+
+impl<L, R> IntoParallelIterator for BiBTreeMap<L, R>
+where
+    L: Ord + Send,
+    R: Ord + Send,
+{
+    type Item = (L, R);
+    type Iter = IntoParIter<L, R>;
+
+    fn into_par_iter(self) -> IntoParIter<L, R> {
+        IntoParIter {
+            inner: self.into_iter().collect::<Vec<_>>().into_par_iter(),
+        }
+    }
+}
+
+/// Evaluates the predicate on the pairs in parallel and returns the
+/// addresses of the left values of the pairs it selects.
+///
+/// Every left value lives in its own allocation for as long as it is in the
+/// bimap, so its address identifies the pair without cloning anything.
+fn par_select<'a, L, R, I, F>(pairs: I, f: F) -> HashSet<usize>
+where
+    L: Sync + 'a,
+    R: Sync + 'a,
+    I: Iterator<Item = (&'a L, &'a R)>,
+    F: Fn(&L, &R) -> bool + Sync,
+{
+    pairs
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .filter(|&(l, r)| f(l, r))
+        .map(|(l, _)| address(l))
+        .collect()
+}
+
+/// Returns the address of a value.
+fn address<T>(value: &T) -> usize {
+    value as *const T as usize
+}
+
+/// Implements `ParallelIterator` and `IndexedParallelIterator` for a type
+/// whose `inner` field is a `rayon::vec::IntoIter`.
+macro_rules! delegate_par_iter {
+    ($name:ident<$($lt:lifetime,)? $($t:ident),*>, $item:ty) => {
+        impl<$($lt,)? $($t),*> ParallelIterator for $name<$($lt,)? $($t),*>
+        where
+            $item: Send,
+        {
+            type Item = $item;
+
+            fn drive_unindexed<C>(self, consumer: C) -> C::Result
+            where
+                C: UnindexedConsumer<Self::Item>,
+            {
+                self.inner.drive_unindexed(consumer)
+            }
+
+            fn opt_len(&self) -> Option<usize> {
+                self.inner.opt_len()
+            }
+        }
+
+        impl<$($lt,)? $($t),*> IndexedParallelIterator for $name<$($lt,)? $($t),*>
+        where
+            $item: Send,
+        {
+            fn drive<C>(self, consumer: C) -> C::Result
+            where
+                C: Consumer<Self::Item>,
+            {
+                self.inner.drive(consumer)
+            }
+
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+
+            fn with_producer<CB>(self, callback: CB) -> CB::Output
+            where
+                CB: ProducerCallback<Self::Item>,
+            {
+                self.inner.with_producer(callback)
+            }
+        }
+    };
+}
+
+/// A parallel iterator over the left-right pairs in a `BiHashMap` or
+/// `BiBTreeMap`.
+///
+/// This struct is created by calling `par_iter` on a bimap.
+#[derive(Debug)]
+pub struct ParIter<'a, L, R> {
+    inner: vec::IntoIter<(&'a L, &'a R)>,
+}
+
+delegate_par_iter!(ParIter<'a, L, R>, (&'a L, &'a R));
+
+/// An owning parallel iterator over the left-right pairs in a `BiHashMap` or
+/// `BiBTreeMap`.
+///
+/// This struct is created by calling `into_par_iter` on a bimap.
+#[derive(Debug)]
+pub struct IntoParIter<L, R> {
+    inner: vec::IntoIter<(L, R)>,
+}
+
+delegate_par_iter!(IntoParIter<L, R>, (L, R));
+
+/// A parallel iterator over the left values in a `BiHashMap` or
+/// `BiBTreeMap`.
+///
+/// This struct is created by the `par_left_values` method of
+/// [`BiHashMap`](BiHashMap::par_left_values) and
+/// [`BiBTreeMap`](BiBTreeMap::par_left_values).
+#[derive(Debug)]
+pub struct ParLeftValues<'a, L> {
+    inner: vec::IntoIter<&'a L>,
+}
+
+delegate_par_iter!(ParLeftValues<'a, L>, &'a L);
+
+/// A parallel iterator over the right values in a `BiHashMap` or
+/// `BiBTreeMap`.
+///
+/// This struct is created by the `par_right_values` method of
+/// [`BiHashMap`](BiHashMap::par_right_values) and
+/// [`BiBTreeMap`](BiBTreeMap::par_right_values).
+#[derive(Debug)]
+pub struct ParRightValues<'a, R> {
+    inner: vec::IntoIter<&'a R>,
+}
+
+delegate_par_iter!(ParRightValues<'a, R>, &'a R);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn collect_range() {
+        let hash: BiHashMap<_, _> = (0..10_000u32).into_par_iter().map(|i| (i, !i)).collect();
+        let btree: BiBTreeMap<_, _> = (0..10_000u32).into_par_iter().map(|i| (i, !i)).collect();
+        assert_eq!(hash.len(), 10_000);
+        assert_eq!(btree.len(), 10_000);
+        for (l, r) in btree.iter() {
+            assert_eq!(hash.get_by_left(l), Some(r));
+            assert_eq!(hash.get_by_right(r), Some(l));
+        }
+    }
+
+    #[test]
+    fn conflict_free_matches_sequential_collect() {
+        let pairs = (0..100_000u32)
+            .map(|i| (i, i.to_string()))
+            .collect::<Vec<_>>();
+
+        let hash: BiHashMap<_, _> = pairs.par_iter().cloned().collect();
+        let expected: BiHashMap<_, _> = pairs.iter().cloned().collect();
+        assert_eq!(hash, expected);
+
+        let btree: BiBTreeMap<_, _> = pairs.par_iter().cloned().collect();
+        let expected: BiBTreeMap<_, _> = pairs.iter().cloned().collect();
+        assert_eq!(btree, expected);
+    }
+
+    #[test]
+    fn duplicate_pairs_are_harmless() {
+        // every pair appears three times, but no two distinct pairs conflict
+        let pairs = (0..30_000u32).map(|i| (i % 10_000, !(i % 10_000)));
+        let pairs = pairs.collect::<Vec<_>>();
+        let hash: BiHashMap<_, _> = pairs.par_iter().cloned().collect();
+        let btree: BiBTreeMap<_, _> = pairs.par_iter().cloned().collect();
+        assert_eq!(hash.len(), 10_000);
+        assert_eq!(btree.len(), 10_000);
+        assert_eq!(hash, pairs.iter().cloned().collect::<BiHashMap<_, _>>());
+    }
+
+    #[test]
+    fn conflicts_keep_bijection() {
+        // many pairs share left or right values, so the surviving pairs and
+        // even their number depend on how rayon splits the work
+        let pairs = (0..10_000u32).map(|i| (i % 97, i % 89)).collect::<Vec<_>>();
+
+        let hash: BiHashMap<_, _> = pairs.par_iter().cloned().collect();
+        assert!(hash.check_invariants().is_ok());
+        assert!((1..=89).contains(&hash.len()));
+        assert!(hash.iter().all(|(l, r)| pairs.contains(&(*l, *r))));
+
+        let btree: BiBTreeMap<_, _> = pairs.par_iter().cloned().collect();
+        assert!(btree.check_invariants().is_ok());
+        assert!((1..=89).contains(&btree.len()));
+        assert!(btree.iter().all(|(l, r)| pairs.contains(&(*l, *r))));
+    }
+
+    #[test]
+    fn par_extend() {
+        let mut hash: BiHashMap<_, _> = (0..10u32).map(|i| (i, i + 1)).collect();
+        hash.par_extend((10..10_000u32).into_par_iter().map(|i| (i, i + 1)));
+        assert_eq!(hash.len(), 10_000);
+        // new pairs overwrite the existing pairs they conflict with
+        hash.par_extend(vec![(0, 5)]);
+        assert_eq!(hash.get_by_left(&0), Some(&5));
+        assert!(!hash.contains_left(&4));
+        assert_eq!(hash.len(), 9_999);
+
+        let mut btree = BiBTreeMap::new();
+        btree.par_extend((0..10_000u32).into_par_iter().map(|i| (i, !i)));
+        btree.par_extend((0..10_000u32).into_par_iter().map(|i| (i, !i)));
+        assert_eq!(btree.len(), 10_000);
+        assert!(btree.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn collect_unindexed() {
+        let bimap: BiBTreeMap<_, _> = (0..1000u32)
+            .into_par_iter()
+            .filter(|i| i % 3 == 0)
+            .map(|i| (i, i.to_string()))
+            .collect();
+        assert_eq!(bimap.len(), 334);
+        assert_eq!(bimap.get_by_right("999"), Some(&999));
+    }
+
+    #[test]
+    fn par_iter_matches_sequential() {
+        // large enough that rayon splits the work between threads
+        let bimap: BiHashMap<u64, u64> = (0..100_000u64).map(|i| (i, i * 3)).collect();
+        let sequential: u64 = bimap.right_values().sum();
+        assert_eq!(bimap.par_iter().map(|(_, r)| r).sum::<u64>(), sequential);
+        assert_eq!(bimap.par_right_values().sum::<u64>(), sequential);
+        assert_eq!(
+            bimap.par_left_values().sum::<u64>(),
+            bimap.left_values().sum::<u64>()
+        );
+        assert_eq!(bimap.par_iter().len(), 100_000);
+        assert!(bimap
+            .par_iter()
+            .all(|(l, r)| bimap.get_by_left(l) == Some(r)));
+
+        let mut pairs: Vec<_> = bimap.clone().into_par_iter().collect();
+        pairs.sort_unstable();
+        let mut expected: Vec<_> = bimap.into_iter().collect();
+        expected.sort_unstable();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn par_iter_empty() {
+        let bimap = BiHashMap::<u32, u32>::new();
+        assert_eq!(bimap.par_iter().count(), 0);
+        assert_eq!(bimap.par_left_values().count(), 0);
+        assert_eq!(bimap.into_par_iter().count(), 0);
+    }
+
+    #[test]
+    fn btree_par_iter_matches_sequential() {
+        let bimap: BiBTreeMap<u64, i64> = (0..100_000u64).map(|i| (i, -(i as i64) * 7)).collect();
+
+        let mut pairs: Vec<_> = Vec::new();
+        bimap.par_iter().collect_into_vec(&mut pairs);
+        assert_eq!(pairs, bimap.iter().collect::<Vec<_>>());
+
+        // compare as multisets when the order isn't preserved
+        let (tx, rx) = std::sync::mpsc::channel();
+        bimap
+            .par_iter()
+            .for_each_with(tx, |tx, (l, r)| tx.send((*l, *r)).unwrap());
+        let mut unordered: Vec<_> = rx.into_iter().collect();
+        unordered.sort_unstable();
+        assert_eq!(unordered.len(), 100_000);
+        assert!(unordered
+            .iter()
+            .zip(bimap.iter())
+            .all(|(a, b)| (&a.0, &a.1) == b));
+
+        assert_eq!(bimap.par_right_values().min(), bimap.right_values().min());
+        assert_eq!(bimap.par_right_values().max(), bimap.right_values().max());
+        assert_eq!(
+            bimap.par_left_values().sum::<u64>(),
+            bimap.left_values().sum::<u64>()
+        );
+        assert_eq!(
+            bimap.par_left_values().find_first(|l| **l > 500),
+            Some(&501)
+        );
+
+        let owned: Vec<_> = bimap.clone().into_par_iter().collect();
+        assert_eq!(owned, bimap.into_iter().collect::<Vec<_>>());
+    }
+
+    /// Returns a pseudo-random predicate that is expensive enough to be
+    /// worth parallelizing.
+    fn expensive(l: &u64, r: &u64) -> bool {
+        let mut state = *l ^ r.rotate_left(32);
+        for _ in 0..100 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        }
+        state >> 62 != 0
+    }
+
+    #[test]
+    fn par_retain_matches_retain() {
+        let hash: BiHashMap<u64, u64> = (0..50_000).map(|i| (i, i * 7)).collect();
+        let mut expected = hash.clone();
+        expected.retain(expensive);
+        let mut parallel = hash.clone();
+        parallel.par_retain(expensive);
+        assert_eq!(parallel, expected);
+        assert!(parallel.len() < hash.len());
+        assert!(parallel.check_invariants().is_ok());
+
+        let btree: BiBTreeMap<u64, u64> = hash.into_iter().collect();
+        let mut expected = btree.clone();
+        expected.retain(expensive);
+        let mut parallel = btree;
+        parallel.par_retain(expensive);
+        assert_eq!(parallel, expected);
+        assert!(parallel.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn par_extract_if_matches_retain() {
+        let hash: BiHashMap<u64, u64> = (0..50_000).map(|i| (i, i * 7)).collect();
+        let mut expected = hash.clone();
+        expected.retain(|l, r| !expensive(l, r));
+        let mut parallel = hash.clone();
+        let mut removed = parallel.par_extract_if(expensive);
+        assert_eq!(parallel, expected);
+        assert_eq!(removed.len() + parallel.len(), hash.len());
+        removed.sort();
+        assert!(removed
+            .iter()
+            .all(|(l, r)| expensive(l, r) && hash.get_by_left(l) == Some(r)));
+
+        let btree: BiBTreeMap<u64, u64> = hash.iter().map(|(l, r)| (*l, *r)).collect();
+        let mut parallel = btree.clone();
+        let extracted = parallel.par_extract_if(expensive);
+        assert_eq!(extracted, removed);
+        assert_eq!(parallel.len(), expected.len());
+        assert!(parallel.check_invariants().is_ok());
+
+        // nothing selected leaves the bimap untouched
+        assert!(parallel.par_extract_if(|_, _| false).is_empty());
+        assert_eq!(parallel.len(), expected.len());
+    }
+}
@@ -0,0 +1,283 @@
+//! Implementations of `rkyv::Archive`, `rkyv::Serialize` and
+//! `rkyv::Deserialize` for `BiHashMap` and `BiBTreeMap`.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `rkyv` feature in your dependency
+//! manifest.
+//!
+//! Bimaps are archived as a sequence of left-right pairs: an
+//! [`ArchivedBiMap`] is an `ArchivedVec` of `Entry` values, with the left
+//! value as the key and the right value as the value. The archive can be
+//! accessed directly without deserializing it, for example to scan the pairs
+//! of a memory-mapped file. Deserializing rebuilds both directions of the
+//! bimap by inserting the pairs in order, so later pairs overwrite earlier
+//! conflicting ones, just like deserializing with serde. Archives produced by
+//! this crate never contain conflicting pairs.
+//!
+//! Archives from untrusted sources can be validated with `bytecheck`, which is
+//! always enabled, through the checked APIs such as `rkyv::access` and
+//! `rkyv::from_bytes`.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{rkyv::ArchivedBiMap, BiHashMap};
+//! use rkyv::rancor::Error;
+//!
+//! let mut bimap = BiHashMap::new();
+//! bimap.insert(String::from("one"), 1u32);
+//! bimap.insert(String::from("two"), 2u32);
+//!
+//! let bytes = rkyv::to_bytes::<Error>(&bimap).unwrap();
+//!
+//! // read the archive in place, without deserializing it
+//! let archived = rkyv::access::<ArchivedBiMap<String, u32>, Error>(&bytes).unwrap();
+//! assert_eq!(archived.len(), 2);
+//! assert!(archived.iter().any(|entry| entry.key == "two" && entry.value == 2));
+//!
+//! // or deserialize it back into a bimap
+//! let bimap2 = rkyv::from_bytes::<BiHashMap<String, u32>, Error>(&bytes).unwrap();
+//! assert_eq!(bimap, bimap2);
+//! ```
+//!
+//! [`BiHashMap`]: crate::BiHashMap
+//! [`BiBTreeMap`]: crate::BiBTreeMap
+
+use crate::BiBTreeMap;
+use alloc::vec::Vec;
+use rkyv::{
+    collections::util::{Entry, EntryAdapter},
+    rancor::Fallible,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Archived, Deserialize, Place, Serialize,
+};
+
+/// The archived form of a bimap with left type `L` and right type `R`.
+///
+/// This is a sequence of entries whose keys are the archived left values and
+/// whose values are the archived right values. Both [`BiHashMap`] and
+/// [`BiBTreeMap`] use this representation, so an archive written from one can
+/// be deserialized as the other.
+///
+/// [`BiHashMap`]: crate::BiHashMap
+pub type ArchivedBiMap<L, R> = ArchivedVec<Entry<Archived<L>, Archived<R>>>;
+
+/// Serializes left-right pairs as an `ArchivedBiMap`.
+fn serialize_pairs<'a, L, R, I, S>(pairs: I, serializer: &mut S) -> Result<VecResolver, S::Error>
+where
+    L: Serialize<S> + 'a,
+    R: Serialize<S> + 'a,
+    I: Iterator<Item = (&'a L, &'a R)>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    let entries = pairs
+        .map(|(left, right)| EntryAdapter::<_, _, L, R>::new(left, right))
+        .collect::<Vec<_>>();
+    ArchivedVec::serialize_from_slice(&entries, serializer)
+}
+
+impl<L, R> Archive for BiBTreeMap<L, R>
+where
+    L: Archive + Ord,
+    R: Archive + Ord,
+{
+    type Archived = ArchivedBiMap<L, R>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<L, R, S> Serialize<S> for BiBTreeMap<L, R>
+where
+    L: Serialize<S> + Ord,
+    R: Serialize<S> + Ord,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        serialize_pairs(self.iter(), serializer)
+    }
+}
+
+impl<L, R, D> Deserialize<BiBTreeMap<L, R>, D> for ArchivedBiMap<L, R>
+where
+    L: Archive + Ord,
+    R: Archive + Ord,
+    Archived<L>: Deserialize<L, D>,
+    Archived<R>: Deserialize<R, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<BiBTreeMap<L, R>, D::Error> {
+        let mut map = BiBTreeMap::new();
+        for entry in self.iter() {
+            map.insert(
+                entry.key.deserialize(deserializer)?,
+                entry.value.deserialize(deserializer)?,
+            );
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "std")]
+mod hash {
+    use super::{serialize_pairs, ArchivedBiMap};
+    use crate::BiHashMap;
+    use rkyv::{
+        rancor::Fallible,
+        ser::{Allocator, Writer},
+        vec::{ArchivedVec, VecResolver},
+        Archive, Archived, Deserialize, Place, Serialize,
+    };
+    use std::hash::{BuildHasher, Hash};
+
+    impl<L, R, LS, RS> Archive for BiHashMap<L, R, LS, RS>
+    where
+        L: Archive + Eq + Hash,
+        R: Archive + Eq + Hash,
+    {
+        type Archived = ArchivedBiMap<L, R>;
+        type Resolver = VecResolver;
+
+        fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+            ArchivedVec::resolve_from_len(self.len(), resolver, out);
+        }
+    }
+
+    impl<L, R, LS, RS, S> Serialize<S> for BiHashMap<L, R, LS, RS>
+    where
+        L: Serialize<S> + Eq + Hash,
+        R: Serialize<S> + Eq + Hash,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+            serialize_pairs(self.iter(), serializer)
+        }
+    }
+
+    impl<L, R, LS, RS, D> Deserialize<BiHashMap<L, R, LS, RS>, D> for ArchivedBiMap<L, R>
+    where
+        L: Archive + Eq + Hash,
+        R: Archive + Eq + Hash,
+        Archived<L>: Deserialize<L, D>,
+        Archived<R>: Deserialize<R, D>,
+        LS: BuildHasher + Default,
+        RS: BuildHasher + Default,
+        D: Fallible + ?Sized,
+    {
+        fn deserialize(&self, deserializer: &mut D) -> Result<BiHashMap<L, R, LS, RS>, D::Error> {
+            let mut map =
+                BiHashMap::with_capacity_and_hashers(self.len(), LS::default(), RS::default());
+            for entry in self.iter() {
+                map.insert(
+                    entry.key.deserialize(deserializer)?,
+                    entry.value.deserialize(deserializer)?,
+                );
+            }
+            Ok(map)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use rkyv::rancor::Error;
+
+    #[test]
+    fn btree_round_trip() {
+        let bimap = (0..100u32)
+            .map(|i| (i, format!("{:03}", 100 - i)))
+            .collect::<BiBTreeMap<_, _>>();
+        let bytes = rkyv::to_bytes::<Error>(&bimap).unwrap();
+        let bimap2 = rkyv::from_bytes::<BiBTreeMap<u32, String>, Error>(&bytes).unwrap();
+        assert_eq!(bimap, bimap2);
+
+        let empty = BiBTreeMap::<String, i64>::new();
+        let bytes = rkyv::to_bytes::<Error>(&empty).unwrap();
+        let empty2 = rkyv::from_bytes::<BiBTreeMap<String, i64>, Error>(&bytes).unwrap();
+        assert_eq!(empty, empty2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_round_trip() {
+        use crate::BiHashMap;
+
+        let bimap = (0..100i64)
+            .map(|i| (format!("key{}", i), -i))
+            .collect::<BiHashMap<_, _>>();
+        let bytes = rkyv::to_bytes::<Error>(&bimap).unwrap();
+        let bimap2 = rkyv::from_bytes::<BiHashMap<String, i64>, Error>(&bytes).unwrap();
+        assert_eq!(bimap, bimap2);
+
+        // both bimaps share the archived representation
+        let btree = rkyv::from_bytes::<BiBTreeMap<String, i64>, Error>(&bytes).unwrap();
+        assert_eq!(btree.len(), 100);
+        assert_eq!(btree.get_by_right(&-42).map(String::as_str), Some("key42"));
+    }
+
+    #[test]
+    fn access_archive() {
+        let bimap = [(1u16, 'a'), (2, 'b'), (3, 'c')]
+            .iter()
+            .copied()
+            .collect::<BiBTreeMap<_, _>>();
+        let bytes = rkyv::to_bytes::<Error>(&bimap).unwrap();
+        let archived = rkyv::access::<ArchivedBiMap<u16, char>, Error>(&bytes).unwrap();
+        let pairs = archived
+            .iter()
+            .map(|entry| (entry.key.to_native(), char::from(entry.value)))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, [(1, 'a'), (2, 'b'), (3, 'c')]);
+    }
+
+    /// Archives plain pairs, which may conflict, as an `ArchivedBiMap`.
+    struct Pairs(Vec<(u8, u8)>);
+
+    impl Archive for Pairs {
+        type Archived = ArchivedBiMap<u8, u8>;
+        type Resolver = VecResolver;
+
+        fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+            ArchivedVec::resolve_from_len(self.0.len(), resolver, out);
+        }
+    }
+
+    impl<S> Serialize<S> for Pairs
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+            serialize_pairs(self.0.iter().map(|(l, r)| (l, r)), serializer)
+        }
+    }
+
+    #[test]
+    fn conflicting_pairs_overwrite() {
+        let pairs = Pairs(alloc::vec![(1, 10), (2, 10), (1, 30), (3, 40)]);
+        let bytes = rkyv::to_bytes::<Error>(&pairs).unwrap();
+        let bimap = rkyv::from_bytes::<BiBTreeMap<u8, u8>, Error>(&bytes).unwrap();
+        assert_eq!(
+            bimap.iter().map(|(&l, &r)| (l, r)).collect::<Vec<_>>(),
+            [(1, 30), (2, 10), (3, 40)]
+        );
+    }
+
+    #[test]
+    fn invalid_archive() {
+        let bimap = [(String::from("a"), String::from("b"))]
+            .iter()
+            .cloned()
+            .collect::<BiBTreeMap<_, _>>();
+        let mut bytes = rkyv::to_bytes::<Error>(&bimap).unwrap();
+        // corrupt the length of the archived vector, which is at the end
+        let len = bytes.len();
+        bytes[len - 4] = 0xff;
+        assert!(rkyv::from_bytes::<BiBTreeMap<String, String>, Error>(&bytes).is_err());
+        assert!(rkyv::access::<ArchivedBiMap<String, String>, Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Implementations of `schemars::JsonSchema` for `BiHashMap` and
+//! `BiBTreeMap`.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `schemars` feature in your dependency
+//! manifest.
+//!
+//! The schemas match the map representation that the `serde` feature uses
+//! for human-readable formats such as JSON, so a bimap has the same schema as
+//! a `HashMap` or `BTreeMap` with the same left and right types: an object
+//! whose `additionalProperties` are described by the right type's schema.
+//! Bimaps serialized with [`serde::as_pairs`] can use
+//! [`serde::as_pairs::json_schema`] instead, through the `schema_with`
+//! attribute.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//! use schemars::JsonSchema;
+//! use serde_json::json;
+//!
+//! #[derive(JsonSchema)]
+//! struct Config {
+//!     ports: BiBTreeMap<String, u16>,
+//! }
+//!
+//! let schema = schemars::schema_for!(Config);
+//! assert_eq!(
+//!     schema.get("properties").unwrap()["ports"],
+//!     json!({
+//!         "type": "object",
+//!         "additionalProperties": {
+//!             "type": "integer",
+//!             "format": "uint16",
+//!             "minimum": 0,
+//!             "maximum": 65535
+//!         }
+//!     })
+//! );
+//! ```
+//!
+//! [`serde::as_pairs`]: crate::serde::as_pairs
+//! [`serde::as_pairs::json_schema`]: crate::serde::as_pairs::json_schema
+
+use crate::BiBTreeMap;
+use alloc::{borrow::Cow, collections::BTreeMap};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+impl<L, R> JsonSchema for BiBTreeMap<L, R>
+where
+    L: JsonSchema,
+    R: JsonSchema,
+{
+    fn inline_schema() -> bool {
+        BTreeMap::<L, R>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        BTreeMap::<L, R>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        BTreeMap::<L, R>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        BTreeMap::<L, R>::json_schema(generator)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L, R, LS, RS> JsonSchema for crate::BiHashMap<L, R, LS, RS>
+where
+    L: JsonSchema,
+    R: JsonSchema,
+{
+    fn inline_schema() -> bool {
+        BTreeMap::<L, R>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        BTreeMap::<L, R>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        BTreeMap::<L, R>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        BTreeMap::<L, R>::json_schema(generator)
+    }
+}
@@ -1,5 +1,5 @@
 //! Implementations of `serde::Serialize` and `serde::Deserialize` for
-//! `BiHashMap` and `BiBTreeMap`.
+//! `BiHashMap`, `BiBTreeMap`, `BiIndexMap`, `BiVecMap` and `FrozenBiMap`.
 //!
 //! You do not need to import anything from this module to use this
 //! functionality, simply enable the `serde` feature in your dependency
@@ -27,7 +27,7 @@
 //! let json = serde_json::to_string(&map).unwrap();
 //!
 //! // convert the json back into a bimap
-//! let map2 = serde_json::from_str(&json).unwrap();
+//! let map2: BiHashMap<_, _> = serde_json::from_str(&json).unwrap();
 //!
 //! // check that the two bimaps are equal
 //! assert_eq!(map, map2);
@@ -48,7 +48,7 @@
 //! let json = serde_json::to_string(&map).unwrap();
 //!
 //! // convert the json back into a bimap
-//! let map2 = serde_json::from_str(&json).unwrap();
+//! let map2: BiBTreeMap<_, _> = serde_json::from_str(&json).unwrap();
 //!
 //! // check that the two bimaps are equal
 //! assert_eq!(map, map2);
@@ -75,10 +75,25 @@
 //!
 //! # Implementation details
 //!
-//! Bimaps are serialized and deserialized as a map data type in serde.
-//! Consequentially, it is possible to serialize and deserialize bimaps to/from
-//! other types that are represented the same way. *This is considered an
-//! implementation detail and should not be relied upon.*
+//! The representation of a bimap depends on the format, as reported by
+//! `Serializer::is_human_readable`:
+//!
+//! - Human-readable formats such as JSON and YAML serialize bimaps as a map
+//!   data type in serde. Consequentially, it is possible to serialize and
+//!   deserialize bimaps to/from other types that are represented the same way.
+//!   *This is considered an implementation detail and should not be relied
+//!   upon.* When deserializing, a sequence of left-right pairs (for example
+//!   `[["A", 1], ["B", 2]]`) is accepted as well as a map.
+//! - Compact binary formats such as bincode and postcard serialize bimaps as a
+//!   sequence of left-right pairs with a known length up front. This works for
+//!   any left type, since no map key restrictions apply. For bincode and
+//!   postcard, this encoding is byte-for-byte identical to that of a map, so
+//!   data written by earlier versions of this crate can still be read.
+//!
+//! Formats that restrict map keys, like JSON, can't represent bimaps whose
+//! left values aren't strings; use the [`as_pairs`] module to serialize those
+//! as a sequence of pairs instead, or the [`display_fromstr_keys`] module to
+//! turn the left values into strings.
 //!
 //! For example, a bimap can be deserialized from the serialized form of a
 //! standard [`HashMap`]. However, *deserializing a bimap silently overwrites
@@ -113,6 +128,29 @@
 //! assert!(bimap.get_by_left(&'B') == Some(&2) || bimap.get_by_left(&'C') == Some(&2))
 //! ```
 //!
+//! Binary formats behave the same way. Entries are inserted in the order they
+//! are read from the input, so whether a later conflicting entry overwrites
+//! an earlier one depends only on the order in which the serializer wrote
+//! them.
+//!
+//! Conflicts are found with the `Eq` implementations of the values, so
+//! composite values conflict whenever their contents are equal. Input that is
+//! valid for a map of strings to byte vectors, for example, loses a pair for
+//! every byte vector that appears under more than one key:
+//!
+//! ```
+//! # use bimap::BiHashMap;
+//! let json = r#"{"a": [1, 2], "b": [3], "c": [1, 2]}"#;
+//! let bimap: BiHashMap<String, Vec<u8>> = serde_json::from_str(json).unwrap();
+//!
+//! // ("a", [1, 2]) was overwritten by the later ("c", [1, 2])
+//! assert_eq!(bimap.len(), 2);
+//! assert_eq!(bimap.get_by_right(&vec![1, 2]).map(String::as_str), Some("c"));
+//! ```
+//!
+//! To find out how many pairs were overwritten, deserialize a
+//! [`WithOverwriteCount`] wrapping the bimap instead.
+//!
 //! The reverse is also possible: bimaps may be serialized and then
 //! deserialized as other compatible types, such as a [`HashMap`].
 //!
@@ -140,13 +178,99 @@
 //! assert_eq!(map[&'B'], 2);
 //! assert_eq!(map[&'C'], 3);
 //! ```
+//!
+//! # Order
+//!
+//! A [`BiBTreeMap`] is written in ascending order of its left values and a
+//! [`BiHashMap`] in arbitrary order. With the `indexmap` feature enabled,
+//! a [`BiIndexMap`] is written in insertion order and deserialized with its
+//! pairs inserted in the order they are read, so its order survives a round
+//! trip. The same holds for a [`BiVecMap`]. A [`FrozenBiMap`] is written in
+//! its iteration order and deserialized through a `BiHashMap`, so its order
+//! is arbitrary.
+//!
+//! # Flattening
+//!
+//! A bimap field marked with `#[serde(flatten)]` has its pairs merged into
+//! the parent map, since bimaps are represented as maps in human-readable
+//! formats. When deserializing, the bimap receives every entry of the parent
+//! map that doesn't belong to another field.
+//!
+//! ```
+//! # use bimap::BiHashMap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Service {
+//!     name: String,
+//!     #[serde(flatten)]
+//!     ports: BiHashMap<String, u16>,
+//! }
+//!
+//! let json = r#"{"name":"web","http":80,"https":443}"#;
+//! let service: Service = serde_json::from_str(json).unwrap();
+//! assert_eq!(service.ports.get_by_right(&443).map(String::as_str), Some("https"));
+//! ```
+//!
+//! Flattening has the usual serde limitations. It requires the map
+//! representation, so it doesn't work with compact formats such as bincode
+//! and postcard. Flattened entries are buffered with their keys as strings,
+//! so left values that aren't deserialized from strings, such as numbers,
+//! fail to deserialize; combine `flatten` with `with = "display_fromstr_keys"`
+//! (see [`display_fromstr_keys`]) to parse them instead.
+//!
+//! # `serde_with` support
+//!
+//! With the `serde_with` feature enabled, [`BiHashMap`] and [`BiBTreeMap`]
+//! implement `SerializeAs` and `DeserializeAs`, so the left and right values
+//! can be converted individually with annotations such as
+//! `#[serde_as(as = "BiHashMap<DisplayFromStr, _>")]`, just like a `HashMap`.
+//!
+//! # Borrowed data
+//!
+//! The `Deserialize` implementations are generic over the deserializer's
+//! lifetime, so bimaps of borrowed types such as `&str` can be deserialized
+//! without copying from formats that support it.
+//!
+//! ```
+//! # use bimap::BiHashMap;
+//! let json = String::from(r#"{"A":1,"B":2}"#);
+//! let bimap: BiHashMap<&str, u32> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(bimap.get_by_right(&2), Some(&"B"));
+//! ```
+//!
+//! Note that serde always deserializes a bare `Cow<str>` as an owned string,
+//! even inside a bimap. To borrow when possible and fall back to an owned
+//! string when the input requires it (for example because of escape
+//! sequences), wrap the `Cow` in a newtype marked with `#[serde(borrow)]`:
+//!
+//! ```
+//! # use bimap::BiHashMap;
+//! use serde::Deserialize;
+//! use std::borrow::Cow;
+//!
+//! #[derive(Deserialize, PartialEq, Eq, Hash)]
+//! struct Key<'a>(#[serde(borrow)] Cow<'a, str>);
+//!
+//! let json = String::from(r#"{"plain":1,"escaped\n":2}"#);
+//! let bimap: BiHashMap<Key, u32> = serde_json::from_str(&json).unwrap();
+//! assert!(matches!(bimap.get_by_right(&1), Some(Key(Cow::Borrowed("plain")))));
+//! assert!(matches!(bimap.get_by_right(&2), Some(Key(Cow::Owned(_)))));
+//! ```
+//!
 //! [`BiHashMap`]: crate::BiHashMap
 //! [`BiBTreeMap`]: crate::BiBTreeMap
+//! [`BiIndexMap`]: crate::BiIndexMap
+//! [`BiVecMap`]: crate::BiVecMap
+//! [`FrozenBiMap`]: crate::FrozenBiMap
 //! [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
 
-use crate::{BiBTreeMap, BiHashMap};
+#[cfg(feature = "indexmap")]
+use crate::BiIndexMap;
+use crate::{BiBTreeMap, BiHashMap, BiVecMap, FrozenBiMap, Overwritten};
 use serde::{
-    de::{MapAccess, Visitor},
+    de::{DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 use std::{
@@ -156,6 +280,59 @@ use std::{
     marker::PhantomData,
 };
 
+/// The largest number of pairs preallocated from a deserializer's size hint
+const MAX_PREALLOC: usize = 4096;
+
+/// Returns the initial capacity to use for a size hint read from untrusted
+/// input.
+///
+/// The hint is clamped so that input claiming a huge length can't make us
+/// allocate before any pairs have been read; the bimap grows normally if more
+/// pairs actually arrive.
+fn cautious_capacity(hint: Option<usize>) -> usize {
+    hint.unwrap_or(0).min(MAX_PREALLOC)
+}
+
+/// Serializes left-right pairs as a map for human-readable formats and as a
+/// sequence of pairs for compact ones.
+fn serialize_pairs<S, I, L, R>(ser: S, len: usize, pairs: I) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    I: IntoIterator<Item = (L, R)>,
+    L: Serialize,
+    R: Serialize,
+{
+    if ser.is_human_readable() {
+        let mut map = ser.serialize_map(Some(len))?;
+        for (left, right) in pairs {
+            map.serialize_entry(&left, &right)?;
+        }
+        map.end()
+    } else {
+        let mut seq = ser.serialize_seq(Some(len))?;
+        for pair in pairs {
+            seq.serialize_element(&pair)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes left-right pairs written by `serialize_pairs`.
+///
+/// Human-readable formats are self-describing, so they may hold either a map
+/// or a sequence of pairs; compact formats hold a sequence of pairs.
+fn deserialize_pairs<'de, D, V>(de: D, visitor: V) -> Result<V::Value, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    if de.is_human_readable() {
+        de.deserialize_any(visitor)
+    } else {
+        de.deserialize_seq(visitor)
+    }
+}
+
 /// Serializer for `BiHashMap`
 impl<L, R, LS, RS> Serialize for BiHashMap<L, R, LS, RS>
 where
@@ -165,11 +342,22 @@ where
     RS: BuildHasher + Default,
 {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
-        ser.collect_map(self.iter())
+        serialize_pairs(ser, self.len(), self.iter())
+    }
+}
+
+/// Serializer for `FrozenBiMap`, in the same format as `BiHashMap`
+impl<L, R, LS, RS> Serialize for FrozenBiMap<L, R, LS, RS>
+where
+    L: Serialize,
+    R: Serialize,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_pairs(ser, self.len(), self.iter())
     }
 }
 
-/// Visitor to construct `BiHashMap` from serialized map entries
+/// Visitor to construct `BiHashMap` from serialized map entries or pairs
 struct BiHashMapVisitor<L, R, LS, RS> {
     marker: PhantomData<BiHashMap<L, R, LS, RS>>,
 }
@@ -182,24 +370,33 @@ where
     RS: BuildHasher + Default,
 {
     fn expecting(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "a map")
+        write!(f, "a map or a sequence of pairs")
     }
 
     type Value = BiHashMap<L, R, LS, RS>;
     fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
-        let mut map = match entries.size_hint() {
-            Some(s) => BiHashMap::<L, R, LS, RS>::with_capacity_and_hashers(
-                s,
-                LS::default(),
-                RS::default(),
-            ),
-            None => BiHashMap::<L, R, LS, RS>::with_hashers(LS::default(), RS::default()),
-        };
+        let mut map = BiHashMap::with_capacity_and_hashers(
+            cautious_capacity(entries.size_hint()),
+            LS::default(),
+            RS::default(),
+        );
         while let Some((l, r)) = entries.next_entry()? {
             map.insert(l, r);
         }
         Ok(map)
     }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiHashMap::with_capacity_and_hashers(
+            cautious_capacity(pairs.size_hint()),
+            LS::default(),
+            RS::default(),
+        );
+        while let Some((l, r)) = pairs.next_element()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
 }
 
 /// Deserializer for `BiHashMap`
@@ -211,9 +408,115 @@ where
     RS: BuildHasher + Default,
 {
     fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
-        de.deserialize_map(BiHashMapVisitor::<L, R, LS, RS> {
-            marker: PhantomData::default(),
-        })
+        let visitor = BiHashMapVisitor::<L, R, LS, RS> {
+            marker: PhantomData,
+        };
+        deserialize_pairs(de, visitor)
+    }
+
+    fn deserialize_in_place<D: Deserializer<'de>>(de: D, place: &mut Self) -> Result<(), D::Error> {
+        // clearing keeps the allocations of the existing bimap for reuse
+        place.clear();
+        BiHashMapSeed::merge_into(place).deserialize(de)
+    }
+}
+
+/// Deserializes pairs into an existing `BiHashMap`.
+///
+/// The pairs are inserted with the usual [`insert`] semantics, so they
+/// overwrite any conflicting pairs already in the bimap while leaving the
+/// rest untouched. This makes it possible to apply an incremental patch to a
+/// populated bimap, or to refresh a bimap without giving up its allocations.
+/// Like the `Deserialize` implementation, the seed accepts a map or, from
+/// self-describing formats, a sequence of pairs.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{serde::BiHashMapSeed, BiHashMap};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut bimap = BiHashMap::new();
+/// bimap.insert(String::from("a"), 1);
+/// bimap.insert(String::from("b"), 2);
+///
+/// let patch = r#"{"b": 3, "c": 1}"#;
+/// let seed = BiHashMapSeed::merge_into(&mut bimap);
+/// seed.deserialize(&mut serde_json::Deserializer::from_str(patch)).unwrap();
+///
+/// // "c" took over 1 from "a", and "b" now maps to 3
+/// assert_eq!(bimap.len(), 2);
+/// assert_eq!(bimap.get_by_right(&1), Some(&String::from("c")));
+/// assert_eq!(bimap.get_by_left("b"), Some(&3));
+/// ```
+///
+/// [`insert`]: BiHashMap::insert
+pub struct BiHashMapSeed<'a, L, R, LS, RS> {
+    map: &'a mut BiHashMap<L, R, LS, RS>,
+}
+
+impl<'a, L, R, LS, RS> BiHashMapSeed<'a, L, R, LS, RS> {
+    /// Creates a seed that inserts the deserialized pairs into `map`.
+    pub fn merge_into(map: &'a mut BiHashMap<L, R, LS, RS>) -> Self {
+        BiHashMapSeed { map }
+    }
+}
+
+impl<'a, 'de, L, R, LS, RS> DeserializeSeed<'de> for BiHashMapSeed<'a, L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<(), D::Error> {
+        deserialize_pairs(de, self)
+    }
+}
+
+impl<'a, 'de, L, R, LS, RS> Visitor<'de> for BiHashMapSeed<'a, L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher,
+    RS: BuildHasher,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map or a sequence of pairs")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<(), A::Error> {
+        self.map.reserve(cautious_capacity(entries.size_hint()));
+        while let Some((l, r)) = entries.next_entry()? {
+            self.map.insert(l, r);
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<(), A::Error> {
+        self.map.reserve(cautious_capacity(pairs.size_hint()));
+        while let Some((l, r)) = pairs.next_element()? {
+            self.map.insert(l, r);
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `FrozenBiMap`, which deserializes a `BiHashMap` and
+/// freezes it
+impl<'de, L, R, LS, RS> Deserialize<'de> for FrozenBiMap<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Clone + Default,
+    RS: BuildHasher + Clone + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        BiHashMap::deserialize(de).map(BiHashMap::freeze)
     }
 }
 
@@ -224,11 +527,11 @@ where
     R: Serialize + Ord,
 {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
-        ser.collect_map(self.iter())
+        serialize_pairs(ser, self.len(), self.iter())
     }
 }
 
-/// Visitor to construct `BiBTreeMap` from serialized map entries
+/// Visitor to construct `BiBTreeMap` from serialized map entries or pairs
 struct BiBTreeMapVisitor<L, R> {
     marker: PhantomData<BiBTreeMap<L, R>>,
 }
@@ -239,7 +542,7 @@ where
     R: Deserialize<'de> + Ord,
 {
     fn expecting(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "a map")
+        write!(f, "a map or a sequence of pairs")
     }
 
     type Value = BiBTreeMap<L, R>;
@@ -250,6 +553,14 @@ where
         }
         Ok(map)
     }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiBTreeMap::new();
+        while let Some((l, r)) = pairs.next_element()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
 }
 
 /// Deserializer for `BiBTreeMap`
@@ -259,103 +570,1981 @@ where
     R: Deserialize<'de> + Ord,
 {
     fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
-        de.deserialize_map(BiBTreeMapVisitor {
-            marker: PhantomData::default(),
-        })
+        let visitor = BiBTreeMapVisitor {
+            marker: PhantomData,
+        };
+        deserialize_pairs(de, visitor)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde::de::value::Error;
-    use std::hash::BuildHasherDefault;
+/// Serializer for `BiIndexMap`, which writes the pairs in insertion order
+#[cfg(feature = "indexmap")]
+impl<L, R, LS, RS> Serialize for BiIndexMap<L, R, LS, RS>
+where
+    L: Serialize + Eq + Hash,
+    R: Serialize + Eq + Hash,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_pairs(ser, self.len(), self.iter())
+    }
+}
 
-    #[test]
-    fn serde_hash() {
-        let mut bimap = BiHashMap::new();
-        bimap.insert('a', 1);
-        bimap.insert('b', 2);
-        bimap.insert('c', 3);
+/// Visitor to construct `BiIndexMap` from serialized map entries or pairs
+#[cfg(feature = "indexmap")]
+struct BiIndexMapVisitor<L, R, LS, RS> {
+    marker: PhantomData<BiIndexMap<L, R, LS, RS>>,
+}
 
-        let json = serde_json::to_string(&bimap).unwrap();
-        let bimap2 = serde_json::from_str(&json).unwrap();
+#[cfg(feature = "indexmap")]
+impl<'de, L, R, LS, RS> Visitor<'de> for BiIndexMapVisitor<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map or a sequence of pairs")
+    }
 
-        assert_eq!(bimap, bimap2);
+    type Value = BiIndexMap<L, R, LS, RS>;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiIndexMap::with_capacity_and_hashers(
+            cautious_capacity(entries.size_hint()),
+            LS::default(),
+            RS::default(),
+        );
+        while let Some((l, r)) = entries.next_entry()? {
+            map.insert(l, r);
+        }
+        Ok(map)
     }
 
-    #[test]
-    fn serde_hash_w_fnv_hasher() {
-        let hasher_builder = BuildHasherDefault::<fnv::FnvHasher>::default();
-        let mut bimap = BiHashMap::<
-            char,
-            u8,
-            BuildHasherDefault<fnv::FnvHasher>,
-            BuildHasherDefault<fnv::FnvHasher>,
-        >::with_capacity_and_hashers(
-            4, hasher_builder.clone(), hasher_builder.clone()
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiIndexMap::with_capacity_and_hashers(
+            cautious_capacity(pairs.size_hint()),
+            LS::default(),
+            RS::default(),
         );
-        bimap.insert('f', 1);
-        bimap.insert('g', 2);
-        bimap.insert('h', 3);
+        while let Some((l, r)) = pairs.next_element()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
+}
 
-        let json = serde_json::to_string(&bimap).unwrap();
-        let bimap2 = serde_json::from_str(&json).unwrap();
+/// Deserializer for `BiIndexMap`, which inserts the pairs in the order they
+/// are read
+#[cfg(feature = "indexmap")]
+impl<'de, L, R, LS, RS> Deserialize<'de> for BiIndexMap<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = BiIndexMapVisitor {
+            marker: PhantomData,
+        };
+        deserialize_pairs(de, visitor)
+    }
+}
 
-        assert_eq!(bimap, bimap2);
+/// Serializer for `BiVecMap`, which writes the pairs in the order they are
+/// stored
+impl<L, R> Serialize for BiVecMap<L, R>
+where
+    L: Serialize,
+    R: Serialize,
+{
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_pairs(ser, self.len(), self.iter())
     }
+}
 
-    #[test]
-    fn serde_hash_w_hashbrown_hasher() {
-        let hasher_builder = hashbrown::hash_map::DefaultHashBuilder::default();
-        let mut bimap = BiHashMap::<
-            char,
-            u8,
-            hashbrown::hash_map::DefaultHashBuilder,
-            hashbrown::hash_map::DefaultHashBuilder,
-        >::with_capacity_and_hashers(
-            4, hasher_builder.clone(), hasher_builder.clone()
-        );
-        bimap.insert('x', 1);
-        bimap.insert('y', 2);
-        bimap.insert('z', 3);
+/// Visitor to construct `BiVecMap` from serialized map entries or pairs
+struct BiVecMapVisitor<L, R> {
+    marker: PhantomData<BiVecMap<L, R>>,
+}
 
-        let json = serde_json::to_string(&bimap).unwrap();
-        let bimap2 = serde_json::from_str(&json).unwrap();
+impl<'de, L, R> Visitor<'de> for BiVecMapVisitor<L, R>
+where
+    L: Deserialize<'de> + PartialEq,
+    R: Deserialize<'de> + PartialEq,
+{
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map or a sequence of pairs")
+    }
 
-        assert_eq!(bimap, bimap2);
+    type Value = BiVecMap<L, R>;
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiVecMap::with_capacity(cautious_capacity(entries.size_hint()));
+        while let Some((l, r)) = entries.next_entry()? {
+            map.insert(l, r);
+        }
+        Ok(map)
     }
 
-    #[test]
-    fn serde_btree() {
-        let mut bimap = BiBTreeMap::new();
-        bimap.insert('a', 1);
-        bimap.insert('b', 2);
-        bimap.insert('c', 3);
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiVecMap::with_capacity(cautious_capacity(pairs.size_hint()));
+        while let Some((l, r)) = pairs.next_element()? {
+            map.insert(l, r);
+        }
+        Ok(map)
+    }
+}
 
-        let json = serde_json::to_string(&bimap).unwrap();
-        let bimap2 = serde_json::from_str(&json).unwrap();
+/// Deserializer for `BiVecMap`, which inserts the pairs in the order they
+/// are read
+impl<'de, L, R> Deserialize<'de> for BiVecMap<L, R>
+where
+    L: Deserialize<'de> + PartialEq,
+    R: Deserialize<'de> + PartialEq,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = BiVecMapVisitor {
+            marker: PhantomData,
+        };
+        deserialize_pairs(de, visitor)
+    }
+}
 
-        assert_eq!(bimap, bimap2);
+/// A deserialized bimap together with the number of pairs that were
+/// overwritten while deserializing it.
+///
+/// Deserializing a bimap silently overwrites conflicting pairs, as described
+/// in the [module documentation](self). Deserializing a `WithOverwriteCount`
+/// instead of the bimap itself accepts exactly the same input and builds the
+/// same bimap, but also counts how many pairs were removed by later
+/// conflicting pairs, so that callers can warn about lossy input without
+/// rejecting it. Since every pair read from the input either ends up in the
+/// bimap or is counted, the input held `map.len() + overwritten` pairs.
+///
+/// Implementations are provided for [`BiHashMap`] and [`BiBTreeMap`].
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{serde::WithOverwriteCount, BiHashMap};
+///
+/// let json = r#"{"a": 1, "b": 2, "c": 2, "a": 3}"#;
+/// let WithOverwriteCount { map, overwritten } =
+///     serde_json::from_str::<WithOverwriteCount<BiHashMap<String, u32>>>(json).unwrap();
+///
+/// // ("b", 2) was overwritten by ("c", 2), and ("a", 1) by ("a", 3)
+/// assert_eq!(overwritten, 2);
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map.get_by_right(&2).map(String::as_str), Some("c"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithOverwriteCount<T> {
+    /// The deserialized bimap.
+    pub map: T,
+    /// The number of pairs that were removed from the bimap by later
+    /// conflicting pairs while deserializing.
+    pub overwritten: usize,
+}
+
+/// Returns the number of pairs removed by an insertion.
+///
+/// A repeated pair counts as one, since only one of the copies in the input
+/// ends up in the bimap.
+fn removed_pairs<L, R>(overwritten: &Overwritten<L, R>) -> usize {
+    match overwritten {
+        Overwritten::Neither => 0,
+        Overwritten::Left(..)
+        | Overwritten::Right(..)
+        | Overwritten::Pair(..)
+        | Overwritten::Unchanged(..) => 1,
+        Overwritten::Both(..) => 2,
     }
+}
 
-    #[test]
-    fn expecting_btree() {
-        let visitor = BiBTreeMapVisitor {
-            marker: PhantomData::<BiBTreeMap<char, i32>>,
-        };
-        let error_str = format!("{:?}", visitor.visit_bool::<Error>(true));
-        let expected = "Err(Error(\"invalid type: boolean `true`, expected a map\"))";
-        assert_eq!(error_str, expected);
+/// Visitor to construct a `BiHashMap` while counting overwritten pairs
+struct CountingBiHashMapVisitor<L, R, LS, RS> {
+    marker: PhantomData<BiHashMap<L, R, LS, RS>>,
+}
+
+impl<'de, L, R, LS, RS> Visitor<'de> for CountingBiHashMapVisitor<L, R, LS, RS>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    type Value = WithOverwriteCount<BiHashMap<L, R, LS, RS>>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map or a sequence of pairs")
     }
 
-    #[test]
-    fn expecting_hash() {
-        let visitor = BiHashMapVisitor {
-            marker: PhantomData::<BiHashMap<char, i32>>,
-        };
-        let error_str = format!("{:?}", visitor.visit_bool::<Error>(true));
-        let expected = "Err(Error(\"invalid type: boolean `true`, expected a map\"))";
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiHashMap::with_capacity_and_hashers(
+            cautious_capacity(entries.size_hint()),
+            LS::default(),
+            RS::default(),
+        );
+        let mut overwritten = 0;
+        while let Some((l, r)) = entries.next_entry()? {
+            overwritten += removed_pairs(&map.insert(l, r));
+        }
+        Ok(WithOverwriteCount { map, overwritten })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiHashMap::with_capacity_and_hashers(
+            cautious_capacity(pairs.size_hint()),
+            LS::default(),
+            RS::default(),
+        );
+        let mut overwritten = 0;
+        while let Some((l, r)) = pairs.next_element()? {
+            overwritten += removed_pairs(&map.insert(l, r));
+        }
+        Ok(WithOverwriteCount { map, overwritten })
+    }
+}
+
+impl<'de, L, R, LS, RS> Deserialize<'de> for WithOverwriteCount<BiHashMap<L, R, LS, RS>>
+where
+    L: Deserialize<'de> + Eq + Hash,
+    R: Deserialize<'de> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = CountingBiHashMapVisitor::<L, R, LS, RS> {
+            marker: PhantomData,
+        };
+        deserialize_pairs(de, visitor)
+    }
+}
+
+/// Visitor to construct a `BiBTreeMap` while counting overwritten pairs
+struct CountingBiBTreeMapVisitor<L, R> {
+    marker: PhantomData<BiBTreeMap<L, R>>,
+}
+
+impl<'de, L, R> Visitor<'de> for CountingBiBTreeMapVisitor<L, R>
+where
+    L: Deserialize<'de> + Ord,
+    R: Deserialize<'de> + Ord,
+{
+    type Value = WithOverwriteCount<BiBTreeMap<L, R>>;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "a map or a sequence of pairs")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiBTreeMap::new();
+        let mut overwritten = 0;
+        while let Some((l, r)) = entries.next_entry()? {
+            overwritten += removed_pairs(&map.insert(l, r));
+        }
+        Ok(WithOverwriteCount { map, overwritten })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+        let mut map = BiBTreeMap::new();
+        let mut overwritten = 0;
+        while let Some((l, r)) = pairs.next_element()? {
+            overwritten += removed_pairs(&map.insert(l, r));
+        }
+        Ok(WithOverwriteCount { map, overwritten })
+    }
+}
+
+impl<'de, L, R> Deserialize<'de> for WithOverwriteCount<BiBTreeMap<L, R>>
+where
+    L: Deserialize<'de> + Ord,
+    R: Deserialize<'de> + Ord,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let visitor = CountingBiBTreeMapVisitor {
+            marker: PhantomData,
+        };
+        deserialize_pairs(de, visitor)
+    }
+}
+
+/// Serialize a `BiHashMap` in ascending order of its left values.
+///
+/// The default `Serialize` implementation for [`BiHashMap`] emits pairs in
+/// the map's arbitrary iteration order, which can differ between runs and
+/// between maps holding the same pairs. This module sorts the pairs by left
+/// value first so that equal bimaps always produce identical output. Use it
+/// with the `#[serde(with = "...")]` field attribute.
+///
+/// The output has the same shape as that of the default `Serialize`
+/// implementation, so it can be deserialized with the default `Deserialize`
+/// implementation as well as with this module.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiHashMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Registry {
+///     #[serde(with = "bimap::serde::sorted")]
+///     ids: BiHashMap<char, u32>,
+/// }
+///
+/// let mut ids = BiHashMap::new();
+/// ids.insert('c', 3);
+/// ids.insert('a', 1);
+/// ids.insert('b', 2);
+/// let registry = Registry { ids };
+///
+/// let json = serde_json::to_string(&registry).unwrap();
+/// assert_eq!(json, r#"{"ids":{"a":1,"b":2,"c":3}}"#);
+///
+/// let registry2: Registry = serde_json::from_str(&json).unwrap();
+/// assert_eq!(registry, registry2);
+/// ```
+pub mod sorted {
+    use crate::BiHashMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::hash::{BuildHasher, Hash};
+
+    /// Serializes the bimap as a map with entries in ascending left order.
+    pub fn serialize<L, R, LS, RS, S>(
+        map: &BiHashMap<L, R, LS, RS>,
+        ser: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        L: Serialize + Eq + Hash + Ord,
+        R: Serialize + Eq + Hash,
+        S: Serializer,
+    {
+        let mut pairs = map.iter().collect::<Vec<_>>();
+        pairs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        super::serialize_pairs(ser, pairs.len(), pairs)
+    }
+
+    /// Deserializes the bimap from a map, exactly like the default
+    /// `Deserialize` implementation.
+    pub fn deserialize<'de, L, R, LS, RS, D>(de: D) -> Result<BiHashMap<L, R, LS, RS>, D::Error>
+    where
+        L: Deserialize<'de> + Eq + Hash,
+        R: Deserialize<'de> + Eq + Hash,
+        LS: BuildHasher + Default,
+        RS: BuildHasher + Default,
+        D: Deserializer<'de>,
+    {
+        BiHashMap::deserialize(de)
+    }
+}
+
+/// Serialize a `BiBTreeMap` in ascending order of its right values.
+///
+/// The default `Serialize` implementation for [`BiBTreeMap`] emits pairs in
+/// ascending order of their left values. This module emits them in ascending
+/// order of their right values instead, which keeps the output stable for
+/// consumers keyed on the right side. Use it with the `#[serde(with =
+/// "...")]` field attribute.
+///
+/// Only the order of the pairs differs from the default implementation, so
+/// the output can be deserialized with the default `Deserialize`
+/// implementation as well as with this module. Deserialization doesn't
+/// assume the pairs are sorted in any way.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Registry {
+///     #[serde(with = "bimap::serde::by_right")]
+///     ids: BiBTreeMap<char, u32>,
+/// }
+///
+/// let mut ids = BiBTreeMap::new();
+/// ids.insert('a', 3);
+/// ids.insert('b', 1);
+/// ids.insert('c', 2);
+/// let registry = Registry { ids };
+///
+/// let json = serde_json::to_string(&registry).unwrap();
+/// assert_eq!(json, r#"{"ids":{"b":1,"c":2,"a":3}}"#);
+///
+/// let registry2: Registry = serde_json::from_str(&json).unwrap();
+/// assert_eq!(registry, registry2);
+/// ```
+pub mod by_right {
+    use crate::BiBTreeMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes the bimap with its pairs in ascending right order.
+    pub fn serialize<L, R, S>(map: &BiBTreeMap<L, R>, ser: S) -> Result<S::Ok, S::Error>
+    where
+        L: Serialize + Ord,
+        R: Serialize + Ord,
+        S: Serializer,
+    {
+        super::serialize_pairs(ser, map.len(), map.right_range::<R, _>(..))
+    }
+
+    /// Deserializes the bimap, exactly like the default `Deserialize`
+    /// implementation.
+    pub fn deserialize<'de, L, R, D>(de: D) -> Result<BiBTreeMap<L, R>, D::Error>
+    where
+        L: Deserialize<'de> + Ord,
+        R: Deserialize<'de> + Ord,
+        D: Deserializer<'de>,
+    {
+        BiBTreeMap::deserialize(de)
+    }
+}
+
+/// Serialize a bimap as a sequence of left-right pairs.
+///
+/// By default, human-readable formats serialize bimaps as maps. Some of them
+/// restrict which types can be used as map keys; JSON, for example, only
+/// allows strings. This module instead represents a bimap as a sequence of
+/// `(left, right)` tuples in every format, which works for any left and
+/// right types. Use it with the `#[serde(with = "...")]` field attribute. It
+/// works for every bimap that can be collected from pairs, including
+/// [`BiHashMap`], [`BiBTreeMap`], [`BiIndexMap`], [`BiVecMap`] and
+/// [`FrozenBiMap`]. The pairs are written in iteration order, so the
+/// insertion order of a `BiIndexMap` or `BiVecMap` survives a round trip.
+///
+/// Deserialization uses the same insert semantics as the default
+/// implementation, so later pairs overwrite earlier conflicting ones.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Grid {
+///     #[serde(with = "bimap::serde::as_pairs")]
+///     labels: BiBTreeMap<(u8, u8), char>,
+/// }
+///
+/// let mut labels = BiBTreeMap::new();
+/// labels.insert((0, 0), 'a');
+/// labels.insert((0, 1), 'b');
+/// let grid = Grid { labels };
+///
+/// let json = serde_json::to_string(&grid).unwrap();
+/// assert_eq!(json, r#"{"labels":[[[0,0],"a"],[[0,1],"b"]]}"#);
+///
+/// let grid2: Grid = serde_json::from_str(&json).unwrap();
+/// assert_eq!(grid, grid2);
+/// ```
+pub mod as_pairs {
+    use serde::{
+        de::{SeqAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{
+        fmt::{Formatter, Result as FmtResult},
+        iter::{self, FromIterator},
+        marker::PhantomData,
+    };
+
+    /// Serializes the bimap as a sequence of left-right pairs.
+    pub fn serialize<'a, T, L, R, S>(map: &'a T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        &'a T: IntoIterator<Item = (&'a L, &'a R)>,
+        L: Serialize + 'a,
+        R: Serialize + 'a,
+        S: Serializer,
+    {
+        ser.collect_seq(map)
+    }
+
+    /// Generates the JSON schema of a bimap serialized as a sequence of
+    /// left-right pairs.
+    ///
+    /// Use it with `#[schemars(schema_with = "...")]` next to
+    /// `#[serde(with = "bimap::serde::as_pairs")]`. The left and right types
+    /// must be given explicitly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    /// use schemars::JsonSchema;
+    /// use serde::Serialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Serialize, JsonSchema)]
+    /// struct Grid {
+    ///     #[serde(with = "bimap::serde::as_pairs")]
+    ///     #[schemars(schema_with = "bimap::serde::as_pairs::json_schema::<u8, char>")]
+    ///     labels: BiBTreeMap<u8, char>,
+    /// }
+    ///
+    /// let schema = schemars::schema_for!(Grid);
+    /// let labels = &schema.get("properties").unwrap()["labels"];
+    /// assert_eq!(labels["type"], "array");
+    /// assert_eq!(labels["items"]["prefixItems"][1]["type"], "string");
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub fn json_schema<L, R>(generator: &mut schemars::SchemaGenerator) -> schemars::Schema
+    where
+        L: schemars::JsonSchema,
+        R: schemars::JsonSchema,
+    {
+        <Vec<(L, R)> as schemars::JsonSchema>::json_schema(generator)
+    }
+
+    /// Deserializes the bimap from a sequence of left-right pairs.
+    pub fn deserialize<'de, T, L, R, D>(de: D) -> Result<T, D::Error>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        de.deserialize_seq(PairsVisitor {
+            marker: PhantomData,
+        })
+    }
+
+    /// Visitor to construct a bimap from a sequence of pairs
+    struct PairsVisitor<T, L, R> {
+        marker: PhantomData<(T, L, R)>,
+    }
+
+    impl<'de, T, L, R> Visitor<'de> for PairsVisitor<T, L, R>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a sequence of pairs")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+            let mut error = None;
+            let map = iter::from_fn(|| match pairs.next_element() {
+                Ok(pair) => pair,
+                Err(e) => {
+                    error = Some(e);
+                    None
+                }
+            })
+            .collect();
+            match error {
+                Some(e) => Err(e),
+                None => Ok(map),
+            }
+        }
+    }
+}
+
+/// Serialize a bimap inside a versioned envelope.
+///
+/// Bimaps that are persisted for a long time may need their on-disk format to
+/// change later. This module wraps the pairs in a struct with an explicit
+/// format version, `{"version": 1, "pairs": [[left, right], ...]}`, so that
+/// future versions can be told apart from the current one. Deserialization
+/// checks the version and fails on any version it doesn't know. Use it with
+/// the `#[serde(with = "...")]` field attribute. It works for both
+/// [`BiHashMap`] and [`BiBTreeMap`].
+///
+/// The pairs are represented as a sequence, exactly like the [`as_pairs`]
+/// module, so any left and right types are supported in every format.
+/// Deserialization uses the same insert semantics as the default
+/// implementation, so later pairs overwrite earlier conflicting ones.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiBTreeMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Snapshot {
+///     #[serde(with = "bimap::serde::versioned")]
+///     ids: BiBTreeMap<char, u32>,
+/// }
+///
+/// let mut ids = BiBTreeMap::new();
+/// ids.insert('a', 1);
+/// ids.insert('b', 2);
+/// let snapshot = Snapshot { ids };
+///
+/// let json = serde_json::to_string(&snapshot).unwrap();
+/// assert_eq!(json, r#"{"ids":{"version":1,"pairs":[["a",1],["b",2]]}}"#);
+///
+/// let snapshot2: Snapshot = serde_json::from_str(&json).unwrap();
+/// assert_eq!(snapshot, snapshot2);
+///
+/// let future = r#"{"ids":{"version":2,"pairs":[]}}"#;
+/// assert!(serde_json::from_str::<Snapshot>(future).is_err());
+/// ```
+pub mod versioned {
+    use super::as_pairs;
+    use serde::{
+        de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor},
+        ser::SerializeStruct,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{
+        fmt::{Formatter, Result as FmtResult},
+        iter::FromIterator,
+        marker::PhantomData,
+    };
+
+    /// The version written by `serialize` and the only one `deserialize`
+    /// accepts
+    const VERSION: u32 = 1;
+
+    /// The fields of the envelope, in order
+    const FIELDS: &[&str] = &["version", "pairs"];
+
+    /// Serializes the bimap as an envelope holding the format version and a
+    /// sequence of left-right pairs.
+    pub fn serialize<'a, T, L, R, S>(map: &'a T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        &'a T: IntoIterator<Item = (&'a L, &'a R)>,
+        L: Serialize + 'a,
+        R: Serialize + 'a,
+        S: Serializer,
+    {
+        let mut envelope = ser.serialize_struct("BiMap", FIELDS.len())?;
+        envelope.serialize_field("version", &VERSION)?;
+        envelope.serialize_field(
+            "pairs",
+            &Pairs {
+                map,
+                marker: PhantomData,
+            },
+        )?;
+        envelope.end()
+    }
+
+    /// Deserializes the bimap from an envelope, failing if its version is
+    /// unknown.
+    pub fn deserialize<'de, T, L, R, D>(de: D) -> Result<T, D::Error>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        de.deserialize_struct(
+            "BiMap",
+            FIELDS,
+            EnvelopeVisitor {
+                marker: PhantomData,
+            },
+        )
+    }
+
+    /// Serializes a bimap as a sequence of left-right pairs
+    struct Pairs<'a, T, L, R> {
+        map: &'a T,
+        marker: PhantomData<(&'a L, &'a R)>,
+    }
+
+    impl<'a, T, L, R> Serialize for Pairs<'a, T, L, R>
+    where
+        &'a T: IntoIterator<Item = (&'a L, &'a R)>,
+        L: Serialize,
+        R: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            as_pairs::serialize(self.map, ser)
+        }
+    }
+
+    /// Deserializes a sequence of left-right pairs straight into a bimap
+    struct PairsSeed<T, L, R> {
+        marker: PhantomData<(T, L, R)>,
+    }
+
+    impl<'de, T, L, R> DeserializeSeed<'de> for PairsSeed<T, L, R>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<T, D::Error> {
+            as_pairs::deserialize(de)
+        }
+    }
+
+    /// Checks that the envelope's version is supported
+    fn check_version<E: de::Error>(version: u32) -> Result<(), E> {
+        if version == VERSION {
+            Ok(())
+        } else {
+            Err(E::invalid_value(
+                Unexpected::Unsigned(version.into()),
+                &"bimap format version 1",
+            ))
+        }
+    }
+
+    /// A field of the envelope
+    enum Field {
+        Version,
+        Pairs,
+        Other,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+            de.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    /// Visitor to identify a field of the envelope
+    struct FieldVisitor;
+
+    impl<'de> Visitor<'de> for FieldVisitor {
+        type Value = Field;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a field identifier")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Field, E> {
+            Ok(match v {
+                0 => Field::Version,
+                1 => Field::Pairs,
+                _ => Field::Other,
+            })
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+            Ok(match v {
+                "version" => Field::Version,
+                "pairs" => Field::Pairs,
+                _ => Field::Other,
+            })
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Field, E> {
+            Ok(match v {
+                b"version" => Field::Version,
+                b"pairs" => Field::Pairs,
+                _ => Field::Other,
+            })
+        }
+    }
+
+    /// Visitor to construct a bimap from a versioned envelope
+    struct EnvelopeVisitor<T, L, R> {
+        marker: PhantomData<(T, L, R)>,
+    }
+
+    impl<'de, T, L, R> Visitor<'de> for EnvelopeVisitor<T, L, R>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a versioned bimap")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut fields: A) -> Result<T, A::Error> {
+            let version = fields
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            check_version(version)?;
+            fields
+                .next_element_seed(PairsSeed {
+                    marker: PhantomData,
+                })?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut fields: A) -> Result<T, A::Error> {
+            let mut version = None;
+            let mut pairs = None;
+            // pairs that arrive before the version are kept aside until it
+            // has been checked
+            let mut early_pairs = None;
+            while let Some(field) = fields.next_key()? {
+                match field {
+                    Field::Version => {
+                        if version.is_some() {
+                            return Err(de::Error::duplicate_field("version"));
+                        }
+                        let v = fields.next_value()?;
+                        check_version(v)?;
+                        version = Some(v);
+                    }
+                    Field::Pairs => {
+                        if pairs.is_some() || early_pairs.is_some() {
+                            return Err(de::Error::duplicate_field("pairs"));
+                        }
+                        if version.is_some() {
+                            pairs = Some(fields.next_value_seed(PairsSeed {
+                                marker: PhantomData,
+                            })?);
+                        } else {
+                            early_pairs =
+                                Some(fields.next_value_seed(PairsSeed::<Vec<(L, R)>, L, R> {
+                                    marker: PhantomData,
+                                })?);
+                        }
+                    }
+                    Field::Other => {
+                        fields.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+            if version.is_none() {
+                return Err(de::Error::missing_field("version"));
+            }
+            match (pairs, early_pairs) {
+                (Some(pairs), _) => Ok(pairs),
+                (None, Some(early_pairs)) => Ok(early_pairs.into_iter().collect()),
+                (None, None) => Err(de::Error::missing_field("pairs")),
+            }
+        }
+    }
+}
+
+/// Serialize a bimap's left values as strings using `Display` and `FromStr`.
+///
+/// JSON object keys must be strings, so bimaps whose left values are numbers
+/// or other non-string types can't be written as ordinary JSON objects. This
+/// module formats each left value with its `Display` implementation when
+/// serializing and parses it back with `FromStr` when deserializing. Right
+/// values are (de)serialized as usual. Use it with the `#[serde(with =
+/// "...")]` field attribute. It works for both [`BiHashMap`] and
+/// [`BiBTreeMap`].
+///
+/// A left value that fails to parse is reported as a deserialization error
+/// that includes the offending string.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiHashMap;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Users {
+///     #[serde(with = "bimap::serde::display_fromstr_keys")]
+///     names: BiHashMap<u64, String>,
+/// }
+///
+/// let mut names = BiHashMap::new();
+/// names.insert(42, String::from("alice"));
+/// let users = Users { names };
+///
+/// let json = serde_json::to_string(&users).unwrap();
+/// assert_eq!(json, r#"{"names":{"42":"alice"}}"#);
+///
+/// let users2: Users = serde_json::from_str(&json).unwrap();
+/// assert_eq!(users, users2);
+///
+/// let err = serde_json::from_str::<Users>(r#"{"names":{"forty-two":"bob"}}"#).unwrap_err();
+/// assert!(err.to_string().contains("forty-two"));
+/// ```
+pub mod display_fromstr_keys {
+    use serde::{
+        de::{Error, MapAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{
+        fmt::{Display, Formatter, Result as FmtResult},
+        iter::{self, FromIterator},
+        marker::PhantomData,
+        str::FromStr,
+    };
+
+    /// Serializes the bimap as a map whose keys are the `Display` output of
+    /// the left values.
+    pub fn serialize<'a, T, L, R, S>(map: &'a T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        &'a T: IntoIterator<Item = (&'a L, &'a R)>,
+        L: Display + 'a,
+        R: Serialize + 'a,
+        S: Serializer,
+    {
+        ser.collect_map(map.into_iter().map(|(l, r)| (DisplayKey(l), r)))
+    }
+
+    /// Deserializes the bimap from a map, parsing each key with `FromStr`.
+    pub fn deserialize<'de, T, L, R, D>(de: D) -> Result<T, D::Error>
+    where
+        T: FromIterator<(L, R)>,
+        L: FromStr,
+        L::Err: Display,
+        R: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        de.deserialize_map(ParsedMapVisitor {
+            marker: PhantomData,
+        })
+    }
+
+    /// Serializes a left value as a string through its `Display` implementation
+    struct DisplayKey<'a, L>(&'a L);
+
+    impl<'a, L: Display> Serialize for DisplayKey<'a, L> {
+        fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.collect_str(self.0)
+        }
+    }
+
+    /// Deserializes a left value from a string through its `FromStr`
+    /// implementation
+    struct ParsedKey<L>(L);
+
+    impl<'de, L> Deserialize<'de> for ParsedKey<L>
+    where
+        L: FromStr,
+        L::Err: Display,
+    {
+        fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+            de.deserialize_str(ParsedKeyVisitor {
+                marker: PhantomData,
+            })
+        }
+    }
+
+    /// Visitor to parse a left value from a string
+    struct ParsedKeyVisitor<L> {
+        marker: PhantomData<L>,
+    }
+
+    impl<'de, L> Visitor<'de> for ParsedKeyVisitor<L>
+    where
+        L: FromStr,
+        L::Err: Display,
+    {
+        type Value = ParsedKey<L>;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a string")
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            v.parse()
+                .map(ParsedKey)
+                .map_err(|e| E::custom(format_args!("invalid key {:?}: {}", v, e)))
+        }
+    }
+
+    /// Visitor to construct a bimap from a map with string keys
+    struct ParsedMapVisitor<T, L, R> {
+        marker: PhantomData<(T, L, R)>,
+    }
+
+    impl<'de, T, L, R> Visitor<'de> for ParsedMapVisitor<T, L, R>
+    where
+        T: FromIterator<(L, R)>,
+        L: FromStr,
+        L::Err: Display,
+        R: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a map with string keys")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+            let mut error = None;
+            let map = iter::from_fn(|| match entries.next_entry::<ParsedKey<L>, R>() {
+                Ok(entry) => entry.map(|(l, r)| (l.0, r)),
+                Err(e) => {
+                    error = Some(e);
+                    None
+                }
+            })
+            .collect();
+            match error {
+                Some(e) => Err(e),
+                None => Ok(map),
+            }
+        }
+    }
+}
+
+/// Deserialize a bimap from untrusted input with a limit on its length.
+///
+/// The regular `Deserialize` implementations accept any number of pairs, so a
+/// malicious payload can make them allocate as much memory as it likes. The
+/// [`deserialize`](bounded::deserialize) function in this module counts the
+/// pairs as they are read and fails with a "too many entries" error as soon
+/// as there are more than `max_len` of them, before the rest of the input is
+/// even looked at. Input that announces more than `max_len` pairs up front is
+/// rejected without reading any of them.
+///
+/// The limit counts the pairs in the input, including ones that overwrite
+/// earlier pairs, so it also bounds the work done for input full of
+/// duplicates. The function works for any bimap that implements
+/// `FromIterator`, and accepts the same representations as the
+/// `Deserialize` implementations.
+///
+/// Since the limit is a parameter, the function is used through a small
+/// wrapper with the `#[serde(deserialize_with = "...")]` field attribute.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiHashMap;
+/// use serde::{Deserialize, Deserializer};
+///
+/// fn at_most_two<'de, D>(de: D) -> Result<BiHashMap<String, u32>, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     bimap::serde::bounded::deserialize(de, 2)
+/// }
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Request {
+///     #[serde(deserialize_with = "at_most_two")]
+///     ids: BiHashMap<String, u32>,
+/// }
+///
+/// let ok: Request = serde_json::from_str(r#"{"ids":{"a":1,"b":2}}"#).unwrap();
+/// assert_eq!(ok.ids.len(), 2);
+///
+/// let err = serde_json::from_str::<Request>(r#"{"ids":{"a":1,"b":2,"c":3}}"#).unwrap_err();
+/// assert!(err.to_string().contains("too many entries"));
+/// ```
+pub mod bounded {
+    use super::deserialize_pairs;
+    use serde::{
+        de::{Error, MapAccess, SeqAccess, Visitor},
+        Deserialize, Deserializer,
+    };
+    use std::{
+        fmt::{Formatter, Result as FmtResult},
+        iter::{self, FromIterator},
+        marker::PhantomData,
+    };
+
+    /// Deserializes a bimap, failing if the input holds more than `max_len`
+    /// pairs.
+    pub fn deserialize<'de, T, L, R, D>(de: D, max_len: usize) -> Result<T, D::Error>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserialize_pairs(
+            de,
+            BoundedVisitor {
+                max_len,
+                marker: PhantomData,
+            },
+        )
+    }
+
+    /// Collects the pairs returned by `next` into a bimap, stopping with an
+    /// error at the first pair past `max_len`.
+    fn collect_bounded<T, L, R, E, F>(max_len: usize, mut next: F) -> Result<T, E>
+    where
+        T: FromIterator<(L, R)>,
+        E: Error,
+        F: FnMut() -> Result<Option<(L, R)>, E>,
+    {
+        let mut error = None;
+        let mut count = 0;
+        let bimap = iter::from_fn(|| {
+            let pair = match next() {
+                Ok(pair) => pair?,
+                Err(e) => {
+                    error = Some(e);
+                    return None;
+                }
+            };
+            count += 1;
+            if count > max_len {
+                error = Some(E::custom("too many entries"));
+                return None;
+            }
+            Some(pair)
+        })
+        .collect();
+        match error {
+            Some(e) => Err(e),
+            None => Ok(bimap),
+        }
+    }
+
+    /// Visitor to construct a bimap from at most `max_len` pairs
+    struct BoundedVisitor<T, L, R> {
+        max_len: usize,
+        marker: PhantomData<(T, L, R)>,
+    }
+
+    impl<T, L, R> BoundedVisitor<T, L, R> {
+        fn check_size_hint<E: Error>(&self, hint: Option<usize>) -> Result<(), E> {
+            match hint {
+                Some(len) if len > self.max_len => Err(E::custom("too many entries")),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl<'de, T, L, R> Visitor<'de> for BoundedVisitor<T, L, R>
+    where
+        T: FromIterator<(L, R)>,
+        L: Deserialize<'de>,
+        R: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a map or a sequence of at most {} pairs", self.max_len)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+            self.check_size_hint(entries.size_hint())?;
+            collect_bounded(self.max_len, || entries.next_entry())
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+            self.check_size_hint(pairs.size_hint())?;
+            collect_bounded(self.max_len, || pairs.next_element())
+        }
+    }
+}
+
+/// Implementations of `serde_with::SerializeAs` and `serde_with::DeserializeAs`
+/// for `BiHashMap` and `BiBTreeMap`
+#[cfg(feature = "serde_with")]
+mod serde_as {
+    use super::{cautious_capacity, deserialize_pairs, serialize_pairs};
+    use crate::{BiBTreeMap, BiHashMap};
+    use serde::{
+        de::{MapAccess, SeqAccess, Visitor},
+        Deserializer, Serializer,
+    };
+    use serde_with::{de::DeserializeAsWrap, ser::SerializeAsWrap, DeserializeAs, SerializeAs};
+    use std::{
+        fmt::{Formatter, Result as FmtResult},
+        hash::{BuildHasher, Hash},
+        marker::PhantomData,
+    };
+
+    impl<L, R, LAs, RAs, LS, RS> SerializeAs<BiHashMap<L, R, LS, RS>> for BiHashMap<LAs, RAs, LS, RS>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+        LAs: SerializeAs<L>,
+        RAs: SerializeAs<R>,
+    {
+        fn serialize_as<S: Serializer>(
+            source: &BiHashMap<L, R, LS, RS>,
+            ser: S,
+        ) -> Result<S::Ok, S::Error> {
+            let pairs = source.iter().map(|(left, right)| {
+                (
+                    SerializeAsWrap::<L, LAs>::new(left),
+                    SerializeAsWrap::<R, RAs>::new(right),
+                )
+            });
+            serialize_pairs(ser, source.len(), pairs)
+        }
+    }
+
+    impl<L, R, LAs, RAs> SerializeAs<BiBTreeMap<L, R>> for BiBTreeMap<LAs, RAs>
+    where
+        L: Ord,
+        R: Ord,
+        LAs: SerializeAs<L>,
+        RAs: SerializeAs<R>,
+    {
+        fn serialize_as<S: Serializer>(
+            source: &BiBTreeMap<L, R>,
+            ser: S,
+        ) -> Result<S::Ok, S::Error> {
+            let pairs = source.iter().map(|(left, right)| {
+                (
+                    SerializeAsWrap::<L, LAs>::new(left),
+                    SerializeAsWrap::<R, RAs>::new(right),
+                )
+            });
+            serialize_pairs(ser, source.len(), pairs)
+        }
+    }
+
+    /// Visitor to construct `BiHashMap` from entries or pairs deserialized
+    /// through `LAs` and `RAs`
+    struct BiHashMapAsVisitor<L, R, LAs, RAs, LS, RS> {
+        marker: PhantomData<BiHashMap<L, R, LS, RS>>,
+        as_marker: PhantomData<(LAs, RAs)>,
+    }
+
+    impl<'de, L, R, LAs, RAs, LS, RS> Visitor<'de> for BiHashMapAsVisitor<L, R, LAs, RAs, LS, RS>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+        LAs: DeserializeAs<'de, L>,
+        RAs: DeserializeAs<'de, R>,
+        LS: BuildHasher + Default,
+        RS: BuildHasher + Default,
+    {
+        type Value = BiHashMap<L, R, LS, RS>;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a map or a sequence of pairs")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+            let mut map = BiHashMap::with_capacity_and_hashers(
+                cautious_capacity(entries.size_hint()),
+                LS::default(),
+                RS::default(),
+            );
+            while let Some((l, r)) =
+                entries.next_entry::<DeserializeAsWrap<L, LAs>, DeserializeAsWrap<R, RAs>>()?
+            {
+                map.insert(l.into_inner(), r.into_inner());
+            }
+            Ok(map)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+            let mut map = BiHashMap::with_capacity_and_hashers(
+                cautious_capacity(pairs.size_hint()),
+                LS::default(),
+                RS::default(),
+            );
+            while let Some((l, r)) =
+                pairs.next_element::<(DeserializeAsWrap<L, LAs>, DeserializeAsWrap<R, RAs>)>()?
+            {
+                map.insert(l.into_inner(), r.into_inner());
+            }
+            Ok(map)
+        }
+    }
+
+    impl<'de, L, R, LAs, RAs, LS, RS> DeserializeAs<'de, BiHashMap<L, R, LS, RS>>
+        for BiHashMap<LAs, RAs, LS, RS>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+        LAs: DeserializeAs<'de, L>,
+        RAs: DeserializeAs<'de, R>,
+        LS: BuildHasher + Default,
+        RS: BuildHasher + Default,
+    {
+        fn deserialize_as<D: Deserializer<'de>>(
+            de: D,
+        ) -> Result<BiHashMap<L, R, LS, RS>, D::Error> {
+            let visitor = BiHashMapAsVisitor::<L, R, LAs, RAs, LS, RS> {
+                marker: PhantomData,
+                as_marker: PhantomData,
+            };
+            deserialize_pairs(de, visitor)
+        }
+    }
+
+    /// Visitor to construct `BiBTreeMap` from entries or pairs deserialized
+    /// through `LAs` and `RAs`
+    struct BiBTreeMapAsVisitor<L, R, LAs, RAs> {
+        marker: PhantomData<BiBTreeMap<L, R>>,
+        as_marker: PhantomData<(LAs, RAs)>,
+    }
+
+    impl<'de, L, R, LAs, RAs> Visitor<'de> for BiBTreeMapAsVisitor<L, R, LAs, RAs>
+    where
+        L: Ord,
+        R: Ord,
+        LAs: DeserializeAs<'de, L>,
+        RAs: DeserializeAs<'de, R>,
+    {
+        type Value = BiBTreeMap<L, R>;
+
+        fn expecting(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "a map or a sequence of pairs")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut entries: A) -> Result<Self::Value, A::Error> {
+            let mut map = BiBTreeMap::new();
+            while let Some((l, r)) =
+                entries.next_entry::<DeserializeAsWrap<L, LAs>, DeserializeAsWrap<R, RAs>>()?
+            {
+                map.insert(l.into_inner(), r.into_inner());
+            }
+            Ok(map)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut pairs: A) -> Result<Self::Value, A::Error> {
+            let mut map = BiBTreeMap::new();
+            while let Some((l, r)) =
+                pairs.next_element::<(DeserializeAsWrap<L, LAs>, DeserializeAsWrap<R, RAs>)>()?
+            {
+                map.insert(l.into_inner(), r.into_inner());
+            }
+            Ok(map)
+        }
+    }
+
+    impl<'de, L, R, LAs, RAs> DeserializeAs<'de, BiBTreeMap<L, R>> for BiBTreeMap<LAs, RAs>
+    where
+        L: Ord,
+        R: Ord,
+        LAs: DeserializeAs<'de, L>,
+        RAs: DeserializeAs<'de, R>,
+    {
+        fn deserialize_as<D: Deserializer<'de>>(de: D) -> Result<BiBTreeMap<L, R>, D::Error> {
+            let visitor = BiBTreeMapAsVisitor::<L, R, LAs, RAs> {
+                marker: PhantomData,
+                as_marker: PhantomData,
+            };
+            deserialize_pairs(de, visitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::{
+        value::{Error, SeqDeserializer},
+        IntoDeserializer,
+    };
+    use std::hash::BuildHasherDefault;
+
+    #[test]
+    fn serde_hash() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        let bimap2: BiHashMap<_, _, _, _> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn serde_hash_w_fnv_hasher() {
+        let hasher_builder = BuildHasherDefault::<fnv::FnvHasher>::default();
+        let mut bimap = BiHashMap::<
+            char,
+            u8,
+            BuildHasherDefault<fnv::FnvHasher>,
+            BuildHasherDefault<fnv::FnvHasher>,
+        >::with_capacity_and_hashers(
+            4, hasher_builder.clone(), hasher_builder.clone()
+        );
+        bimap.insert('f', 1);
+        bimap.insert('g', 2);
+        bimap.insert('h', 3);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        let bimap2: BiHashMap<_, _, _, _> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn serde_hash_w_hashbrown_hasher() {
+        let hasher_builder = hashbrown::DefaultHashBuilder::default();
+        let mut bimap = BiHashMap::<
+            char,
+            u8,
+            hashbrown::DefaultHashBuilder,
+            hashbrown::DefaultHashBuilder,
+        >::with_capacity_and_hashers(
+            4, hasher_builder.clone(), hasher_builder.clone()
+        );
+        bimap.insert('x', 1);
+        bimap.insert('y', 2);
+        bimap.insert('z', 3);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        let bimap2: BiHashMap<_, _, _, _> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn serde_btree() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        let bimap2: BiBTreeMap<_, _> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn serde_flatten() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            name: String,
+            #[serde(flatten)]
+            ports: BiHashMap<String, u16>,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct SortedConfig {
+            #[serde(flatten)]
+            ports: BiBTreeMap<String, u16>,
+            name: String,
+        }
+
+        let mut ports = BiHashMap::new();
+        ports.insert("http".to_string(), 80);
+        ports.insert("https".to_string(), 443);
+        let config = Config {
+            name: "web".to_string(),
+            ports,
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"name": "web", "http": 80, "https": 443})
+        );
+        assert_eq!(serde_json::from_value::<Config>(json).unwrap(), config);
+
+        let sorted = SortedConfig {
+            ports: config.ports.iter().map(|(l, r)| (l.clone(), *r)).collect(),
+            name: "web".to_string(),
+        };
+        let json = serde_json::to_string(&sorted).unwrap();
+        assert_eq!(json, r#"{"http":80,"https":443,"name":"web"}"#);
+        assert_eq!(serde_json::from_str::<SortedConfig>(&json).unwrap(), sorted);
+    }
+
+    #[test]
+    fn serde_flatten_non_string_keys() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Codes {
+            #[serde(flatten)]
+            codes: BiBTreeMap<u16, char>,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct ParsedCodes {
+            #[serde(flatten, with = "display_fromstr_keys")]
+            codes: BiBTreeMap<u16, char>,
+        }
+
+        let codes: BiBTreeMap<u16, char> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+        let json = serde_json::to_string(&Codes {
+            codes: codes.clone(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"1":"a","2":"b"}"#);
+        // flatten buffers the keys as strings, which can't be read as numbers
+        assert!(serde_json::from_str::<Codes>(&json).is_err());
+
+        let parsed = ParsedCodes { codes };
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+        assert_eq!(serde_json::from_str::<ParsedCodes>(&json).unwrap(), parsed);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn serde_index_keeps_order() {
+        let mut bimap = BiIndexMap::new();
+        bimap.insert('z', 26);
+        bimap.insert('a', 1);
+        bimap.insert('m', 13);
+        bimap.shift_remove_by_left(&'a');
+        bimap.insert('b', 2);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        assert_eq!(json, r#"{"z":26,"m":13,"b":2}"#);
+        let bimap2: BiIndexMap<char, i32> = serde_json::from_str(&json).unwrap();
+        assert!(bimap2.iter().eq(bimap.iter()));
+
+        let bytes = bincode::serialize(&bimap).unwrap();
+        let bimap3: BiIndexMap<char, i32> = bincode::deserialize(&bytes).unwrap();
+        assert!(bimap3.iter().eq(bimap.iter()));
+    }
+
+    #[test]
+    fn serde_vec_keeps_order() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('z', 26);
+        bimap.insert('a', 1);
+        bimap.insert('m', 13);
+        bimap.remove_by_left(&'a');
+        bimap.insert('b', 2);
+
+        let json = serde_json::to_string(&bimap).unwrap();
+        assert_eq!(json, r#"{"z":26,"m":13,"b":2}"#);
+        let bimap2: BiVecMap<char, i32> = serde_json::from_str(&json).unwrap();
+        assert!(bimap2.iter().eq(bimap.iter()));
+
+        let bytes = bincode::serialize(&bimap).unwrap();
+        let bimap3: BiVecMap<char, i32> = bincode::deserialize(&bytes).unwrap();
+        assert!(bimap3.iter().eq(bimap.iter()));
+
+        // conflicting pairs overwrite earlier ones, as for the other bimaps
+        let bimap4: BiVecMap<char, i32> = serde_json::from_str(r#"{"a":1,"b":2,"c":1}"#).unwrap();
+        let pairs: Vec<_> = bimap4.iter().collect();
+        assert_eq!(pairs, [(&'b', &2), (&'c', &1)]);
+    }
+
+    #[test]
+    fn serde_frozen() {
+        let bimap: BiHashMap<char, i32> = ('a'..='z').zip(0..).collect();
+        let json = serde_json::to_string(&bimap).unwrap();
+        let bytes = bincode::serialize(&bimap).unwrap();
+
+        // same format and order as the bimap it was frozen from
+        let frozen = bimap.freeze();
+        assert_eq!(serde_json::to_string(&frozen).unwrap(), json);
+        assert_eq!(bincode::serialize(&frozen).unwrap(), bytes);
+
+        let frozen2: FrozenBiMap<char, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(frozen2, frozen);
+        let frozen3: FrozenBiMap<char, i32> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(frozen3, frozen);
+
+        let bimap2: BiHashMap<char, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(bimap2, frozen.thaw());
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn as_pairs_other_bimaps() {
+        #[derive(Serialize, Deserialize)]
+        struct Pairs {
+            #[serde(with = "as_pairs")]
+            index: BiIndexMap<(u8, u8), char>,
+            #[serde(with = "as_pairs")]
+            vec: BiVecMap<(u8, u8), char>,
+            #[serde(with = "as_pairs")]
+            frozen: FrozenBiMap<(u8, u8), char>,
+        }
+
+        let input = [((1, 1), 'z'), ((0, 0), 'a'), ((0, 1), 'm')];
+        let pairs = Pairs {
+            index: input.iter().copied().collect(),
+            vec: input.iter().copied().collect(),
+            frozen: input.iter().copied().collect(),
+        };
+        let json = serde_json::to_string(&pairs).unwrap();
+        assert!(json.starts_with(
+            r#"{"index":[[[1,1],"z"],[[0,0],"a"],[[0,1],"m"]],"vec":[[[1,1],"z"],[[0,0],"a"],[[0,1],"m"]],"frozen":"#
+        ));
+
+        let pairs2: Pairs = serde_json::from_str(&json).unwrap();
+        assert!(pairs2.index.iter().eq(pairs.index.iter()));
+        assert!(pairs2.vec.iter().eq(pairs.vec.iter()));
+        assert_eq!(pairs2.frozen, pairs.frozen);
+    }
+
+    #[test]
+    fn deserialize_hash_from_pairs() {
+        let from_map: BiHashMap<char, i32> =
+            serde_json::from_str(r#"{"a":1,"b":2,"c":3}"#).unwrap();
+        let from_seq: BiHashMap<char, i32> =
+            serde_json::from_str(r#"[["a",1],["b",2],["c",3]]"#).unwrap();
+        assert_eq!(from_map, from_seq);
+
+        // the same insert semantics apply to both shapes
+        let from_seq: BiHashMap<char, i32> =
+            serde_json::from_str(r#"[["a",1],["b",2],["c",2]]"#).unwrap();
+        assert_eq!(from_seq.len(), 2);
+        assert_eq!(from_seq.get_by_right(&2), Some(&'c'));
+    }
+
+    #[test]
+    fn deserialize_btree_from_pairs() {
+        let from_map: BiBTreeMap<char, i32> =
+            serde_json::from_str(r#"{"a":1,"b":2,"c":3}"#).unwrap();
+        let from_seq: BiBTreeMap<char, i32> =
+            serde_json::from_str(r#"[["a",1],["b",2],["c",3]]"#).unwrap();
+        assert_eq!(from_map, from_seq);
+
+        let from_seq: BiBTreeMap<char, i32> =
+            serde_json::from_str(r#"[["a",1],["b",2],["c",2]]"#).unwrap();
+        assert_eq!(from_seq.len(), 2);
+        assert_eq!(from_seq.get_by_right(&2), Some(&'c'));
+    }
+
+    #[test]
+    fn deserialize_from_pairs_bad_element() {
+        let result = serde_json::from_str::<BiHashMap<char, i32>>(r#"[["a",1],["b"]]"#);
+        assert!(result.is_err());
+        let result = serde_json::from_str::<BiBTreeMap<char, i32>>(r#"[["a",1],"b"]"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn serde_hash_sorted() {
+        let mut bimap = BiHashMap::new();
+        let mut bimap2 = BiHashMap::new();
+        for i in 0..100 {
+            bimap.insert(i, -i);
+            bimap2.insert(99 - i, i - 99);
+        }
+
+        let mut json = Vec::new();
+        sorted::serialize(&bimap, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let mut json2 = Vec::new();
+        sorted::serialize(&bimap2, &mut serde_json::Serializer::new(&mut json2)).unwrap();
+        assert_eq!(json, json2);
+
+        let bimap3: BiHashMap<i32, i32> =
+            sorted::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+        assert_eq!(bimap, bimap3);
+    }
+
+    #[test]
+    fn serde_hash_sorted_bincode() {
+        let mut bimap = BiHashMap::new();
+        let mut bimap2 = BiHashMap::new();
+        for i in 0..100 {
+            bimap.insert(i, -i);
+            bimap2.insert(99 - i, i - 99);
+        }
+
+        let options = bincode::DefaultOptions::new();
+        let mut bytes = Vec::new();
+        sorted::serialize(&bimap, &mut bincode::Serializer::new(&mut bytes, options)).unwrap();
+        let mut bytes2 = Vec::new();
+        sorted::serialize(&bimap2, &mut bincode::Serializer::new(&mut bytes2, options)).unwrap();
+        assert_eq!(bytes, bytes2);
+
+        let bimap3: BiHashMap<i32, i32> =
+            sorted::deserialize(&mut bincode::Deserializer::from_slice(&bytes, options)).unwrap();
+        assert_eq!(bimap, bimap3);
+    }
+
+    #[test]
+    fn with_overwrite_count() {
+        // ('b', 2) is overwritten on the right, ('a', 1) on the left, and
+        // ('c', 2) and ('d', 4) both at once
+        let json = r#"[["a",1],["b",2],["c",2],["a",3],["d",4],["c",4]]"#;
+        let counted: WithOverwriteCount<BiHashMap<char, i32>> = serde_json::from_str(json).unwrap();
+        assert_eq!(counted.overwritten, 4);
+        assert_eq!(
+            counted.map,
+            serde_json::from_str::<BiHashMap<_, _>>(json).unwrap()
+        );
+        assert_eq!(counted.map.len() + counted.overwritten, 6);
+
+        let counted: WithOverwriteCount<BiBTreeMap<char, i32>> =
+            serde_json::from_str(json).unwrap();
+        assert_eq!(counted.overwritten, 4);
+        assert_eq!(
+            counted.map,
+            serde_json::from_str::<BiBTreeMap<_, _>>(json).unwrap()
+        );
+
+        let counted: WithOverwriteCount<BiBTreeMap<char, i32>> =
+            serde_json::from_str(r#"{"a":1,"b":2}"#).unwrap();
+        assert_eq!(counted.overwritten, 0);
+        assert_eq!(counted.map.len(), 2);
+    }
+
+    #[test]
+    fn with_overwrite_count_bincode() {
+        let pairs = vec![(1u8, 1u8), (2, 2), (1, 1), (3, 2)];
+        let bytes = bincode::serialize(&pairs).unwrap();
+
+        let counted: WithOverwriteCount<BiHashMap<u8, u8>> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(counted.overwritten, 2);
+        assert_eq!(counted.map.get_by_right(&2), Some(&3));
+
+        let counted: WithOverwriteCount<BiBTreeMap<u8, u8>> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(counted.overwritten, 2);
+        assert_eq!(counted.map.len(), 2);
+    }
+
+    #[test]
+    fn deserialize_in_place_keeps_capacity() {
+        let mut bimap = BiHashMap::with_capacity(1000);
+        for i in 0..1000 {
+            bimap.insert(i, -i);
+        }
+        let capacity = bimap.capacity();
+
+        let mut de = serde_json::Deserializer::from_str(r#"{"1":2,"3":4}"#);
+        Deserialize::deserialize_in_place(&mut de, &mut bimap).unwrap();
+        let expected: BiHashMap<_, _> = [(1, 2), (3, 4)].iter().copied().collect();
+        assert_eq!(bimap, expected);
+        assert!(bimap.capacity() >= capacity);
+
+        let mut de = serde_json::Deserializer::from_str("[[5,6]]");
+        Deserialize::deserialize_in_place(&mut de, &mut bimap).unwrap();
+        let expected: BiHashMap<_, _> = [(5, 6)].iter().copied().collect();
+        assert_eq!(bimap, expected);
+        assert!(bimap.capacity() >= capacity);
+    }
+
+    #[test]
+    fn merge_into_overwrites() {
+        let mut bimap: BiHashMap<char, i32> = [('a', 1), ('b', 2), ('c', 3), ('d', 4)]
+            .iter()
+            .copied()
+            .collect();
+        // 'a' changes value, 'e' takes 2 from 'b', and 'c' and 'd' swap values
+        let patch = r#"{"a":10,"e":2,"c":4,"d":3}"#;
+        let seed = BiHashMapSeed::merge_into(&mut bimap);
+        seed.deserialize(&mut serde_json::Deserializer::from_str(patch))
+            .unwrap();
+
+        let expected: BiHashMap<_, _> = [('a', 10), ('e', 2), ('c', 4), ('d', 3)]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(bimap, expected);
+
+        // binary formats support merging too
+        use bincode::Options;
+        let options = bincode::DefaultOptions::new();
+        let mut patch = BiHashMap::new();
+        patch.insert('f', 10);
+        let bytes = options.serialize(&patch).unwrap();
+        let seed = BiHashMapSeed::merge_into(&mut bimap);
+        seed.deserialize(&mut bincode::Deserializer::from_slice(&bytes, options))
+            .unwrap();
+        assert_eq!(bimap.len(), 4);
+        assert_eq!(bimap.get_by_right(&10), Some(&'f'));
+    }
+
+    #[test]
+    fn merge_into_error_keeps_earlier_pairs() {
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        let seed = BiHashMapSeed::merge_into(&mut bimap);
+        let result = seed.deserialize(&mut serde_json::Deserializer::from_str(
+            r#"{"b":2,"c":"x"}"#,
+        ));
+        assert!(result.is_err());
+        assert_eq!(bimap.len(), 2);
+        assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    }
+
+    /// Map and sequence access that claims far more pairs than it yields
+    struct LyingAccess {
+        pairs: std::vec::IntoIter<(i32, i32)>,
+        value: Option<i32>,
+    }
+
+    impl LyingAccess {
+        fn new(pairs: Vec<(i32, i32)>) -> Self {
+            LyingAccess {
+                pairs: pairs.into_iter(),
+                value: None,
+            }
+        }
+    }
+
+    impl<'de> MapAccess<'de> for LyingAccess {
+        type Error = Error;
+
+        fn next_key_seed<K: DeserializeSeed<'de>>(
+            &mut self,
+            seed: K,
+        ) -> Result<Option<K::Value>, Error> {
+            match self.pairs.next() {
+                Some((l, r)) => {
+                    self.value = Some(r);
+                    seed.deserialize(l.into_deserializer()).map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+            seed.deserialize(self.value.take().unwrap().into_deserializer())
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(usize::MAX)
+        }
+    }
+
+    impl<'de> SeqAccess<'de> for LyingAccess {
+        type Error = Error;
+
+        fn next_element_seed<T: DeserializeSeed<'de>>(
+            &mut self,
+            seed: T,
+        ) -> Result<Option<T::Value>, Error> {
+            match self.pairs.next() {
+                Some((l, r)) => {
+                    let pair = SeqDeserializer::new(vec![l, r].into_iter());
+                    seed.deserialize(pair).map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(usize::MAX)
+        }
+    }
+
+    #[test]
+    fn size_hint_is_clamped() {
+        let pairs = [(1, -1), (2, -2), (3, -3)];
+        let visitor = || BiHashMapVisitor {
+            marker: PhantomData::<BiHashMap<i32, i32>>,
+        };
+
+        // preallocating from the hint would overflow the capacity and panic
+        let bimap = visitor()
+            .visit_map(LyingAccess::new(pairs.to_vec()))
+            .unwrap();
+        assert_eq!(bimap.len(), 3);
+        assert!(bimap.capacity() <= 2 * MAX_PREALLOC);
+
+        let bimap = visitor()
+            .visit_seq(LyingAccess::new(pairs.to_vec()))
+            .unwrap();
+        assert_eq!(bimap.len(), 3);
+        assert!(bimap.capacity() <= 2 * MAX_PREALLOC);
+    }
+
+    #[test]
+    fn cautious_capacity_limits() {
+        assert_eq!(cautious_capacity(None), 0);
+        assert_eq!(cautious_capacity(Some(10)), 10);
+        assert_eq!(cautious_capacity(Some(usize::MAX)), MAX_PREALLOC);
+    }
+
+    #[test]
+    fn bounded_limit() {
+        fn parse(json: &str, max_len: usize) -> Result<BiBTreeMap<char, i32>, serde_json::Error> {
+            bounded::deserialize(&mut serde_json::Deserializer::from_str(json), max_len)
+        }
+
+        let bimap = parse(r#"{"a":1,"b":2}"#, 2).unwrap();
+        assert_eq!(bimap.len(), 2);
+        let bimap = parse(r#"[["a",1],["b",2]]"#, 2).unwrap();
+        assert_eq!(bimap.len(), 2);
+        assert!(parse("{}", 0).unwrap().is_empty());
+
+        let err = parse(r#"{"a":1,"b":2,"c":3}"#, 2).unwrap_err();
+        assert!(err.to_string().starts_with("too many entries"));
+        let err = parse(r#"[["a",1],["b",2],["c",3]]"#, 2).unwrap_err();
+        assert!(err.to_string().starts_with("too many entries"));
+
+        // overwritten pairs count towards the limit
+        assert!(parse(r#"{"a":1,"a":2,"a":3}"#, 2).is_err());
+
+        // the input is rejected before the rest of it is read
+        let err = parse(r#"{"a":1,"b":2,"c":3,!!!"#, 2).unwrap_err();
+        assert!(err.to_string().starts_with("too many entries"));
+    }
+
+    #[test]
+    fn bounded_size_hint() {
+        use bincode::Options;
+        let options = bincode::DefaultOptions::new();
+        let bimap: BiHashMap<char, i32> = [('a', 1), ('b', 2), ('c', 3)].iter().copied().collect();
+        let bytes = options.serialize(&bimap).unwrap();
+
+        let mut de = bincode::Deserializer::from_slice(&bytes, options);
+        let bimap2: BiHashMap<char, i32> = bounded::deserialize(&mut de, 3).unwrap();
+        assert_eq!(bimap, bimap2);
+
+        // the length prefix alone is enough to reject the input
+        let mut de = bincode::Deserializer::from_slice(&bytes[..1], options);
+        let err = bounded::deserialize::<BiHashMap<char, i32>, _, _, _>(&mut de, 2).unwrap_err();
+        assert_eq!(err.to_string(), "too many entries");
+    }
+
+    #[test]
+    fn expecting_btree() {
+        let visitor = BiBTreeMapVisitor {
+            marker: PhantomData::<BiBTreeMap<char, i32>>,
+        };
+        let error_str = format!("{:?}", visitor.visit_bool::<Error>(true));
+        let expected =
+            "Err(Error(\"invalid type: boolean `true`, expected a map or a sequence of pairs\"))";
+        assert_eq!(error_str, expected);
+    }
+
+    #[test]
+    fn expecting_hash() {
+        let visitor = BiHashMapVisitor {
+            marker: PhantomData::<BiHashMap<char, i32>>,
+        };
+        let error_str = format!("{:?}", visitor.visit_bool::<Error>(true));
+        let expected =
+            "Err(Error(\"invalid type: boolean `true`, expected a map or a sequence of pairs\"))";
         assert_eq!(error_str, expected);
     }
+
+    #[test]
+    fn serde_composite_right() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Pairs {
+            #[serde(with = "as_pairs")]
+            hash: BiHashMap<String, Vec<u8>>,
+            #[serde(with = "as_pairs")]
+            btree: BiBTreeMap<String, Vec<u8>>,
+        }
+
+        let input = [("empty", vec![]), ("one", vec![1]), ("two", vec![1, 2])];
+        let hash: BiHashMap<_, _> = input
+            .iter()
+            .map(|(l, r)| (l.to_string(), r.clone()))
+            .collect();
+        let btree: BiBTreeMap<_, _> = hash.iter().map(|(l, r)| (l.clone(), r.clone())).collect();
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BiHashMap<_, _>>(&json).unwrap(),
+            hash
+        );
+        let bytes = bincode::serialize(&btree).unwrap();
+        assert_eq!(
+            bincode::deserialize::<BiBTreeMap<_, _>>(&bytes).unwrap(),
+            btree
+        );
+
+        let pairs = Pairs { hash, btree };
+        let json = serde_json::to_string(&pairs).unwrap();
+        assert_eq!(serde_json::from_str::<Pairs>(&json).unwrap(), pairs);
+        let bytes = postcard::to_allocvec(&pairs).unwrap();
+        assert_eq!(postcard::from_bytes::<Pairs>(&bytes).unwrap(), pairs);
+    }
+
+    #[test]
+    fn serde_composite_right_conflicts() {
+        // the equal byte vectors under "a" and "c" conflict, and the later
+        // pair wins in both representations
+        let map = r#"{"a": [1, 2], "b": [], "c": [1, 2]}"#;
+        let seq = r#"[["a", [1, 2]], ["b", []], ["c", [1, 2]]]"#;
+        for json in [map, seq] {
+            let WithOverwriteCount { map, overwritten } =
+                serde_json::from_str::<WithOverwriteCount<BiHashMap<String, Vec<u8>>>>(json)
+                    .unwrap();
+            assert_eq!(overwritten, 1);
+            assert_eq!(map.len(), 2);
+            assert!(!map.contains_left("a"));
+            assert_eq!(map.get_by_right(&vec![1, 2]).map(String::as_str), Some("c"));
+            assert_eq!(map.get_by_left("b"), Some(&vec![]));
+            assert!(map.check_invariants().is_ok());
+
+            let btree: BiBTreeMap<String, Vec<u8>> = serde_json::from_str(json).unwrap();
+            assert_eq!(btree.len(), 2);
+            assert_eq!(
+                btree.get_by_right(&vec![1, 2]).map(String::as_str),
+                Some("c")
+            );
+        }
+    }
 }
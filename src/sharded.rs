@@ -0,0 +1,997 @@
+//! A thread-safe bimap sharded across many `RwLock`s.
+//!
+//! A [`ShardedBiMap`] splits its pairs across a fixed number of shards, each
+//! behind its own [`RwLock`]. Left values and right values are assigned to
+//! shards independently by hash, so a pair is typically stored in two
+//! different shards: its left-to-right entry lives in the shard of the left
+//! value, and its right-to-left entry in the shard of the right value.
+//!
+//! Lookups only take a read lock on a single shard, so readers never contend
+//! with each other, and writers only block the shards they touch. Operations
+//! that modify the bimap lock every shard they need in ascending order, which
+//! rules out deadlocks between concurrent writers. If a writer finds that it
+//! needs another shard after taking its locks (for example, because inserting
+//! a pair overwrites an existing pair stored elsewhere), it releases its
+//! locks and tries again with the larger set of shards.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::ShardedBiMap;
+//! use std::{sync::Arc, thread};
+//!
+//! let ids = Arc::new(ShardedBiMap::new());
+//!
+//! let handles = (0..4)
+//!     .map(|t| {
+//!         let ids = Arc::clone(&ids);
+//!         thread::spawn(move || {
+//!             for i in 0..100 {
+//!                 let id = t * 100 + i;
+//!                 ids.insert(format!("user{}", id), id);
+//!             }
+//!         })
+//!     })
+//!     .collect::<Vec<_>>();
+//! for handle in handles {
+//!     handle.join().unwrap();
+//! }
+//!
+//! assert_eq!(ids.len(), 400);
+//! assert_eq!(ids.get_by_left("user123"), Some(123));
+//! assert_eq!(ids.get_by_right(&399), Some(String::from("user399")));
+//! ```
+
+use crate::{
+    mem::{hash_one, SyncRef, Wrapper},
+    Overwritten,
+};
+use std::{
+    borrow::Borrow,
+    collections::{hash_map, HashMap},
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::FromIterator,
+    num::NonZeroUsize,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    thread,
+};
+
+/// A thread-safe bimap whose pairs are spread across independently locked
+/// shards.
+///
+/// Values can't be borrowed out of a shard once its lock is released, so the
+/// lookup methods return clones of the stored values.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::sharded
+pub struct ShardedBiMap<L, R, S = hash_map::RandomState> {
+    shards: Box<[RwLock<Shard<L, R, S>>]>,
+    hash_builder: S,
+}
+
+/// One shard of a `ShardedBiMap`, holding the left-to-right entries of the
+/// left values and the right-to-left entries of the right values that hash
+/// to it
+struct Shard<L, R, S> {
+    left2right: HashMap<SyncRef<L>, SyncRef<R>, S>,
+    right2left: HashMap<SyncRef<R>, SyncRef<L>, S>,
+}
+
+/// The write guards of a set of shards, along with their sorted indices
+struct Locked<'a, L, R, S> {
+    indices: Vec<usize>,
+    guards: Vec<RwLockWriteGuard<'a, Shard<L, R, S>>>,
+}
+
+impl<'a, L, R, S> Locked<'a, L, R, S> {
+    fn shard(&mut self, index: usize) -> &mut Shard<L, R, S> {
+        // unwrap is safe because the shard was locked for the operation
+        let i = self.indices.binary_search(&index).unwrap();
+        &mut self.guards[i]
+    }
+}
+
+impl<L, R> ShardedBiMap<L, R, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Creates an empty `ShardedBiMap` with a default number of shards.
+    ///
+    /// The default is four shards per available CPU.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::<char, i32>::new();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        let cpus = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::with_shards(4 * cpus)
+    }
+
+    /// Creates an empty `ShardedBiMap` with the given number of shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::<char, i32>::with_shards(16);
+    /// assert_eq!(bimap.shard_count(), 16);
+    /// ```
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, hash_map::RandomState::new())
+    }
+}
+
+impl<L, R, S> ShardedBiMap<L, R, S>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    S: BuildHasher + Clone,
+{
+    /// Creates an empty `ShardedBiMap` with the given number of shards, using
+    /// `hash_builder` both to assign values to shards and to hash them within
+    /// each shard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let bimap = ShardedBiMap::<char, i32>::with_shards_and_hasher(8, RandomState::new());
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(1));
+    /// ```
+    pub fn with_shards_and_hasher(shards: usize, hash_builder: S) -> Self {
+        assert!(shards > 0, "a ShardedBiMap needs at least one shard");
+        let shards = (0..shards)
+            .map(|_| {
+                RwLock::new(Shard {
+                    left2right: HashMap::with_hasher(hash_builder.clone()),
+                    right2left: HashMap::with_hasher(hash_builder.clone()),
+                })
+            })
+            .collect();
+        ShardedBiMap {
+            shards,
+            hash_builder,
+        }
+    }
+}
+
+impl<L, R, S> ShardedBiMap<L, R, S>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Returns the number of shards in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::<char, i32>::with_shards(4);
+    /// assert_eq!(bimap.shard_count(), 4);
+    /// ```
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// The shards are counted one at a time, so if other threads are
+    /// modifying the bimap at the same time the result may not correspond to
+    /// any single moment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// assert_eq!(bimap.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| read(shard).left2right.len())
+            .sum()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// assert!(bimap.is_empty());
+    /// bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// bimap.remove_by_right(&1);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| read(shard).left2right.is_empty())
+    }
+
+    /// Returns clones of all left-right pairs in the bimap, in arbitrary
+    /// order.
+    ///
+    /// Every shard is read-locked for the duration of the copy, so unlike
+    /// [`len`](ShardedBiMap::len), the result is a consistent snapshot of a
+    /// single moment: each left value and each right value appears at most
+    /// once. Writers are blocked while the snapshot is taken, but lookups
+    /// are not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    ///
+    /// let mut pairs = bimap.snapshot();
+    /// pairs.sort();
+    /// assert_eq!(pairs, [('a', 1), ('b', 2)]);
+    /// ```
+    pub fn snapshot(&self) -> Vec<(L, R)>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let guards = self.shards.iter().map(read).collect::<Vec<_>>();
+        guards
+            .iter()
+            .flat_map(|shard| {
+                shard
+                    .left2right
+                    .iter()
+                    .map(|(left, right)| ((*left.0).clone(), (*right.0).clone()))
+            })
+            .collect()
+    }
+
+    /// Removes all pairs from the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.clear();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        // every shard must be held at once so that no pair is half removed
+        let mut guards = self.shards.iter().map(write).collect::<Vec<_>>();
+        for shard in guards.iter_mut() {
+            shard.left2right.clear();
+            shard.right2left.clear();
+        }
+    }
+
+    /// Returns a clone of the right value corresponding to the given left
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<R>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        R: Clone,
+    {
+        read(&self.shards[self.shard_of(left)])
+            .left2right
+            .get(Wrapper::wrap(left))
+            .map(|r| R::clone(&r.0))
+    }
+
+    /// Returns a clone of the left value corresponding to the given right
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some('a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<L>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        L: Clone,
+    {
+        read(&self.shards[self.shard_of(right)])
+            .right2left
+            .get(Wrapper::wrap(right))
+            .map(|l| L::clone(&l.0))
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        read(&self.shards[self.shard_of(left)])
+            .left2right
+            .contains_key(Wrapper::wrap(left))
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        read(&self.shards[self.shard_of(right)])
+            .right2left
+            .contains_key(Wrapper::wrap(right))
+    }
+
+    /// Removes the left-right pair corresponding to the given left value.
+    ///
+    /// Returns the previous left-right pair if the map contained the left value
+    /// and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_left(&'b'), None);
+    /// ```
+    pub fn remove_by_left<Q>(&self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let left_shard = self.shard_of(left);
+        let mut locked = self.lock(&[left_shard], |locked| {
+            let right = locked.shard(left_shard).left2right.get(Wrapper::wrap(left));
+            [right.map(|r| self.shard_of(&*r.0)), None]
+        });
+        self.remove_left_in(&mut locked, left_shard, left)
+    }
+
+    /// Removes the left-right pair corresponding to the given right value.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but `Eq`
+    /// and `Hash` on the borrowed form *must* match those for the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_right(&2), None);
+    /// ```
+    pub fn remove_by_right<Q>(&self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let right_shard = self.shard_of(right);
+        let mut locked = self.lock(&[right_shard], |locked| {
+            let left = locked
+                .shard(right_shard)
+                .right2left
+                .get(Wrapper::wrap(right));
+            [left.map(|l| self.shard_of(&*l.0)), None]
+        });
+        self.remove_right_in(&mut locked, right_shard, right)
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`, exactly like
+    /// [`BiHashMap::insert`](crate::BiHashMap::insert). The whole operation
+    /// is atomic: other threads either see the bimap before the insertion or
+    /// after it, never in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{Overwritten, ShardedBiMap};
+    ///
+    /// let bimap = ShardedBiMap::new();
+    ///
+    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Left('a', 1));
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Right('a', 2));
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Unchanged('b', 2));
+    ///
+    /// assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+    /// assert_eq!(bimap.insert('b', 3), Overwritten::Both(('b', 2), ('c', 3)));
+    /// ```
+    pub fn insert(&self, left: L, right: R) -> Overwritten<L, R> {
+        let left_shard = self.shard_of(&left);
+        let right_shard = self.shard_of(&right);
+        let mut locked = self.lock(&[left_shard, right_shard], |locked| {
+            // the shards holding the other halves of any overwritten pairs
+            let old_right = locked
+                .shard(left_shard)
+                .left2right
+                .get(Wrapper::wrap(&left));
+            let old_right = old_right.map(|r| self.shard_of(&*r.0));
+            let old_left = locked
+                .shard(right_shard)
+                .right2left
+                .get(Wrapper::wrap(&right));
+            let old_left = old_left.map(|l| self.shard_of(&*l.0));
+            [old_right, old_left]
+        });
+        if matches!(
+            locked.shard(left_shard).left2right.get(Wrapper::wrap(&left)),
+            Some(r) if *r.0 == right
+        ) {
+            // the pair is already present, so neither map needs to change
+            return Overwritten::Unchanged(left, right);
+        }
+        let retval = match (
+            self.remove_left_in(&mut locked, left_shard, &left),
+            self.remove_right_in(&mut locked, right_shard, &right),
+        ) {
+            (None, None) => Overwritten::Neither,
+            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
+            (Some(l_pair), None) => Overwritten::Left(l_pair.0, l_pair.1),
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        };
+        Self::insert_unchecked(&mut locked, left_shard, right_shard, left, right);
+        retval
+    }
+
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right))` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::ShardedBiMap;
+    ///
+    /// let bimap = ShardedBiMap::new();
+    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('b', 2), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
+    /// assert_eq!(bimap.insert_no_overwrite('c', 2), Err(('c', 2)));
+    /// ```
+    pub fn insert_no_overwrite(&self, left: L, right: R) -> Result<(), (L, R)> {
+        let left_shard = self.shard_of(&left);
+        let right_shard = self.shard_of(&right);
+        let mut locked = self.lock(&[left_shard, right_shard], |_| [None, None]);
+        if locked
+            .shard(left_shard)
+            .left2right
+            .contains_key(Wrapper::wrap(&left))
+            || locked
+                .shard(right_shard)
+                .right2left
+                .contains_key(Wrapper::wrap(&right))
+        {
+            Err((left, right))
+        } else {
+            Self::insert_unchecked(&mut locked, left_shard, right_shard, left, right);
+            Ok(())
+        }
+    }
+
+    /// Returns the index of the shard responsible for the given value.
+    fn shard_of<Q: Hash + ?Sized>(&self, value: &Q) -> usize {
+        // the shards' maps hash with the same hasher and pick buckets from
+        // the low bits, so the shard is picked from the high bits to keep the
+        // values of a shard spread over its buckets
+        let hash = hash_one(&self.hash_builder, value);
+        ((hash >> 32) % self.shards.len() as u64) as usize
+    }
+
+    /// Write-locks the shards in `initial` plus any shards that `required`
+    /// reports as also being needed, and returns the guards of all of them.
+    ///
+    /// Shards are always locked in ascending order so that concurrent callers
+    /// can't deadlock. When `required` asks for a shard that isn't held yet,
+    /// every lock is released and the larger set is locked from scratch,
+    /// since the values that pointed to it may have changed in the meantime.
+    /// The set of shards only grows between attempts, so this terminates.
+    fn lock(
+        &self,
+        initial: &[usize],
+        required: impl Fn(&mut Locked<L, R, S>) -> [Option<usize>; 2],
+    ) -> Locked<'_, L, R, S> {
+        let mut indices = initial.to_vec();
+        loop {
+            indices.sort_unstable();
+            indices.dedup();
+            let guards = indices.iter().map(|&i| write(&self.shards[i])).collect();
+            let mut locked = Locked { indices, guards };
+            let missing = required(&mut locked)
+                .iter()
+                .flatten()
+                .filter(|i| locked.indices.binary_search(i).is_err())
+                .copied()
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                return locked;
+            }
+            indices = locked.indices;
+            drop(locked.guards);
+            indices.extend(missing);
+        }
+    }
+
+    /// Inserts the given pair into the locked shards without checking for
+    /// existing values.
+    fn insert_unchecked(
+        locked: &mut Locked<L, R, S>,
+        left_shard: usize,
+        right_shard: usize,
+        left: L,
+        right: R,
+    ) {
+        let left = SyncRef(Arc::new(left));
+        let right = SyncRef(Arc::new(right));
+        locked
+            .shard(left_shard)
+            .left2right
+            .insert(left.clone(), right.clone());
+        locked.shard(right_shard).right2left.insert(right, left);
+    }
+
+    /// Removes the pair of the given left value, whose left-to-right entry is
+    /// in `left_shard`. The shard of its right value must also be locked.
+    fn remove_left_in<Q>(
+        &self,
+        locked: &mut Locked<L, R, S>,
+        left_shard: usize,
+        left: &Q,
+    ) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let right_rc = locked
+            .shard(left_shard)
+            .left2right
+            .remove(Wrapper::wrap(left))?;
+        // unwrap is safe because we know right2left contains the key (it's a bimap)
+        let left_rc = locked
+            .shard(self.shard_of(&*right_rc.0))
+            .right2left
+            .remove(&right_rc)
+            .unwrap();
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Arc::try_unwrap(left_rc.0).ok().unwrap(),
+            Arc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+
+    /// Removes the pair of the given right value, whose right-to-left entry
+    /// is in `right_shard`. The shard of its left value must also be locked.
+    fn remove_right_in<Q>(
+        &self,
+        locked: &mut Locked<L, R, S>,
+        right_shard: usize,
+        right: &Q,
+    ) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let left_rc = locked
+            .shard(right_shard)
+            .right2left
+            .remove(Wrapper::wrap(right))?;
+        // unwrap is safe because we know left2right contains the key (it's a bimap)
+        let right_rc = locked
+            .shard(self.shard_of(&*left_rc.0))
+            .left2right
+            .remove(&left_rc)
+            .unwrap();
+        // at this point we can safely unwrap because the other pointers are gone
+        Some((
+            Arc::try_unwrap(left_rc.0).ok().unwrap(),
+            Arc::try_unwrap(right_rc.0).ok().unwrap(),
+        ))
+    }
+}
+
+/// Read-locks a shard, propagating any panic that poisoned it
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read()
+        .expect("a thread panicked while modifying the bimap")
+}
+
+/// Write-locks a shard, propagating any panic that poisoned it
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write()
+        .expect("a thread panicked while modifying the bimap")
+}
+
+impl<L, R, S> fmt::Debug for ShardedBiMap<L, R, S>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct EntryDebugger<'a, L, R> {
+            left: &'a L,
+            right: &'a R,
+        }
+        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+        where
+            L: fmt::Debug,
+            R: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.left.fmt(f)?;
+                write!(f, " <> ")?;
+                self.right.fmt(f)
+            }
+        }
+        // hold every shard so that the output is a consistent snapshot
+        let guards = self.shards.iter().map(read).collect::<Vec<_>>();
+        f.debug_set()
+            .entries(guards.iter().flat_map(|shard| {
+                shard.left2right.iter().map(|(left, right)| EntryDebugger {
+                    left: &*left.0,
+                    right: &*right.0,
+                })
+            }))
+            .finish()
+    }
+}
+
+impl<L, R> Default for ShardedBiMap<L, R, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    fn default() -> ShardedBiMap<L, R, hash_map::RandomState> {
+        ShardedBiMap::new()
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for ShardedBiMap<L, R, hash_map::RandomState>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    fn from_iter<I>(iter: I) -> ShardedBiMap<L, R, hash_map::RandomState>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let bimap = ShardedBiMap::new();
+        for (left, right) in iter {
+            bimap.insert(left, right);
+        }
+        bimap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::Barrier, thread};
+
+    /// Checks that every left-to-right entry has a matching right-to-left
+    /// entry in the right shard, and vice versa.
+    fn assert_consistent<L, R, S>(bimap: &ShardedBiMap<L, R, S>)
+    where
+        L: Eq + Hash + fmt::Debug,
+        R: Eq + Hash + fmt::Debug,
+        S: BuildHasher,
+    {
+        let guards = bimap.shards.iter().map(read).collect::<Vec<_>>();
+        let mut lefts = 0;
+        let mut rights = 0;
+        for (i, shard) in guards.iter().enumerate() {
+            for (left, right) in &shard.left2right {
+                assert_eq!(bimap.shard_of(&*left.0), i);
+                let back = guards[bimap.shard_of(&*right.0)].right2left.get(right);
+                assert_eq!(back, Some(left));
+                lefts += 1;
+            }
+            for (right, left) in &shard.right2left {
+                assert_eq!(bimap.shard_of(&*right.0), i);
+                let back = guards[bimap.shard_of(&*left.0)].left2right.get(left);
+                assert_eq!(back, Some(right));
+                rights += 1;
+            }
+        }
+        assert_eq!(lefts, rights);
+    }
+
+    #[test]
+    fn insert() {
+        let bimap = ShardedBiMap::with_shards(7);
+
+        assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+        assert_eq!(bimap.insert('a', 2), Overwritten::Left('a', 1));
+        assert_eq!(bimap.insert('b', 2), Overwritten::Right('a', 2));
+        assert_eq!(bimap.insert('b', 2), Overwritten::Unchanged('b', 2));
+
+        assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+        assert_eq!(bimap.insert('b', 3), Overwritten::Both(('b', 2), ('c', 3)));
+        assert_eq!(bimap.len(), 1);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn shard_low_bits_spread() {
+        let bimap = ShardedBiMap::with_shards(8);
+        for i in 0..1024 {
+            bimap.insert(i, i);
+        }
+        // the values of a shard must not share the low hash bits that its
+        // maps use to pick buckets
+        let shard = read(&bimap.shards[0]);
+        let low_bits = shard
+            .left2right
+            .keys()
+            .map(|l| hash_one(&bimap.hash_builder, &*l.0) % 8)
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(low_bits.len(), 8);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn insert_no_overwrite() {
+        let bimap = ShardedBiMap::with_shards(3);
+
+        assert!(bimap.insert_no_overwrite('a', 1).is_ok());
+        assert!(bimap.insert_no_overwrite('a', 2).is_err());
+        assert!(bimap.insert_no_overwrite('b', 1).is_err());
+        assert_eq!(bimap.len(), 1);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn remove() {
+        let bimap = (0..100).map(|i| (i, -i)).collect::<ShardedBiMap<_, _>>();
+        for i in 0..50 {
+            assert_eq!(bimap.remove_by_left(&i), Some((i, -i)));
+            assert_eq!(bimap.remove_by_right(&(-i - 50)), Some((i + 50, -i - 50)));
+        }
+        assert!(bimap.is_empty());
+        assert_eq!(bimap.remove_by_left(&0), None);
+        assert_eq!(bimap.remove_by_right(&0), None);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn single_shard() {
+        let bimap = ShardedBiMap::with_shards(1);
+        for i in 0..10 {
+            bimap.insert(i, i % 3);
+        }
+        assert_eq!(bimap.len(), 3);
+        assert_eq!(bimap.get_by_right(&0), Some(9));
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_shards() {
+        ShardedBiMap::<char, i32>::with_shards(0);
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        let bimap = ShardedBiMap::new();
+        bimap.insert(String::from("a"), String::from("b"));
+        assert_eq!(bimap.get_by_left("a"), Some(String::from("b")));
+        assert_eq!(bimap.get_by_right("b"), Some(String::from("a")));
+        assert!(bimap.contains_left("a"));
+        assert!(bimap.contains_right("b"));
+        assert_eq!(
+            bimap.remove_by_left("a"),
+            Some((String::from("a"), String::from("b")))
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let bimap = ShardedBiMap::new();
+        assert_eq!("{}", format!("{:?}", bimap));
+        bimap.insert('a', 1);
+        assert_eq!("{'a' <> 1}", format!("{:?}", bimap));
+    }
+
+    #[test]
+    fn clear() {
+        let bimap = (0..10).map(|i| (i, i)).collect::<ShardedBiMap<_, _>>();
+        bimap.clear();
+        assert!(bimap.is_empty());
+        assert_eq!(bimap.get_by_left(&1), None);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn concurrent_overwrites() {
+        // a small key space with few shards forces inserts to overwrite pairs
+        // stored in shards they didn't initially lock
+        const THREADS: usize = 8;
+        let bimap = Arc::new(ShardedBiMap::with_shards(4));
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles = (0..THREADS)
+            .map(|t| {
+                let bimap = Arc::clone(&bimap);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..2000 {
+                        let left = (i * 7 + t) % 32;
+                        let right = (i * 13 + t * 3) % 32;
+                        match i % 4 {
+                            0 => {
+                                bimap.remove_by_left(&left);
+                            }
+                            1 => {
+                                bimap.remove_by_right(&right);
+                            }
+                            _ => {
+                                bimap.insert(left, right);
+                            }
+                        }
+                        if let Some(r) = bimap.get_by_left(&left) {
+                            assert!(r < 32);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(bimap.len() <= 32);
+        assert_consistent(&bimap);
+    }
+
+    #[test]
+    fn snapshot_is_consistent() {
+        const WRITERS: usize = 4;
+        let bimap = Arc::new(ShardedBiMap::with_shards(8));
+        let barrier = Arc::new(Barrier::new(WRITERS + 1));
+        let writers = (0..WRITERS)
+            .map(|t| {
+                let bimap = Arc::clone(&bimap);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..2000 {
+                        // conflicting pairs keep overwriting each other
+                        bimap.insert((i * 5 + t) % 16, (i * 11 + t * 7) % 16);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        barrier.wait();
+        for _ in 0..200 {
+            let pairs = bimap.snapshot();
+            let mut lefts = pairs.iter().map(|&(l, _)| l).collect::<Vec<_>>();
+            let mut rights = pairs.iter().map(|&(_, r)| r).collect::<Vec<_>>();
+            lefts.sort_unstable();
+            lefts.dedup();
+            rights.sort_unstable();
+            rights.dedup();
+            assert_eq!(lefts.len(), pairs.len());
+            assert_eq!(rights.len(), pairs.len());
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_consistent(&bimap);
+        assert_eq!(bimap.snapshot().len(), bimap.len());
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ShardedBiMap<String, u64>>();
+    }
+}
@@ -0,0 +1,265 @@
+//! Implementations of `speedy::Readable` and `speedy::Writable` for
+//! `BiHashMap` and `BiBTreeMap`.
+//!
+//! You do not need to import anything from this module to use this
+//! functionality, simply enable the `speedy` feature in your dependency
+//! manifest.
+//!
+//! A bimap is written as a `u32` length followed by its left-right pairs,
+//! each written as the left value and then the right value, which is the
+//! same layout speedy uses for a `HashMap` or `BTreeMap`. Integers are written
+//! with the endianness of the speedy context. A `BiHashMap` writes its pairs
+//! in arbitrary order, while a `BiBTreeMap` writes them in ascending left
+//! order.
+//!
+//! Reading rebuilds both directions of the bimap. If the input contains the
+//! same left value or the same right value more than once, reading fails with
+//! an error instead of silently overwriting the earlier pair. To accept such
+//! input with the usual overwriting semantics of `insert`, read a
+//! `Vec<(L, R)>` and collect it into a bimap instead.
+//!
+//! The length is not trusted when reading: at most a small number of pairs
+//! is preallocated, so a corrupted length fails with an end of input error
+//! instead of exhausting memory.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiHashMap;
+//! use speedy::{Endianness, Readable, Writable};
+//!
+//! let mut bimap = BiHashMap::new();
+//! bimap.insert(String::from("one"), 1u32);
+//! bimap.insert(String::from("two"), 2u32);
+//!
+//! let bytes = bimap.write_to_vec_with_ctx(Endianness::BigEndian).unwrap();
+//! let bimap2 = BiHashMap::read_from_buffer_with_ctx(Endianness::BigEndian, &bytes).unwrap();
+//! assert_eq!(bimap, bimap2);
+//!
+//! // duplicate right values are rejected
+//! let pairs = vec![(String::from("one"), 1u32), (String::from("uno"), 1u32)];
+//! let bytes = pairs.write_to_vec().unwrap();
+//! assert!(BiHashMap::<String, u32>::read_from_buffer(&bytes).is_err());
+//! ```
+
+use crate::{BiBTreeMap, BiHashMap};
+use speedy::{Context, Readable, Reader, Writable, Writer};
+use std::{
+    convert::TryFrom,
+    hash::{BuildHasher, Hash},
+    mem,
+};
+
+/// The largest number of pairs preallocated from an untrusted length.
+const MAX_PREALLOC: usize = 4096;
+
+/// Writes the length and then the given pairs.
+fn write_pairs<'a, C, W, L, R, I>(len: usize, pairs: I, writer: &mut W) -> Result<(), C::Error>
+where
+    C: Context,
+    W: ?Sized + Writer<C>,
+    L: Writable<C> + 'a,
+    R: Writable<C> + 'a,
+    I: IntoIterator<Item = (&'a L, &'a R)>,
+{
+    let len = u32::try_from(len).map_err(|_| speedy::Error::custom("bimap is too long"))?;
+    writer.write_u32(len)?;
+    for (left, right) in pairs {
+        writer.write_value(left)?;
+        writer.write_value(right)?;
+    }
+    Ok(())
+}
+
+/// Returns the number of bytes needed to write the given pairs.
+fn pairs_bytes_needed<'a, C, L, R, I>(pairs: I) -> Result<usize, C::Error>
+where
+    C: Context,
+    L: Writable<C> + 'a,
+    R: Writable<C> + 'a,
+    I: IntoIterator<Item = (&'a L, &'a R)>,
+{
+    let mut count = mem::size_of::<u32>();
+    for (left, right) in pairs {
+        count += left.bytes_needed()? + right.bytes_needed()?;
+    }
+    Ok(count)
+}
+
+/// Reads the length and then the pairs into a map created by `with_capacity`.
+///
+/// `insert` returns `false` if the pair conflicts with an earlier one.
+fn read_pairs<'a, C, Rd, L, R, M>(
+    reader: &mut Rd,
+    with_capacity: impl FnOnce(usize) -> M,
+    mut insert: impl FnMut(&mut M, L, R) -> bool,
+) -> Result<M, C::Error>
+where
+    C: Context,
+    Rd: Reader<'a, C>,
+    L: Readable<'a, C>,
+    R: Readable<'a, C>,
+{
+    let len = reader.read_u32()? as usize;
+    let mut map = with_capacity(len.min(MAX_PREALLOC));
+    for _ in 0..len {
+        let left = reader.read_value()?;
+        let right = reader.read_value()?;
+        if !insert(&mut map, left, right) {
+            return Err(speedy::Error::custom("duplicate left or right value in bimap").into());
+        }
+    }
+    Ok(map)
+}
+
+impl<C, L, R, LS, RS> Writable<C> for BiHashMap<L, R, LS, RS>
+where
+    C: Context,
+    L: Writable<C> + Eq + Hash,
+    R: Writable<C> + Eq + Hash,
+{
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        write_pairs(self.len(), self.iter(), writer)
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        pairs_bytes_needed(self.iter())
+    }
+}
+
+impl<'a, C, L, R, LS, RS> Readable<'a, C> for BiHashMap<L, R, LS, RS>
+where
+    C: Context,
+    L: Readable<'a, C> + Eq + Hash,
+    R: Readable<'a, C> + Eq + Hash,
+    LS: BuildHasher + Default,
+    RS: BuildHasher + Default,
+{
+    fn read_from<Rd: Reader<'a, C>>(reader: &mut Rd) -> Result<Self, C::Error> {
+        read_pairs(
+            reader,
+            |capacity| BiHashMap::with_capacity_and_hashers(capacity, LS::default(), RS::default()),
+            |map, left, right| map.insert_no_overwrite(left, right).is_ok(),
+        )
+    }
+
+    fn minimum_bytes_needed() -> usize {
+        mem::size_of::<u32>()
+    }
+}
+
+impl<C, L, R> Writable<C> for BiBTreeMap<L, R>
+where
+    C: Context,
+    L: Writable<C> + Ord,
+    R: Writable<C> + Ord,
+{
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        write_pairs(self.len(), self.iter(), writer)
+    }
+
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        pairs_bytes_needed(self.iter())
+    }
+}
+
+impl<'a, C, L, R> Readable<'a, C> for BiBTreeMap<L, R>
+where
+    C: Context,
+    L: Readable<'a, C> + Ord,
+    R: Readable<'a, C> + Ord,
+{
+    fn read_from<Rd: Reader<'a, C>>(reader: &mut Rd) -> Result<Self, C::Error> {
+        read_pairs(
+            reader,
+            |_| BiBTreeMap::new(),
+            |map, left, right| map.insert_no_overwrite(left, right).is_ok(),
+        )
+    }
+
+    fn minimum_bytes_needed() -> usize {
+        mem::size_of::<u32>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use speedy::{Endianness, IsEof};
+
+    #[test]
+    fn round_trip() {
+        let hash = (0..100u64)
+            .map(|i| (format!("key{}", i), i * 1000))
+            .collect::<BiHashMap<_, _>>();
+        let btree = (0..100i16)
+            .map(|i| (i, i.to_string()))
+            .collect::<BiBTreeMap<_, _>>();
+
+        for &endianness in &[Endianness::LittleEndian, Endianness::BigEndian] {
+            let bytes = hash.write_to_vec_with_ctx(endianness).unwrap();
+            assert_eq!(
+                bytes.len(),
+                Writable::<Endianness>::bytes_needed(&hash).unwrap()
+            );
+            let hash2 = BiHashMap::read_from_buffer_with_ctx(endianness, &bytes).unwrap();
+            assert_eq!(hash, hash2);
+
+            let bytes = btree.write_to_vec_with_ctx(endianness).unwrap();
+            assert_eq!(
+                bytes.len(),
+                Writable::<Endianness>::bytes_needed(&btree).unwrap()
+            );
+            let btree2 = BiBTreeMap::read_from_buffer_with_ctx(endianness, &bytes).unwrap();
+            assert_eq!(btree, btree2);
+        }
+    }
+
+    #[test]
+    fn same_layout_as_maps() {
+        let pairs = [(1u16, 'a'), (2, 'b'), (3, 'c')];
+        let bimap = pairs.iter().copied().collect::<BiBTreeMap<_, _>>();
+        let map = pairs
+            .iter()
+            .copied()
+            .collect::<std::collections::BTreeMap<_, _>>();
+        let ctx = Endianness::BigEndian;
+        assert_eq!(
+            bimap.write_to_vec_with_ctx(ctx).unwrap(),
+            map.write_to_vec_with_ctx(ctx).unwrap()
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        for pairs in &[
+            vec![(1u8, 2u8), (1, 3)],
+            vec![(1, 2), (3, 2)],
+            vec![(1, 2), (1, 2)],
+        ] {
+            let bytes = pairs.write_to_vec().unwrap();
+            assert!(BiHashMap::<u8, u8>::read_from_buffer(&bytes).is_err());
+            assert!(BiBTreeMap::<u8, u8>::read_from_buffer(&bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn corrupted_length() {
+        let bimap = vec![(String::from("a"), 1u32)]
+            .into_iter()
+            .collect::<BiHashMap<_, _>>();
+        let mut bytes = bimap
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap();
+        // claim that there are u32::MAX pairs
+        bytes[..4].copy_from_slice(&[0xff; 4]);
+        let err =
+            BiHashMap::<String, u32>::read_from_buffer_with_ctx(Endianness::LittleEndian, &bytes)
+                .unwrap_err();
+        assert!(err.is_eof());
+        let err =
+            BiBTreeMap::<String, u32>::read_from_buffer_with_ctx(Endianness::LittleEndian, &bytes)
+                .unwrap_err();
+        assert!(err.is_eof());
+    }
+}
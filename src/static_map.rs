@@ -0,0 +1,697 @@
+//! A bimap built at compile time by the [`static_bimap!`] macro.
+//!
+//! A [`StaticBiMap`] is a table of pairs known at compile time, such as
+//! keywords and their opcodes. The macro stores the pairs in a static array
+//! and sorts two arrays of indices into it, one by left value and one by
+//! right value, while the program is being compiled. Lookups are binary
+//! searches over these arrays, so nothing is allocated or initialized at run
+//! time, and the table can live in a `static` item. Duplicate left or right
+//! values are a compile error.
+//!
+//! Since the values are compared during compilation, they must be of one of
+//! the types the macro knows how to compare: the integer types, `bool`,
+//! `char`, `&'static str` and `&'static [u8]`. Sorting uses insertion sort, so
+//! the macro is meant for tables of up to a few thousand pairs.
+//!
+//! [`static_bimap!`]: crate::static_bimap
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::{static_bimap, StaticBiMap};
+//!
+//! static_bimap! {
+//!     /// The opcode of each keyword.
+//!     static OPCODES: StaticBiMap<&'static str, u8> = [
+//!         ("nop", 0x00),
+//!         ("push", 0x01),
+//!         ("pop", 0x02),
+//!         ("add", 0x10),
+//!     ];
+//! }
+//!
+//! assert_eq!(OPCODES.len(), 4);
+//! assert_eq!(OPCODES.get_by_left("push"), Some(&0x01));
+//! assert_eq!(OPCODES.get_by_right(&0x10), Some(&"add"));
+//! assert_eq!(OPCODES.get_by_left("mul"), None);
+//!
+//! // iteration follows the order of the macro input
+//! let keywords: Vec<_> = OPCODES.left_values().copied().collect();
+//! assert_eq!(keywords, ["nop", "push", "pop", "add"]);
+//! ```
+//!
+//! Duplicate values on either side are rejected when compiling:
+//!
+//! ```compile_fail
+//! use bimap::{static_bimap, StaticBiMap};
+//!
+//! static_bimap! {
+//!     static OPCODES: StaticBiMap<&'static str, u8> = [
+//!         ("push", 0x01),
+//!         ("pop", 0x01),
+//!     ];
+//! }
+//! # let _ = OPCODES.len();
+//! ```
+
+use core::{borrow::Borrow, cmp::Ordering, fmt, iter::FusedIterator, marker::PhantomData, slice};
+
+/// A bimap whose pairs and indices are built at compile time by
+/// [`static_bimap!`](crate::static_bimap).
+///
+/// Iteration follows the order in which the pairs were given to the macro.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::static_map
+pub struct StaticBiMap<L: 'static, R: 'static> {
+    pairs: &'static [(L, R)],
+    // indices into pairs, sorted by left value and by right value
+    left_index: &'static [usize],
+    right_index: &'static [usize],
+}
+
+impl<L, R> StaticBiMap<L, R> {
+    /// Creates a bimap from its pairs and the index arrays that sort them.
+    ///
+    /// This is an implementation detail of [`static_bimap!`] and not part of
+    /// the public API.
+    #[doc(hidden)]
+    pub const fn __from_sorted_parts(
+        pairs: &'static [(L, R)],
+        left_index: &'static [usize],
+        right_index: &'static [usize],
+    ) -> Self {
+        StaticBiMap {
+            pairs,
+            left_index,
+            right_index,
+        }
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static DIGITS: StaticBiMap<char, u8> = [('0', 0), ('1', 1)];
+    /// }
+    /// assert_eq!(DIGITS.len(), 2);
+    /// ```
+    pub const fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static EMPTY: StaticBiMap<char, u8> = [];
+    /// }
+    /// assert!(EMPTY.is_empty());
+    /// ```
+    pub const fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap, in the
+    /// order they were given to [`static_bimap!`](crate::static_bimap).
+    ///
+    /// The iterator element type is `(&'static L, &'static R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static DIGITS: StaticBiMap<char, u8> = [('1', 1), ('0', 0)];
+    /// }
+    /// let pairs: Vec<_> = DIGITS.iter().collect();
+    /// assert_eq!(pairs, [(&'1', &1), (&'0', &0)]);
+    /// ```
+    pub fn iter(&self) -> Iter<L, R> {
+        Iter {
+            inner: self.pairs.iter(),
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap, in the order
+    /// they were given to [`static_bimap!`](crate::static_bimap).
+    ///
+    /// The iterator element type is `&'static L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static DIGITS: StaticBiMap<char, u8> = [('1', 1), ('0', 0)];
+    /// }
+    /// let lefts: Vec<_> = DIGITS.left_values().collect();
+    /// assert_eq!(lefts, [&'1', &'0']);
+    /// ```
+    pub fn left_values(&self) -> LeftValues<L, R> {
+        LeftValues {
+            inner: self.pairs.iter(),
+        }
+    }
+
+    /// Creates an iterator over the right values in the bimap, in the order
+    /// they were given to [`static_bimap!`](crate::static_bimap).
+    ///
+    /// The iterator element type is `&'static R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static DIGITS: StaticBiMap<char, u8> = [('1', 1), ('0', 0)];
+    /// }
+    /// let rights: Vec<_> = DIGITS.right_values().collect();
+    /// assert_eq!(rights, [&1, &0]);
+    /// ```
+    pub fn right_values(&self) -> RightValues<L, R> {
+        RightValues {
+            inner: self.pairs.iter(),
+        }
+    }
+}
+
+impl<L, R> StaticBiMap<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static COLORS: StaticBiMap<&'static str, u32> = [("red", 0xff0000)];
+    /// }
+    /// assert_eq!(COLORS.get_by_left("red"), Some(&0xff0000));
+    /// assert_eq!(COLORS.get_by_left("blue"), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&'static R>
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let pairs = self.pairs;
+        search(self.left_index, |i| pairs[i].0.borrow().cmp(left)).map(|i| &pairs[i].1)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but the
+    /// ordering on the borrowed form *must* match the ordering on the right
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static COLORS: StaticBiMap<&'static str, u32> = [("red", 0xff0000)];
+    /// }
+    /// assert_eq!(COLORS.get_by_right(&0xff0000), Some(&"red"));
+    /// assert_eq!(COLORS.get_by_right(&0x0000ff), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&'static L>
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let pairs = self.pairs;
+        search(self.right_index, |i| pairs[i].1.borrow().cmp(right)).map(|i| &pairs[i].0)
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but the
+    /// ordering on the borrowed form *must* match the ordering on the left
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static COLORS: StaticBiMap<&'static str, u32> = [("red", 0xff0000)];
+    /// }
+    /// assert!(COLORS.contains_left("red"));
+    /// assert!(!COLORS.contains_left("blue"));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_by_left(left).is_some()
+    }
+
+    /// Returns `true` if the bimap contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but the
+    /// ordering on the borrowed form *must* match the ordering on the right
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{static_bimap, StaticBiMap};
+    ///
+    /// static_bimap! {
+    ///     static COLORS: StaticBiMap<&'static str, u32> = [("red", 0xff0000)];
+    /// }
+    /// assert!(COLORS.contains_right(&0xff0000));
+    /// assert!(!COLORS.contains_right(&0x0000ff));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_by_right(right).is_some()
+    }
+}
+
+/// Binary searches an index array sorted by `cmp`, returning the index of
+/// the pair for which `cmp` returns `Equal`.
+fn search<F>(index: &[usize], mut cmp: F) -> Option<usize>
+where
+    F: FnMut(usize) -> Ordering,
+{
+    index
+        .binary_search_by(|&i| cmp(i))
+        .ok()
+        .map(|pos| index[pos])
+}
+
+impl<L, R> Clone for StaticBiMap<L, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L, R> Copy for StaticBiMap<L, R> {}
+
+impl<L, R> fmt::Debug for StaticBiMap<L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct EntryDebugger<'a, L, R> {
+            left: &'a L,
+            right: &'a R,
+        }
+        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+        where
+            L: fmt::Debug,
+            R: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.left.fmt(f)?;
+                write!(f, " <> ")?;
+                self.right.fmt(f)
+            }
+        }
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R> IntoIterator for &StaticBiMap<L, R> {
+    type Item = (&'static L, &'static R);
+    type IntoIter = Iter<L, R>;
+
+    fn into_iter(self) -> Iter<L, R> {
+        self.iter()
+    }
+}
+
+/// An iterator over the left-right pairs in a `StaticBiMap`.
+///
+/// This struct is created by the [`iter`] method of `StaticBiMap`.
+///
+/// [`iter`]: StaticBiMap::iter
+#[derive(Debug, Clone)]
+pub struct Iter<L: 'static, R: 'static> {
+    inner: slice::Iter<'static, (L, R)>,
+}
+
+impl<L, R> ExactSizeIterator for Iter<L, R> {}
+
+impl<L, R> FusedIterator for Iter<L, R> {}
+
+impl<L, R> Iterator for Iter<L, R> {
+    type Item = (&'static L, &'static R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (l, r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `StaticBiMap`.
+///
+/// This struct is created by the [`left_values`] method of `StaticBiMap`.
+///
+/// [`left_values`]: StaticBiMap::left_values
+#[derive(Debug, Clone)]
+pub struct LeftValues<L: 'static, R: 'static> {
+    inner: slice::Iter<'static, (L, R)>,
+}
+
+impl<L, R> ExactSizeIterator for LeftValues<L, R> {}
+
+impl<L, R> FusedIterator for LeftValues<L, R> {}
+
+impl<L, R> Iterator for LeftValues<L, R> {
+    type Item = &'static L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, _)| l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `StaticBiMap`.
+///
+/// This struct is created by the [`right_values`] method of `StaticBiMap`.
+///
+/// [`right_values`]: StaticBiMap::right_values
+#[derive(Debug, Clone)]
+pub struct RightValues<L: 'static, R: 'static> {
+    inner: slice::Iter<'static, (L, R)>,
+}
+
+impl<L, R> ExactSizeIterator for RightValues<L, R> {}
+
+impl<L, R> FusedIterator for RightValues<L, R> {}
+
+impl<L, R> Iterator for RightValues<L, R> {
+    type Item = &'static R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, r)| r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Compile-time comparison of the value types supported by
+/// [`static_bimap!`].
+///
+/// Trait methods can't be called in constants, so each supported type gets
+/// an inherent `cmp` function on its own instantiation of this type instead,
+/// which the macro picks by naming the type. Every one of them agrees with
+/// the type's `Ord` implementation, which the lookups rely on.
+///
+/// This is an implementation detail of [`static_bimap!`] and not part of the
+/// public API.
+#[doc(hidden)]
+pub struct __ConstOrd<T: ?Sized>(PhantomData<T>);
+
+macro_rules! impl_const_ord {
+    ($($t:ty)*) => {
+        $(
+            impl __ConstOrd<$t> {
+                #[doc(hidden)]
+                pub const fn cmp(a: &$t, b: &$t) -> Ordering {
+                    if *a < *b {
+                        Ordering::Less
+                    } else if *a > *b {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Equal
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_const_ord!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize char);
+
+impl __ConstOrd<bool> {
+    #[doc(hidden)]
+    pub const fn cmp(a: &bool, b: &bool) -> Ordering {
+        __ConstOrd::<u8>::cmp(&(*a as u8), &(*b as u8))
+    }
+}
+
+impl __ConstOrd<&'static [u8]> {
+    #[doc(hidden)]
+    pub const fn cmp(a: &&[u8], b: &&[u8]) -> Ordering {
+        let (a, b) = (*a, *b);
+        let mut i = 0;
+        while i < a.len() && i < b.len() {
+            if a[i] != b[i] {
+                return __ConstOrd::<u8>::cmp(&a[i], &b[i]);
+            }
+            i += 1;
+        }
+        __ConstOrd::<usize>::cmp(&a.len(), &b.len())
+    }
+}
+
+impl __ConstOrd<&'static str> {
+    #[doc(hidden)]
+    pub const fn cmp(a: &&str, b: &&str) -> Ordering {
+        __ConstOrd::<&[u8]>::cmp(&a.as_bytes(), &b.as_bytes())
+    }
+}
+
+/// Creates a `static` [`StaticBiMap`] from a list of pairs, sorting its
+/// indices at compile time.
+///
+/// The left and right types must be spelled out in the item's type. They
+/// must be integer types, `bool`, `char`, `&'static str` or
+/// `&'static [u8]`. If two pairs share a left value or a right value, the
+/// constant evaluation of the item fails and the crate doesn't compile.
+///
+/// See the [module-level documentation](crate::static_map) for details.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{static_bimap, StaticBiMap};
+///
+/// static_bimap! {
+///     pub static HTTP_STATUS: StaticBiMap<u16, &'static str> = [
+///         (200, "OK"),
+///         (404, "Not Found"),
+///         (500, "Internal Server Error"),
+///     ];
+/// }
+///
+/// assert_eq!(HTTP_STATUS.get_by_left(&404), Some(&"Not Found"));
+/// assert_eq!(HTTP_STATUS.get_by_right("OK"), Some(&200));
+/// ```
+#[macro_export]
+macro_rules! static_bimap {
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: StaticBiMap<$l:ty, $r:ty> = [
+            $(($left:expr, $right:expr)),* $(,)?
+        ];
+    ) => {
+        $(#[$attr])*
+        $vis static $name: $crate::StaticBiMap<$l, $r> = {
+            const PAIRS: &[($l, $r)] = &[$(($left, $right)),*];
+            const LEFT_INDEX: [usize; PAIRS.len()] =
+                $crate::__static_bimap_index!(PAIRS, $l, 0, "duplicate left value in static_bimap!");
+            const RIGHT_INDEX: [usize; PAIRS.len()] =
+                $crate::__static_bimap_index!(PAIRS, $r, 1, "duplicate right value in static_bimap!");
+            $crate::StaticBiMap::__from_sorted_parts(PAIRS, &LEFT_INDEX, &RIGHT_INDEX)
+        };
+    };
+}
+
+/// Sorts the indices of `$pairs` by the field `$field` of type `$t` at
+/// compile time, panicking with `$duplicate` if two pairs share a value.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_bimap_index {
+    ($pairs:ident, $t:ty, $field:tt, $duplicate:literal) => {{
+        const LEN: usize = $pairs.len();
+        let mut index = [0; LEN];
+        let mut i = 0;
+        while i < LEN {
+            index[i] = i;
+            i += 1;
+        }
+        // insertion sort, since trait methods like `sort` aren't available
+        let mut i = 1;
+        while i < LEN {
+            let mut j = i;
+            while j > 0 {
+                let prev = &$pairs[index[j - 1]].$field;
+                let next = &$pairs[index[j]].$field;
+                match $crate::static_map::__ConstOrd::<$t>::cmp(prev, next) {
+                    ::core::cmp::Ordering::Greater => {
+                        let tmp = index[j];
+                        index[j] = index[j - 1];
+                        index[j - 1] = tmp;
+                        j -= 1;
+                    }
+                    ::core::cmp::Ordering::Equal => panic!($duplicate),
+                    ::core::cmp::Ordering::Less => break,
+                }
+            }
+            i += 1;
+        }
+        index
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{format, vec::Vec};
+
+    static_bimap! {
+        static KEYWORDS: StaticBiMap<&'static str, u8> = [
+            ("while", 7),
+            ("if", 3),
+            ("else", 4),
+            ("fn", 1),
+            ("let", 2),
+            ("return", 9),
+            ("loop", 5),
+        ];
+    }
+
+    #[test]
+    fn lookups() {
+        for (l, r) in KEYWORDS.iter() {
+            assert_eq!(KEYWORDS.get_by_left(l), Some(r));
+            assert_eq!(KEYWORDS.get_by_right(r), Some(l));
+        }
+        assert_eq!(KEYWORDS.get_by_left("for"), None);
+        assert_eq!(KEYWORDS.get_by_left(""), None);
+        assert_eq!(KEYWORDS.get_by_left("whilst"), None);
+        assert_eq!(KEYWORDS.get_by_right(&0), None);
+        assert_eq!(KEYWORDS.get_by_right(&6), None);
+        assert_eq!(KEYWORDS.get_by_right(&255), None);
+    }
+
+    #[test]
+    fn indices_are_sorted() {
+        let lefts: Vec<_> = KEYWORDS
+            .left_index
+            .iter()
+            .map(|&i| KEYWORDS.pairs[i].0)
+            .collect();
+        assert_eq!(
+            lefts,
+            ["else", "fn", "if", "let", "loop", "return", "while"]
+        );
+        let rights: Vec<_> = KEYWORDS
+            .right_index
+            .iter()
+            .map(|&i| KEYWORDS.pairs[i].1)
+            .collect();
+        assert_eq!(rights, [1, 2, 3, 4, 5, 7, 9]);
+    }
+
+    #[test]
+    fn iter_keeps_input_order() {
+        assert_eq!(KEYWORDS.len(), 7);
+        assert!(!KEYWORDS.is_empty());
+        let lefts: Vec<_> = KEYWORDS.left_values().copied().collect();
+        assert_eq!(
+            lefts,
+            ["while", "if", "else", "fn", "let", "return", "loop"]
+        );
+        let rights: Vec<_> = KEYWORDS.right_values().copied().collect();
+        assert_eq!(rights, [7, 3, 4, 1, 2, 9, 5]);
+        assert_eq!(KEYWORDS.iter().len(), 7);
+    }
+
+    #[test]
+    fn const_ord_matches_ord() {
+        let strs = ["", "a", "ab", "b", "\u{e9}", "z"];
+        for a in strs.iter() {
+            for b in strs.iter() {
+                assert_eq!(__ConstOrd::<&str>::cmp(a, b), a.cmp(b));
+            }
+        }
+        let ints = [i64::MIN, -1, 0, 1, i64::MAX];
+        for a in ints.iter() {
+            for b in ints.iter() {
+                assert_eq!(__ConstOrd::<i64>::cmp(a, b), a.cmp(b));
+            }
+        }
+        assert_eq!(__ConstOrd::<bool>::cmp(&false, &true), false.cmp(&true));
+        assert_eq!(__ConstOrd::<char>::cmp(&'b', &'a'), 'b'.cmp(&'a'));
+    }
+
+    #[test]
+    fn other_types() {
+        static_bimap! {
+            static FLAGS: StaticBiMap<bool, &'static [u8]> = [
+                (true, b"yes"),
+                (false, b"no"),
+            ];
+        }
+        assert_eq!(FLAGS.get_by_left(&false), Some(&&b"no"[..]));
+        assert_eq!(FLAGS.get_by_right(&&b"yes"[..]), Some(&true));
+
+        static_bimap! {
+            static EMPTY: StaticBiMap<i32, char> = [];
+        }
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.get_by_left(&0), None);
+    }
+
+    #[test]
+    fn debug_and_copy() {
+        static_bimap! {
+            static ONE: StaticBiMap<char, u8> = [('a', 1)];
+        }
+        let copy = ONE;
+        assert_eq!(format!("{:?}", copy), "{'a' <> 1}");
+    }
+}
@@ -0,0 +1,111 @@
+//! Lookup counters for the `stats` feature.
+//!
+//! Every bimap carries a `Counters` value. With the feature enabled it holds
+//! atomic counters that are bumped on each lookup; without it, it is a
+//! zero-sized type whose methods compile to nothing.
+
+#[cfg(feature = "stats")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// A snapshot of the lookups performed on a bimap, returned by
+/// `lookup_stats`.
+///
+/// Every lookup is counted as either a hit or a miss, so `hits + misses`
+/// always equals `by_left + by_right`.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::{BiBTreeMap, LookupStats};
+///
+/// let mut bimap = BiBTreeMap::new();
+/// bimap.insert('a', 1);
+///
+/// bimap.get_by_left(&'a');
+/// bimap.get_by_right(&2);
+/// assert_eq!(
+///     bimap.lookup_stats(),
+///     LookupStats { by_left: 1, by_right: 1, hits: 1, misses: 1 }
+/// );
+/// ```
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LookupStats {
+    /// The number of lookups by left value.
+    pub by_left: u64,
+    /// The number of lookups by right value.
+    pub by_right: u64,
+    /// The number of lookups that found a pair.
+    pub hits: u64,
+    /// The number of lookups that did not find a pair.
+    pub misses: u64,
+}
+
+#[cfg(feature = "stats")]
+#[derive(Debug, Default)]
+pub struct Counters {
+    by_left: AtomicU64,
+    by_right: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[cfg(feature = "stats")]
+impl Counters {
+    pub const fn new() -> Self {
+        Self {
+            by_left: AtomicU64::new(0),
+            by_right: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record_left(&self, hit: bool) {
+        self.by_left.fetch_add(1, Ordering::Relaxed);
+        self.record_result(hit);
+    }
+
+    pub fn record_right(&self, hit: bool) {
+        self.by_right.fetch_add(1, Ordering::Relaxed);
+        self.record_result(hit);
+    }
+
+    fn record_result(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> LookupStats {
+        LookupStats {
+            by_left: self.by_left.load(Ordering::Relaxed),
+            by_right: self.by_right.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset(&self) {
+        self.by_left.store(0, Ordering::Relaxed);
+        self.by_right.store(0, Ordering::Relaxed);
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(feature = "stats"))]
+#[derive(Debug, Default)]
+pub struct Counters;
+
+#[cfg(not(feature = "stats"))]
+impl Counters {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    pub fn record_left(&self, _hit: bool) {}
+
+    #[inline(always)]
+    pub fn record_right(&self, _hit: bool) {}
+}
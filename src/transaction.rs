@@ -0,0 +1,301 @@
+//! Transactions that roll a bimap back unless they are committed.
+//!
+//! A [`Transaction`] borrows a bimap mutably and records the inverse of every
+//! change made through it. Committing the transaction keeps the changes;
+//! dropping it without committing, including while unwinding from a panic,
+//! undoes them in reverse order, restoring the pairs the bimap had when the
+//! transaction began. Transactions are started with the `begin` and
+//! `transaction` methods of [`BiHashMap`] and [`BiBTreeMap`].
+//!
+//! The transaction dereferences to the bimap, so reads made through it see
+//! the uncommitted changes.
+//!
+//! # Examples
+//!
+//! ```
+//! use bimap::BiBTreeMap;
+//!
+//! let mut ports = BiBTreeMap::new();
+//! ports.insert("http", 80);
+//!
+//! // a failed validation rolls back the whole edit
+//! let result = ports.transaction(|tx| {
+//!     tx.insert("https", 443);
+//!     tx.remove_by_left(&"http");
+//!     if tx.len() < 2 {
+//!         return Err("http must stay configured");
+//!     }
+//!     Ok(())
+//! });
+//! assert_eq!(result, Err("http must stay configured"));
+//! assert_eq!(ports, [("http", 80)]);
+//!
+//! // a committed edit is kept
+//! let mut tx = ports.begin();
+//! tx.insert("https", 443);
+//! assert_eq!(tx.get_by_right(&443), Some(&"https"));
+//! tx.commit();
+//! assert_eq!(ports.len(), 2);
+//! ```
+//!
+//! [`BiHashMap`]: crate::BiHashMap
+//! [`BiBTreeMap`]: crate::BiBTreeMap
+
+use crate::{BiMapLike, Overwritten};
+use alloc::vec::Vec;
+use core::{fmt, ops::Deref};
+
+/// A pending set of changes to a bimap that is undone when dropped without
+/// being committed.
+///
+/// Only the pairs are restored on rollback: the bimap's generation counter
+/// and lookup statistics keep counting the undone changes, and a
+/// `BiHashMap` may iterate over the restored pairs in a different order.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// [module-level documentation]: crate::transaction
+pub struct Transaction<'a, M, L, R>
+where
+    M: BiMapLike<L, R>,
+{
+    bimap: &'a mut M,
+    undo: Vec<Undo<L, R>>,
+}
+
+/// The inverse of a change made through a transaction.
+enum Undo<L, R> {
+    /// The pair with this left value was inserted.
+    Inserted(L),
+    /// This pair was removed.
+    Removed(L, R),
+}
+
+impl<'a, M, L, R> Transaction<'a, M, L, R>
+where
+    M: BiMapLike<L, R>,
+    L: Clone,
+    R: Clone,
+{
+    pub(crate) fn new(bimap: &'a mut M) -> Self {
+        Transaction {
+            bimap,
+            undo: Vec::new(),
+        }
+    }
+
+    /// Inserts the given left-right pair into the bimap, returning any pairs
+    /// that were overwritten, as with the bimap's own `insert`.
+    ///
+    /// The left value and any overwritten pairs are cloned into the
+    /// transaction's undo log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiBTreeMap, Overwritten};
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut tx = bimap.begin();
+    /// assert_eq!(tx.insert('a', 2), Overwritten::Left('a', 1));
+    /// assert_eq!(tx.get_by_left(&'a'), Some(&2));
+    /// drop(tx);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let overwritten = self.bimap.insert(left.clone(), right);
+        if let Overwritten::Unchanged(..) = overwritten {
+            // the existing pair was kept, so there is nothing to undo
+            return overwritten;
+        }
+        for (l, r) in overwritten.pairs() {
+            self.undo.push(Undo::Removed(l.clone(), r.clone()));
+        }
+        self.undo.push(Undo::Inserted(left));
+        overwritten
+    }
+
+    /// Removes the left-right pair corresponding to the given left value,
+    /// as with the bimap's own `remove_by_left`.
+    ///
+    /// The removed pair is cloned into the transaction's undo log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut tx = bimap.begin();
+    /// assert_eq!(tx.remove_by_left(&'a'), Some(('a', 1)));
+    /// assert_eq!(tx.remove_by_left(&'a'), None);
+    /// tx.rollback();
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// ```
+    pub fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        let (l, r) = self.bimap.remove_by_left(left)?;
+        self.undo.push(Undo::Removed(l.clone(), r.clone()));
+        Some((l, r))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value,
+    /// as with the bimap's own `remove_by_right`.
+    ///
+    /// The removed pair is cloned into the transaction's undo log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiBTreeMap;
+    ///
+    /// let mut bimap = BiBTreeMap::new();
+    /// bimap.insert('a', 1);
+    ///
+    /// let mut tx = bimap.begin();
+    /// assert_eq!(tx.remove_by_right(&1), Some(('a', 1)));
+    /// tx.commit();
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        let (l, r) = self.bimap.remove_by_right(right)?;
+        self.undo.push(Undo::Removed(l.clone(), r.clone()));
+        Some((l, r))
+    }
+
+    /// Keeps the changes made through the transaction.
+    pub fn commit(mut self) {
+        self.undo.clear();
+    }
+
+    /// Undoes the changes made through the transaction.
+    ///
+    /// This is the same as dropping the transaction.
+    pub fn rollback(self) {}
+}
+
+impl<'a, M, L, R> Deref for Transaction<'a, M, L, R>
+where
+    M: BiMapLike<L, R>,
+{
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        self.bimap
+    }
+}
+
+impl<'a, M, L, R> Drop for Transaction<'a, M, L, R>
+where
+    M: BiMapLike<L, R>,
+{
+    fn drop(&mut self) {
+        // undoing the changes in reverse order never overwrites anything,
+        // since each step restores the state the next older change was made in
+        while let Some(undo) = self.undo.pop() {
+            match undo {
+                Undo::Inserted(left) => {
+                    self.bimap.remove_by_left(&left);
+                }
+                Undo::Removed(left, right) => {
+                    self.bimap.insert(left, right);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, M, L, R> fmt::Debug for Transaction<'a, M, L, R>
+where
+    M: BiMapLike<L, R> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Transaction")
+            .field("bimap", &self.bimap)
+            .field("changes", &self.undo.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BiBTreeMap;
+
+    #[test]
+    fn rollback_restores_pairs() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+        let before = bimap.clone();
+
+        let mut tx = bimap.begin();
+        tx.insert('a', 2);
+        tx.insert('a', 2);
+        tx.insert('d', 4);
+        tx.remove_by_right(&3);
+        tx.insert('c', 5);
+        tx.remove_by_left(&'d');
+        tx.insert('e', 1);
+        assert_eq!(*tx, [('a', 2), ('c', 5), ('e', 1)]);
+        tx.rollback();
+
+        assert_eq!(bimap, before);
+        assert!(bimap.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn commit_keeps_pairs() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+
+        let mut tx = bimap.begin();
+        tx.insert('b', 2);
+        tx.remove_by_left(&'a');
+        tx.commit();
+        assert_eq!(bimap, [('b', 2)]);
+    }
+
+    #[test]
+    fn transaction_result() {
+        let mut bimap = BiBTreeMap::new();
+        bimap.insert('a', 1);
+
+        let result: Result<(), ()> = bimap.transaction(|tx| {
+            tx.insert('b', 2);
+            // reads see the uncommitted insertion
+            assert_eq!(tx.get_by_left(&'b'), Some(&2));
+            Err(())
+        });
+        assert_eq!(result, Err(()));
+        assert_eq!(bimap, [('a', 1)]);
+
+        let result: Result<_, ()> = bimap.transaction(|tx| Ok(tx.insert('a', 2)));
+        assert!(result.unwrap().did_overwrite());
+        assert_eq!(bimap, [('a', 2)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn panic_rolls_back() {
+        use crate::BiHashMap;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut bimap = BiHashMap::new();
+        bimap.insert('a', 1);
+        let before = bimap.clone();
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            bimap.transaction(|tx| -> Result<(), ()> {
+                tx.remove_by_left(&'a');
+                tx.insert('b', 1);
+                panic!("validation failed");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(bimap, before);
+    }
+}
@@ -0,0 +1,1187 @@
+//! A bimap backed by a vector of pairs.
+
+use crate::{BiMapLike, Overwritten};
+use alloc::vec::{self, Vec};
+use core::{
+    borrow::Borrow,
+    fmt,
+    iter::{Extend, FromIterator, FusedIterator},
+    slice,
+};
+
+/// A bimap backed by a single vector of left-right pairs.
+///
+/// Every lookup is a linear scan, so a `BiVecMap` is only suited to small
+/// bimaps, where it avoids the cost of hashing or tree balancing. In return,
+/// it only requires `PartialEq` of its left and right values, so it can hold
+/// types that implement neither `Hash` nor `Ord`.
+///
+/// Pairs are kept in insertion order. Like the other bimaps, inserting a pair
+/// removes any pairs that share its left or right value, so the uniqueness
+/// invariant holds as long as `PartialEq` behaves like an equivalence
+/// relation.
+///
+/// See the [module-level documentation] for more details and examples.
+///
+/// # Examples
+///
+/// ```
+/// use bimap::BiVecMap;
+///
+/// // only `PartialEq`, no `Hash` or `Ord`
+/// #[derive(Debug, PartialEq)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let mut bimap = BiVecMap::new();
+/// bimap.insert(Point { x: 0, y: 0 }, "origin");
+/// bimap.insert(Point { x: 1, y: 0 }, "east");
+///
+/// assert_eq!(bimap.get_by_left(&Point { x: 1, y: 0 }), Some(&"east"));
+/// assert_eq!(bimap.get_by_right(&"origin"), Some(&Point { x: 0, y: 0 }));
+/// ```
+///
+/// [module-level documentation]: crate
+pub struct BiVecMap<L, R> {
+    pairs: Vec<(L, R)>,
+    generation: u64,
+}
+
+impl<L, R> BiVecMap<L, R> {
+    /// Creates an empty `BiVecMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let bimap = BiVecMap::<char, i32>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            pairs: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    /// Creates a new empty `BiVecMap` with the given capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let bimap = BiVecMap::<char, i32>::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            pairs: Vec::with_capacity(capacity),
+            generation: 0,
+        }
+    }
+
+    /// Returns the number of left-right pairs in the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// assert_eq!(bimap.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if the bimap contains no left-right pairs, and `false`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// assert!(bimap.is_empty());
+    /// bimap.insert('a', 1);
+    /// assert!(!bimap.is_empty());
+    /// bimap.remove_by_right(&1);
+    /// assert!(bimap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns the number of left-right pairs the bimap can hold without
+    /// reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let bimap: BiVecMap<char, i32> = BiVecMap::with_capacity(10);
+    /// assert!(bimap.capacity() >= 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.pairs.capacity()
+    }
+
+    /// Returns the bimap's generation, a counter that changes whenever the
+    /// left-right pairs may have changed.
+    ///
+    /// Every method that inserts, removes or modifies pairs, including
+    /// `clear` and `retain`, advances the generation, while lookups and
+    /// capacity changes leave it unchanged. The counter starts at zero and
+    /// only increases, but its exact value is not specified; clones start
+    /// their own count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// let generation = bimap.generation();
+    ///
+    /// // lookups don't change the generation
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.generation(), generation);
+    ///
+    /// // mutations do
+    /// bimap.remove_by_left(&'a');
+    /// assert!(bimap.generation() > generation);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Advances the generation after a mutation.
+    fn bump_generation(&mut self) {
+        self.generation += 1;
+    }
+
+    /// Removes all left-right pairs from the bimap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.clear();
+    /// assert!(bimap.len() == 0);
+    /// ```
+    pub fn clear(&mut self) {
+        self.bump_generation();
+        self.pairs.clear();
+    }
+
+    /// Creates an iterator over the left-right pairs in the bimap in
+    /// insertion order.
+    ///
+    /// The iterator element type is `(&'a L, &'a R)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// for (left, right) in bimap.iter() {
+    ///     println!("({}, {})", left, right);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.pairs.iter(),
+        }
+    }
+
+    /// Creates an iterator over the left values in the bimap in insertion
+    /// order.
+    ///
+    /// The iterator element type is `&'a L`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// for char_value in bimap.left_values() {
+    ///     println!("{}", char_value);
+    /// }
+    /// ```
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: self.pairs.iter(),
+        }
+    }
+
+    /// Creates an iterator over the right values in the bimap in insertion
+    /// order.
+    ///
+    /// The iterator element type is `&'a R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// for int_value in bimap.right_values() {
+    ///     println!("{}", int_value);
+    /// }
+    /// ```
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.pairs.iter(),
+        }
+    }
+}
+
+impl<L, R> BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    /// Returns a reference to the right value corresponding to the given left
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but
+    /// `PartialEq` on the borrowed form *must* match that of the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    /// assert_eq!(bimap.get_by_left(&'z'), None);
+    /// ```
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position_of_left(left).map(|i| &self.pairs[i].1)
+    }
+
+    /// Returns a reference to the left value corresponding to the given right
+    /// value.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but
+    /// `PartialEq` on the borrowed form *must* match that of the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+    /// assert_eq!(bimap.get_by_right(&2), None);
+    /// ```
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position_of_right(right).map(|i| &self.pairs[i].0)
+    }
+
+    /// Returns `true` if the bimap contains the given left value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but
+    /// `PartialEq` on the borrowed form *must* match that of the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_left(&'a'));
+    /// assert!(!bimap.contains_left(&'b'));
+    /// ```
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position_of_left(left).is_some()
+    }
+
+    /// Returns `true` if the map contains the given right value and `false`
+    /// otherwise.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but
+    /// `PartialEq` on the borrowed form *must* match that of the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// assert!(bimap.contains_right(&1));
+    /// assert!(!bimap.contains_right(&2));
+    /// ```
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position_of_right(right).is_some()
+    }
+
+    /// Removes the left-right pair corresponding to the given left value.
+    ///
+    /// Returns the previous left-right pair if the map contained the left value
+    /// and `None` otherwise. The remaining pairs keep their order.
+    ///
+    /// The input may be any borrowed form of the bimap's left type, but
+    /// `PartialEq` on the borrowed form *must* match that of the left type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_left(&'b'), None);
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        let index = self.position_of_left(left)?;
+        self.bump_generation();
+        Some(self.pairs.remove(index))
+    }
+
+    /// Removes the left-right pair corresponding to the given right value.
+    ///
+    /// Returns the previous left-right pair if the map contained the right
+    /// value and `None` otherwise. The remaining pairs keep their order.
+    ///
+    /// The input may be any borrowed form of the bimap's right type, but
+    /// `PartialEq` on the borrowed form *must* match that of the right type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    ///
+    /// assert_eq!(bimap.remove_by_right(&2), Some(('b', 2)));
+    /// assert_eq!(bimap.remove_by_right(&2), None);
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        let index = self.position_of_right(right)?;
+        self.bump_generation();
+        Some(self.pairs.remove(index))
+    }
+
+    /// Inserts the given left-right pair into the bimap.
+    ///
+    /// Returns an enum `Overwritten` representing any left-right pairs that
+    /// were overwritten by the call to `insert`. The example below details
+    /// all possible enum variants that can be returned.
+    ///
+    /// The pair is always inserted at the end of the bimap, and any
+    /// overwritten pairs are removed without changing the order of the
+    /// remaining pairs.
+    ///
+    /// # Warnings
+    ///
+    /// Somewhat paradoxically, calling `insert()` can actually reduce the size
+    /// of the bimap! This is because of the invariant that each left value
+    /// maps to exactly one right value and vice versa.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::{BiVecMap, Overwritten};
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// assert_eq!(bimap.len(), 0); // {}
+    ///
+    /// // no values are overwritten.
+    /// assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(bimap.len(), 1); // {'a' <> 1}
+    ///
+    /// // no values are overwritten.
+    /// assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(bimap.len(), 2); // {'a' <> 1, 'b' <> 2}
+    ///
+    /// // ('a', 1) already exists, so inserting ('a', 4) overwrites 'a', the left value.
+    /// // the previous left-right pair ('a', 1) is returned.
+    /// assert_eq!(bimap.insert('a', 4), Overwritten::Left('a', 1));
+    /// assert_eq!(bimap.len(), 2); // {'b' <> 2, 'a' <> 4}
+    ///
+    /// // ('b', 2) already exists, so inserting ('c', 2) overwrites 2, the right value.
+    /// // the previous left-right pair ('b', 2) is returned.
+    /// assert_eq!(bimap.insert('c', 2), Overwritten::Right('b', 2));
+    /// assert_eq!(bimap.len(), 2); // {'a' <> 4, 'c' <> 2}
+    ///
+    /// // both ('a', 4) and ('c', 2) already exist, so inserting ('a', 2) overwrites both.
+    /// // ('a', 4) has the overwritten left value ('a'), so it's the first tuple returned.
+    /// // ('c', 2) has the overwritten right value (2), so it's the second tuple returned.
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 4), ('c', 2)));
+    /// assert_eq!(bimap.len(), 1); // {'a' <> 2} // bimap is smaller than before!
+    ///
+    /// // ('a', 2) already exists, so inserting ('a', 2) overwrites the pair.
+    /// // the previous left-right pair ('a', 2) is returned.
+    /// assert_eq!(bimap.insert('a', 2), Overwritten::Pair('a', 2));
+    /// assert_eq!(bimap.len(), 1); // {'a' <> 2}
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let retval = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
+            (None, None) => Overwritten::Neither,
+            (None, Some(r_pair)) => Overwritten::Right(r_pair.0, r_pair.1),
+            (Some(l_pair), None) => {
+                // since remove_by_left() was called first, it's possible the right value was
+                // removed if a duplicate pair is being inserted
+                if l_pair.1 == right {
+                    Overwritten::Pair(l_pair.0, l_pair.1)
+                } else {
+                    Overwritten::Left(l_pair.0, l_pair.1)
+                }
+            }
+            (Some(l_pair), Some(r_pair)) => Overwritten::Both(l_pair, r_pair),
+        };
+        self.insert_unchecked(left, right);
+        retval
+    }
+
+    /// Inserts the given left-right pair into the bimap without overwriting any
+    /// existing values.
+    ///
+    /// Returns `Ok(())` if the pair was successfully inserted into the bimap.
+    /// If either value exists in the map, `Err((left, right))` is returned
+    /// with the attempted left-right pair and the map is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// assert_eq!(bimap.insert_no_overwrite('a', 1), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('b', 2), Ok(()));
+    /// assert_eq!(bimap.insert_no_overwrite('a', 3), Err(('a', 3)));
+    /// assert_eq!(bimap.insert_no_overwrite('c', 2), Err(('c', 2)));
+    /// ```
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            Err((left, right))
+        } else {
+            self.insert_unchecked(left, right);
+            Ok(())
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all left-right pairs `(l, r)` such that `f(&l,
+    /// &r)` returns `false`. The pairs are visited in insertion order, and
+    /// the retained pairs keep their order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiVecMap;
+    ///
+    /// let mut bimap = BiVecMap::new();
+    /// bimap.insert('a', 1);
+    /// bimap.insert('b', 2);
+    /// bimap.insert('c', 3);
+    /// bimap.retain(|&l, &r| r >= 2 && l != 'c');
+    /// assert_eq!(bimap.len(), 1);
+    /// assert_eq!(bimap.get_by_left(&'b'), Some(&2));
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        let mut f = f;
+        self.bump_generation();
+        self.pairs.retain(|(l, r)| f(l, r));
+    }
+
+    /// Inserts the given left-right pair into the bimap without checking if the
+    /// pair already exists.
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        self.bump_generation();
+        self.pairs.push((left, right));
+    }
+
+    fn position_of_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.pairs.iter().position(|(l, _)| l.borrow() == left)
+    }
+
+    fn position_of_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        R: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.pairs.iter().position(|(_, r)| r.borrow() == right)
+    }
+}
+
+impl<L, R> crate::sealed::Sealed for BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+}
+
+impl<L, R> BiMapLike<L, R> for BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    type Iter<'a>
+        = Iter<'a, L, R>
+    where
+        Self: 'a,
+        L: 'a,
+        R: 'a;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter(&self) -> Iter<'_, L, R> {
+        self.iter()
+    }
+
+    fn get_by_left(&self, left: &L) -> Option<&R> {
+        self.get_by_left(left)
+    }
+
+    fn get_by_right(&self, right: &R) -> Option<&L> {
+        self.get_by_right(right)
+    }
+
+    fn contains_left(&self, left: &L) -> bool {
+        self.contains_left(left)
+    }
+
+    fn contains_right(&self, right: &R) -> bool {
+        self.contains_right(right)
+    }
+
+    fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        self.insert(left, right)
+    }
+
+    fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        self.remove_by_left(left)
+    }
+
+    fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        self.remove_by_right(right)
+    }
+}
+
+impl<L, R> Clone for BiVecMap<L, R>
+where
+    L: Clone,
+    R: Clone,
+{
+    fn clone(&self) -> BiVecMap<L, R> {
+        BiVecMap {
+            pairs: self.pairs.clone(),
+            generation: 0,
+        }
+    }
+}
+
+impl<L, R> fmt::Debug for BiVecMap<L, R>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct EntryDebugger<'a, L, R> {
+            left: &'a L,
+            right: &'a R,
+        }
+        impl<'a, L, R> fmt::Debug for EntryDebugger<'a, L, R>
+        where
+            L: fmt::Debug,
+            R: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.left.fmt(f)?;
+                write!(f, " <> ")?;
+                self.right.fmt(f)
+            }
+        }
+        f.debug_set()
+            .entries(
+                self.iter()
+                    .map(|(left, right)| EntryDebugger { left, right }),
+            )
+            .finish()
+    }
+}
+
+impl<L, R> Default for BiVecMap<L, R> {
+    fn default() -> BiVecMap<L, R> {
+        BiVecMap::new()
+    }
+}
+
+impl<L, R> Eq for BiVecMap<L, R>
+where
+    L: Eq,
+    R: Eq,
+{
+}
+
+impl<L, R> FromIterator<(L, R)> for BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    fn from_iter<I>(iter: I) -> BiVecMap<L, R>
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut bimap = BiVecMap::new();
+        bimap.extend(iter);
+        bimap
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a BiVecMap<L, R> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Iter<'a, L, R> {
+        self.iter()
+    }
+}
+
+impl<L, R> IntoIterator for BiVecMap<L, R> {
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> IntoIter<L, R> {
+        IntoIter {
+            inner: self.pairs.into_iter(),
+        }
+    }
+}
+
+impl<L, R> Extend<(L, R)> for BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(l, r)| {
+            self.insert(l, r);
+        });
+    }
+}
+
+/// Two `BiVecMap`s are equal if they contain the same pairs, regardless of
+/// the order in which the pairs were inserted.
+impl<L, R> PartialEq for BiVecMap<L, R>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(l, r)| other.get_by_left(l) == Some(r))
+    }
+}
+
+/// An owning iterator over the left-right pairs in a `BiVecMap`.
+pub struct IntoIter<L, R> {
+    inner: vec::IntoIter<(L, R)>,
+}
+
+impl<L, R> ExactSizeIterator for IntoIter<L, R> {}
+
+impl<L, R> FusedIterator for IntoIter<L, R> {}
+
+impl<L, R> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left-right pairs in a `BiVecMap`.
+///
+/// This struct is created by the [`iter`] method of `BiVecMap`.
+///
+/// [`iter`]: BiVecMap::iter
+#[derive(Debug, Clone)]
+pub struct Iter<'a, L, R> {
+    inner: slice::Iter<'a, (L, R)>,
+}
+
+impl<'a, L, R> ExactSizeIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for Iter<'a, L, R> {}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (l, r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the left values in a `BiVecMap`.
+///
+/// This struct is created by the [`left_values`] method of `BiVecMap`.
+///
+/// [`left_values`]: BiVecMap::left_values
+#[derive(Debug, Clone)]
+pub struct LeftValues<'a, L, R> {
+    inner: slice::Iter<'a, (L, R)>,
+}
+
+impl<'a, L, R> ExactSizeIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for LeftValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, _)| l)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the right values in a `BiVecMap`.
+///
+/// This struct is created by the [`right_values`] method of `BiVecMap`.
+///
+/// [`right_values`]: BiVecMap::right_values
+#[derive(Debug, Clone)]
+pub struct RightValues<'a, L, R> {
+    inner: slice::Iter<'a, (L, R)>,
+}
+
+impl<'a, L, R> ExactSizeIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> FusedIterator for RightValues<'a, L, R> {}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, r)| r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{format, vec::Vec};
+
+    /// A value that only implements `PartialEq`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Opaque(f32);
+
+    #[test]
+    fn clone() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let bimap2 = bimap.clone();
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn debug() {
+        let mut bimap = BiVecMap::new();
+        assert_eq!("{}", format!("{:?}", bimap));
+
+        bimap.insert('b', 2);
+        bimap.insert('a', 1);
+        assert_eq!("{'b' <> 2, 'a' <> 1}", format!("{:?}", bimap));
+    }
+
+    #[test]
+    fn default() {
+        let _ = BiVecMap::<char, i32>::default();
+    }
+
+    #[test]
+    fn eq() {
+        let mut bimap = BiVecMap::new();
+        assert_eq!(bimap, bimap);
+        bimap.insert('a', 1);
+        assert_eq!(bimap, bimap);
+        bimap.insert('b', 2);
+        assert_eq!(bimap, bimap);
+
+        let mut bimap2 = BiVecMap::new();
+        assert_ne!(bimap, bimap2);
+        bimap2.insert('b', 2);
+        assert_ne!(bimap, bimap2);
+        // order doesn't matter
+        bimap2.insert('a', 1);
+        assert_eq!(bimap, bimap2);
+        bimap2.insert('c', 3);
+        assert_ne!(bimap, bimap2);
+
+        let mut bimap3 = BiVecMap::new();
+        bimap3.insert('a', 1);
+        bimap3.insert('b', 3);
+        assert_ne!(bimap, bimap3);
+    }
+
+    #[test]
+    fn from_iter() {
+        let bimap = BiVecMap::from_iter(vec![
+            ('a', 1),
+            ('b', 2),
+            ('c', 3),
+            ('b', 2),
+            ('a', 4),
+            ('b', 3),
+        ]);
+        let mut bimap2 = BiVecMap::new();
+        bimap2.insert('a', 4);
+        bimap2.insert('b', 3);
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('c', 1);
+        bimap.insert('b', 2);
+        let pairs = bimap.into_iter().collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('a', 3), ('c', 1), ('b', 2)]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('a', 3);
+        bimap.insert('b', 2);
+        bimap.extend(vec![('c', 3), ('b', 1), ('a', 4)]);
+        let mut bimap2 = BiVecMap::new();
+        bimap2.insert('a', 4);
+        bimap2.insert('b', 1);
+        bimap2.insert('c', 3);
+        assert_eq!(bimap, bimap2);
+    }
+
+    #[test]
+    fn iter() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('c', 3);
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let pairs = bimap.iter().map(|(c, i)| (*c, *i)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('c', 3), ('a', 1), ('b', 2)]);
+        assert_eq!(bimap.left_values().collect::<Vec<_>>(), [&'c', &'a', &'b']);
+        assert_eq!(bimap.right_values().collect::<Vec<_>>(), [&3, &1, &2]);
+    }
+
+    #[test]
+    fn iter_exact_size_fused() {
+        fn check<I: ExactSizeIterator + FusedIterator>(mut iter: I, len: usize) {
+            for remaining in (0..=len).rev() {
+                assert_eq!(iter.len(), remaining);
+                assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+                assert_eq!(iter.next().is_some(), remaining > 0);
+            }
+            assert!(iter.next().is_none());
+            assert_eq!(iter.len(), 0);
+        }
+
+        let bimap: BiVecMap<u32, char> = (0..26).zip('a'..='z').collect();
+        check(bimap.iter(), bimap.len());
+        check(bimap.left_values(), bimap.len());
+        check(bimap.right_values(), bimap.len());
+        check(bimap.clone().into_iter(), bimap.len());
+        check(BiVecMap::<u32, char>::new().iter(), 0);
+    }
+
+    #[test]
+    fn capacity() {
+        let bimap = BiVecMap::<char, i32>::with_capacity(10);
+        assert!(bimap.capacity() >= 10);
+    }
+
+    #[test]
+    fn clear() {
+        let mut bimap = vec![('a', 1)].into_iter().collect::<BiVecMap<_, _>>();
+        assert_eq!(bimap.len(), 1);
+        assert!(!bimap.is_empty());
+
+        bimap.clear();
+
+        assert_eq!(bimap.len(), 0);
+        assert!(bimap.is_empty());
+    }
+
+    #[test]
+    fn get_contains() {
+        let bimap = vec![('a', 1)].into_iter().collect::<BiVecMap<_, _>>();
+
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+        assert!(bimap.contains_left(&'a'));
+
+        assert_eq!(bimap.get_by_left(&'b'), None);
+        assert!(!bimap.contains_left(&'b'));
+
+        assert_eq!(bimap.get_by_right(&1), Some(&'a'));
+        assert!(bimap.contains_right(&1));
+
+        assert_eq!(bimap.get_by_right(&2), None);
+        assert!(!bimap.contains_right(&2));
+    }
+
+    #[test]
+    fn borrowed_lookups() {
+        use alloc::string::{String, ToString};
+
+        let mut bimap: BiVecMap<String, String> = BiVecMap::new();
+        bimap.insert("a".to_string(), "1".to_string());
+        assert_eq!(bimap.get_by_left("a").map(String::as_str), Some("1"));
+        assert!(bimap.contains_right("1"));
+        assert_eq!(
+            bimap.remove_by_right("1"),
+            Some(("a".to_string(), "1".to_string()))
+        );
+    }
+
+    #[test]
+    fn insert() {
+        let mut bimap = BiVecMap::new();
+
+        assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+        assert_eq!(bimap.insert('a', 2), Overwritten::Left('a', 1));
+        assert_eq!(bimap.insert('b', 2), Overwritten::Right('a', 2));
+        assert_eq!(bimap.insert('b', 2), Overwritten::Pair('b', 2));
+
+        assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+        assert_eq!(bimap.insert('b', 3), Overwritten::Both(('b', 2), ('c', 3)));
+    }
+
+    #[test]
+    fn insert_keeps_order() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        // overwritten pairs are removed and the new pair goes at the end
+        assert_eq!(bimap.insert('a', 4), Overwritten::Left('a', 1));
+        let pairs = bimap.iter().map(|(&l, &r)| (l, r)).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![('b', 2), ('c', 3), ('a', 4)]);
+    }
+
+    #[test]
+    fn insert_no_overwrite() {
+        let mut bimap = BiVecMap::new();
+
+        assert!(bimap.insert_no_overwrite('a', 1).is_ok());
+        assert!(bimap.insert_no_overwrite('a', 2).is_err());
+        assert!(bimap.insert_no_overwrite('b', 1).is_err());
+    }
+
+    #[test]
+    fn insert_no_overwrite_leaves_map_unchanged() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        let before = bimap.clone();
+        let generation = bimap.generation();
+
+        assert_eq!(bimap.insert_no_overwrite('a', 2), Err(('a', 2)));
+        assert_eq!(bimap.insert_no_overwrite('c', 1), Err(('c', 1)));
+        assert_eq!(bimap, before);
+        assert_eq!(bimap.generation(), generation);
+    }
+
+    #[test]
+    fn remove() {
+        let mut bimap = BiVecMap::new();
+        bimap.insert('a', 1);
+        bimap.insert('b', 2);
+        bimap.insert('c', 3);
+
+        assert_eq!(bimap.remove_by_left(&'b'), Some(('b', 2)));
+        assert_eq!(bimap.remove_by_left(&'b'), None);
+        assert_eq!(bimap.remove_by_right(&3), Some(('c', 3)));
+        assert_eq!(bimap.remove_by_right(&3), None);
+        assert_eq!(bimap.len(), 1);
+        assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    }
+
+    #[test]
+    fn retain() {
+        let mut bimap: BiVecMap<_, _> = (0..10).map(|i| (i, i * 2)).collect();
+        bimap.retain(|l, _| l % 3 == 0);
+        let pairs = bimap.into_iter().collect::<Vec<_>>();
+        assert_eq!(pairs, vec![(0, 0), (3, 6), (6, 12), (9, 18)]);
+    }
+
+    #[test]
+    fn partial_eq_only() {
+        let mut bimap = BiVecMap::new();
+        assert_eq!(
+            bimap.insert(Opaque(1.0), Opaque(-1.0)),
+            Overwritten::Neither
+        );
+        assert_eq!(
+            bimap.insert(Opaque(2.0), Opaque(-2.0)),
+            Overwritten::Neither
+        );
+        assert_eq!(
+            bimap.insert(Opaque(1.0), Opaque(-2.0)),
+            Overwritten::Both((Opaque(1.0), Opaque(-1.0)), (Opaque(2.0), Opaque(-2.0)))
+        );
+        assert_eq!(bimap.get_by_left(&Opaque(1.0)), Some(&Opaque(-2.0)));
+        assert_eq!(bimap.get_by_right(&Opaque(-2.0)), Some(&Opaque(1.0)));
+        assert_eq!(bimap.len(), 1);
+    }
+
+    #[test]
+    fn generation() {
+        let mut bimap = BiVecMap::new();
+        let mut last = bimap.generation();
+        let mut changed = |bimap: &BiVecMap<char, i32>| {
+            let changed = bimap.generation() > last;
+            last = bimap.generation();
+            changed
+        };
+        assert!(!changed(&bimap));
+
+        bimap.insert('a', 1);
+        assert!(changed(&bimap));
+        bimap.insert('a', 2);
+        assert!(changed(&bimap));
+        assert!(bimap.insert_no_overwrite('b', 3).is_ok());
+        assert!(changed(&bimap));
+        assert!(bimap.insert_no_overwrite('b', 4).is_err());
+        assert!(!changed(&bimap));
+        bimap.extend(vec![('c', 5)]);
+        assert!(changed(&bimap));
+
+        assert_eq!(bimap.remove_by_left(&'z'), None);
+        assert_eq!(bimap.remove_by_right(&0), None);
+        assert!(bimap.contains_left(&'a'));
+        assert_eq!(bimap.get_by_right(&2), Some(&'a'));
+        assert!(!changed(&bimap));
+
+        assert!(bimap.remove_by_left(&'a').is_some());
+        assert!(changed(&bimap));
+        assert!(bimap.remove_by_right(&3).is_some());
+        assert!(changed(&bimap));
+        bimap.retain(|_, _| true);
+        assert!(changed(&bimap));
+        bimap.clear();
+        assert!(changed(&bimap));
+    }
+
+    #[test]
+    fn bimap_like() {
+        fn exercise<M: BiMapLike<char, i32>>(bimap: &mut M) {
+            assert!(bimap.is_empty());
+            assert_eq!(bimap.insert('a', 1), Overwritten::Neither);
+            assert_eq!(bimap.insert('b', 2), Overwritten::Neither);
+            assert_eq!(bimap.insert('a', 2), Overwritten::Both(('a', 1), ('b', 2)));
+            assert_eq!(bimap.insert('c', 3), Overwritten::Neither);
+            assert_eq!(bimap.len(), 2);
+            assert_eq!(bimap.iter().len(), 2);
+            assert_eq!(bimap.get_by_left(&'a'), Some(&2));
+            assert_eq!(bimap.get_by_right(&3), Some(&'c'));
+            assert!(bimap.contains_left(&'c'));
+            assert!(!bimap.contains_right(&1));
+            assert_eq!(bimap.remove_by_left(&'a'), Some(('a', 2)));
+            assert_eq!(bimap.remove_by_right(&3), Some(('c', 3)));
+            assert_eq!(bimap.remove_by_right(&3), None);
+            assert!(bimap.is_empty());
+        }
+
+        exercise(&mut BiVecMap::new());
+    }
+}
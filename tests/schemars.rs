@@ -0,0 +1,135 @@
+//! Tests that the `JsonSchema` implementations describe the JSON produced by
+//! the `serde` implementations.
+
+#![cfg(all(feature = "schemars", feature = "serde"))]
+
+use bimap::{BiBTreeMap, BiHashMap};
+use schemars::{schema_for, JsonSchema};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Serialize, JsonSchema)]
+struct Config {
+    ports: BiHashMap<String, u16>,
+    names: BiBTreeMap<u32, String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Expected {
+    ports: HashMap<String, u16>,
+    names: BTreeMap<u32, String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct Pairs {
+    #[serde(with = "bimap::serde::as_pairs")]
+    #[schemars(schema_with = "bimap::serde::as_pairs::json_schema::<u8, char>")]
+    labels: BiBTreeMap<u8, char>,
+}
+
+/// Checks `value` against the subset of JSON Schema used by the schemas in
+/// these tests.
+fn validate(schema: &Value, value: &Value) {
+    match schema["type"].as_str() {
+        Some("object") => {
+            let object = value.as_object().expect("expected an object");
+            for (key, item) in object {
+                let item_schema = schema["properties"]
+                    .get(key)
+                    .or_else(|| {
+                        let patterns = schema["patternProperties"].as_object()?;
+                        // the only pattern used is the one for integer keys
+                        assert!(key.parse::<u64>().is_ok(), "bad key {:?}", key);
+                        patterns.values().next()
+                    })
+                    .or_else(|| schema.get("additionalProperties"))
+                    .unwrap_or_else(|| panic!("unexpected property {:?}", key));
+                validate(item_schema, item);
+            }
+        }
+        Some("array") => {
+            let items = value.as_array().expect("expected an array");
+            if let Some(prefix) = schema["prefixItems"].as_array() {
+                assert_eq!(items.len(), prefix.len());
+                for (item_schema, item) in prefix.iter().zip(items) {
+                    validate(item_schema, item);
+                }
+            } else {
+                for item in items {
+                    validate(&schema["items"], item);
+                }
+            }
+        }
+        Some("integer") => {
+            let n = value.as_i64().expect("expected an integer");
+            if let Some(min) = schema["minimum"].as_i64() {
+                assert!(n >= min);
+            }
+            if let Some(max) = schema["maximum"].as_i64() {
+                assert!(n <= max);
+            }
+        }
+        Some("string") => {
+            let s = value.as_str().expect("expected a string");
+            if let Some(len) = schema["maxLength"].as_u64() {
+                assert!(s.chars().count() as u64 <= len);
+            }
+        }
+        other => panic!("unsupported schema type {:?}", other),
+    }
+}
+
+#[test]
+fn same_schema_as_maps() {
+    let schema = serde_json::to_value(schema_for!(Config)).unwrap();
+    let expected = serde_json::to_value(schema_for!(Expected)).unwrap();
+    assert_eq!(schema["properties"], expected["properties"]);
+    assert_eq!(
+        schema["properties"]["ports"],
+        json!({
+            "type": "object",
+            "additionalProperties": {
+                "type": "integer",
+                "format": "uint16",
+                "minimum": 0,
+                "maximum": 65535
+            }
+        })
+    );
+}
+
+#[test]
+fn schema_describes_serialized_bimap() {
+    let mut config = Config {
+        ports: BiHashMap::new(),
+        names: BiBTreeMap::new(),
+    };
+    config.ports.insert(String::from("http"), 80);
+    config.ports.insert(String::from("https"), 443);
+    config.names.insert(1, String::from("one"));
+    config.names.insert(2, String::from("two"));
+
+    let schema = serde_json::to_value(schema_for!(Config)).unwrap();
+    let value = serde_json::to_value(&config).unwrap();
+    validate(&schema, &value);
+}
+
+#[test]
+fn as_pairs_schema() {
+    let mut pairs = Pairs {
+        labels: BiBTreeMap::new(),
+    };
+    pairs.labels.insert(0, 'a');
+    pairs.labels.insert(1, 'b');
+
+    let schema = serde_json::to_value(schema_for!(Pairs)).unwrap();
+    let expected = serde_json::to_value(
+        schemars::SchemaGenerator::default().subschema_for::<Vec<(u8, char)>>(),
+    )
+    .unwrap();
+    assert_eq!(schema["properties"]["labels"], expected);
+
+    let value = serde_json::to_value(&pairs).unwrap();
+    validate(&schema, &value);
+}
@@ -0,0 +1,576 @@
+//! Round-trip tests for the `serde` implementations across a human-readable
+//! format (JSON) and a binary format (bincode).
+
+#![cfg(all(feature = "serde", feature = "std"))]
+
+use bimap::{BiBTreeMap, BiHashMap};
+use std::collections::BTreeMap;
+
+fn json_round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+fn bincode_round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let bytes = bincode::serialize(value).unwrap();
+    bincode::deserialize(&bytes).unwrap()
+}
+
+#[test]
+fn empty_hash() {
+    let bimap = BiHashMap::<char, i32>::new();
+    assert_eq!(json_round_trip(&bimap), bimap);
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn empty_btree() {
+    let bimap = BiBTreeMap::<char, i32>::new();
+    assert_eq!(json_round_trip(&bimap), bimap);
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn single_pair_hash() {
+    let mut bimap = BiHashMap::new();
+    bimap.insert(String::from("a"), 1);
+    assert_eq!(json_round_trip(&bimap), bimap);
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn single_pair_btree() {
+    let mut bimap = BiBTreeMap::new();
+    bimap.insert(String::from("a"), 1);
+    assert_eq!(json_round_trip(&bimap), bimap);
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn non_string_keys_hash() {
+    let bimap = (0..100u32)
+        .map(|i| (i, char::from(b'!' + i as u8)))
+        .collect::<BiHashMap<_, _>>();
+    assert_eq!(json_round_trip(&bimap), bimap);
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn non_string_keys_btree() {
+    let bimap = (0..100u32)
+        .map(|i| (i, char::from(b'!' + i as u8)))
+        .collect::<BiBTreeMap<_, _>>();
+    assert_eq!(json_round_trip(&bimap), bimap);
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn composite_keys_bincode() {
+    // JSON only supports string-like map keys, but binary formats don't care
+    let bimap = vec![((1u8, 'a'), vec![1u16]), ((2u8, 'b'), vec![2u16, 3])]
+        .into_iter()
+        .collect::<BiHashMap<_, _>>();
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+
+    let bimap = vec![((1u8, 'a'), vec![1u16]), ((2u8, 'b'), vec![2u16, 3])]
+        .into_iter()
+        .collect::<BiBTreeMap<_, _>>();
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn duplicate_values_json() {
+    // 'b' and 'c' share the right value 2, so the later entry wins
+    let json = r#"{"a":1,"b":2,"c":2}"#;
+
+    let bimap: BiHashMap<char, i32> = serde_json::from_str(json).unwrap();
+    assert_eq!(bimap.len(), 2);
+    assert_eq!(bimap.get_by_right(&2), Some(&'c'));
+
+    let bimap: BiBTreeMap<char, i32> = serde_json::from_str(json).unwrap();
+    assert_eq!(bimap.len(), 2);
+    assert_eq!(bimap.get_by_right(&2), Some(&'c'));
+}
+
+#[test]
+fn duplicate_values_bincode() {
+    // bincode encodes maps as a length followed by the entries in iteration
+    // order, so a `BTreeMap` gives a deterministic entry order. As with JSON,
+    // entries are inserted in the order they are read and later entries
+    // overwrite earlier ones.
+    let mut map = BTreeMap::new();
+    map.insert('a', 1);
+    map.insert('b', 2);
+    map.insert('c', 2);
+    let bytes = bincode::serialize(&map).unwrap();
+
+    let bimap: BiHashMap<char, i32> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(bimap.len(), 2);
+    assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    assert_eq!(bimap.get_by_right(&2), Some(&'c'));
+
+    let bimap: BiBTreeMap<char, i32> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(bimap.len(), 2);
+    assert_eq!(bimap.get_by_left(&'a'), Some(&1));
+    assert_eq!(bimap.get_by_right(&2), Some(&'c'));
+}
+
+#[test]
+fn bincode_matches_btreemap_encoding() {
+    let bimap = vec![('a', 1), ('b', 2), ('c', 3)]
+        .into_iter()
+        .collect::<BiBTreeMap<_, _>>();
+    let map = bimap
+        .iter()
+        .map(|(l, r)| (*l, *r))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(
+        bincode::serialize(&bimap).unwrap(),
+        bincode::serialize(&map).unwrap()
+    );
+}
+
+#[test]
+fn representation_per_format() {
+    let pairs = vec![('a', 1), ('b', 2), ('c', 3)];
+    let hash = pairs.iter().copied().collect::<BiHashMap<_, _>>();
+    let btree = pairs.iter().copied().collect::<BiBTreeMap<_, _>>();
+
+    // human-readable formats get a map
+    assert_eq!(
+        serde_json::to_string(&btree).unwrap(),
+        r#"{"a":1,"b":2,"c":3}"#
+    );
+    let json: serde_json::Value = serde_json::to_value(&hash).unwrap();
+    assert!(json.is_object());
+
+    // compact formats get a length-prefixed sequence of pairs
+    assert_eq!(
+        bincode::serialize(&btree).unwrap(),
+        bincode::serialize(&pairs).unwrap()
+    );
+    assert_eq!(
+        postcard::to_allocvec(&btree).unwrap(),
+        postcard::to_allocvec(&pairs).unwrap()
+    );
+    let decoded: Vec<(char, i32)> =
+        bincode::deserialize(&bincode::serialize(&hash).unwrap()).unwrap();
+    assert_eq!(decoded.into_iter().collect::<BiHashMap<_, _>>(), hash);
+}
+
+#[test]
+fn reads_map_encoded_data() {
+    // data written when bimaps were always serialized as maps
+    let map = vec![('a', 1), ('b', 2), ('c', 3)]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let expected = map
+        .iter()
+        .map(|(l, r)| (*l, *r))
+        .collect::<BiBTreeMap<_, _>>();
+
+    let json = r#"{"a":1,"b":2,"c":3}"#;
+    assert_eq!(
+        serde_json::from_str::<BiBTreeMap<char, i32>>(json).unwrap(),
+        expected
+    );
+    let hash = serde_json::from_str::<BiHashMap<char, i32>>(json).unwrap();
+    assert_eq!(hash.len(), 3);
+    assert_eq!(hash.get_by_left(&'b'), Some(&2));
+
+    let bytes = bincode::serialize(&map).unwrap();
+    assert_eq!(
+        bincode::deserialize::<BiBTreeMap<char, i32>>(&bytes).unwrap(),
+        expected
+    );
+    let bytes = postcard::to_allocvec(&map).unwrap();
+    assert_eq!(
+        postcard::from_bytes::<BiBTreeMap<char, i32>>(&bytes).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn non_string_keys_compact() {
+    // compact formats don't restrict map keys, and neither do pairs
+    let bimap = vec![(vec![1u8, 2], Some('a')), (vec![], None)]
+        .into_iter()
+        .collect::<BiHashMap<_, _>>();
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+    assert!(serde_json::to_string(&bimap).is_err());
+}
+
+fn postcard_round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let bytes = postcard::to_allocvec(value).unwrap();
+    postcard::from_bytes(&bytes).unwrap()
+}
+
+#[test]
+fn postcard_hash() {
+    let bimap = BiHashMap::<char, i32>::new();
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+
+    let bimap = vec![('a', 1), ('b', 2), ('c', 3)]
+        .into_iter()
+        .collect::<BiHashMap<_, _>>();
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn postcard_btree() {
+    let bimap = BiBTreeMap::<char, i32>::new();
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+
+    let bimap = vec![('a', 1), ('b', 2), ('c', 3)]
+        .into_iter()
+        .collect::<BiBTreeMap<_, _>>();
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+}
+
+#[test]
+fn option_values() {
+    let bimap = vec![(Some('a'), None), (None, Some(2u8)), (Some('c'), Some(3))]
+        .into_iter()
+        .collect::<BiHashMap<_, _>>();
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+
+    let bimap = vec![(Some('a'), None), (None, Some(2u8)), (Some('c'), Some(3))]
+        .into_iter()
+        .collect::<BiBTreeMap<_, _>>();
+    assert_eq!(bincode_round_trip(&bimap), bimap);
+    assert_eq!(postcard_round_trip(&bimap), bimap);
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct Pairs {
+    #[serde(with = "bimap::serde::as_pairs")]
+    hash: BiHashMap<(u8, u8), Option<char>>,
+    #[serde(with = "bimap::serde::as_pairs")]
+    btree: BiBTreeMap<(u8, u8), Option<char>>,
+}
+
+#[test]
+fn as_pairs() {
+    let pairs = [((0, 0), Some('a')), ((0, 1), None), ((1, 0), Some('c'))];
+    let value = Pairs {
+        hash: pairs.iter().cloned().collect(),
+        btree: pairs.iter().cloned().collect(),
+    };
+    assert_eq!(json_round_trip(&value), value);
+    assert_eq!(bincode_round_trip(&value), value);
+    assert_eq!(postcard_round_trip(&value), value);
+
+    let empty = Pairs {
+        hash: BiHashMap::new(),
+        btree: BiBTreeMap::new(),
+    };
+    assert_eq!(json_round_trip(&empty), empty);
+    assert_eq!(bincode_round_trip(&empty), empty);
+    assert_eq!(postcard_round_trip(&empty), empty);
+}
+
+#[test]
+fn as_pairs_overwrites() {
+    let json = r#"{"hash":[[[0,0],"a"],[[0,1],"a"]],"btree":[[[0,0],"a"],[[0,0],"b"]]}"#;
+    let value: Pairs = serde_json::from_str(json).unwrap();
+    assert_eq!(value.hash.len(), 1);
+    assert_eq!(value.hash.get_by_right(&Some('a')), Some(&(0, 1)));
+    assert_eq!(value.btree.len(), 1);
+    assert_eq!(value.btree.get_by_left(&(0, 0)), Some(&Some('b')));
+}
+
+#[test]
+fn borrowed_str_keys() {
+    let json = String::from(r#"{"a":1,"b":2,"c":3}"#);
+
+    let bimap: BiHashMap<&str, u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(bimap.get_by_left("b"), Some(&2));
+    assert_eq!(bimap.get_by_right(&3), Some(&"c"));
+
+    let bimap: BiBTreeMap<&str, u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        bimap.left_values().collect::<Vec<_>>(),
+        vec![&"a", &"b", &"c"]
+    );
+}
+
+#[test]
+fn borrowed_str_pairs() {
+    let json = String::from(r#"[["a","x"],["b","y"]]"#);
+    let bimap: BiHashMap<&str, &str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(bimap.get_by_left("a"), Some(&"x"));
+    assert_eq!(bimap.get_by_right("y"), Some(&"b"));
+}
+
+#[derive(serde::Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+struct CowKey<'a>(#[serde(borrow)] std::borrow::Cow<'a, str>);
+
+#[test]
+fn borrowed_cow_keys() {
+    use std::borrow::Cow;
+
+    let json = String::from(r#"{"plain":1,"esc\"aped":2}"#);
+
+    let bimap: BiHashMap<CowKey, u32> = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        bimap.get_by_right(&1),
+        Some(CowKey(Cow::Borrowed("plain")))
+    ));
+    match bimap.get_by_right(&2) {
+        Some(CowKey(Cow::Owned(s))) => assert_eq!(s, "esc\"aped"),
+        other => panic!("expected an owned key, got {:?}", other),
+    }
+
+    let bimap: BiBTreeMap<CowKey, u32> = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        bimap.get_by_right(&1),
+        Some(CowKey(Cow::Borrowed("plain")))
+    ));
+    assert!(matches!(
+        bimap.get_by_right(&2),
+        Some(CowKey(Cow::Owned(_)))
+    ));
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct IntKeys {
+    #[serde(with = "bimap::serde::display_fromstr_keys")]
+    hash: BiHashMap<u64, String>,
+    #[serde(with = "bimap::serde::display_fromstr_keys")]
+    btree: BiBTreeMap<i32, char>,
+}
+
+#[test]
+fn display_fromstr_int_keys() {
+    let mut hash = BiHashMap::new();
+    hash.insert(u64::MAX, String::from("max"));
+    let mut btree = BiBTreeMap::new();
+    btree.insert(-1, 'a');
+    btree.insert(10, 'b');
+    let value = IntKeys { hash, btree };
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(
+        json,
+        r#"{"hash":{"18446744073709551615":"max"},"btree":{"-1":"a","10":"b"}}"#
+    );
+    assert_eq!(json_round_trip(&value), value);
+    assert_eq!(bincode_round_trip(&value), value);
+}
+
+/// A UUID-like key, formatted as 32 hex digits in groups of 8-4-4-4-12
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone, Copy)]
+struct Id(u128);
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let hex = format!("{:032x}", self.0);
+        write!(
+            f,
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+impl std::str::FromStr for Id {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let groups = s.split('-').map(str::len).collect::<Vec<_>>();
+        if groups != [8, 4, 4, 4, 12] {
+            return Err(String::from("malformed id"));
+        }
+        u128::from_str_radix(&s.replace('-', ""), 16)
+            .map(Id)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct IdKeys {
+    #[serde(with = "bimap::serde::display_fromstr_keys")]
+    hash: BiHashMap<Id, String>,
+    #[serde(with = "bimap::serde::display_fromstr_keys")]
+    btree: BiBTreeMap<Id, String>,
+}
+
+#[test]
+fn display_fromstr_id_keys() {
+    let pairs = [
+        (Id(1), String::from("first")),
+        (
+            Id(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef),
+            String::from("second"),
+        ),
+    ];
+    let value = IdKeys {
+        hash: pairs.iter().cloned().collect(),
+        btree: pairs.iter().cloned().collect(),
+    };
+
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(json.contains(r#""00000000-0000-0000-0000-000000000001":"first""#));
+    assert!(json.contains(r#""01234567-89ab-cdef-0123-456789abcdef":"second""#));
+    assert_eq!(json_round_trip(&value), value);
+    assert_eq!(bincode_round_trip(&value), value);
+}
+
+#[test]
+fn display_fromstr_malformed_key() {
+    let json = r#"{"hash":{"12":"a","1x2":"b"},"btree":{}}"#;
+    let err = serde_json::from_str::<IntKeys>(json).unwrap_err();
+    assert!(err.to_string().contains(r#"invalid key "1x2""#), "{}", err);
+
+    let json = r#"{"hash":{"0000-0000":"a"},"btree":{}}"#;
+    let err = serde_json::from_str::<IdKeys>(json).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(r#"invalid key "0000-0000": malformed id"#),
+        "{}",
+        err
+    );
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct Versioned {
+    #[serde(with = "bimap::serde::versioned")]
+    hash: BiHashMap<(u8, u8), String>,
+    #[serde(with = "bimap::serde::versioned")]
+    btree: BiBTreeMap<char, i32>,
+}
+
+#[test]
+fn versioned_round_trip() {
+    let value = Versioned {
+        hash: vec![((0, 1), String::from("a")), ((2, 3), String::from("b"))]
+            .into_iter()
+            .collect(),
+        btree: vec![('a', 1), ('b', 2)].into_iter().collect(),
+    };
+    assert_eq!(json_round_trip(&value), value);
+    assert_eq!(bincode_round_trip(&value), value);
+    assert_eq!(postcard_round_trip(&value), value);
+
+    let empty = Versioned {
+        hash: BiHashMap::new(),
+        btree: BiBTreeMap::new(),
+    };
+    assert_eq!(
+        serde_json::to_string(&empty).unwrap(),
+        r#"{"hash":{"version":1,"pairs":[]},"btree":{"version":1,"pairs":[]}}"#
+    );
+    assert_eq!(json_round_trip(&empty), empty);
+    assert_eq!(bincode_round_trip(&empty), empty);
+}
+
+#[test]
+fn versioned_field_order() {
+    let json = r#"{
+        "hash": {"pairs": [[[0, 0], "x"], [[0, 1], "x"]], "version": 1},
+        "btree": {"extra": true, "version": 1, "pairs": [["a", 1]]}
+    }"#;
+    let value: Versioned = serde_json::from_str(json).unwrap();
+    assert_eq!(value.hash.len(), 1);
+    assert_eq!(value.hash.get_by_right("x"), Some(&(0, 1)));
+    assert_eq!(value.btree.get_by_left(&'a'), Some(&1));
+}
+
+#[test]
+fn versioned_unknown_version() {
+    let json = r#"{"hash":{"version":1,"pairs":[]},"btree":{"version":2,"pairs":[]}}"#;
+    let err = serde_json::from_str::<Versioned>(json).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid value: integer `2`, expected bimap format version 1"),
+        "{}",
+        err
+    );
+
+    // the version is checked even when the pairs come first
+    let json = r#"{"hash":{"version":1,"pairs":[]},"btree":{"pairs":[],"version":0}}"#;
+    assert!(serde_json::from_str::<Versioned>(json).is_err());
+
+    // and for compact formats
+    let mut bytes = bincode::serialize(&Versioned {
+        hash: BiHashMap::new(),
+        btree: BiBTreeMap::new(),
+    })
+    .unwrap();
+    // the version of the first bimap is the first field, a little-endian u32
+    bytes[0] = 7;
+    assert!(bincode::deserialize::<Versioned>(&bytes).is_err());
+}
+
+#[test]
+fn versioned_missing_fields() {
+    let json = r#"{"hash":{"pairs":[]},"btree":{"version":1,"pairs":[]}}"#;
+    let err = serde_json::from_str::<Versioned>(json).unwrap_err();
+    assert!(
+        err.to_string().contains("missing field `version`"),
+        "{}",
+        err
+    );
+
+    let json = r#"{"hash":{"version":1},"btree":{"version":1,"pairs":[]}}"#;
+    let err = serde_json::from_str::<Versioned>(json).unwrap_err();
+    assert!(err.to_string().contains("missing field `pairs`"), "{}", err);
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct ByRight {
+    #[serde(with = "bimap::serde::by_right")]
+    ids: BiBTreeMap<String, u32>,
+}
+
+#[test]
+fn by_right_order() {
+    let value = ByRight {
+        ids: vec![("x", 30), ("y", 10), ("z", 20), ("w", 40)]
+            .into_iter()
+            .map(|(l, r)| (String::from(l), r))
+            .collect(),
+    };
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"{"ids":{"y":10,"z":20,"x":30,"w":40}}"#
+    );
+    let pairs: Vec<(String, u32)> =
+        bincode::deserialize(&bincode::serialize(&value).unwrap()).unwrap();
+    let rights = pairs.iter().map(|(_, r)| *r).collect::<Vec<_>>();
+    assert_eq!(rights, [10, 20, 30, 40]);
+}
+
+#[test]
+fn by_right_round_trip() {
+    let value = ByRight {
+        ids: (0..50).map(|i| (format!("{:02}", i), 100 - i)).collect(),
+    };
+    assert_eq!(json_round_trip(&value), value);
+    assert_eq!(bincode_round_trip(&value), value);
+    assert_eq!(postcard_round_trip(&value), value);
+
+    // input in neither left nor right order is accepted
+    let json = r#"{"ids":{"b":3,"c":1,"a":2}}"#;
+    let value: ByRight = serde_json::from_str(json).unwrap();
+    let expected = [("a", 2), ("b", 3), ("c", 1)]
+        .iter()
+        .map(|&(l, r)| (String::from(l), r))
+        .collect::<BiBTreeMap<_, _>>();
+    assert_eq!(value.ids, expected);
+}
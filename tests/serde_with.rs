@@ -0,0 +1,67 @@
+#![cfg(feature = "serde_with")]
+
+use bimap::{BiBTreeMap, BiHashMap};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr, Same};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Ids {
+    #[serde_as(as = "BiHashMap<DisplayFromStr, _>")]
+    hash: BiHashMap<u64, char>,
+    #[serde_as(as = "BiBTreeMap<DisplayFromStr, Same>")]
+    btree: BiBTreeMap<u64, char>,
+}
+
+#[test]
+fn display_from_str_round_trip() {
+    let mut hash = BiHashMap::new();
+    hash.insert(1, 'a');
+    hash.insert(u64::MAX, 'z');
+    let mut btree = BiBTreeMap::new();
+    btree.insert(2, 'b');
+    btree.insert(10, 'c');
+    let ids = Ids { hash, btree };
+
+    let json = serde_json::to_string(&ids).unwrap();
+    assert!(json.contains(r#""18446744073709551615":"z""#));
+    assert!(json.contains(r#""btree":{"2":"b","10":"c"}"#));
+
+    let ids2: Ids = serde_json::from_str(&json).unwrap();
+    assert_eq!(ids, ids2);
+}
+
+#[test]
+fn display_from_str_right_values() {
+    #[serde_as]
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Rights(#[serde_as(as = "BiBTreeMap<_, DisplayFromStr>")] BiBTreeMap<char, u8>);
+
+    let mut map = BiBTreeMap::new();
+    map.insert('a', 1);
+    map.insert('b', 2);
+    let rights = Rights(map);
+
+    let json = serde_json::to_string(&rights).unwrap();
+    assert_eq!(json, r#"{"a":"1","b":"2"}"#);
+    assert_eq!(serde_json::from_str::<Rights>(&json).unwrap(), rights);
+}
+
+#[test]
+fn display_from_str_pairs() {
+    let json = r#"{"hash":[["1","a"],["2","a"]],"btree":[["3","c"]]}"#;
+    let ids: Ids = serde_json::from_str(json).unwrap();
+    assert_eq!(ids.hash.len(), 1);
+    assert_eq!(ids.hash.get_by_right(&'a'), Some(&2));
+    assert_eq!(ids.btree.get_by_left(&3), Some(&'c'));
+}
+
+#[test]
+fn display_from_str_parse_error() {
+    let json = r#"{"hash":{"1":"a","not a number":"b"},"btree":{}}"#;
+    let err = serde_json::from_str::<Ids>(json).unwrap_err();
+    assert!(err.to_string().contains("invalid digit"), "{}", err);
+
+    let json = r#"{"hash":{},"btree":{"-1":"a"}}"#;
+    assert!(serde_json::from_str::<Ids>(json).is_err());
+}